@@ -2,6 +2,9 @@ use std::path::PathBuf;
 
 use clap::Parser;
 
+use crate::diagnostics::Severity;
+use crate::terraform::{BlockType, DEFAULT_MAX_ATTRIBUTE_DEPTH};
+
 /// Least Privilege Policy Creator
 ///
 /// Generates minimal AWS IAM policies based on static analysis of Terraform code.
@@ -15,36 +18,332 @@ use clap::Parser;
 #[command(about, long_about)]
 pub struct Cli {
     /// Suppress colored output (useful for CI/CD pipelines)
-    #[arg(short = 'n', long = "no-color")]
+    #[arg(short = 'n', long = "no-color", global = true)]
     pub no_color: bool,
 
     /// Enable verbose output for debugging
-    #[arg(long = "verbose")]
+    #[arg(long = "verbose", global = true)]
     pub verbose: bool,
 
+    /// How to print a failure to stderr: `text`, or `json` for CI tools
+    #[arg(
+        long = "error-format",
+        value_name = "FORMAT",
+        global = true,
+        default_value = "text"
+    )]
+    pub error_format: ErrorFormat,
+
     /// Working directory containing Terraform files
-    #[arg(short = 'd', long = "working-dir")]
+    #[arg(short = 'd', long = "working-dir", global = true)]
     pub working_dir: Option<PathBuf>,
 
-    /// Output directory for generated policy files
+    /// Output directory for generated policy files; `{env}` and `{region}` are replaced
     #[arg(short = 'o', long = "output-dir")]
     pub output_dir: Option<PathBuf>,
 
-    /// Output format: json, json-grouped, hcl, hcl-grouped
-    #[arg(short = 'f', long = "output-format", default_value = "hcl-grouped")]
-    pub output_format: OutputFormat,
+    /// Environment name substituted for `{env}` in --output-dir, e.g. `dev`
+    #[arg(long = "env", value_name = "ENV")]
+    pub env: Option<String>,
+
+    /// Prefix for generated file names, e.g. `iam-policy-`
+    #[arg(long = "output-prefix", requires = "output_dir")]
+    pub output_prefix: Option<String>,
+
+    /// Suffix for generated file names (before the extension), e.g. `-v2`
+    #[arg(long = "output-suffix", requires = "output_dir")]
+    pub output_suffix: Option<String>,
+
+    /// Output format: json, json-grouped, hcl, hcl-grouped, hcl-locals, terraform-role, policy-document, yaml, debug [default: hcl-grouped]
+    #[arg(short = 'f', long = "output-format")]
+    pub output_format: Option<OutputFormat>,
 
-    /// URL of the git repository containing mapping files
+    /// Template file (minijinja) each group is rendered through instead of --output-format
     #[arg(
-        short = 'm',
-        long = "mappings-url",
-        default_value = "https://github.com/bebold-jhr/lppc-aws-mappings"
+        long = "template-file",
+        value_name = "PATH",
+        conflicts_with = "output_format"
     )]
-    pub mappings_url: String,
+    pub template_file: Option<PathBuf>,
+
+    /// File extension of the output rendered with --template-file
+    #[arg(
+        long = "template-extension",
+        value_name = "EXT",
+        default_value = "txt",
+        requires = "template_file"
+    )]
+    pub template_extension: String,
+
+    /// Split json/json-grouped policies over the 6144-character managed policy limit into <Group>.1.json, <Group>.2.json, ...
+    #[arg(long = "split-oversized")]
+    pub split_oversized: bool,
+
+    /// Sid of the statements in the json, json-grouped, and yaml formats: service, sequential, none
+    #[arg(long = "sid-strategy", value_name = "STRATEGY", default_value = "none")]
+    pub sid_strategy: SidStrategy,
+
+    /// URL of the git repository containing mapping files [default: https://github.com/bebold-jhr/lppc-aws-mappings]
+    #[arg(short = 'm', long = "mappings-url", global = true)]
+    pub mappings_url: Option<String>,
+
+    /// Branch, tag, or commit of the mapping repository to use instead of its default branch
+    #[arg(long = "mappings-ref", value_name = "REF", global = true)]
+    pub mappings_ref: Option<String>,
 
     /// Force refresh of the mapping repository
-    #[arg(short = 'r', long = "refresh-mappings")]
+    #[arg(short = 'r', long = "refresh-mappings", global = true)]
     pub refresh_mappings: bool,
+
+    /// Refresh the cached mapping repository once it is this old, e.g. 30m, 12h, 7d, or 0 for every run [default: 24h]
+    #[arg(long = "cache-ttl", value_name = "DURATION", global = true)]
+    pub cache_ttl: Option<String>,
+
+    /// Use the cached mapping repository without any clone or update, failing if nothing is cached
+    #[arg(long = "offline", conflicts_with = "refresh_mappings", global = true)]
+    pub offline: bool,
+
+    /// Use mappings from this local directory instead of cloning the mapping repository
+    #[arg(
+        long = "local-mappings",
+        value_name = "DIR",
+        conflicts_with_all = ["mappings_url", "mappings_ref", "refresh_mappings"],
+        global = true
+    )]
+    pub local_mappings: Option<PathBuf>,
+
+    /// Resolve actions present in both allow and deny: deny-wins, allow-wins
+    #[arg(long = "resolve-conflicts")]
+    pub resolve_conflicts: Option<ConflictResolution>,
+
+    /// Remove allow actions that a deny action of the same group matches (deny wins, as in AWS)
+    #[arg(long = "apply-deny", conflicts_with = "resolve_conflicts")]
+    pub apply_deny: bool,
+
+    /// Fail if a group with resources resolves no allow and no deny actions
+    #[arg(long = "fail-on-empty-group")]
+    pub fail_on_empty_group: bool,
+
+    /// Fail (after writing the output) if any Terraform type has no mapping
+    #[arg(long = "fail-on-missing-mappings")]
+    pub fail_on_missing_mappings: bool,
+
+    /// Merge groups with identical allow and deny sets into one combined group
+    #[arg(
+        long = "dedupe-identical-groups",
+        conflicts_with_all = ["conditions", "emit_manifest"]
+    )]
+    pub dedupe_identical_groups: bool,
+
+    /// Drop allow actions already granted by a wildcard of the same service, e.g. s3:GetObject next to s3:Get*
+    #[arg(long = "collapse-wildcards")]
+    pub collapse_wildcards: bool,
+
+    /// Write one policy per AWS service (e.g. s3, ec2) with its actions from all groups
+    #[arg(
+        long = "group-by-service",
+        conflicts_with_all = ["dedupe_identical_groups", "conditions"]
+    )]
+    pub group_by_service: bool,
+
+    /// Write one policy per AWS account (from each group's role ARN) with the actions of its groups
+    #[arg(
+        long = "group-by-account",
+        conflicts_with_all = ["group_by_service", "dedupe_identical_groups", "conditions"]
+    )]
+    pub group_by_account: bool,
+
+    /// Write a single policy NAME (default Combined) with the actions of all groups
+    #[arg(
+        long = "combine",
+        value_name = "NAME",
+        num_args = 0..=1,
+        default_missing_value = "Combined",
+        conflicts_with_all = ["group_by_service", "group_by_account", "dedupe_identical_groups", "conditions"]
+    )]
+    pub combine: Option<String>,
+
+    /// Only output the groups whose name matches PATTERN (`*` and `?` wildcards)
+    #[arg(long = "groups", value_name = "PATTERN")]
+    pub groups: Option<String>,
+
+    /// Group providers whose role ARNs differ only in the account ID
+    #[arg(long = "role-arn-canonicalize")]
+    pub role_arn_canonicalize: bool,
+
+    /// Name groups with interpolated role ARNs after the static role name
+    #[arg(long = "role-name-groups")]
+    pub role_name_groups: bool,
+
+    /// Keep only blocks whose provider is configured for REGION (providers without a region are kept)
+    #[arg(long = "region", value_name = "REGION")]
+    pub region: Option<String>,
+
+    /// Do not record attributes nested deeper than DEPTH levels (conditionals on them never fire)
+    #[arg(
+        long = "max-attribute-depth",
+        value_name = "DEPTH",
+        default_value_t = DEFAULT_MAX_ATTRIBUTE_DEPTH
+    )]
+    pub max_attribute_depth: usize,
+
+    /// Replace the account ID in printed role ARNs (manifest, terraform-role output, logs) with XXXXXXXXXXXX
+    #[arg(long = "redact-account-ids")]
+    pub redact_account_ids: bool,
+
+    /// Write a stub mapping file for each missing mapping into this directory
+    #[arg(long = "dump-missing")]
+    pub dump_missing: Option<PathBuf>,
+
+    /// Suggest the closest existing mapping type for each missing mapping
+    #[arg(long = "explain-missing")]
+    pub explain_missing: bool,
+
+    /// Print which conditional keys of a block's mapping fired, e.g. aws_s3_bucket.main
+    #[arg(long = "explain-conditional", value_name = "ADDRESS")]
+    pub explain_conditional: Option<String>,
+
+    /// Print how the policies would change with the mappings at REF (branch, tag, or commit)
+    #[arg(
+        long = "compare-mappings-ref",
+        value_name = "REF",
+        conflicts_with_all = ["offline", "local_mappings"]
+    )]
+    pub compare_mappings_ref: Option<String>,
+
+    /// Print only `Group: N allow, M deny` per group to stdout instead of the policies
+    #[arg(long = "compact-stdout", conflicts_with = "output_dir")]
+    pub compact_stdout: bool,
+
+    /// Write a MANIFEST.md listing each group, its role ARN, and covered types
+    #[arg(long = "emit-manifest", requires = "output_dir")]
+    pub emit_manifest: bool,
+
+    /// Write a <Group>.sources.json listing the Terraform addresses of each group
+    #[arg(long = "emit-sources", requires = "output_dir")]
+    pub emit_sources: bool,
+
+    /// Write a <Group>.provenance.json with the lppc version and mapping repository commit
+    #[arg(long = "emit-provenance", requires = "output_dir")]
+    pub emit_provenance: bool,
+
+    /// Write the union of all groups' deny actions as one deny-only policy to FILE
+    #[arg(long = "emit-deny-guardrail", value_name = "FILE")]
+    pub emit_deny_guardrail: Option<PathBuf>,
+
+    /// Write missing mappings and shadowed actions as a SARIF 2.1.0 report to FILE
+    #[arg(long = "sarif", value_name = "FILE")]
+    pub sarif: Option<PathBuf>,
+
+    /// Run a command for each written policy file; `{}` is replaced with the file path
+    #[arg(
+        long = "validate-cmd",
+        value_name = "TEMPLATE",
+        requires = "output_dir"
+    )]
+    pub validate_cmd: Option<String>,
+
+    /// Skip `terraform init`, e.g. without registry access; resources inside modules are not analyzed
+    #[arg(long = "no-init")]
+    pub no_init: bool,
+
+    /// Kill `terraform init` if it runs longer than this many seconds
+    #[arg(long = "init-timeout", value_name = "SECONDS")]
+    pub init_timeout: Option<u64>,
+
+    /// Fail if resources of these types are present (comma-separated or repeatable)
+    #[arg(
+        long = "forbidden-types",
+        value_name = "TYPE",
+        num_args = 1..,
+        value_delimiter = ','
+    )]
+    pub forbidden_types: Vec<String>,
+
+    /// Only resolve permissions for these block types (comma-separated or repeatable) [default: all]
+    #[arg(
+        long = "block-types",
+        value_name = "TYPE",
+        num_args = 1..,
+        value_delimiter = ','
+    )]
+    pub block_types: Vec<BlockType>,
+
+    /// Fail unless the generated groups match the names listed in FILE (one per line)
+    #[arg(long = "expected-groups", value_name = "FILE")]
+    pub expected_groups: Option<PathBuf>,
+
+    /// Drop and report actions not matched by FILE (one action per line or a JSON list, `*` wildcards)
+    #[arg(long = "action-allowlist", value_name = "FILE")]
+    pub action_allowlist: Option<PathBuf>,
+
+    /// Deny dangerous actions only granted via a wildcard allow, from the bundled catalog or FILE
+    #[arg(long = "auto-deny-dangerous", value_name = "FILE", num_args = 0..=1)]
+    pub auto_deny_dangerous: Option<Option<PathBuf>>,
+
+    /// Fail if a mapping allows every action (`*`) or has an unknown service prefix (e.g. `s33:GetObject`)
+    #[arg(long = "validate-mappings-strict")]
+    pub validate_mappings_strict: bool,
+
+    /// Skip modules that fail to parse instead of aborting (result is partial)
+    #[arg(long = "best-effort")]
+    pub best_effort: bool,
+
+    /// Keep the sandbox directory after the run, at PATH or a fixed temp location
+    #[arg(long = "keep-sandbox", value_name = "PATH", num_args = 0..=1)]
+    pub keep_sandbox: Option<Option<PathBuf>>,
+
+    /// Print how long each pipeline stage took to stderr
+    #[arg(long = "timings")]
+    pub timings: bool,
+
+    /// List the AWS managed policies attached by the configuration at the end of the run
+    #[arg(long = "report-managed-policies")]
+    pub report_managed_policies: bool,
+
+    /// List the mapping files of the repository that no block of the configuration used
+    #[arg(long = "report-unused")]
+    pub report_unused: bool,
+
+    /// Print the module tree with sources, provider mappings, and block counts to stderr
+    #[arg(long = "print-tree")]
+    pub print_tree: bool,
+
+    /// Only report diagnostics of at least this severity to stderr
+    #[arg(long = "min-severity", value_name = "SEVERITY", default_value = "info")]
+    pub min_severity: Severity,
+
+    /// Overwrite existing files in the output directory without asking
+    #[arg(long = "force", visible_alias = "yes")]
+    pub force: bool,
+
+    /// Attach an IAM Condition to a group's Allow statements (repeatable)
+    #[arg(long = "condition", value_name = "GROUP:JSON")]
+    pub conditions: Vec<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Commands that run instead of generating policies.
+///
+/// The mapping repository options (`--mappings-url`, `--local-mappings`, ...)
+/// and `--working-dir` may also be given after the command.
+#[derive(Debug, Clone, PartialEq, Eq, clap::Subcommand)]
+pub enum Command {
+    /// Lint every mapping file of the mapping repository and exit non-zero if any is invalid
+    ValidateMappings,
+
+    /// Print statistics about the mapping repository
+    #[command(visible_alias = "stats")]
+    MappingStats,
+
+    /// Compare the policies of --working-dir with JSON policies written earlier and exit non-zero if they differ
+    Diff {
+        /// Directory with the previously written JSON policies (`--output-format json` or `json-grouped`)
+        #[arg(long = "against", value_name = "DIR")]
+        against: PathBuf,
+    },
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
@@ -54,4 +353,41 @@ pub enum OutputFormat {
     Hcl,
     #[default]
     HclGrouped,
+    HclLocals,
+    TerraformRole,
+    PolicyDocument,
+    Yaml,
+    Debug,
+}
+
+/// How the `Sid` of the statements in the JSON formats is generated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SidStrategy {
+    /// Named after the effect and, for json-grouped, the service, e.g. `AllowS3`;
+    /// the other formats use the effect alone, e.g. `Allow`
+    Service,
+    /// Numbered in document order: `Stmt1`, `Stmt2`, ...
+    Sequential,
+    /// No `Sid`
+    #[default]
+    None,
+}
+
+/// How a failed run is reported on stderr.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ErrorFormat {
+    /// `Error: ...` followed by the causes
+    #[default]
+    Text,
+    /// `{"error": "...", "context": ["...", ...]}` on a single line
+    Json,
+}
+
+/// Strategy for actions that end up in both the allow and the deny set of a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConflictResolution {
+    /// Remove conflicting actions from the allow set
+    DenyWins,
+    /// Remove conflicting actions from the deny set
+    AllowWins,
 }
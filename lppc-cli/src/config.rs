@@ -1,7 +1,28 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::Duration;
 
-use crate::cli::{Cli, OutputFormat};
+use regex::Regex;
+
+use crate::cli::{Cli, Command, ConflictResolution, OutputFormat, SidStrategy};
+use crate::config_file::ConfigFile;
+use crate::diagnostics::Severity;
 use crate::error::LppcError;
+use crate::terraform::BlockType;
+
+/// Mapping repository used without `--mappings-url` or `mappings_url` in `.lppc.toml`.
+pub const DEFAULT_MAPPINGS_URL: &str = "https://github.com/bebold-jhr/lppc-aws-mappings";
+
+/// Cache TTL used without `--cache-ttl` or `cache_ttl` in `.lppc.toml`.
+pub const DEFAULT_CACHE_TTL: &str = "24h";
+
+/// Directory name in the system temp directory used by `--keep-sandbox` without a path.
+pub const DEFAULT_SANDBOX_DIR: &str = "lppc-sandbox";
+
+/// Matches a `{token}` in the `--output-dir` template.
+static OUTPUT_DIR_TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{([^{}]*)\}").expect("valid regex"));
 
 #[derive(Debug)]
 pub struct Config {
@@ -9,9 +30,64 @@ pub struct Config {
     pub verbose: bool,
     pub working_dir: PathBuf,
     pub output_dir: Option<PathBuf>,
+    pub output_prefix: Option<String>,
+    pub output_suffix: Option<String>,
     pub output_format: OutputFormat,
+    pub sid_strategy: SidStrategy,
+    pub split_oversized: bool,
+    pub template: Option<String>,
+    pub template_extension: String,
     pub mappings_url: String,
+    pub mappings_ref: Option<String>,
     pub refresh_mappings: bool,
+    pub cache_ttl: Duration,
+    pub local_mappings: Option<PathBuf>,
+    pub offline: bool,
+    pub command: Option<Command>,
+    pub resolve_conflicts: Option<ConflictResolution>,
+    pub apply_deny: bool,
+    pub fail_on_empty_group: bool,
+    pub fail_on_missing_mappings: bool,
+    pub dedupe_identical_groups: bool,
+    pub collapse_wildcards: bool,
+    pub group_by_service: bool,
+    pub group_by_account: bool,
+    pub combine: Option<String>,
+    pub groups: Option<String>,
+    pub role_arn_canonicalize: bool,
+    pub role_name_groups: bool,
+    pub region: Option<String>,
+    pub max_attribute_depth: usize,
+    pub redact_account_ids: bool,
+    pub dump_missing: Option<PathBuf>,
+    pub explain_missing: bool,
+    pub explain_conditional: Option<String>,
+    pub compare_mappings_ref: Option<String>,
+    pub compact_stdout: bool,
+    pub emit_manifest: bool,
+    pub emit_sources: bool,
+    pub emit_provenance: bool,
+    pub emit_deny_guardrail: Option<PathBuf>,
+    pub sarif: Option<PathBuf>,
+    pub validate_cmd: Option<String>,
+    pub no_init: bool,
+    pub init_timeout: Option<Duration>,
+    pub forbidden_types: Vec<String>,
+    pub block_types: Vec<BlockType>,
+    pub expected_groups: Option<Vec<String>>,
+    pub action_allowlist: Option<Vec<String>>,
+    pub auto_deny_dangerous: bool,
+    pub dangerous_actions: Option<Vec<String>>,
+    pub validate_mappings_strict: bool,
+    pub best_effort: bool,
+    pub keep_sandbox: Option<PathBuf>,
+    pub timings: bool,
+    pub print_tree: bool,
+    pub report_managed_policies: bool,
+    pub report_unused: bool,
+    pub min_severity: Severity,
+    pub force: bool,
+    pub conditions: HashMap<String, serde_json::Value>,
 }
 
 impl Config {
@@ -48,17 +124,379 @@ impl Config {
             ))
         })?;
 
+        // Settings of the project configuration file apply unless given on the CLI
+        let file = ConfigFile::discover(&working_dir)?.unwrap_or_default();
+
+        let conditions = Self::parse_conditions(&cli.conditions)?;
+
+        let output_dir = cli
+            .output_dir
+            .or(file.output_dir)
+            .as_deref()
+            .map(|template| {
+                Self::expand_output_dir(template, cli.env.as_deref(), cli.region.as_deref())
+            })
+            .transpose()?;
+
+        let expected_groups = cli
+            .expected_groups
+            .as_deref()
+            .map(Self::read_expected_groups)
+            .transpose()?;
+
+        let action_allowlist = cli
+            .action_allowlist
+            .as_deref()
+            .map(Self::read_action_allowlist)
+            .transpose()?;
+
+        let template = cli
+            .template_file
+            .as_deref()
+            .map(Self::read_template)
+            .transpose()?;
+
+        let dangerous_actions = cli
+            .auto_deny_dangerous
+            .as_ref()
+            .and_then(Option::as_deref)
+            .map(Self::read_dangerous_actions)
+            .transpose()?;
+
+        let block_types = if cli.block_types.is_empty() {
+            BlockType::ALL.to_vec()
+        } else {
+            cli.block_types
+        };
+
+        let cache_ttl = Self::parse_cache_ttl(
+            cli.cache_ttl
+                .as_deref()
+                .or(file.cache_ttl.as_deref())
+                .unwrap_or(DEFAULT_CACHE_TTL),
+        )?;
+
+        // The working directory is copied into the sandbox, so it must not contain it
+        let keep_sandbox = match cli.keep_sandbox {
+            Some(Some(path)) => Some(Self::resolve_path(&path)?),
+            Some(None) => Some(std::env::temp_dir().join(DEFAULT_SANDBOX_DIR)),
+            None => None,
+        };
+        if let Some(sandbox) = &keep_sandbox
+            && sandbox.starts_with(&working_dir)
+        {
+            return Err(LppcError::Config(format!(
+                "--keep-sandbox path {} must not be inside the working directory",
+                sandbox.display()
+            )));
+        }
+
+        // Merged group names have no provider, so no role ARN to build a role from
+        let output_format = cli.output_format.or(file.output_format).unwrap_or_default();
+        if cli.dedupe_identical_groups && output_format == OutputFormat::TerraformRole {
+            return Err(LppcError::Config(
+                "--dedupe-identical-groups cannot be used with the terraform-role output format"
+                    .to_string(),
+            ));
+        }
+
         Ok(Self {
-            no_color: cli.no_color,
+            no_color: cli.no_color || file.no_color.unwrap_or(false),
             verbose: cli.verbose,
             working_dir,
-            output_dir: cli.output_dir,
-            output_format: cli.output_format,
-            mappings_url: cli.mappings_url,
+            output_dir,
+            output_prefix: cli.output_prefix,
+            output_suffix: cli.output_suffix,
+            output_format,
+            sid_strategy: cli.sid_strategy,
+            split_oversized: cli.split_oversized,
+            template,
+            template_extension: cli.template_extension,
+            mappings_url: cli
+                .mappings_url
+                .or(file.mappings_url)
+                .unwrap_or_else(|| DEFAULT_MAPPINGS_URL.to_string()),
+            mappings_ref: cli.mappings_ref,
             refresh_mappings: cli.refresh_mappings,
+            cache_ttl,
+            local_mappings: cli.local_mappings,
+            offline: cli.offline,
+            command: cli.command,
+            resolve_conflicts: cli.resolve_conflicts,
+            apply_deny: cli.apply_deny,
+            fail_on_empty_group: cli.fail_on_empty_group,
+            fail_on_missing_mappings: cli.fail_on_missing_mappings,
+            dedupe_identical_groups: cli.dedupe_identical_groups,
+            collapse_wildcards: cli.collapse_wildcards,
+            group_by_service: cli.group_by_service,
+            group_by_account: cli.group_by_account,
+            combine: cli.combine,
+            groups: cli.groups,
+            role_arn_canonicalize: cli.role_arn_canonicalize,
+            role_name_groups: cli.role_name_groups,
+            region: cli.region,
+            max_attribute_depth: cli.max_attribute_depth,
+            redact_account_ids: cli.redact_account_ids,
+            dump_missing: cli.dump_missing,
+            explain_missing: cli.explain_missing,
+            explain_conditional: cli.explain_conditional,
+            compare_mappings_ref: cli.compare_mappings_ref,
+            compact_stdout: cli.compact_stdout,
+            emit_manifest: cli.emit_manifest,
+            emit_sources: cli.emit_sources,
+            emit_provenance: cli.emit_provenance,
+            emit_deny_guardrail: cli.emit_deny_guardrail,
+            sarif: cli.sarif,
+            validate_cmd: cli.validate_cmd,
+            no_init: cli.no_init,
+            init_timeout: cli.init_timeout.map(Duration::from_secs),
+            forbidden_types: cli.forbidden_types,
+            block_types,
+            expected_groups,
+            action_allowlist,
+            auto_deny_dangerous: cli.auto_deny_dangerous.is_some(),
+            dangerous_actions,
+            validate_mappings_strict: cli.validate_mappings_strict,
+            best_effort: cli.best_effort,
+            keep_sandbox,
+            timings: cli.timings,
+            print_tree: cli.print_tree,
+            report_managed_policies: cli.report_managed_policies,
+            report_unused: cli.report_unused,
+            min_severity: cli.min_severity,
+            force: cli.force,
+            conditions,
         })
     }
 
+    /// Parses `--condition GROUP:JSON` values into a map of group name to condition.
+    ///
+    /// The JSON part must be an object (e.g., `{"StringEquals":{"aws:RequestedRegion":"eu-central-1"}}`).
+    /// Each group may only be given one condition.
+    pub fn parse_conditions(
+        values: &[String],
+    ) -> Result<HashMap<String, serde_json::Value>, LppcError> {
+        let mut conditions = HashMap::new();
+
+        for value in values {
+            let (group, json) = value.split_once(':').ok_or_else(|| {
+                LppcError::Config(format!(
+                    "Invalid condition '{}': expected GROUP:JSON",
+                    value
+                ))
+            })?;
+
+            let group = group.trim();
+            if group.is_empty() {
+                return Err(LppcError::Config(format!(
+                    "Invalid condition '{}': group name is empty",
+                    value
+                )));
+            }
+
+            let condition: serde_json::Value = serde_json::from_str(json).map_err(|e| {
+                LppcError::Config(format!(
+                    "Invalid condition JSON for group '{}': {}",
+                    group, e
+                ))
+            })?;
+
+            if !condition.is_object() {
+                return Err(LppcError::Config(format!(
+                    "Invalid condition JSON for group '{}': expected an object",
+                    group
+                )));
+            }
+
+            if conditions.insert(group.to_string(), condition).is_some() {
+                return Err(LppcError::Config(format!(
+                    "Condition for group '{}' given more than once",
+                    group
+                )));
+            }
+        }
+
+        Ok(conditions)
+    }
+
+    /// Expands the `{env}` (`--env`) and `{region}` (`--region`) tokens of the
+    /// `--output-dir` template, e.g. `policies/{env}` -> `policies/dev`.
+    ///
+    /// Token values must be a single path segment of letters, digits, `-`,
+    /// `_`, and `.` (not `..`), so they cannot escape the template's directory.
+    /// Unknown tokens and tokens whose option is not set are errors.
+    pub fn expand_output_dir(
+        template: &Path,
+        env: Option<&str>,
+        region: Option<&str>,
+    ) -> Result<PathBuf, LppcError> {
+        // Non-UTF-8 paths cannot contain tokens
+        let Some(text) = template.to_str() else {
+            return Ok(template.to_path_buf());
+        };
+
+        let mut expanded = String::new();
+        let mut last = 0;
+        for captures in OUTPUT_DIR_TOKEN.captures_iter(text) {
+            let token = captures.get(0).expect("whole match");
+            let (value, option) = match &captures[1] {
+                "env" => (env, "--env"),
+                "region" => (region, "--region"),
+                other => {
+                    return Err(LppcError::Config(format!(
+                        "Unknown token {{{}}} in --output-dir (supported: {{env}}, {{region}})",
+                        other
+                    )));
+                }
+            };
+            let value = value.ok_or_else(|| {
+                LppcError::Config(format!(
+                    "--output-dir contains {}, but {} is not set",
+                    token.as_str(),
+                    option
+                ))
+            })?;
+
+            let is_safe = !value.is_empty()
+                && value != "."
+                && !value.contains("..")
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            if !is_safe {
+                return Err(LppcError::Config(format!(
+                    "Invalid {} value '{}' for --output-dir: only letters, digits, '-', '_', and '.' are allowed",
+                    option, value
+                )));
+            }
+
+            expanded.push_str(&text[last..token.start()]);
+            expanded.push_str(value);
+            last = token.end();
+        }
+        expanded.push_str(&text[last..]);
+
+        Ok(PathBuf::from(expanded))
+    }
+
+    /// Reads the `--expected-groups` file: one group name per line.
+    ///
+    /// Surrounding whitespace is trimmed; empty lines and lines starting with
+    /// `#` are ignored. The names are returned sorted and deduplicated.
+    pub fn read_expected_groups(path: &Path) -> Result<Vec<String>, LppcError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            LppcError::Config(format!(
+                "Cannot read expected groups file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let mut groups: Vec<String> = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+        groups.sort();
+        groups.dedup();
+
+        Ok(groups)
+    }
+
+    /// Reads the `--action-allowlist` file.
+    ///
+    /// The file is either a JSON list of actions (if it starts with `[`) or
+    /// one action per line, where empty lines and lines starting with `#` are
+    /// ignored. Surrounding whitespace is trimmed.
+    pub fn read_action_allowlist(path: &Path) -> Result<Vec<String>, LppcError> {
+        Self::read_action_list(path, "action allowlist")
+    }
+
+    /// Parses a `--cache-ttl` duration: a number with the unit `s`, `m`, `h`,
+    /// or `d` (e.g. `30m`, `7d`), or `0`.
+    pub fn parse_cache_ttl(value: &str) -> Result<Duration, LppcError> {
+        let invalid = || {
+            LppcError::Config(format!(
+                "Invalid --cache-ttl '{}': expected a number with unit s, m, h, or d (e.g. 30m, 12h, 7d)",
+                value
+            ))
+        };
+
+        let value = value.trim();
+        if value == "0" {
+            return Ok(Duration::ZERO);
+        }
+
+        let split = value.len().saturating_sub(1);
+        let seconds_per_unit = match value.get(split..) {
+            Some("s") => 1,
+            Some("m") => 60,
+            Some("h") => 60 * 60,
+            Some("d") => 24 * 60 * 60,
+            _ => return Err(invalid()),
+        };
+        let count: u64 = value[..split]
+            .parse()
+            .ok()
+            .filter(|_| value[..split].bytes().all(|b| b.is_ascii_digit()))
+            .ok_or_else(invalid)?;
+
+        count
+            .checked_mul(seconds_per_unit)
+            .map(Duration::from_secs)
+            .ok_or_else(invalid)
+    }
+
+    /// Reads the `--template-file` template.
+    pub fn read_template(path: &Path) -> Result<String, LppcError> {
+        std::fs::read_to_string(path).map_err(|e| {
+            LppcError::Config(format!(
+                "Cannot read template file {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Reads the dangerous actions catalog of `--auto-deny-dangerous FILE`,
+    /// in the same formats as the action allowlist.
+    pub fn read_dangerous_actions(path: &Path) -> Result<Vec<String>, LppcError> {
+        Self::read_action_list(path, "dangerous actions")
+    }
+
+    /// Reads a list of actions, either a JSON list or one action per line.
+    fn read_action_list(path: &Path, kind: &str) -> Result<Vec<String>, LppcError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            LppcError::Config(format!(
+                "Cannot read {} file {}: {}",
+                kind,
+                path.display(),
+                e
+            ))
+        })?;
+
+        let entries: Vec<String> = if content.trim_start().starts_with('[') {
+            serde_json::from_str(&content).map_err(|e| {
+                LppcError::Config(format!(
+                    "Invalid JSON list in {} file {}: {}",
+                    kind,
+                    path.display(),
+                    e
+                ))
+            })?
+        } else {
+            content.lines().map(str::to_string).collect()
+        };
+
+        Ok(entries
+            .iter()
+            .map(|entry| entry.trim())
+            .filter(|entry| !entry.is_empty() && !entry.starts_with('#'))
+            .map(str::to_string)
+            .collect())
+    }
+
     /// Resolves a path to an absolute path.
     /// - Absolute paths are returned as-is
     /// - Relative paths are resolved relative to current directory
@@ -77,6 +515,75 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::ErrorFormat;
+
+    fn default_cli() -> Cli {
+        Cli {
+            no_color: false,
+            verbose: false,
+            error_format: ErrorFormat::Text,
+            working_dir: None,
+            output_dir: None,
+            env: None,
+            output_prefix: None,
+            output_suffix: None,
+            output_format: Some(OutputFormat::HclGrouped),
+            template_file: None,
+            template_extension: "txt".to_string(),
+            split_oversized: false,
+            sid_strategy: SidStrategy::None,
+            mappings_url: Some("https://example.com".to_string()),
+            mappings_ref: None,
+            refresh_mappings: false,
+            cache_ttl: None,
+            local_mappings: None,
+            offline: false,
+            command: None,
+            resolve_conflicts: None,
+            apply_deny: false,
+            fail_on_empty_group: false,
+            fail_on_missing_mappings: false,
+            dedupe_identical_groups: false,
+            collapse_wildcards: false,
+            group_by_service: false,
+            group_by_account: false,
+            combine: None,
+            groups: None,
+            role_arn_canonicalize: false,
+            role_name_groups: false,
+            region: None,
+            max_attribute_depth: 16,
+            redact_account_ids: false,
+            dump_missing: None,
+            explain_missing: false,
+            explain_conditional: None,
+            compare_mappings_ref: None,
+            compact_stdout: false,
+            emit_manifest: false,
+            emit_sources: false,
+            emit_provenance: false,
+            emit_deny_guardrail: None,
+            sarif: None,
+            validate_cmd: None,
+            no_init: false,
+            init_timeout: None,
+            forbidden_types: Vec::new(),
+            block_types: Vec::new(),
+            expected_groups: None,
+            action_allowlist: None,
+            auto_deny_dangerous: None,
+            validate_mappings_strict: false,
+            best_effort: false,
+            keep_sandbox: None,
+            timings: false,
+            print_tree: false,
+            report_managed_policies: false,
+            report_unused: false,
+            min_severity: Severity::Info,
+            force: false,
+            conditions: Vec::new(),
+        }
+    }
 
     #[test]
     fn from_cli_with_defaults() {
@@ -85,9 +592,10 @@ mod tests {
             verbose: false,
             working_dir: None,
             output_dir: None,
-            output_format: OutputFormat::HclGrouped,
-            mappings_url: "https://github.com/bebold-jhr/lppc-aws-test".to_string(),
+            output_format: Some(OutputFormat::HclGrouped),
+            mappings_url: Some("https://github.com/bebold-jhr/lppc-aws-test".to_string()),
             refresh_mappings: false,
+            ..default_cli()
         };
 
         let config = Config::from_cli(cli).expect("Config creation should succeed");
@@ -114,9 +622,10 @@ mod tests {
             verbose: true,
             working_dir: Some(temp_dir.clone()),
             output_dir: Some(temp_dir.clone()),
-            output_format: OutputFormat::Json,
-            mappings_url: "https://example.com/repo".to_string(),
+            output_format: Some(OutputFormat::Json),
+            mappings_url: Some("https://example.com/repo".to_string()),
             refresh_mappings: true,
+            ..default_cli()
         };
 
         let config = Config::from_cli(cli).expect("Config creation should succeed");
@@ -130,6 +639,57 @@ mod tests {
         assert!(config.refresh_mappings);
     }
 
+    #[test]
+    fn from_cli_expands_output_dir_template() {
+        let cli = Cli {
+            output_dir: Some(PathBuf::from("policies/{env}/{region}")),
+            env: Some("dev".to_string()),
+            region: Some("eu-central-1".to_string()),
+            ..default_cli()
+        };
+
+        let config = Config::from_cli(cli).unwrap();
+
+        assert_eq!(
+            config.output_dir,
+            Some(PathBuf::from("policies/dev/eu-central-1"))
+        );
+    }
+
+    #[test]
+    fn output_dir_without_tokens_is_unchanged() {
+        let expanded = Config::expand_output_dir(Path::new("policies"), Some("dev"), None).unwrap();
+
+        assert_eq!(expanded, PathBuf::from("policies"));
+    }
+
+    #[test]
+    fn output_dir_token_without_value_fails() {
+        let error = Config::expand_output_dir(Path::new("policies/{env}"), None, None)
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("--output-dir contains {env}, but --env is not set"));
+    }
+
+    #[test]
+    fn output_dir_unknown_token_fails() {
+        let error = Config::expand_output_dir(Path::new("policies/{stage}"), Some("dev"), None)
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("Unknown token {stage}"));
+    }
+
+    #[test]
+    fn output_dir_token_value_is_sanitized() {
+        for env in ["../prod", "dev/prod", "..", "", "dev prod"] {
+            let result = Config::expand_output_dir(Path::new("policies/{env}"), Some(env), None);
+
+            assert!(result.is_err(), "accepted env {:?}", env);
+        }
+    }
+
     #[test]
     fn from_cli_all_output_formats() {
         let formats = [
@@ -137,6 +697,10 @@ mod tests {
             OutputFormat::JsonGrouped,
             OutputFormat::Hcl,
             OutputFormat::HclGrouped,
+            OutputFormat::HclLocals,
+            OutputFormat::TerraformRole,
+            OutputFormat::PolicyDocument,
+            OutputFormat::Yaml,
         ];
 
         for format in formats {
@@ -145,9 +709,10 @@ mod tests {
                 verbose: false,
                 working_dir: None,
                 output_dir: None,
-                output_format: format,
-                mappings_url: "https://example.com".to_string(),
+                output_format: Some(format),
+                mappings_url: Some("https://example.com".to_string()),
                 refresh_mappings: false,
+                ..default_cli()
             };
 
             let config = Config::from_cli(cli).expect("Config creation should succeed");
@@ -155,6 +720,363 @@ mod tests {
         }
     }
 
+    #[test]
+    fn from_cli_passes_conflict_resolution() {
+        let cli = Cli {
+            resolve_conflicts: Some(ConflictResolution::DenyWins),
+            ..default_cli()
+        };
+
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert_eq!(config.resolve_conflicts, Some(ConflictResolution::DenyWins));
+    }
+
+    #[test]
+    fn from_cli_passes_dump_missing_dir() {
+        let cli = Cli {
+            dump_missing: Some(PathBuf::from("stubs")),
+            ..default_cli()
+        };
+
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert_eq!(config.dump_missing, Some(PathBuf::from("stubs")));
+    }
+
+    #[test]
+    fn from_cli_converts_init_timeout_to_duration() {
+        let cli = Cli {
+            init_timeout: Some(90),
+            ..default_cli()
+        };
+
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert_eq!(config.init_timeout, Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn from_cli_merges_config_file_with_cli_winning() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let stack = temp_dir.path().join("stacks/network");
+        std::fs::create_dir_all(&stack).unwrap();
+        std::fs::create_dir(temp_dir.path().join(".git")).unwrap();
+        std::fs::write(
+            temp_dir.path().join(".lppc.toml"),
+            "mappings_url = \"https://example.com/file\"\n\
+             output_format = \"json\"\n\
+             output_dir = \"policies\"\n\
+             no_color = true\n\
+             cache_ttl = \"1h\"\n",
+        )
+        .unwrap();
+
+        let from_file = Config::from_cli(Cli {
+            working_dir: Some(stack.clone()),
+            mappings_url: None,
+            output_format: None,
+            ..default_cli()
+        })
+        .unwrap();
+        assert_eq!(from_file.mappings_url, "https://example.com/file");
+        assert_eq!(from_file.output_format, OutputFormat::Json);
+        assert_eq!(
+            from_file.output_dir,
+            Some(temp_dir.path().canonicalize().unwrap().join("policies"))
+        );
+        assert!(from_file.no_color);
+        assert_eq!(from_file.cache_ttl, Duration::from_secs(60 * 60));
+
+        let from_cli = Config::from_cli(Cli {
+            working_dir: Some(stack),
+            mappings_url: Some("https://example.com/cli".to_string()),
+            output_format: Some(OutputFormat::Hcl),
+            output_dir: Some(PathBuf::from("out")),
+            cache_ttl: Some("0".to_string()),
+            ..default_cli()
+        })
+        .unwrap();
+        assert_eq!(from_cli.mappings_url, "https://example.com/cli");
+        assert_eq!(from_cli.output_format, OutputFormat::Hcl);
+        assert_eq!(from_cli.output_dir, Some(PathBuf::from("out")));
+        assert_eq!(from_cli.cache_ttl, Duration::ZERO);
+    }
+
+    #[test]
+    fn parse_cache_ttl_accepts_units_and_zero() {
+        assert_eq!(
+            Config::parse_cache_ttl("30m").unwrap(),
+            Duration::from_secs(30 * 60)
+        );
+        assert_eq!(
+            Config::parse_cache_ttl("12h").unwrap(),
+            Duration::from_secs(12 * 60 * 60)
+        );
+        assert_eq!(
+            Config::parse_cache_ttl("7d").unwrap(),
+            Duration::from_secs(7 * 24 * 60 * 60)
+        );
+        assert_eq!(Config::parse_cache_ttl("0").unwrap(), Duration::ZERO);
+        assert_eq!(
+            Config::from_cli(default_cli()).unwrap().cache_ttl,
+            Duration::from_secs(24 * 60 * 60)
+        );
+    }
+
+    #[test]
+    fn parse_cache_ttl_rejects_malformed_durations() {
+        for value in [
+            "",
+            "h",
+            "12",
+            "12x",
+            "1.5h",
+            "-1h",
+            "+1h",
+            "1 h",
+            "99999999999999999999d",
+        ] {
+            assert!(
+                matches!(Config::parse_cache_ttl(value), Err(LppcError::Config(_))),
+                "{} should be rejected",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn from_cli_resolves_keep_sandbox_path() {
+        let cli = Cli {
+            keep_sandbox: Some(None),
+            ..default_cli()
+        };
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert_eq!(
+            config.keep_sandbox,
+            Some(std::env::temp_dir().join(DEFAULT_SANDBOX_DIR))
+        );
+
+        let sandbox = std::env::temp_dir().join("lppc-custom-sandbox");
+        let cli = Cli {
+            keep_sandbox: Some(Some(sandbox.clone())),
+            ..default_cli()
+        };
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert_eq!(config.keep_sandbox, Some(sandbox));
+    }
+
+    #[test]
+    fn from_cli_rejects_keep_sandbox_inside_working_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let working_dir = temp_dir.path().canonicalize().unwrap();
+        let cli = Cli {
+            working_dir: Some(working_dir.clone()),
+            keep_sandbox: Some(Some(working_dir.join("sandbox"))),
+            ..default_cli()
+        };
+
+        let result = Config::from_cli(cli);
+
+        assert!(matches!(result, Err(LppcError::Config(_))));
+    }
+
+    #[test]
+    fn from_cli_rejects_dedupe_identical_groups_with_terraform_role() {
+        let cli = Cli {
+            dedupe_identical_groups: true,
+            output_format: Some(OutputFormat::TerraformRole),
+            ..default_cli()
+        };
+
+        let result = Config::from_cli(cli);
+
+        assert!(matches!(result, Err(LppcError::Config(_))));
+    }
+
+    #[test]
+    fn from_cli_parses_conditions() {
+        let cli = Cli {
+            conditions: vec![
+                r#"NetworkDeployer:{"StringEquals":{"aws:RequestedRegion":"eu-central-1"}}"#
+                    .to_string(),
+            ],
+            ..default_cli()
+        };
+
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert_eq!(config.conditions.len(), 1);
+        assert_eq!(
+            config.conditions["NetworkDeployer"]["StringEquals"]["aws:RequestedRegion"],
+            "eu-central-1"
+        );
+    }
+
+    #[test]
+    fn from_cli_reads_expected_groups() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("expected-groups.txt");
+        std::fs::write(
+            &path,
+            "# Reviewed deployer roles\nNetworkDeployer\n\n  DnsDeployer  \nNetworkDeployer\n",
+        )
+        .unwrap();
+
+        let cli = Cli {
+            expected_groups: Some(path),
+            ..default_cli()
+        };
+
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert_eq!(
+            config.expected_groups,
+            Some(vec![
+                "DnsDeployer".to_string(),
+                "NetworkDeployer".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn from_cli_missing_expected_groups_file_fails() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cli = Cli {
+            expected_groups: Some(temp_dir.path().join("missing.txt")),
+            ..default_cli()
+        };
+
+        let result = Config::from_cli(cli);
+
+        let error_message = result.unwrap_err().to_string();
+        assert!(error_message.contains("Cannot read expected groups file"));
+    }
+
+    #[test]
+    fn from_cli_reads_action_allowlist_lines() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("allowlist.txt");
+        std::fs::write(
+            &path,
+            "# Approved actions
+s3:CreateBucket
+
+  ec2:Describe*  
+",
+        )
+        .unwrap();
+
+        let cli = Cli {
+            action_allowlist: Some(path),
+            ..default_cli()
+        };
+
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert_eq!(
+            config.action_allowlist,
+            Some(vec![
+                "s3:CreateBucket".to_string(),
+                "ec2:Describe*".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn from_cli_reads_action_allowlist_json() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("allowlist.json");
+        std::fs::write(&path, r#"["s3:CreateBucket", "ec2:Describe*"]"#).unwrap();
+
+        let cli = Cli {
+            action_allowlist: Some(path),
+            ..default_cli()
+        };
+
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert_eq!(
+            config.action_allowlist,
+            Some(vec![
+                "s3:CreateBucket".to_string(),
+                "ec2:Describe*".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn from_cli_invalid_action_allowlist_json_fails() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("allowlist.json");
+        std::fs::write(&path, r#"["s3:CreateBucket", 42]"#).unwrap();
+
+        let cli = Cli {
+            action_allowlist: Some(path),
+            ..default_cli()
+        };
+
+        let error_message = Config::from_cli(cli).unwrap_err().to_string();
+        assert!(error_message.contains("Invalid JSON list in action allowlist file"));
+    }
+
+    #[test]
+    fn from_cli_auto_deny_dangerous_without_file_uses_bundled_catalog() {
+        let cli = Cli {
+            auto_deny_dangerous: Some(None),
+            ..default_cli()
+        };
+
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert!(config.auto_deny_dangerous);
+        assert_eq!(config.dangerous_actions, None);
+    }
+
+    #[test]
+    fn from_cli_auto_deny_dangerous_reads_catalog_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("dangerous.txt");
+        std::fs::write(&path, "# destructive\ns3:DeleteBucket\n").unwrap();
+
+        let cli = Cli {
+            auto_deny_dangerous: Some(Some(path)),
+            ..default_cli()
+        };
+
+        let config = Config::from_cli(cli).expect("Config creation should succeed");
+        assert!(config.auto_deny_dangerous);
+        assert_eq!(
+            config.dangerous_actions,
+            Some(vec!["s3:DeleteBucket".to_string()])
+        );
+    }
+
+    #[test]
+    fn parse_conditions_rejects_invalid_values() {
+        let cases = [
+            ("NetworkDeployer", "expected GROUP:JSON"),
+            (":{}", "group name is empty"),
+            ("NetworkDeployer:{invalid", "Invalid condition JSON"),
+            (r#"NetworkDeployer:["a"]"#, "expected an object"),
+        ];
+
+        for (value, expected) in cases {
+            let result = Config::parse_conditions(&[value.to_string()]);
+            let error_message = result.unwrap_err().to_string();
+            assert!(
+                error_message.contains(expected),
+                "'{}' should fail with '{}', got '{}'",
+                value,
+                expected,
+                error_message
+            );
+        }
+    }
+
+    #[test]
+    fn parse_conditions_rejects_duplicate_groups() {
+        let values = vec![
+            "NetworkDeployer:{}".to_string(),
+            "NetworkDeployer:{}".to_string(),
+        ];
+
+        let result = Config::parse_conditions(&values);
+        assert!(result.unwrap_err().to_string().contains("more than once"));
+    }
+
     #[test]
     fn from_cli_nonexistent_working_dir_fails() {
         let cli = Cli {
@@ -162,9 +1084,10 @@ mod tests {
             verbose: false,
             working_dir: Some(PathBuf::from("/nonexistent/path/that/does/not/exist")),
             output_dir: None,
-            output_format: OutputFormat::HclGrouped,
-            mappings_url: "https://example.com".to_string(),
+            output_format: Some(OutputFormat::HclGrouped),
+            mappings_url: Some("https://example.com".to_string()),
             refresh_mappings: false,
+            ..default_cli()
         };
 
         let result = Config::from_cli(cli);
@@ -184,9 +1107,10 @@ mod tests {
             verbose: false,
             working_dir: Some(file_path),
             output_dir: None,
-            output_format: OutputFormat::HclGrouped,
-            mappings_url: "https://example.com".to_string(),
+            output_format: Some(OutputFormat::HclGrouped),
+            mappings_url: Some("https://example.com".to_string()),
             refresh_mappings: false,
+            ..default_cli()
         };
 
         let result = Config::from_cli(cli);
@@ -0,0 +1,219 @@
+//! Project configuration file (`.lppc.toml`).
+//!
+//! A `.lppc.toml` in the working directory or one of its parents (up to the
+//! root of the git repository containing it) provides defaults for the most
+//! common flags, so a monorepo with many stacks does not repeat them on every
+//! invocation. `Config::from_cli` merges it with the CLI arguments, which
+//! take precedence:
+//!
+//! ```toml
+//! mappings_url = "https://github.com/example/lppc-aws-mappings"
+//! output_format = "json"
+//! output_dir = "policies/{env}"
+//! no_color = true
+//! cache_ttl = "12h"
+//! ```
+//!
+//! A relative `output_dir` is relative to the directory of the file. Unknown
+//! keys are reported with a warning and ignored.
+
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+
+use crate::cli::OutputFormat;
+use crate::error::LppcError;
+
+/// File name of the project configuration file.
+pub const CONFIG_FILE_NAME: &str = ".lppc.toml";
+
+/// Keys of the project configuration file, mirroring the CLI flags.
+const KNOWN_KEYS: [&str; 5] = [
+    "mappings_url",
+    "output_format",
+    "output_dir",
+    "no_color",
+    "cache_ttl",
+];
+
+/// Settings read from a `.lppc.toml` file; `None` for keys that are not set.
+#[derive(Debug, Default, PartialEq)]
+pub struct ConfigFile {
+    /// Path of the file the settings were read from
+    pub path: PathBuf,
+    pub mappings_url: Option<String>,
+    pub output_format: Option<OutputFormat>,
+    pub output_dir: Option<PathBuf>,
+    pub no_color: Option<bool>,
+    pub cache_ttl: Option<String>,
+}
+
+impl ConfigFile {
+    /// Finds and reads the `.lppc.toml` closest to `start`.
+    ///
+    /// Checks `start` and its parents, and stops after the first directory
+    /// containing `.git` (the repository root) or at the filesystem root.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LppcError::Config` if the file cannot be read or parsed.
+    pub fn discover(start: &Path) -> Result<Option<Self>, LppcError> {
+        for dir in start.ancestors() {
+            let path = dir.join(CONFIG_FILE_NAME);
+            if path.is_file() {
+                let content = std::fs::read_to_string(&path).map_err(|e| {
+                    LppcError::Config(format!("Cannot read {}: {}", path.display(), e))
+                })?;
+                log::info!("Using configuration file {}", path.display());
+                return Self::parse(&content, &path).map(Some);
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Parses the content of a `.lppc.toml` file located at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LppcError::Config` if the content is not valid TOML or a known
+    /// key has a value of the wrong type.
+    pub fn parse(content: &str, path: &Path) -> Result<Self, LppcError> {
+        let table: toml::Table = content
+            .parse()
+            .map_err(|e| LppcError::Config(format!("Invalid {}: {}", path.display(), e)))?;
+
+        let invalid = |key: &str, expected: &str| {
+            LppcError::Config(format!(
+                "Invalid {}: '{}' must be {}",
+                path.display(),
+                key,
+                expected
+            ))
+        };
+        let string = |key: &str| -> Result<Option<String>, LppcError> {
+            table
+                .get(key)
+                .map(|value| {
+                    value
+                        .as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| invalid(key, "a string"))
+                })
+                .transpose()
+        };
+
+        for key in table.keys() {
+            if !KNOWN_KEYS.contains(&key.as_str()) {
+                log::warn!(
+                    "{}: unknown key '{}' ignored (supported: {})",
+                    path.display(),
+                    key,
+                    KNOWN_KEYS.join(", ")
+                );
+            }
+        }
+
+        let output_format = string("output_format")?
+            .map(|format| {
+                OutputFormat::from_str(&format, false)
+                    .map_err(|_| invalid("output_format", "an output format, e.g. \"json\""))
+            })
+            .transpose()?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let output_dir = string("output_dir")?.map(|dir| base_dir.join(dir));
+        let no_color = table
+            .get("no_color")
+            .map(|value| {
+                value
+                    .as_bool()
+                    .ok_or_else(|| invalid("no_color", "a boolean"))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            mappings_url: string("mappings_url")?,
+            output_format,
+            output_dir,
+            no_color,
+            cache_ttl: string("cache_ttl")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn parse_reads_every_supported_key() {
+        let path = Path::new("/repo/.lppc.toml");
+        let config = ConfigFile::parse(
+            "mappings_url = \"https://example.com/mappings\"\n\
+             output_format = \"json-grouped\"\n\
+             output_dir = \"policies/{env}\"\n\
+             no_color = true\n\
+             cache_ttl = \"12h\"\n\
+             unknown = 1\n",
+            path,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config,
+            ConfigFile {
+                path: path.to_path_buf(),
+                mappings_url: Some("https://example.com/mappings".to_string()),
+                output_format: Some(OutputFormat::JsonGrouped),
+                output_dir: Some(PathBuf::from("/repo/policies/{env}")),
+                no_color: Some(true),
+                cache_ttl: Some("12h".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_rejects_invalid_toml_and_wrong_types() {
+        let path = Path::new(".lppc.toml");
+
+        for content in [
+            "mappings_url = ",
+            "no_color = \"yes\"",
+            "output_format = \"xml\"",
+            "cache_ttl = 12",
+        ] {
+            assert!(
+                matches!(ConfigFile::parse(content, path), Err(LppcError::Config(_))),
+                "{} should be rejected",
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn discover_finds_closest_file_up_to_repository_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        let stack = repo.join("stacks/network");
+        fs::create_dir_all(&stack).unwrap();
+        fs::create_dir(repo.join(".git")).unwrap();
+
+        // Above the repository root: never used
+        fs::write(temp_dir.path().join(CONFIG_FILE_NAME), "no_color = true\n").unwrap();
+        assert_eq!(ConfigFile::discover(&stack).unwrap(), None);
+
+        fs::write(repo.join(CONFIG_FILE_NAME), "cache_ttl = \"1h\"\n").unwrap();
+        let config = ConfigFile::discover(&stack).unwrap().unwrap();
+        assert_eq!(config.path, repo.join(CONFIG_FILE_NAME));
+        assert_eq!(config.cache_ttl.as_deref(), Some("1h"));
+
+        fs::write(stack.join(CONFIG_FILE_NAME), "cache_ttl = \"2h\"\n").unwrap();
+        let config = ConfigFile::discover(&stack).unwrap().unwrap();
+        assert_eq!(config.cache_ttl.as_deref(), Some("2h"));
+    }
+}
@@ -0,0 +1,165 @@
+//! Findings reported to the user at the end of a run.
+//!
+//! This module provides `Diagnostics`, which collects the findings of the
+//! pipeline stages (unmapped blocks, shadowed actions, dropped actions,
+//! skipped modules, ...) with a `Severity`, so they are reported uniformly
+//! and can be filtered with `--min-severity`.
+
+use std::fmt;
+
+/// Severity of a diagnostic, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Severity {
+    /// Informational, e.g. an action the user asked to remove was removed
+    #[default]
+    Info,
+    /// The result may not be what the user expects
+    Warn,
+    /// The result is known to be wrong or incomplete
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Error => "error",
+        };
+        f.write_str(label)
+    }
+}
+
+/// A single finding with its severity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Creates an informational diagnostic.
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Info,
+            message: message.into(),
+        }
+    }
+
+    /// Creates a warning diagnostic.
+    pub fn warn(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warn,
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error diagnostic.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+}
+
+/// Diagnostics collected during a run, in the order they were found.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    entries: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Creates an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a diagnostic.
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.entries.push(diagnostic);
+    }
+
+    /// Returns the diagnostics with at least the given severity, in order.
+    pub fn at_least(&self, min_severity: Severity) -> impl Iterator<Item = &Diagnostic> {
+        self.entries
+            .iter()
+            .filter(move |diagnostic| diagnostic.severity >= min_severity)
+    }
+
+    /// Logs the diagnostics with at least the given severity to stderr.
+    pub fn report(&self, min_severity: Severity) {
+        for diagnostic in self.at_least(min_severity) {
+            let level = match diagnostic.severity {
+                Severity::Info => log::Level::Info,
+                Severity::Warn => log::Level::Warn,
+                Severity::Error => log::Level::Error,
+            };
+            log::log!(level, "{}", diagnostic.message);
+        }
+    }
+}
+
+impl Extend<Diagnostic> for Diagnostics {
+    fn extend<T: IntoIterator<Item = Diagnostic>>(&mut self, iter: T) {
+        self.entries.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostics() -> Diagnostics {
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.push(Diagnostic::info("Denied 1 dangerous action(s)"));
+        diagnostics.push(Diagnostic::warn(
+            "1 blocks could not be mapped to a provider",
+        ));
+        diagnostics.push(Diagnostic::error("Result is PARTIAL"));
+        diagnostics
+    }
+
+    fn messages(diagnostics: &Diagnostics, min_severity: Severity) -> Vec<&str> {
+        diagnostics
+            .at_least(min_severity)
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn diagnostics_below_threshold_are_suppressed() {
+        let diagnostics = diagnostics();
+
+        assert_eq!(
+            messages(&diagnostics, Severity::Warn),
+            vec![
+                "1 blocks could not be mapped to a provider",
+                "Result is PARTIAL"
+            ]
+        );
+        assert_eq!(
+            messages(&diagnostics, Severity::Error),
+            vec!["Result is PARTIAL"]
+        );
+    }
+
+    #[test]
+    fn info_threshold_shows_all_diagnostics_in_order() {
+        assert_eq!(
+            messages(&diagnostics(), Severity::Info),
+            vec![
+                "Denied 1 dangerous action(s)",
+                "1 blocks could not be mapped to a provider",
+                "Result is PARTIAL"
+            ]
+        );
+    }
+
+    #[test]
+    fn severities_are_ordered() {
+        assert!(Severity::Info < Severity::Warn);
+        assert!(Severity::Warn < Severity::Error);
+        assert_eq!(Severity::Warn.to_string(), "warn");
+    }
+}
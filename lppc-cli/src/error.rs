@@ -1,7 +1,13 @@
+use std::error::Error as StdError;
+
 use thiserror::Error;
 
 use crate::mapping::MappingError;
-use crate::terraform::TerraformError;
+use crate::mapping::lint::LintError;
+use crate::mapping::loader::LoadError;
+use crate::mapping::matcher::MatchError;
+use crate::mapping::repository::GitError;
+use crate::terraform::{HclParseError, TerraformError};
 
 #[derive(Error, Debug)]
 pub enum LppcError {
@@ -19,3 +25,201 @@ pub enum LppcError {
 }
 
 pub type Result<T> = std::result::Result<T, LppcError>;
+
+/// Exit code of a failed run, so scripts can tell the causes apart.
+///
+/// `2` is not used: clap exits with it on invalid arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Any failure without a more specific code
+    Failure = 1,
+    /// The `terraform` binary is not installed or not in `PATH`
+    TerraformNotFound = 3,
+    /// The mapping repository could not be cloned or updated
+    Network = 4,
+    /// `--fail-on-empty-group` found groups that resolved no permissions
+    EmptyGroups = 5,
+    /// A Terraform file or a mapping file could not be parsed, or
+    /// `validate-mappings` found invalid mapping files
+    Parse = 6,
+    /// `--fail-on-missing-mappings` found Terraform types without a mapping
+    IncompleteCoverage = 7,
+    /// `diff` found policy changes against the previously written policies
+    PolicyChanges = 8,
+}
+
+impl ExitCode {
+    /// Returns the exit code of an error, determined by the first error in its
+    /// source chain that has a specific code.
+    pub fn classify(error: &(dyn StdError + 'static)) -> Self {
+        std::iter::successors(Some(error), |&error| error.source())
+            .find_map(Self::of)
+            .unwrap_or(Self::Failure)
+    }
+
+    /// Returns the numeric exit code.
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the specific exit code of a single error, if it has one.
+    fn of(error: &(dyn StdError + 'static)) -> Option<Self> {
+        if let Some(error) = error.downcast_ref::<TerraformError>() {
+            return match error {
+                TerraformError::NotFound => Some(Self::TerraformNotFound),
+                TerraformError::ParseFailed(_) => Some(Self::Parse),
+                _ => None,
+            };
+        }
+        if let Some(error) = error.downcast_ref::<MappingError>() {
+            // NotAvailable carries the clone error as a message only; NotCached
+            // (--offline without a cache) is no network failure
+            return matches!(error, MappingError::NotAvailable(_)).then_some(Self::Network);
+        }
+        if error.is::<GitError>() {
+            return Some(Self::Network);
+        }
+        match error.downcast_ref::<MatchError>() {
+            Some(MatchError::EmptyGroups(_)) => return Some(Self::EmptyGroups),
+            Some(MatchError::MissingMappings(_)) => return Some(Self::IncompleteCoverage),
+            Some(MatchError::PolicyChanges(..)) => return Some(Self::PolicyChanges),
+            _ => {}
+        }
+        if matches!(
+            error.downcast_ref::<LoadError>(),
+            Some(LoadError::Parse(..))
+        ) || error.is::<HclParseError>()
+            || error.is::<crate::mapping::yaml_parser::ParseError>()
+            || error.is::<LintError>()
+        {
+            return Some(Self::Parse);
+        }
+        None
+    }
+}
+
+/// Renders an error for `--error-format json`.
+///
+/// The result is a single line `{"error": "...", "context": [...]}` with the
+/// message of `error` and the messages of its causes, outermost first.
+pub fn json_report(error: &anyhow::Error) -> String {
+    let context: Vec<String> = error.chain().skip(1).map(ToString::to_string).collect();
+    serde_json::json!({
+        "error": error.to_string(),
+        "context": context,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn classify(error: impl StdError + 'static) -> ExitCode {
+        ExitCode::classify(&error)
+    }
+
+    #[test]
+    fn errors_map_to_their_exit_code() {
+        assert_eq!(
+            classify(TerraformError::NotFound),
+            ExitCode::TerraformNotFound
+        );
+        assert_eq!(
+            classify(MappingError::NotAvailable("clone failed".to_string())),
+            ExitCode::Network
+        );
+        assert_eq!(
+            classify(MappingError::NotCached("--offline".to_string())),
+            ExitCode::Failure
+        );
+        assert_eq!(
+            classify(MatchError::EmptyGroups("DnsDeployer".to_string())),
+            ExitCode::EmptyGroups
+        );
+        assert_eq!(
+            classify(MatchError::MissingMappings(
+                "resource.aws_s3_bucket".to_string()
+            )),
+            ExitCode::IncompleteCoverage
+        );
+        assert_eq!(
+            classify(MatchError::PolicyChanges(
+                "policies".to_string(),
+                "DnsDeployer".to_string()
+            )),
+            ExitCode::PolicyChanges
+        );
+        assert_eq!(
+            classify(TerraformError::ParseFailed("main.tf".to_string())),
+            ExitCode::Parse
+        );
+        assert_eq!(classify(LintError::InvalidFiles(1, 3)), ExitCode::Parse);
+        assert_eq!(
+            classify(LppcError::Config("bad".to_string())),
+            ExitCode::Failure
+        );
+    }
+
+    #[test]
+    fn nested_errors_are_classified_by_their_source() {
+        assert_eq!(
+            classify(LppcError::Mapping(MappingError::Git(
+                GitError::NetworkUnreachable
+            ))),
+            ExitCode::Network
+        );
+        assert_eq!(
+            classify(MatchError::Load(LoadError::Parse(
+                PathBuf::from("mappings/resource/aws_s3_bucket.yaml"),
+                "invalid YAML".to_string()
+            ))),
+            ExitCode::Parse
+        );
+        assert_eq!(
+            classify(LppcError::Terraform(TerraformError::InitFailed(
+                String::new()
+            ))),
+            ExitCode::Failure
+        );
+    }
+
+    #[test]
+    fn json_report_lists_the_causes_as_context() {
+        let error = anyhow::Error::from(MatchError::Load(LoadError::Parse(
+            PathBuf::from("aws_s3_bucket.yaml"),
+            "invalid YAML".to_string(),
+        )))
+        .context("Failed to resolve permissions");
+
+        let report: serde_json::Value = serde_json::from_str(&json_report(&error)).unwrap();
+
+        assert_eq!(report["error"], "Failed to resolve permissions");
+        let context = report["context"].as_array().unwrap();
+        assert_eq!(context.len(), 2);
+        assert!(context[0].as_str().unwrap().starts_with("Load error:"));
+        assert!(context[1].as_str().unwrap().contains("invalid YAML"));
+    }
+
+    #[test]
+    fn json_report_of_an_error_without_causes_has_empty_context() {
+        let error = anyhow::Error::from(LppcError::Config("bad".to_string()));
+
+        assert_eq!(
+            json_report(&error),
+            r#"{"context":[],"error":"Configuration error: bad"}"#
+        );
+    }
+
+    #[test]
+    fn exit_codes_are_stable() {
+        assert_eq!(ExitCode::Failure.code(), 1);
+        assert_eq!(ExitCode::TerraformNotFound.code(), 3);
+        assert_eq!(ExitCode::Network.code(), 4);
+        assert_eq!(ExitCode::EmptyGroups.code(), 5);
+        assert_eq!(ExitCode::Parse.code(), 6);
+        assert_eq!(ExitCode::IncompleteCoverage.code(), 7);
+        assert_eq!(ExitCode::PolicyChanges.code(), 8);
+    }
+}
@@ -1,7 +1,14 @@
 pub mod cli;
 pub mod config;
+pub mod config_file;
+pub mod diagnostics;
 pub mod error;
 pub mod logging;
 pub mod mapping;
 pub mod output;
+pub mod redact;
 pub mod terraform;
+pub mod timings;
+
+#[cfg(test)]
+mod test_support;
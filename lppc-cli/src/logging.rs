@@ -1,12 +1,20 @@
-use colored::control::set_override;
+use colored::control::{SHOULD_COLORIZE, set_override};
 use env_logger::Builder;
 use log::LevelFilter;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-pub fn init_logging(verbose: bool, no_color: bool) {
-    // Disable colors globally if requested
-    if no_color {
-        set_override(false);
-    }
+use crate::redact::redact_account_ids;
+
+/// Whether account IDs are redacted from log messages
+static REDACT: AtomicBool = AtomicBool::new(false);
+
+/// Initializes the logger from the command line flags.
+///
+/// The logger is needed while the configuration file is read, so the merged
+/// settings are applied afterwards with `apply_logging_settings`.
+pub fn init_logging(verbose: bool, no_color: bool, redact: bool) {
+    apply_logging_settings(no_color, redact);
 
     let level = if verbose {
         LevelFilter::Debug
@@ -14,10 +22,43 @@ pub fn init_logging(verbose: bool, no_color: bool) {
         LevelFilter::Info
     };
 
-    Builder::new()
-        .filter_level(level)
-        .format_timestamp(None)
-        .init();
+    let mut builder = Builder::new();
+    builder.filter_level(level).format_timestamp(None);
+
+    // Same layout as the default format, with colors and redaction looked up
+    // per record, since both may change once the configuration is loaded
+    builder.format(|buf, record| {
+        let style = if SHOULD_COLORIZE.should_colorize() {
+            buf.default_level_style(record.level())
+        } else {
+            Default::default()
+        };
+        let message = record.args().to_string();
+        let message = if REDACT.load(Ordering::Relaxed) {
+            redact_account_ids(&message)
+        } else {
+            message
+        };
+        writeln!(
+            buf,
+            "[{style}{:<5}{style:#} {}] {}",
+            record.level(),
+            record.target(),
+            message
+        )
+    });
+
+    builder.init();
+}
+
+/// Applies the color and redaction settings of the merged configuration
+/// (command line and `.lppc.toml`) to the logger.
+pub fn apply_logging_settings(no_color: bool, redact: bool) {
+    // Disable colors globally if requested
+    if no_color {
+        set_override(false);
+    }
+    REDACT.store(redact, Ordering::Relaxed);
 }
 
 #[cfg(test)]
@@ -46,4 +87,13 @@ mod tests {
         };
         assert_eq!(level, LevelFilter::Info);
     }
+
+    #[test]
+    fn apply_logging_settings_switches_redaction() {
+        apply_logging_settings(false, true);
+        assert!(REDACT.load(Ordering::Relaxed));
+
+        apply_logging_settings(false, false);
+        assert!(!REDACT.load(Ordering::Relaxed));
+    }
 }
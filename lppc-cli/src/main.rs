@@ -1,35 +1,127 @@
 use clap::Parser;
 use lppc::{
-    cli::Cli,
+    cli::{Cli, Command, ErrorFormat},
     config::Config,
-    logging::init_logging,
-    mapping::{MappingLoader, MappingRepository, PermissionMatcher},
-    output::OutputWriter,
-    terraform::PlanExecutor,
+    diagnostics::{Diagnostic, Diagnostics, Severity},
+    error::{ExitCode, json_report},
+    logging::{apply_logging_settings, init_logging},
+    mapping::{
+        ActionAllowlist, DangerousActions, MappingDelta, MappingLint, MappingLoader,
+        MappingRepository, MappingStats, PermissionMatcher, PermissionResult, PolicyDiff,
+        reconcile::apply_deny,
+        service_prefixes::check_service_prefixes,
+        wildcards::{check_full_wildcards, wildcard_diagnostics},
+    },
+    output::{
+        OutputWriter, Provenance, TemplateFormatter, read_policies, run_validation,
+        write_deny_guardrail, write_manifest, write_mapping_stubs, write_provenance, write_sarif,
+        write_sources,
+    },
+    terraform::{
+        BlockType, CloudProvider, ParseOptions, PlanExecutor, TerraformBlock, TerraformConfig,
+    },
+    timings::Timings,
 };
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
 
-fn main() -> anyhow::Result<()> {
+fn main() -> std::process::ExitCode {
     let cli = Cli::parse();
 
-    init_logging(cli.verbose, cli.no_color);
+    init_logging(cli.verbose, cli.no_color, cli.redact_account_ids);
+    let error_format = cli.error_format;
 
-    let config = Config::from_cli(cli)?;
+    let result = Config::from_cli(cli)
+        .map_err(anyhow::Error::from)
+        .and_then(|config| {
+            // `.lppc.toml` may disable colors as well
+            apply_logging_settings(config.no_color, config.redact_account_ids);
+            run_with_timings(config)
+        });
 
+    match result {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(error) => {
+            match error_format {
+                ErrorFormat::Text => eprintln!("Error: {:?}", error),
+                ErrorFormat::Json => eprintln!("{}", json_report(&error)),
+            }
+            std::process::ExitCode::from(ExitCode::classify(error.as_ref()).code())
+        }
+    }
+}
+
+/// Runs lppc and prints the stage timings afterwards if `--timings` is set.
+fn run_with_timings(config: Config) -> anyhow::Result<()> {
     log::debug!("Configuration: {:?}", config);
 
+    let show_timings = config.timings;
+    let mut timings = Timings::new();
+
+    let result = run(config, &mut timings);
+
+    // Printed even if the run failed, to show which stage was slow
+    if show_timings {
+        eprint!("{}", timings.report());
+    }
+
+    result
+}
+
+fn run(config: Config, timings: &mut Timings) -> anyhow::Result<()> {
+    // Compiled before the slow stages, so template errors are reported immediately
+    let template = config
+        .template
+        .as_deref()
+        .map(|source| TemplateFormatter::new(source, &config.template_extension))
+        .transpose()?;
+
     // Ensure mapping repository is available
-    let mapping_repo =
-        MappingRepository::ensure_available(&config.mappings_url, config.refresh_mappings)?;
+    let mapping_repo = timings.time("mappings", || match &config.local_mappings {
+        Some(dir) => MappingRepository::from_local_dir(dir),
+        None if config.offline => {
+            MappingRepository::from_cache(&config.mappings_url, config.mappings_ref.as_deref())
+        }
+        None => MappingRepository::ensure_available(
+            &config.mappings_url,
+            config.mappings_ref.as_deref(),
+            config.refresh_mappings,
+            config.cache_ttl,
+        ),
+    })?;
 
     log::debug!("Mapping repository path: {:?}", mapping_repo.local_path);
     if mapping_repo.was_refreshed {
         log::debug!("Mapping repository was refreshed in this run");
     }
 
+    if config.command == Some(Command::ValidateMappings) {
+        let lint = MappingLint::collect(&mapping_repo.local_path)?;
+        print!("{}", lint.render());
+        lint.check()?;
+        return Ok(());
+    }
+
+    if config.command == Some(Command::MappingStats) {
+        let loader = MappingLoader::new(mapping_repo.local_path.clone());
+        print!("{}", MappingStats::collect(&loader)?.render());
+        return Ok(());
+    }
+
     // Execute terraform init and parse HCL files directly
     // No AWS credentials or backend configuration required!
-    let executor = PlanExecutor::new()?;
-    let terraform_config = match executor.execute(&config.working_dir)? {
+    let executor = PlanExecutor::new()?
+        .with_parse_options(ParseOptions {
+            canonicalize_role_arns: config.role_arn_canonicalize,
+            best_effort: config.best_effort,
+            role_name_groups: config.role_name_groups,
+            region: config.region.clone(),
+            max_attribute_depth: config.max_attribute_depth,
+        })
+        .with_no_init(config.no_init)
+        .with_init_timeout(config.init_timeout)
+        .with_keep_sandbox(config.keep_sandbox.clone());
+    let mut terraform_config = match executor.execute_timed(&config.working_dir, timings)? {
         Some(config) => config,
         None => {
             log::info!("No Terraform files found, nothing to analyze");
@@ -37,6 +129,45 @@ fn main() -> anyhow::Result<()> {
         }
     };
 
+    // The diagnostics are reported even if the analysis fails, since a run
+    // that fails a gate (e.g., `--fail-on-missing-mappings`) needs them most
+    let min_severity = config.min_severity;
+    let mut diagnostics = Diagnostics::new();
+    let outcome = analyze(
+        config,
+        &mut terraform_config,
+        &mapping_repo,
+        template,
+        timings,
+        &mut diagnostics,
+    );
+    diagnostics.extend(skipped_modules_diagnostic(
+        &terraform_config.skipped_modules,
+    ));
+    diagnostics.extend(unresolved_modules_diagnostic(
+        &terraform_config.unresolved_modules,
+    ));
+    diagnostics.report(min_severity);
+
+    outcome
+}
+
+/// Resolves, checks, and writes the permissions of a parsed configuration,
+/// collecting diagnostics along the way.
+fn analyze(
+    config: Config,
+    terraform_config: &mut TerraformConfig,
+    mapping_repo: &MappingRepository,
+    template: Option<TemplateFormatter>,
+    timings: &mut Timings,
+    diagnostics: &mut Diagnostics,
+) -> anyhow::Result<()> {
+    // --group-by-account needs the real account IDs, so take the ARNs before redacting them
+    let account_role_arns = terraform_config.role_arns();
+    if config.redact_account_ids {
+        terraform_config.redact_account_ids();
+    }
+
     log::debug!(
         "Parsed {} provider groups from HCL files",
         terraform_config.provider_groups.len()
@@ -46,34 +177,371 @@ fn main() -> anyhow::Result<()> {
         log::debug!("  {}: {} blocks", name, group.blocks.len());
     }
 
-    if !terraform_config.unmapped_blocks.is_empty() {
-        log::warn!(
-            "{} blocks could not be mapped to a provider",
-            terraform_config.unmapped_blocks.len()
-        );
-        for block in &terraform_config.unmapped_blocks {
-            log::warn!("  Unmapped: {}", block.address);
-        }
+    if config.print_tree
+        && let Some(tree) = &terraform_config.module_tree
+    {
+        eprint!("{}", tree.render());
+    }
+
+    diagnostics.extend(version_constraints_diagnostic(
+        &terraform_config.provider_version_constraints,
+    ));
+
+    // Blocks of unsupported providers (e.g., random_id) are expected, the
+    // others point at a provider lppc failed to resolve
+    let (supported, unsupported): (Vec<&TerraformBlock>, Vec<&TerraformBlock>) = terraform_config
+        .unmapped_blocks
+        .iter()
+        .partition(|block| CloudProvider::for_type(&block.type_name).is_some());
+    let addresses = |blocks: &[&TerraformBlock]| -> String {
+        let addresses: Vec<String> = blocks.iter().map(|block| block.display_address()).collect();
+        addresses.join(", ")
+    };
+    if !supported.is_empty() {
+        diagnostics.push(Diagnostic::warn(format!(
+            "{} blocks could not be mapped to a provider: {}",
+            supported.len(),
+            addresses(&supported)
+        )));
     }
+    if !unsupported.is_empty() {
+        diagnostics.push(Diagnostic::info(format!(
+            "{} blocks of unsupported providers are not analyzed: {}",
+            unsupported.len(),
+            addresses(&unsupported)
+        )));
+    }
+
+    // Guardrail: refuse to generate policies for forbidden resource types
+    terraform_config.check_forbidden_types(&config.forbidden_types)?;
 
     if terraform_config.provider_groups.is_empty() {
         log::info!("No AWS resources found to analyze");
+        if let Some(expected) = &config.expected_groups {
+            PermissionResult::default().check_expected_groups(expected)?;
+        }
         return Ok(());
     }
 
     // Resolve permissions
-    let loader = MappingLoader::new(mapping_repo.local_path);
-    let matcher = PermissionMatcher::new(&loader);
-    let result = matcher.resolve(&terraform_config)?;
+    let provenance = config
+        .emit_provenance
+        .then(|| Provenance::new(&mapping_repo.url, mapping_repo.commit_sha()));
+    let loader = MappingLoader::new(mapping_repo.local_path.clone());
+    let matcher = PermissionMatcher::new(&loader).with_block_types(&config.block_types);
+    let mut unused_types = Vec::new();
+    let result = timings.time("resolve", || -> anyhow::Result<_> {
+        let mut result = matcher.resolve(terraform_config)?;
+
+        // Taken right after resolving, before other steps look up mappings
+        if config.report_unused {
+            unused_types = loader.unused_types()?;
+        }
+
+        // Least privilege: flag mappings that allow every action (`*`, `*:*`)
+        diagnostics.extend(wildcard_diagnostics(&loader));
+
+        if config.validate_mappings_strict {
+            check_full_wildcards(&loader)?;
+            check_service_prefixes(&loader)?;
+        }
+
+        if config.explain_missing {
+            result.suggest_missing(&loader)?;
+        }
+
+        // Show the effective allow set: deny wins, also over wildcard overlaps
+        if config.apply_deny {
+            diagnostics.extend(apply_deny(&mut result));
+        }
+
+        // Report (and optionally resolve) actions that are both allowed and denied
+        diagnostics.extend(result.resolve_conflicts(config.resolve_conflicts));
+
+        // Governance: drop actions that are not in the approved catalog
+        if let Some(allowlist) = &config.action_allowlist {
+            let dropped = ActionAllowlist::new(allowlist.clone()).apply(&mut result);
+            if !dropped.is_empty() {
+                diagnostics.push(Diagnostic::warn(format!(
+                    "Dropped {} action(s) not on the action allowlist: {}",
+                    dropped.len(),
+                    dropped.join(", ")
+                )));
+            }
+        }
+
+        // Least privilege: deny dangerous actions that only a wildcard allow grants
+        if config.auto_deny_dangerous {
+            let catalog = config
+                .dangerous_actions
+                .clone()
+                .map_or_else(DangerousActions::bundled, DangerousActions::new);
+            let added = catalog.apply(&mut result);
+            if !added.is_empty() {
+                diagnostics.push(Diagnostic::info(format!(
+                    "Denied {} dangerous action(s) granted only by a wildcard: {}",
+                    added.len(),
+                    added.join(", ")
+                )));
+            }
+        }
+
+        // After the dangerous action check, which looks for actions granted only by a wildcard
+        if config.collapse_wildcards {
+            let collapsed = result.collapse_wildcards();
+            if !collapsed.is_empty() {
+                diagnostics.push(Diagnostic::info(format!(
+                    "Removed {} action(s) already granted by a wildcard: {}",
+                    collapsed.len(),
+                    collapsed.join(", ")
+                )));
+            }
+        }
+
+        if config.dedupe_identical_groups {
+            result.dedupe_identical_groups();
+        }
+
+        // Refuse to continue if a group with resources resolved no permissions
+        if config.fail_on_empty_group {
+            result.check_empty_groups()?;
+        }
+
+        // Governance: refuse to continue if the set of groups changed unexpectedly
+        if let Some(expected) = &config.expected_groups {
+            result.check_expected_groups(expected)?;
+        }
+
+        if config.group_by_service {
+            result.regroup_by_service();
+        }
+
+        if config.group_by_account {
+            result.regroup_by_account(&account_role_arns);
+        }
+
+        if let Some(name) = &config.combine {
+            result = result.combined(name);
+        }
+
+        // Filter by the final output names, after regrouping and combining
+        if let Some(pattern) = &config.groups {
+            let dropped = result.retain_groups(pattern);
+            if result.groups.is_empty() && !dropped.is_empty() {
+                diagnostics.push(Diagnostic::warn(format!(
+                    "No group matches --groups '{}', nothing is written (groups: {})",
+                    pattern,
+                    dropped.join(", ")
+                )));
+            }
+        }
+
+        Ok(result)
+    })?;
+
+    if let Some(address) = &config.explain_conditional {
+        match matcher.explain_conditionals(terraform_config, address)? {
+            Some(explanation) => eprint!("{}", explanation.render()),
+            None => diagnostics.push(Diagnostic::warn(format!(
+                "No block with address {} found to explain",
+                address
+            ))),
+        }
+    }
+
+    // Resolve the unchanged configuration against another mapping version
+    if let Some(reference) = &config.compare_mappings_ref {
+        let checkout = mapping_repo.checkout_ref(reference)?;
+        let compared_loader = MappingLoader::new(checkout.path().to_path_buf());
+        let compared = PermissionMatcher::new(&compared_loader)
+            .with_block_types(&config.block_types)
+            .resolve(terraform_config)?;
+        let current = matcher.resolve(terraform_config)?;
+        eprint!(
+            "{}",
+            MappingDelta::compute(reference, &current, &compared).render()
+        );
+    }
+
+    // Compare with the policies written by an earlier run instead of writing
+    if let Some(Command::Diff { against }) = &config.command {
+        let previous = read_policies(against)?;
+        let diff = PolicyDiff::compute(against, &previous, &result);
+        print!("{}", diff.render());
+        diff.check()?;
+        return Ok(());
+    }
 
     // Generate output
-    let writer = OutputWriter::new(config.output_format, config.output_dir, config.no_color);
+    let writer = OutputWriter::new(
+        config.output_format,
+        config.output_dir.clone(),
+        config.no_color,
+    )
+    .with_conditions(config.conditions)
+    .with_filename_affixes(
+        config.output_prefix.as_deref().unwrap_or(""),
+        config.output_suffix.as_deref().unwrap_or(""),
+    )
+    .with_compact_stdout(config.compact_stdout)
+    .with_sid_strategy(config.sid_strategy)
+    .with_template(template)
+    .with_split_oversized(config.split_oversized)
+    .with_overwrite_confirmation(
+        !config.force && io::stdin().is_terminal() && io::stderr().is_terminal(),
+    )
+    .with_role_arns(terraform_config.role_arns());
+
+    let written_files = timings.time("write", || -> anyhow::Result<_> {
+        // Write missing mappings warning to stderr
+        if config.min_severity <= Severity::Warn {
+            writer.write_missing_mappings(&result);
+        }
 
-    // Write missing mappings warning to stderr
-    writer.write_missing_mappings(&result);
+        if let Some(dump_dir) = &config.dump_missing {
+            let stubs = write_mapping_stubs(&result.missing_mappings, dump_dir)?;
+            log::info!(
+                "Wrote {} stub mapping files to {}",
+                stubs.len(),
+                dump_dir.display()
+            );
+        }
+
+        // Write formatted permissions, unless --groups matched nothing
+        let written_files = if config.groups.is_some() && result.groups.is_empty() {
+            Vec::new()
+        } else {
+            writer.write(&result)?
+        };
+
+        // --emit-manifest requires --output-dir (enforced by clap)
+        if config.emit_manifest
+            && let Some(output_dir) = &config.output_dir
+        {
+            write_manifest(output_dir, &result, terraform_config)?;
+        }
 
-    // Write formatted permissions
-    writer.write(&result)?;
+        // --emit-sources requires --output-dir (enforced by clap)
+        if config.emit_sources
+            && let Some(output_dir) = &config.output_dir
+        {
+            write_sources(output_dir, &result)?;
+        }
+
+        // --emit-provenance requires --output-dir (enforced by clap)
+        if let Some(provenance) = &provenance
+            && let Some(output_dir) = &config.output_dir
+        {
+            write_provenance(output_dir, &result, provenance)?;
+        }
+
+        if let Some(guardrail_path) = &config.emit_deny_guardrail {
+            write_deny_guardrail(guardrail_path, &result)?;
+        }
+
+        if let Some(sarif_path) = &config.sarif {
+            write_sarif(sarif_path, &result)?;
+        }
+
+        Ok(written_files)
+    })?;
+
+    // --validate-cmd requires --output-dir (enforced by clap)
+    if let Some(template) = &config.validate_cmd {
+        timings.time("validate", || run_validation(template, &written_files))?;
+    }
+
+    // Checked after writing, so the output and the missing mappings warning are still produced
+    if config.fail_on_missing_mappings {
+        result.check_missing_mappings()?;
+    }
+
+    if config.report_managed_policies {
+        report_managed_policies(terraform_config);
+    }
+    if config.report_unused {
+        report_unused_mappings(&unused_types);
+    }
 
     Ok(())
 }
+
+/// Lists the AWS managed policies attached by the configuration (`--report-managed-policies`).
+///
+/// Their contents are not bundled, so only the ARNs and the attaching blocks are shown.
+fn report_managed_policies(terraform_config: &TerraformConfig) {
+    let attachments = terraform_config.managed_policy_attachments();
+    if attachments.is_empty() {
+        log::info!("No AWS managed policies are attached");
+        return;
+    }
+
+    log::info!(
+        "{} AWS managed policy(ies) are attached (contents not analyzed):",
+        attachments.len()
+    );
+    for (arn, addresses) in attachments {
+        log::info!("  {} ({})", arn, addresses.join(", "));
+    }
+}
+
+/// Lists the mapping files no block of the configuration used (`--report-unused`).
+fn report_unused_mappings(unused_types: &[(BlockType, String)]) {
+    if unused_types.is_empty() {
+        log::info!("Every mapping of the repository was used");
+        return;
+    }
+
+    log::info!(
+        "{} mapping(s) of the repository were not used:",
+        unused_types.len()
+    );
+    for (block_type, type_name) in unused_types {
+        log::info!("  {}.{}", block_type.as_str(), type_name);
+    }
+}
+
+/// Reports the `required_providers` version constraints of the root module, sorted by provider.
+fn version_constraints_diagnostic(constraints: &HashMap<String, String>) -> Option<Diagnostic> {
+    if constraints.is_empty() {
+        return None;
+    }
+
+    let mut constraints: Vec<String> = constraints
+        .iter()
+        .map(|(provider, constraint)| format!("{} {}", provider, constraint))
+        .collect();
+    constraints.sort();
+
+    Some(Diagnostic::info(format!(
+        "Provider version constraints: {}",
+        constraints.join(", ")
+    )))
+}
+
+/// Reports that the result is partial because modules were skipped (`--best-effort`).
+fn skipped_modules_diagnostic(skipped_modules: &[String]) -> Option<Diagnostic> {
+    if skipped_modules.is_empty() {
+        return None;
+    }
+
+    Some(Diagnostic::error(format!(
+        "Result is PARTIAL: {} module(s) failed to parse and were skipped: {}",
+        skipped_modules.len(),
+        skipped_modules.join(", ")
+    )))
+}
+
+/// Reports module calls that were not analyzed because no modules manifest was found.
+fn unresolved_modules_diagnostic(unresolved_modules: &[String]) -> Option<Diagnostic> {
+    if unresolved_modules.is_empty() {
+        return None;
+    }
+
+    Some(Diagnostic::warn(format!(
+        "{} module(s) were not analyzed because no modules manifest was found \
+         (run terraform init), permissions may be incomplete: {}",
+        unresolved_modules.len(),
+        unresolved_modules.join(", ")
+    )))
+}
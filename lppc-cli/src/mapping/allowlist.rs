@@ -0,0 +1,230 @@
+//! Restriction of generated actions to an approved catalog.
+//!
+//! This module filters the resolved permissions against an action allowlist
+//! (`--action-allowlist`). Every allow or deny action, including the
+//! `NotAction` sets `not_allow` and `not_deny`, that no allowlist entry
+//! matches is dropped and reported, which surfaces over-broad mappings before
+//! their actions end up in a policy. Entries may use the IAM wildcards `*`
+//! and `?`, e.g. `s3:Get*`.
+
+use super::matcher::PermissionResult;
+
+/// Approved actions, matched case-insensitively as IAM does.
+#[derive(Debug, Clone)]
+pub struct ActionAllowlist {
+    patterns: Vec<String>,
+}
+
+impl ActionAllowlist {
+    /// Creates an allowlist from action patterns (e.g., `s3:GetObject`, `ec2:Describe*`).
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self {
+            patterns: patterns.iter().map(|p| p.to_lowercase()).collect(),
+        }
+    }
+
+    /// Checks whether any allowlist entry matches the action.
+    pub fn allows(&self, action: &str) -> bool {
+        let action = action.to_lowercase();
+        self.patterns
+            .iter()
+            .any(|pattern| wildcard_match(pattern, &action))
+    }
+
+    /// Removes all actions not on the allowlist from every group.
+    ///
+    /// The allow and deny sets and the actions of every `NotAction` statement
+    /// are filtered; a statement left without actions is dropped, since it
+    /// would cover every action. Groups left without any action are moved
+    /// from `groups` to `empty_groups`.
+    ///
+    /// # Returns
+    ///
+    /// The dropped actions as `"{group}: {action}"`, sorted.
+    pub fn apply(&self, result: &mut PermissionResult) -> Vec<String> {
+        let mut dropped = Vec::new();
+
+        for (name, permissions) in &mut result.groups {
+            let mut keep = |action: &String| {
+                let allowed = self.allows(action);
+                if !allowed {
+                    dropped.push(format!("{}: {}", name, action));
+                }
+                allowed
+            };
+
+            for set in [&mut permissions.allow, &mut permissions.deny] {
+                set.retain(|action| keep(action));
+            }
+            for statements in [&mut permissions.not_allow, &mut permissions.not_deny] {
+                *statements = std::mem::take(statements)
+                    .into_iter()
+                    .filter_map(|mut statement| {
+                        statement.actions.retain(|action| keep(action));
+                        (!statement.actions.is_empty()).then_some(statement)
+                    })
+                    .collect();
+            }
+        }
+
+        let mut emptied: Vec<String> = result
+            .groups
+            .iter()
+            .filter(|(_, permissions)| permissions.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in &emptied {
+            result.groups.remove(name);
+        }
+        result.empty_groups.append(&mut emptied);
+        result.empty_groups.sort();
+
+        dropped.sort();
+        dropped.dedup();
+        dropped
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` matches any sequence of
+/// characters and `?` matches a single character.
+pub(super) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` consume one more character
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{actions, not_action, result};
+    use std::collections::BTreeSet;
+
+    fn allowlist(patterns: &[&str]) -> ActionAllowlist {
+        ActionAllowlist::new(patterns.iter().map(|p| p.to_string()).collect())
+    }
+
+    #[test]
+    fn listed_action_is_kept() {
+        let mut result = result(&[("StorageDeployer", &["s3:CreateBucket"], &[])]);
+
+        let dropped = allowlist(&["s3:CreateBucket"]).apply(&mut result);
+
+        assert!(dropped.is_empty());
+        assert!(
+            result.groups["StorageDeployer"]
+                .allow
+                .contains("s3:CreateBucket")
+        );
+    }
+
+    #[test]
+    fn unlisted_action_is_dropped_and_reported() {
+        let mut result = result(&[(
+            "StorageDeployer",
+            &["s3:CreateBucket", "s3:PutBucketPolicy"],
+            &["s3:DeleteBucket"],
+        )]);
+
+        let dropped = allowlist(&["s3:CreateBucket"]).apply(&mut result);
+
+        assert_eq!(
+            dropped,
+            vec![
+                "StorageDeployer: s3:DeleteBucket",
+                "StorageDeployer: s3:PutBucketPolicy"
+            ]
+        );
+        let storage = &result.groups["StorageDeployer"];
+        assert_eq!(storage.allow, actions(&["s3:CreateBucket"]));
+        assert!(storage.deny.is_empty());
+    }
+
+    #[test]
+    fn unlisted_not_actions_are_dropped_and_reported() {
+        let mut result = result(&[("StorageDeployer", &["s3:CreateBucket"], &[])]);
+        let storage = result.groups.get_mut("StorageDeployer").unwrap();
+        storage.not_allow = BTreeSet::from([not_action(
+            "resource.aws_s3_bucket",
+            &["iam:*", "s3:GetObject"],
+        )]);
+        storage.not_deny = BTreeSet::from([not_action("resource.aws_kms_key", &["kms:Decrypt"])]);
+
+        let dropped = allowlist(&["s3:*"]).apply(&mut result);
+
+        assert_eq!(
+            dropped,
+            vec!["StorageDeployer: iam:*", "StorageDeployer: kms:Decrypt"]
+        );
+        let storage = &result.groups["StorageDeployer"];
+        assert_eq!(
+            storage.not_allow,
+            BTreeSet::from([not_action("resource.aws_s3_bucket", &["s3:GetObject"])])
+        );
+        // A statement without actions would deny every action
+        assert!(storage.not_deny.is_empty());
+    }
+
+    #[test]
+    fn wildcard_entry_matches_actions() {
+        let mut result = result(&[(
+            "NetworkDeployer",
+            &["ec2:DescribeVpcs", "ec2:describesubnets", "ec2:CreateVpc"],
+            &[],
+        )]);
+
+        let dropped = allowlist(&["ec2:Describe*"]).apply(&mut result);
+
+        assert_eq!(dropped, vec!["NetworkDeployer: ec2:CreateVpc"]);
+        assert_eq!(
+            result.groups["NetworkDeployer"].allow,
+            actions(&["ec2:DescribeVpcs", "ec2:describesubnets"])
+        );
+    }
+
+    #[test]
+    fn group_without_remaining_actions_becomes_empty() {
+        let mut result = result(&[
+            ("StorageDeployer", &["s3:CreateBucket"], &[]),
+            ("QueueDeployer", &["sqs:CreateQueue"], &[]),
+        ]);
+
+        allowlist(&["s3:*"]).apply(&mut result);
+
+        assert!(result.groups.contains_key("StorageDeployer"));
+        assert!(!result.groups.contains_key("QueueDeployer"));
+        assert_eq!(result.empty_groups, vec!["QueueDeployer"]);
+    }
+
+    #[test]
+    fn wildcard_match_supports_star_and_question_mark() {
+        assert!(wildcard_match("s3:get*", "s3:getobject"));
+        assert!(wildcard_match("*", "s3:getobject"));
+        assert!(wildcard_match("s3:*object", "s3:getobject"));
+        assert!(wildcard_match("s?:getobject", "s3:getobject"));
+        assert!(wildcard_match("s3:*", "s3:*"));
+        assert!(!wildcard_match("s3:get*", "s3:putobject"));
+        assert!(!wildcard_match("s3:getobject", "s3:getobjectacl"));
+        assert!(!wildcard_match("s3:*", "s33:getobject"));
+    }
+}
@@ -9,8 +9,8 @@ use std::path::PathBuf;
 use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
-/// Cache expiry duration in hours.
-const CACHE_EXPIRY_HOURS: u64 = 24;
+/// Default age after which the cached repository is refreshed (`--cache-ttl`).
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
 
 /// Error types for cache operations.
 #[derive(Debug, Error)]
@@ -21,6 +21,9 @@ pub enum CacheError {
     #[error("Invalid repository URL: {0}")]
     InvalidUrl(String),
 
+    #[error("Invalid mapping repository ref: {0}")]
+    InvalidRef(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -29,6 +32,8 @@ pub enum CacheError {
 pub struct CacheManager {
     /// Base cache directory (typically ~/.lppc)
     base_dir: PathBuf,
+    /// Age after which `needs_refresh` reports the cache as expired
+    ttl: Duration,
 }
 
 impl CacheManager {
@@ -43,7 +48,18 @@ impl CacheManager {
             log::debug!("Created cache directory: {:?}", base_dir);
         }
 
-        Ok(Self { base_dir })
+        Ok(Self {
+            base_dir,
+            ttl: DEFAULT_CACHE_TTL,
+        })
+    }
+
+    /// Sets the age after which the cache needs a refresh (default: 24 hours).
+    ///
+    /// A TTL of zero refreshes on every run.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
     }
 
     /// Creates a cache manager with a custom base directory (for testing).
@@ -52,28 +68,41 @@ impl CacheManager {
         if !base_dir.exists() {
             fs::create_dir_all(&base_dir)?;
         }
-        Ok(Self { base_dir })
+        Ok(Self {
+            base_dir,
+            ttl: DEFAULT_CACHE_TTL,
+        })
     }
 
-    /// Returns the local path for a given repository URL.
+    /// Returns the local path for a given repository URL and optional ref.
     ///
     /// Example: `https://github.com/bebold-jhr/lppc-aws-test` -> `~/.lppc/bebold-jhr/lppc-aws-test`
-    pub fn get_repo_path(&self, url: &str) -> Result<PathBuf, CacheError> {
+    ///
+    /// A ref is appended to the directory name, so checkouts of different refs
+    /// don't overwrite each other: `release/v2` -> `~/.lppc/bebold-jhr/lppc-aws-test@release+v2`
+    pub fn get_repo_path(&self, url: &str, reference: Option<&str>) -> Result<PathBuf, CacheError> {
         let repo_path = Self::parse_repo_path(url)?;
-        Ok(self.base_dir.join(repo_path))
+        match reference {
+            Some(reference) => {
+                Ok(self
+                    .base_dir
+                    .join(format!("{}@{}", repo_path, Self::ref_component(reference)?)))
+            }
+            None => Ok(self.base_dir.join(repo_path)),
+        }
     }
 
-    /// Checks if the repository exists in cache.
-    pub fn is_cached(&self, url: &str) -> bool {
-        match self.get_repo_path(url) {
+    /// Checks if the repository (at the given ref) exists in cache.
+    pub fn is_cached(&self, url: &str, reference: Option<&str>) -> bool {
+        match self.get_repo_path(url, reference) {
             Ok(path) => path.exists() && path.join(".git").exists(),
             Err(_) => false,
         }
     }
 
-    /// Checks if the cache needs refresh (older than 24 hours or no timestamp file).
-    pub fn needs_refresh(&self, url: &str) -> Result<bool, CacheError> {
-        let timestamp_path = self.timestamp_file_path(url);
+    /// Checks if the cache needs refresh (at least as old as the TTL or no timestamp file).
+    pub fn needs_refresh(&self, url: &str, reference: Option<&str>) -> Result<bool, CacheError> {
+        let timestamp_path = self.timestamp_file_path(url, reference);
 
         if !timestamp_path.exists() {
             return Ok(true);
@@ -81,19 +110,18 @@ impl CacheManager {
 
         let metadata = fs::metadata(&timestamp_path)?;
         let modified = metadata.modified()?;
-        let expiry_duration = Duration::from_secs(CACHE_EXPIRY_HOURS * 60 * 60);
 
         let is_expired = SystemTime::now()
             .duration_since(modified)
-            .map(|age| age > expiry_duration)
+            .map(|age| age >= self.ttl)
             .unwrap_or(true);
 
         Ok(is_expired)
     }
 
     /// Updates the last refresh timestamp by touching the timestamp file.
-    pub fn update_timestamp(&self, url: &str) -> Result<(), CacheError> {
-        let timestamp_path = self.timestamp_file_path(url);
+    pub fn update_timestamp(&self, url: &str, reference: Option<&str>) -> Result<(), CacheError> {
+        let timestamp_path = self.timestamp_file_path(url, reference);
 
         // Write current timestamp to file
         let now = chrono::Utc::now().to_rfc3339();
@@ -105,12 +133,32 @@ impl CacheManager {
 
     /// Gets the timestamp file path for a repository.
     ///
-    /// Uses a hash of the URL to create a unique filename.
-    fn timestamp_file_path(&self, url: &str) -> PathBuf {
-        let hash = Self::hash_url(url);
+    /// Uses a hash of the URL (and ref, if any) to create a unique filename.
+    fn timestamp_file_path(&self, url: &str, reference: Option<&str>) -> PathBuf {
+        let hash = match reference {
+            Some(reference) => Self::hash_url(&format!("{}@{}", url, reference)),
+            None => Self::hash_url(url),
+        };
         self.base_dir.join(format!(".last_update_{}", hash))
     }
 
+    /// Turns a ref into a suffix for the cache directory name.
+    ///
+    /// Slashes (e.g., `release/v2`) become `+`, which refs cannot contain, so
+    /// distinct refs keep distinct directories.
+    fn ref_component(reference: &str) -> Result<String, CacheError> {
+        let is_valid = !reference.is_empty()
+            && !reference.starts_with(['-', '.'])
+            && !reference.contains("..")
+            && reference
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/' | '.'));
+        if !is_valid {
+            return Err(CacheError::InvalidRef(reference.to_string()));
+        }
+        Ok(reference.replace('/', "+"))
+    }
+
     /// Creates a short hash of the URL for the timestamp filename.
     fn hash_url(url: &str) -> String {
         let mut hasher = Sha256::new();
@@ -343,7 +391,7 @@ mod tests {
 
         let manager = CacheManager::with_base_dir(base_dir.clone()).unwrap();
         let path = manager
-            .get_repo_path("https://github.com/bebold-jhr/lppc-aws-test")
+            .get_repo_path("https://github.com/bebold-jhr/lppc-aws-test", None)
             .unwrap();
 
         assert_eq!(path, base_dir.join("bebold-jhr/lppc-aws-test"));
@@ -352,6 +400,34 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_get_repo_path_with_ref() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("lppc_test_repo_ref_{}", std::process::id()));
+        let base_dir = temp_dir.join(".lppc");
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        let manager = CacheManager::with_base_dir(base_dir.clone()).unwrap();
+        let url = "https://github.com/bebold-jhr/lppc-aws-test";
+
+        assert_eq!(
+            manager.get_repo_path(url, Some("release/v2")).unwrap(),
+            base_dir.join("bebold-jhr/lppc-aws-test@release+v2")
+        );
+        assert_ne!(
+            manager.timestamp_file_path(url, Some("v1.0")),
+            manager.timestamp_file_path(url, None)
+        );
+        assert!(matches!(
+            manager.get_repo_path(url, Some("../escape")),
+            Err(CacheError::InvalidRef(_))
+        ));
+        assert!(manager.get_repo_path(url, Some("-x")).is_err());
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
     #[test]
     fn test_is_cached_false_when_not_exists() {
         let temp_dir = std::env::temp_dir().join(format!("lppc_test_cache_{}", std::process::id()));
@@ -359,7 +435,7 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
 
         let manager = CacheManager::with_base_dir(base_dir).unwrap();
-        assert!(!manager.is_cached("https://github.com/user/nonexistent"));
+        assert!(!manager.is_cached("https://github.com/user/nonexistent", None));
 
         // Cleanup
         let _ = fs::remove_dir_all(&temp_dir);
@@ -378,7 +454,7 @@ mod tests {
         let repo_path = base_dir.join("user/repo");
         fs::create_dir_all(repo_path.join(".git")).unwrap();
 
-        assert!(manager.is_cached("https://github.com/user/repo"));
+        assert!(manager.is_cached("https://github.com/user/repo", None));
 
         // Cleanup
         let _ = fs::remove_dir_all(&temp_dir);
@@ -394,7 +470,7 @@ mod tests {
         let manager = CacheManager::with_base_dir(base_dir).unwrap();
         assert!(
             manager
-                .needs_refresh("https://github.com/user/repo")
+                .needs_refresh("https://github.com/user/repo", None)
                 .unwrap()
         );
 
@@ -412,15 +488,28 @@ mod tests {
         let url = "https://github.com/user/repo";
 
         // Update timestamp
-        manager.update_timestamp(url).unwrap();
+        manager.update_timestamp(url, None).unwrap();
 
         // Should not need refresh immediately after update
-        assert!(!manager.needs_refresh(url).unwrap());
+        assert!(!manager.needs_refresh(url, None).unwrap());
 
         // Cleanup
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_zero_ttl_always_needs_refresh() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let manager = CacheManager::with_base_dir(temp_dir.path().join(".lppc"))
+            .unwrap()
+            .with_ttl(Duration::ZERO);
+        let url = "https://github.com/user/repo";
+
+        manager.update_timestamp(url, None).unwrap();
+
+        assert!(manager.needs_refresh(url, None).unwrap());
+    }
+
     #[test]
     fn test_timestamp_file_path_unique_per_url() {
         let temp_dir =
@@ -430,8 +519,8 @@ mod tests {
 
         let manager = CacheManager::with_base_dir(base_dir).unwrap();
 
-        let path1 = manager.timestamp_file_path("https://github.com/user/repo1");
-        let path2 = manager.timestamp_file_path("https://github.com/user/repo2");
+        let path1 = manager.timestamp_file_path("https://github.com/user/repo1", None);
+        let path2 = manager.timestamp_file_path("https://github.com/user/repo2", None);
 
         assert_ne!(path1, path2);
 
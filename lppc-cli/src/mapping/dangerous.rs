@@ -0,0 +1,136 @@
+//! Automatic denial of dangerous actions granted by wildcards.
+//!
+//! Mappings sometimes allow a whole service (e.g. `s3:*`) although the
+//! resource needs only part of it. With `--auto-deny-dangerous`, every action
+//! of a dangerous actions catalog that such a wildcard allow matches, but that
+//! no mapping allows explicitly, is added to the group's deny set. The
+//! catalog is bundled in `dangerous_actions.txt` and can be replaced by a file.
+
+use super::allowlist::wildcard_match;
+use super::matcher::PermissionResult;
+
+/// Bundled catalog of dangerous actions, one per line.
+const DANGEROUS_ACTIONS: &str = include_str!("dangerous_actions.txt");
+
+/// Actions to deny when they are only granted through a wildcard allow.
+#[derive(Debug, Clone)]
+pub struct DangerousActions {
+    actions: Vec<String>,
+}
+
+impl DangerousActions {
+    /// Creates a catalog from the given actions (e.g., `s3:DeleteBucket`).
+    pub fn new(actions: Vec<String>) -> Self {
+        Self { actions }
+    }
+
+    /// Creates the bundled catalog.
+    pub fn bundled() -> Self {
+        Self::new(
+            DANGEROUS_ACTIONS
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+
+    /// Adds the dangerous actions matched by a wildcard allow to the deny set
+    /// of each group.
+    ///
+    /// An action is skipped if the group allows or denies it explicitly
+    /// (compared case-insensitively, as IAM does).
+    ///
+    /// # Returns
+    ///
+    /// The added deny actions as `"{group}: {action}"`, sorted.
+    pub fn apply(&self, result: &mut PermissionResult) -> Vec<String> {
+        let mut added = Vec::new();
+
+        for (name, permissions) in &mut result.groups {
+            let allow: Vec<String> = permissions.allow.iter().map(|a| a.to_lowercase()).collect();
+            let deny: Vec<String> = permissions.deny.iter().map(|a| a.to_lowercase()).collect();
+            let wildcards: Vec<&String> = allow
+                .iter()
+                .filter(|action| action.contains(['*', '?']))
+                .collect();
+
+            for action in &self.actions {
+                let lowercase = action.to_lowercase();
+                if allow.contains(&lowercase) || deny.contains(&lowercase) {
+                    continue;
+                }
+
+                if wildcards
+                    .iter()
+                    .any(|pattern| wildcard_match(pattern, &lowercase))
+                {
+                    permissions.deny.insert(action.clone());
+                    added.push(format!("{}: {}", name, action));
+                }
+            }
+        }
+
+        added.sort();
+        added.dedup();
+        added
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{actions, result};
+
+    fn catalog(names: &[&str]) -> DangerousActions {
+        DangerousActions::new(names.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn wildcard_allow_denies_matched_dangerous_action() {
+        let mut result = result(&[("StorageDeployer", &["s3:*"], &[])]);
+
+        let added = catalog(&["s3:DeleteBucket", "iam:CreateUser"]).apply(&mut result);
+
+        assert_eq!(added, vec!["StorageDeployer: s3:DeleteBucket"]);
+        let storage = &result.groups["StorageDeployer"];
+        assert_eq!(storage.allow, actions(&["s3:*"]));
+        assert_eq!(storage.deny, actions(&["s3:DeleteBucket"]));
+    }
+
+    #[test]
+    fn explicitly_allowed_dangerous_action_is_not_denied() {
+        let mut result = result(&[("StorageDeployer", &["s3:*", "s3:deletebucket"], &[])]);
+
+        let added = catalog(&["s3:DeleteBucket"]).apply(&mut result);
+
+        assert!(added.is_empty());
+        assert!(result.groups["StorageDeployer"].deny.is_empty());
+    }
+
+    #[test]
+    fn dangerous_action_without_matching_wildcard_is_not_denied() {
+        let mut result = result(&[(
+            "StorageDeployer",
+            &["s3:Get*", "s3:CreateBucket"],
+            &["s3:PutBucketAcl"],
+        )]);
+
+        let added = catalog(&["s3:DeleteBucket", "s3:PutBucketAcl"]).apply(&mut result);
+
+        assert!(added.is_empty());
+        assert_eq!(
+            result.groups["StorageDeployer"].deny,
+            actions(&["s3:PutBucketAcl"])
+        );
+    }
+
+    #[test]
+    fn bundled_catalog_contains_actions_only() {
+        let bundled = DangerousActions::bundled();
+
+        assert!(bundled.actions.contains(&"s3:DeleteBucket".to_string()));
+        assert!(bundled.actions.iter().all(|action| action.contains(':')));
+    }
+}
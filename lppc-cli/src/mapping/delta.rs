@@ -0,0 +1,365 @@
+//! Policy changes caused by another version of the mapping repository.
+//!
+//! With `--compare-mappings-ref REF`, the configuration is resolved a second
+//! time against REF of the mapping repository. Since the configuration is the
+//! same, every difference between the two results is caused by the mapping
+//! change. The differences are reported per group, as the actions that would
+//! be added or removed when switching from the current mappings to REF.
+//!
+//! `lppc diff --against DIR` reports the same per-group differences between
+//! the policies written to DIR by an earlier run and the current result.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use super::matcher::{GroupPermissions, MatchError, PermissionResult};
+
+/// Actions added and removed in a single group, each sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupDelta {
+    pub added_allow: Vec<String>,
+    pub removed_allow: Vec<String>,
+    pub added_deny: Vec<String>,
+    pub removed_deny: Vec<String>,
+}
+
+impl GroupDelta {
+    /// Returns true if the group's permissions are unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.added_allow.is_empty()
+            && self.removed_allow.is_empty()
+            && self.added_deny.is_empty()
+            && self.removed_deny.is_empty()
+    }
+}
+
+/// Per-group differences between the current mappings and another ref.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappingDelta {
+    /// The compared ref of the mapping repository (e.g., a branch or tag)
+    pub reference: String,
+    /// Changed groups only, by name
+    pub groups: BTreeMap<String, GroupDelta>,
+}
+
+impl MappingDelta {
+    /// Computes how `current` would change into `compared`.
+    ///
+    /// Groups present in only one result count as entirely added or removed.
+    pub fn compute(
+        reference: &str,
+        current: &PermissionResult,
+        compared: &PermissionResult,
+    ) -> Self {
+        Self {
+            reference: reference.to_string(),
+            groups: group_deltas(current, compared),
+        }
+    }
+
+    /// Returns true if no group would change.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Renders the changes, one `+`/`-` line per action, grouped by name.
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return format!("No policy changes with mappings at {}\n", self.reference);
+        }
+
+        let mut output = format!("Policy changes with mappings at {}:\n", self.reference);
+        render_groups(&mut output, &self.groups);
+        output
+    }
+}
+
+/// Per-group differences between previously written policies and the current
+/// result (`lppc diff --against DIR`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyDiff {
+    /// Directory the previous policies were read from
+    pub against: PathBuf,
+    /// Changed groups only, by name
+    pub groups: BTreeMap<String, GroupDelta>,
+}
+
+impl PolicyDiff {
+    /// Computes how the `previous` policies changed into `current`.
+    ///
+    /// Groups present in only one result count as entirely added or removed.
+    pub fn compute(
+        against: &Path,
+        previous: &PermissionResult,
+        current: &PermissionResult,
+    ) -> Self {
+        Self {
+            against: against.to_path_buf(),
+            groups: group_deltas(previous, current),
+        }
+    }
+
+    /// Returns true if no group changed.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Renders the changes, one `+`/`-` line per action, grouped by name.
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return format!("No policy changes against {}\n", self.against.display());
+        }
+
+        let mut output = format!("Policy changes against {}:\n", self.against.display());
+        render_groups(&mut output, &self.groups);
+        output
+    }
+
+    /// Fails if any group changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MatchError::PolicyChanges` listing the changed groups.
+    pub fn check(&self) -> Result<(), MatchError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let names: Vec<&str> = self.groups.keys().map(String::as_str).collect();
+        Err(MatchError::PolicyChanges(
+            self.against.display().to_string(),
+            names.join(", "),
+        ))
+    }
+}
+
+/// Computes the changed groups between two results, by name.
+fn group_deltas(
+    before: &PermissionResult,
+    after: &PermissionResult,
+) -> BTreeMap<String, GroupDelta> {
+    let empty = HashSet::new();
+    let names: HashSet<&String> = before.groups.keys().chain(after.groups.keys()).collect();
+
+    let mut groups = BTreeMap::new();
+    for name in names {
+        let (allow_before, deny_before) = action_sets(before.groups.get(name), &empty);
+        let (allow_after, deny_after) = action_sets(after.groups.get(name), &empty);
+
+        let delta = GroupDelta {
+            added_allow: difference(allow_after, allow_before),
+            removed_allow: difference(allow_before, allow_after),
+            added_deny: difference(deny_after, deny_before),
+            removed_deny: difference(deny_before, deny_after),
+        };
+        if !delta.is_empty() {
+            groups.insert(name.clone(), delta);
+        }
+    }
+    groups
+}
+
+/// Appends one `+`/`-` line per changed action, grouped by name.
+fn render_groups(output: &mut String, groups: &BTreeMap<String, GroupDelta>) {
+    for (name, delta) in groups {
+        let _ = writeln!(output, "  {}:", name);
+        for (sign, effect, actions) in [
+            ('+', "allow", &delta.added_allow),
+            ('-', "allow", &delta.removed_allow),
+            ('+', "deny", &delta.added_deny),
+            ('-', "deny", &delta.removed_deny),
+        ] {
+            for action in actions {
+                let _ = writeln!(output, "    {} {} {}", sign, effect, action);
+            }
+        }
+    }
+}
+
+/// Returns the allow and deny sets of a group, or `empty` for a missing group.
+fn action_sets<'a>(
+    permissions: Option<&'a GroupPermissions>,
+    empty: &'a HashSet<String>,
+) -> (&'a HashSet<String>, &'a HashSet<String>) {
+    match permissions {
+        Some(permissions) => (&permissions.allow, &permissions.deny),
+        None => (empty, empty),
+    }
+}
+
+/// Returns the actions in `left` but not in `right`, sorted.
+fn difference(left: &HashSet<String>, right: &HashSet<String>) -> Vec<String> {
+    let mut actions: Vec<String> = left.difference(right).cloned().collect();
+    actions.sort();
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::{MappingLoader, PermissionMatcher};
+    use crate::terraform::{BlockType, Instances, ProviderGroup, TerraformBlock, TerraformConfig};
+    use crate::test_support::action_list;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Creates a mapping directory standing in for one ref of the mapping repository.
+    fn mapping_dir(mappings: &[(&str, &str)]) -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let resource_dir = temp_dir.path().join("mappings/resource");
+        fs::create_dir_all(&resource_dir).unwrap();
+        for (type_name, content) in mappings {
+            fs::write(resource_dir.join(format!("{}.yaml", type_name)), content).unwrap();
+        }
+        temp_dir
+    }
+
+    fn block(type_name: &str, attributes: &[&str]) -> TerraformBlock {
+        TerraformBlock {
+            block_type: BlockType::Resource,
+            type_name: type_name.to_string(),
+            name: "main".to_string(),
+            provider_config_key: "aws".to_string(),
+            attribute_values: HashMap::new(),
+            present_attributes: attributes.iter().map(|a| vec![a.to_string()]).collect(),
+            address: format!("{}.main", type_name),
+            managed_policy_arns: Vec::new(),
+            instances: Instances::Single,
+        }
+    }
+
+    fn config() -> TerraformConfig {
+        let group = |name: &str, blocks: Vec<TerraformBlock>| {
+            (
+                name.to_string(),
+                ProviderGroup {
+                    output_name: name.to_string(),
+                    role_arn: None,
+                    has_custom_endpoints: false,
+                    blocks,
+                },
+            )
+        };
+
+        TerraformConfig {
+            provider_groups: HashMap::from([
+                group("StorageDeployer", vec![block("aws_s3_bucket", &["tags"])]),
+                group("NetworkDeployer", vec![block("aws_vpc", &[])]),
+            ]),
+            unmapped_blocks: Vec::new(),
+            provider_version_constraints: HashMap::new(),
+            skipped_modules: Vec::new(),
+            unresolved_modules: Vec::new(),
+            module_tree: None,
+        }
+    }
+
+    fn resolve(mappings: &Path) -> PermissionResult {
+        let loader = MappingLoader::new(mappings.to_path_buf());
+        PermissionMatcher::new(&loader).resolve(&config()).unwrap()
+    }
+
+    #[test]
+    fn delta_lists_actions_changed_by_mappings() {
+        let vpc = "allow:\n  - ec2:CreateVpc";
+        let current = mapping_dir(&[
+            (
+                "aws_s3_bucket",
+                "allow:\n  - s3:CreateBucket\n  - s3:DeleteBucket",
+            ),
+            ("aws_vpc", vpc),
+        ]);
+        let compared = mapping_dir(&[
+            (
+                "aws_s3_bucket",
+                "allow:\n  - s3:CreateBucket\n  - s3:ListBucket\ndeny:\n  - s3:DeleteBucketPolicy\nconditional:\n  tags:\n    - s3:PutBucketTagging",
+            ),
+            ("aws_vpc", vpc),
+        ]);
+
+        let delta = MappingDelta::compute(
+            "feature/s3",
+            &resolve(current.path()),
+            &resolve(compared.path()),
+        );
+
+        // NetworkDeployer is unchanged and therefore not listed
+        assert_eq!(
+            delta.groups,
+            BTreeMap::from([(
+                "StorageDeployer".to_string(),
+                GroupDelta {
+                    added_allow: action_list(&["s3:ListBucket", "s3:PutBucketTagging"]),
+                    removed_allow: action_list(&["s3:DeleteBucket"]),
+                    added_deny: action_list(&["s3:DeleteBucketPolicy"]),
+                    removed_deny: Vec::new(),
+                }
+            )])
+        );
+        assert_eq!(
+            delta.render(),
+            "Policy changes with mappings at feature/s3:\n\
+             \x20 StorageDeployer:\n\
+             \x20   + allow s3:ListBucket\n\
+             \x20   + allow s3:PutBucketTagging\n\
+             \x20   - allow s3:DeleteBucket\n\
+             \x20   + deny s3:DeleteBucketPolicy\n"
+        );
+    }
+
+    #[test]
+    fn identical_mappings_produce_no_delta() {
+        let mappings = [
+            ("aws_s3_bucket", "allow:\n  - s3:CreateBucket"),
+            ("aws_vpc", "allow:\n  - ec2:CreateVpc"),
+        ];
+        let current = mapping_dir(&mappings);
+        let compared = mapping_dir(&mappings);
+
+        let delta =
+            MappingDelta::compute("v2", &resolve(current.path()), &resolve(compared.path()));
+
+        assert!(delta.is_empty());
+        assert_eq!(delta.render(), "No policy changes with mappings at v2\n");
+    }
+
+    #[test]
+    fn policy_diff_lists_added_and_removed_groups_and_fails_check() {
+        let mappings = mapping_dir(&[
+            ("aws_s3_bucket", "allow:\n  - s3:CreateBucket"),
+            ("aws_vpc", "allow:\n  - ec2:CreateVpc"),
+        ]);
+        let current = resolve(mappings.path());
+        let mut previous = resolve(mappings.path());
+        previous.groups.remove("NetworkDeployer");
+        previous.groups.insert(
+            "LegacyDeployer".to_string(),
+            GroupPermissions {
+                deny: HashSet::from(["iam:*".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        let diff = PolicyDiff::compute(Path::new("policies"), &previous, &current);
+
+        assert_eq!(
+            diff.render(),
+            "Policy changes against policies:\n\
+             \x20 LegacyDeployer:\n\
+             \x20   - deny iam:*\n\
+             \x20 NetworkDeployer:\n\
+             \x20   + allow ec2:CreateVpc\n"
+        );
+        assert!(matches!(
+            diff.check(),
+            Err(MatchError::PolicyChanges(dir, groups))
+                if dir == "policies" && groups == "LegacyDeployer, NetworkDeployer"
+        ));
+
+        let unchanged = PolicyDiff::compute(Path::new("policies"), &current, &current);
+        assert_eq!(unchanged.render(), "No policy changes against policies\n");
+        assert!(unchanged.check().is_ok());
+    }
+}
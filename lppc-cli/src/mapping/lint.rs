@@ -0,0 +1,373 @@
+//! Linting of the mapping repository.
+//!
+//! `lppc validate-mappings` checks every mapping file below `mappings/`
+//! (including the directories of other providers, e.g. `mappings/google/`),
+//! so mistakes in hand-written mappings are found before a run silently
+//! ignores them. The parser is lenient: unknown keys and entries that are not
+//! strings are dropped without notice. The linter reports:
+//!
+//! - files that fail to parse (invalid YAML or JSON, bad structure)
+//! - unknown top-level keys, with a suggestion for likely typos (`alow`)
+//! - sections that are not lists of strings
+//! - AWS actions that do not have the `service:Action` shape (permissions of
+//!   other providers, e.g. `storage.buckets.create`, have their own syntax)
+//! - `conditional` entries whose leaves are not action lists
+//!
+//! Files are enumerated through `MappingLoader`, so exactly the files a run
+//! can load are checked. Skip files (`.skip`), files with other extensions,
+//! and files outside `mappings/[<provider>/]<block_type>/` are not checked.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use saphyr::{LoadableYamlNode, Yaml};
+use thiserror::Error;
+
+use super::loader::{LoadError, MAPPING_BLOCK_TYPES, MAX_MAPPING_FILE_SIZE, MappingLoader};
+use super::suggest::closest_match;
+use super::yaml_parser;
+
+/// Top-level keys of a mapping file.
+const KNOWN_KEYS: [&str; 9] = [
+    "allow",
+    "deny",
+    "not_allow",
+    "not_deny",
+    "conditional",
+    "import",
+    "conditions",
+    "resources",
+    "resource_arn_template",
+];
+
+/// Matches an IAM action of the form `service:Action` (wildcards allowed).
+static ACTION_SHAPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z0-9-]+:[A-Za-z0-9*?]+$").expect("valid regex"));
+
+/// Errors reported by the mapping linter.
+#[derive(Debug, Error)]
+pub enum LintError {
+    #[error("{0} of {1} mapping files are invalid")]
+    InvalidFiles(usize, usize),
+}
+
+/// Problems found in one mapping file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileLint {
+    /// Path relative to the repository root, with `/` separators
+    pub path: String,
+    /// Human-readable problems; empty if the file is valid
+    pub problems: Vec<String>,
+}
+
+/// Result of linting every mapping file of a repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappingLint {
+    /// One entry per checked file, sorted by path
+    pub files: Vec<FileLint>,
+}
+
+impl MappingLint {
+    /// Lints every `.yaml` and `.json` file the loader reads, of every
+    /// provider (`MappingLoader::list_files()`).
+    pub fn collect(repo_path: &Path) -> Result<Self, LoadError> {
+        let loader = MappingLoader::new(repo_path.to_path_buf());
+        let mut paths = Vec::new();
+        for provider in loader.list_providers()? {
+            for block_type in MAPPING_BLOCK_TYPES {
+                for path in loader.list_files(&provider, block_type)? {
+                    if path.extension().is_some_and(|ext| ext != "skip") {
+                        paths.push((path, provider == "aws"));
+                    }
+                }
+            }
+        }
+        paths.sort();
+
+        let mut files = Vec::new();
+        for (path, is_aws) in paths {
+            let relative = path
+                .strip_prefix(repo_path)
+                .unwrap_or(&path)
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            let problems = if std::fs::metadata(&path)?.len() > MAX_MAPPING_FILE_SIZE {
+                vec![format!(
+                    "File is larger than {} bytes and is never loaded",
+                    MAX_MAPPING_FILE_SIZE
+                )]
+            } else {
+                let is_json = path.extension().is_some_and(|ext| ext == "json");
+                lint_mapping(&std::fs::read_to_string(&path)?, is_json, is_aws)
+            };
+
+            files.push(FileLint {
+                path: relative,
+                problems,
+            });
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Returns the number of files with at least one problem.
+    pub fn invalid_files(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|file| !file.problems.is_empty())
+            .count()
+    }
+
+    /// Fails if any file has a problem.
+    pub fn check(&self) -> Result<(), LintError> {
+        match self.invalid_files() {
+            0 => Ok(()),
+            invalid => Err(LintError::InvalidFiles(invalid, self.files.len())),
+        }
+    }
+
+    /// Renders one line per file (`ok` or `invalid`) with its problems
+    /// indented below it, followed by a summary line.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        for file in &self.files {
+            if file.problems.is_empty() {
+                let _ = writeln!(output, "ok       {}", file.path);
+                continue;
+            }
+
+            let _ = writeln!(output, "invalid  {}", file.path);
+            for problem in &file.problems {
+                let _ = writeln!(output, "           - {}", problem);
+            }
+        }
+        let _ = writeln!(
+            output,
+            "{} mapping files checked, {} invalid",
+            self.files.len(),
+            self.invalid_files()
+        );
+        output
+    }
+}
+
+/// Returns the problems of a mapping file's content.
+///
+/// The `service:Action` shape is only checked for AWS mappings (`is_aws`).
+pub fn lint_mapping(content: &str, is_json: bool, is_aws: bool) -> Vec<String> {
+    let parsed = if is_json {
+        yaml_parser::parse_json_mapping(content)
+    } else {
+        yaml_parser::parse_mapping(content)
+    };
+    if let Err(e) = parsed {
+        return vec![e.to_string()];
+    }
+
+    // Parsing succeeded, so the document is a mapping
+    let documents = Yaml::load_from_str(content).unwrap_or_default();
+    let Some(root) = documents.first().and_then(Yaml::as_mapping) else {
+        return Vec::new();
+    };
+
+    let known_keys: Vec<String> = KNOWN_KEYS.iter().map(|key| key.to_string()).collect();
+    let mut problems = Vec::new();
+    for (key, value) in root {
+        let Some(key) = key.as_str() else {
+            problems.push("Top-level key is not a string".to_string());
+            continue;
+        };
+
+        match key {
+            "allow" | "deny" | "not_allow" | "not_deny" | "import" => {
+                lint_action_list(key, value, is_aws, &mut problems)
+            }
+            "conditional" => lint_conditional(key, value, is_aws, &mut problems),
+            // The parser already rejects unknown groups and malformed entries
+            "conditions" | "resources" => {}
+            "resource_arn_template" => {
+                if value.as_str().is_none() {
+                    problems.push("'resource_arn_template' must be a string".to_string());
+                }
+            }
+            _ => problems.push(match closest_match(key, &known_keys) {
+                Some(suggestion) => {
+                    format!("Unknown key '{}' (did you mean '{}'?)", key, suggestion)
+                }
+                None => format!("Unknown key '{}'", key),
+            }),
+        }
+    }
+    problems
+}
+
+/// Checks that a node is a list of strings, of the form `service:Action`
+/// for AWS mappings.
+fn lint_action_list(location: &str, node: &Yaml, is_aws: bool, problems: &mut Vec<String>) {
+    let Some(entries) = node.as_sequence() else {
+        problems.push(format!("'{}' must be a list of actions", location));
+        return;
+    };
+
+    for (index, entry) in entries.iter().enumerate() {
+        match entry.as_str() {
+            Some(action) if !is_aws || action == "*" || ACTION_SHAPE.is_match(action) => {}
+            Some(action) => problems.push(format!(
+                "'{}' contains '{}', which is not of the form service:Action",
+                location, action
+            )),
+            None => problems.push(format!(
+                "'{}' entry {} is not a string",
+                location,
+                index + 1
+            )),
+        }
+    }
+}
+
+/// Checks that every leaf of a conditional tree is a list of actions.
+fn lint_conditional(location: &str, node: &Yaml, is_aws: bool, problems: &mut Vec<String>) {
+    let Some(children) = node.as_mapping() else {
+        if node.is_sequence() {
+            lint_action_list(location, node, is_aws, problems);
+        } else {
+            problems.push(format!(
+                "'{}' must be a list of actions or nested attributes",
+                location
+            ));
+        }
+        return;
+    };
+
+    if children.is_empty() {
+        problems.push(format!("'{}' has no attributes", location));
+    }
+    for (key, child) in children {
+        match key.as_str() {
+            Some(key) => {
+                lint_conditional(&format!("{}.{}", location, key), child, is_aws, problems)
+            }
+            None => problems.push(format!("'{}' has a key that is not a string", location)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn valid_mapping_has_no_problems() {
+        let yaml = r#"
+allow:
+  - "s3:CreateBucket"
+  - "s3:Get*"
+deny:
+  - "s3:DeleteBucket"
+conditional:
+  logging:
+    target_bucket:
+      - "s3:PutBucketLogging"
+import:
+  - "s3:ListBucket"
+resource_arn_template: "arn:aws:s3:::${bucket}"
+"#;
+        assert_eq!(lint_mapping(yaml, false, true), Vec::<String>::new());
+        assert_eq!(
+            lint_mapping(r#"{"allow": ["ec2:CreateVpc"]}"#, true, true),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn lint_reports_typos_bad_actions_and_conditional_leaves() {
+        let yaml = r#"
+alow:
+  - "s3:CreateBucket"
+allow:
+  - "s3 CreateBucket"
+  - 42
+deny: "s3:DeleteBucket"
+conditional:
+  tags: {}
+  logging:
+    target_bucket:
+"#;
+        assert_eq!(
+            lint_mapping(yaml, false, true),
+            vec![
+                "Unknown key 'alow' (did you mean 'allow'?)".to_string(),
+                "'allow' contains 's3 CreateBucket', which is not of the form service:Action"
+                    .to_string(),
+                "'allow' entry 2 is not a string".to_string(),
+                "'deny' must be a list of actions".to_string(),
+                "'conditional.tags' has no attributes".to_string(),
+                "'conditional.logging.target_bucket' must be a list of actions or nested attributes"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_reports_unparsable_files() {
+        let problems = lint_mapping("allow:\n  - \"s3:CreateBucket\"\n bad: [", false, true);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("YAML parse error"));
+
+        let problems = lint_mapping("{\"allow\": [\"s3:CreateBucket\",]}", true, true);
+        assert!(problems[0].starts_with("JSON parse error"));
+
+        let problems = lint_mapping(
+            "conditional:\n  tags: \"s3:PutBucketTagging\"\n",
+            false,
+            true,
+        );
+        assert!(problems[0].starts_with("Invalid structure"));
+    }
+
+    #[test]
+    fn collect_walks_provider_directories_and_renders_summary() {
+        let repo = TempDir::new().unwrap();
+        let resource_dir = repo.path().join("mappings/resource");
+        let google_dir = repo.path().join("mappings/google/resource");
+        std::fs::create_dir_all(&resource_dir).unwrap();
+        std::fs::create_dir_all(&google_dir).unwrap();
+        std::fs::write(
+            resource_dir.join("aws_s3_bucket.yaml"),
+            "allow:\n  - \"s3:CreateBucket\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            resource_dir.join("aws_sqs_queue.yaml"),
+            "allow:\n  - \"sqs.CreateQueue\"\n",
+        )
+        .unwrap();
+        std::fs::write(resource_dir.join("aws_vpc.skip"), "").unwrap();
+        // Never loaded, so not checked
+        std::fs::write(repo.path().join("mappings/notes.yaml"), "alow: []\n").unwrap();
+        std::fs::write(
+            google_dir.join("google_storage_bucket.json"),
+            r#"{"allow": ["storage.buckets.create"]}"#,
+        )
+        .unwrap();
+
+        let lint = MappingLint::collect(repo.path()).unwrap();
+
+        assert_eq!(lint.invalid_files(), 1);
+        assert!(matches!(lint.check(), Err(LintError::InvalidFiles(1, 3))));
+        assert_eq!(
+            lint.render(),
+            "ok       mappings/google/resource/google_storage_bucket.json\n\
+             ok       mappings/resource/aws_s3_bucket.yaml\n\
+             invalid  mappings/resource/aws_sqs_queue.yaml\n\
+             \x20          - 'allow' contains 'sqs.CreateQueue', which is not of the form service:Action\n\
+             3 mapping files checked, 1 invalid\n"
+        );
+    }
+}
@@ -1,11 +1,11 @@
 //! Mapping loader with in-memory caching.
 //!
-//! This module handles loading YAML mapping files from the cached repository
+//! This module handles loading YAML (or JSON) mapping files from the cached repository
 //! and maintains an in-memory cache to avoid repeated file I/O when multiple
 //! blocks of the same type are processed.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use thiserror::Error;
 
@@ -13,18 +13,28 @@ use super::schema::ActionMapping;
 use super::yaml_parser;
 use crate::terraform::BlockType;
 
-/// Maximum YAML file size (1 MB) to prevent resource exhaustion attacks.
-const MAX_YAML_FILE_SIZE: u64 = 1024 * 1024;
+/// Maximum mapping file size (1 MB) to prevent resource exhaustion attacks.
+pub(super) const MAX_MAPPING_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Block types with their own `mappings/{block_type}` directory.
+///
+/// Import blocks use the `import` section of the resource mappings.
+pub(super) const MAPPING_BLOCK_TYPES: [BlockType; 4] = [
+    BlockType::Resource,
+    BlockType::Data,
+    BlockType::Ephemeral,
+    BlockType::Action,
+];
 
 /// Result of looking up a mapping for a Terraform type.
 ///
 /// Represents the three possible outcomes:
-/// - `Found`: A `.yaml` mapping file exists with IAM permissions
+/// - `Found`: A `.yaml` or `.json` mapping file exists with IAM permissions
 /// - `Skipped`: A `.skip` file exists, marking the type as intentionally needing no permissions
 /// - `NotFound`: Neither file exists — the type is unmapped
 #[derive(Debug, Clone)]
 pub enum MappingLookup {
-    Found(ActionMapping),
+    Found(Box<ActionMapping>),
     Skipped,
     NotFound,
 }
@@ -80,7 +90,9 @@ impl MappingLoader {
 
     /// Loads a mapping for a given block.
     ///
-    /// Checks for a `.yaml` mapping file first, then a `.skip` file.
+    /// Checks for a `.yaml` mapping file first, then a `.json` mapping file,
+    /// then a `.skip` file. If both a `.yaml` and a `.json` file exist, a
+    /// warning naming both files is logged and the `.yaml` file is used.
     /// Results are cached in memory, so subsequent calls for the same block type
     /// will return the cached value without file I/O.
     ///
@@ -92,7 +104,7 @@ impl MappingLoader {
     ///
     /// # Returns
     ///
-    /// * `Ok(MappingLookup::Found(mapping))` - YAML mapping file exists and was parsed
+    /// * `Ok(MappingLookup::Found(mapping))` - YAML or JSON mapping file exists and was parsed
     /// * `Ok(MappingLookup::Skipped)` - A `.skip` file exists (type needs no permissions)
     /// * `Ok(MappingLookup::NotFound)` - Neither file exists or name is invalid
     /// * `Err(_)` - IO or parse error
@@ -123,29 +135,30 @@ impl MappingLoader {
             }
         }
 
-        // Not in cache — check for .yaml file first
+        // Not in cache — check for .yaml file first, then .json
         let block_type_dir = self
             .repo_path
-            .join("mappings")
-            .join(block_type.as_str());
+            .join(Self::mapping_dir(provider, block_type.as_str()));
 
         let yaml_path = block_type_dir.join(format!("{}.yaml", type_name));
+        let json_path = block_type_dir.join(format!("{}.json", type_name));
 
-        let lookup = if yaml_path.exists() {
-            log::debug!("Loading mapping from {:?}", yaml_path);
-
-            // Check file size before reading to prevent resource exhaustion
-            let metadata = std::fs::metadata(&yaml_path)?;
-            if metadata.len() > MAX_YAML_FILE_SIZE {
-                return Err(LoadError::FileTooLarge(yaml_path));
-            }
+        if let Some(warning) = Self::ambiguity_warning(&yaml_path, &json_path) {
+            log::warn!("{}", warning);
+        }
 
-            let content = std::fs::read_to_string(&yaml_path)?;
-            let mapping = yaml_parser::parse_mapping(&content)
-                .map_err(|e| LoadError::Parse(yaml_path.clone(), e.to_string()))?;
-            MappingLookup::Found(mapping)
+        let lookup = if yaml_path.exists() {
+            MappingLookup::Found(Box::new(Self::read_mapping_file(
+                &yaml_path,
+                yaml_parser::parse_mapping,
+            )?))
+        } else if json_path.exists() {
+            MappingLookup::Found(Box::new(Self::read_mapping_file(
+                &json_path,
+                yaml_parser::parse_json_mapping,
+            )?))
         } else {
-            // No .yaml file — check for .skip file
+            // No mapping file — check for .skip file
             let skip_path = block_type_dir.join(format!("{}.skip", type_name));
 
             if skip_path.exists() {
@@ -166,6 +179,203 @@ impl MappingLoader {
         Ok(lookup)
     }
 
+    /// Returns a warning if a type has both a `.yaml` and a `.json` mapping file.
+    ///
+    /// The `.yaml` file takes precedence, so the warning names the ignored file.
+    fn ambiguity_warning(yaml_path: &Path, json_path: &Path) -> Option<String> {
+        if !(yaml_path.exists() && json_path.exists()) {
+            return None;
+        }
+
+        Some(format!(
+            "Ambiguous mapping: both {} and {} exist, using {} and ignoring {}",
+            yaml_path.display(),
+            json_path.display(),
+            yaml_path.display(),
+            json_path.display()
+        ))
+    }
+
+    /// Reads and parses a single mapping file, enforcing the file size limit.
+    fn read_mapping_file(
+        path: &Path,
+        parse: fn(&str) -> Result<ActionMapping, yaml_parser::ParseError>,
+    ) -> Result<ActionMapping, LoadError> {
+        log::debug!("Loading mapping from {:?}", path);
+
+        // Check file size before reading to prevent resource exhaustion
+        let metadata = std::fs::metadata(path)?;
+        if metadata.len() > MAX_MAPPING_FILE_SIZE {
+            return Err(LoadError::FileTooLarge(path.to_path_buf()));
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        parse(&content).map_err(|e| LoadError::Parse(path.to_path_buf(), e.to_string()))
+    }
+
+    /// Lists the providers that have mappings in the repository.
+    ///
+    /// Returns `aws`, whose mappings live directly in `mappings/{block_type}`,
+    /// and the name of every other directory of `mappings/` (e.g., `google`
+    /// for `mappings/google/{block_type}`), sorted.
+    pub fn list_providers(&self) -> Result<Vec<String>, LoadError> {
+        let mut providers = vec!["aws".to_string()];
+
+        let mappings_dir = self.repo_path.join("mappings");
+        if mappings_dir.is_dir() {
+            for entry in std::fs::read_dir(&mappings_dir)? {
+                let path = entry?.path();
+                let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                let is_block_type_dir = MAPPING_BLOCK_TYPES
+                    .iter()
+                    .any(|block_type| block_type.as_str() == name);
+
+                if path.is_dir() && !is_block_type_dir && is_valid_path_component(name) {
+                    providers.push(name.to_string());
+                }
+            }
+        }
+
+        providers.sort();
+        providers.dedup();
+        Ok(providers)
+    }
+
+    /// Lists the `.yaml`, `.json`, and `.skip` files of a provider for a block
+    /// type, sorted.
+    ///
+    /// These are the files `load()` reads from `mapping_dir()`; a missing
+    /// directory yields an empty list.
+    pub fn list_files(
+        &self,
+        provider: &str,
+        block_type: BlockType,
+    ) -> Result<Vec<PathBuf>, LoadError> {
+        let block_type_dir = self
+            .repo_path
+            .join(Self::mapping_dir(provider, block_type.as_str()));
+        if !block_type_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&block_type_dir)? {
+            let path = entry?.path();
+            let has_mapping_extension = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| matches!(ext, "yaml" | "json" | "skip"));
+
+            if has_mapping_extension && path.is_file() {
+                files.push(path);
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+
+    /// Lists the type names that have a mapping or skip file for a block type.
+    ///
+    /// Returns the sorted, deduplicated file stems of `list_files()`.
+    pub fn list_types(
+        &self,
+        provider: &str,
+        block_type: BlockType,
+    ) -> Result<Vec<String>, LoadError> {
+        let mut types: Vec<String> = self
+            .list_files(provider, block_type)?
+            .iter()
+            .filter_map(|path| path.file_stem().and_then(|stem| stem.to_str()))
+            .map(str::to_string)
+            .collect();
+
+        types.sort();
+        types.dedup();
+        Ok(types)
+    }
+
+    /// Loads every mapping in the repository in a single pass.
+    ///
+    /// Walks `mappings/{resource,data,ephemeral,action}` of every provider of
+    /// `list_providers()` once and returns one `(block_type, type_name,
+    /// mapping)` entry per mapped type, sorted by provider, block type
+    /// directory, and type name. Skipped types are not included. All lookups
+    /// go through `load()`, so they are cached for later calls.
+    pub fn load_all(&self) -> Result<Vec<(BlockType, String, ActionMapping)>, LoadError> {
+        let mut mappings = Vec::new();
+
+        for provider in self.list_providers()? {
+            for block_type in MAPPING_BLOCK_TYPES {
+                for type_name in self.list_types(&provider, block_type)? {
+                    if let MappingLookup::Found(mapping) =
+                        self.load(&provider, block_type, &type_name)?
+                    {
+                        mappings.push((block_type, type_name, *mapping));
+                    }
+                }
+            }
+        }
+
+        Ok(mappings)
+    }
+
+    /// Lists the mapping and skip files of the repository that no lookup has
+    /// used so far (`--report-unused`).
+    ///
+    /// Returns `(block_type, type_name)` for every type of `list_types()`, of
+    /// any provider, that was never passed to `load()`, sorted by provider,
+    /// block type directory, and type name.
+    pub fn unused_types(&self) -> Result<Vec<(BlockType, String)>, LoadError> {
+        let mut unused = Vec::new();
+
+        for provider in self.list_providers()? {
+            for block_type in MAPPING_BLOCK_TYPES {
+                for type_name in self.list_types(&provider, block_type)? {
+                    let cache_key = format!("{}/{}/{}", provider, block_type.as_str(), type_name);
+                    if !self.cache.lock().unwrap().contains_key(&cache_key) {
+                        unused.push((block_type, type_name));
+                    }
+                }
+            }
+        }
+
+        Ok(unused)
+    }
+
+    /// Returns all mappings loaded so far, keyed like the cache.
+    ///
+    /// Keys have the form `"{provider}/{block_type}/{type_name}"` (e.g.,
+    /// `"aws/resource/aws_s3_bucket"`) and are sorted. Skipped and missing
+    /// types are not included.
+    pub fn loaded_mappings(&self) -> Vec<(String, ActionMapping)> {
+        let cache = self.cache.lock().unwrap();
+        let mut mappings: Vec<(String, ActionMapping)> = cache
+            .iter()
+            .filter_map(|(key, lookup)| match lookup {
+                MappingLookup::Found(mapping) => Some((key.clone(), mapping.as_ref().clone())),
+                MappingLookup::Skipped | MappingLookup::NotFound => None,
+            })
+            .collect();
+        mappings.sort_by(|(a, _), (b, _)| a.cmp(b));
+        mappings
+    }
+
+    /// Returns the directory of a provider's mappings for a block type,
+    /// relative to the repository root.
+    ///
+    /// AWS mappings live directly in `mappings/{block_type}`, those of other
+    /// providers in `mappings/{provider}/{block_type}`.
+    pub fn mapping_dir(provider: &str, block_type: &str) -> String {
+        if provider == "aws" {
+            format!("mappings/{}", block_type)
+        } else {
+            format!("mappings/{}/{}", provider, block_type)
+        }
+    }
+
     /// Extracts the provider name from a type name.
     ///
     /// # Arguments
@@ -225,6 +435,29 @@ mod tests {
         assert_eq!(MappingLoader::extract_provider("_resource"), None);
     }
 
+    #[test]
+    fn loader_reads_non_aws_mappings_from_provider_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let google_dir = temp_dir.path().join("mappings/google/resource");
+        fs::create_dir_all(&google_dir).unwrap();
+        fs::write(
+            google_dir.join("google_storage_bucket.yaml"),
+            "allow:\n  - storage.buckets.create\n",
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        let MappingLookup::Found(mapping) = loader
+            .load("google", BlockType::Resource, "google_storage_bucket")
+            .unwrap()
+        else {
+            panic!("expected google mapping to be found");
+        };
+        assert_eq!(mapping.allow, vec!["storage.buckets.create"]);
+        assert_eq!(MappingLoader::mapping_dir("aws", "data"), "mappings/data");
+    }
+
     #[test]
     fn loader_returns_not_found_for_missing_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -264,6 +497,86 @@ mod tests {
         }
     }
 
+    #[test]
+    fn unused_types_lists_mappings_never_looked_up() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("mappings/data")).unwrap();
+        for file in [
+            "resource/aws_s3_bucket.yaml",
+            "resource/aws_iam_role.json",
+            "resource/aws_caller_identity.skip",
+            "data/aws_s3_bucket.yaml",
+        ] {
+            fs::write(
+                temp_dir.path().join("mappings").join(file),
+                "allow:\n  - s3:ListBucket",
+            )
+            .unwrap();
+        }
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        loader
+            .load("aws", BlockType::Resource, "aws_s3_bucket")
+            .unwrap();
+        loader
+            .load("aws", BlockType::Resource, "aws_caller_identity")
+            .unwrap();
+
+        assert_eq!(
+            loader.unused_types().unwrap(),
+            vec![
+                (BlockType::Resource, "aws_iam_role".to_string()),
+                (BlockType::Data, "aws_s3_bucket".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn loaded_mappings_lists_only_found_mappings() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_iam_role.yaml"),
+            "allow:\n  - iam:CreateRole",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir
+                .path()
+                .join("mappings/resource/aws_caller_identity.skip"),
+            "",
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        for type_name in [
+            "aws_s3_bucket",
+            "aws_iam_role",
+            "aws_caller_identity",
+            "aws_nonexistent",
+        ] {
+            loader.load("aws", BlockType::Resource, type_name).unwrap();
+        }
+
+        let keys: Vec<String> = loader
+            .loaded_mappings()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec!["aws/resource/aws_iam_role", "aws/resource/aws_s3_bucket"]
+        );
+    }
+
     #[test]
     fn loader_caches_found_mappings() {
         let temp_dir = TempDir::new().unwrap();
@@ -319,7 +632,9 @@ mod tests {
         // Create mapping file for data source
         fs::create_dir_all(temp_dir.path().join("mappings/data")).unwrap();
         fs::write(
-            temp_dir.path().join("mappings/data/aws_availability_zones.yaml"),
+            temp_dir
+                .path()
+                .join("mappings/data/aws_availability_zones.yaml"),
             "allow:\n  - ec2:DescribeAvailabilityZones",
         )
         .unwrap();
@@ -442,7 +757,7 @@ conditional:
     fn loader_returns_error_for_oversized_file() {
         let temp_dir = TempDir::new().unwrap();
 
-        // Create a file larger than MAX_YAML_FILE_SIZE
+        // Create a file larger than MAX_MAPPING_FILE_SIZE
         fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
         let large_content = "a".repeat(2 * 1024 * 1024); // 2 MB
         fs::write(
@@ -465,17 +780,11 @@ conditional:
 
         // Create a .skip file (no .yaml)
         fs::create_dir_all(temp_dir.path().join("mappings/data")).unwrap();
-        fs::write(
-            temp_dir.path().join("mappings/data/aws_arn.skip"),
-            "",
-        )
-        .unwrap();
+        fs::write(temp_dir.path().join("mappings/data/aws_arn.skip"), "").unwrap();
 
         let loader = MappingLoader::new(temp_dir.path().to_path_buf());
 
-        let result = loader
-            .load("aws", BlockType::Data, "aws_arn")
-            .unwrap();
+        let result = loader.load("aws", BlockType::Data, "aws_arn").unwrap();
         assert!(matches!(result, MappingLookup::Skipped));
     }
 
@@ -484,11 +793,7 @@ conditional:
         let temp_dir = TempDir::new().unwrap();
 
         fs::create_dir_all(temp_dir.path().join("mappings/data")).unwrap();
-        fs::write(
-            temp_dir.path().join("mappings/data/aws_arn.skip"),
-            "",
-        )
-        .unwrap();
+        fs::write(temp_dir.path().join("mappings/data/aws_arn.skip"), "").unwrap();
 
         let loader = MappingLoader::new(temp_dir.path().to_path_buf());
 
@@ -530,6 +835,164 @@ conditional:
         }
     }
 
+    #[test]
+    fn loader_loads_json_mapping() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.json"),
+            r#"{
+  "allow": ["s3:CreateBucket"],
+  "deny": ["s3:GetObject"],
+  "conditional": { "tags": ["s3:PutBucketTagging"] }
+}"#,
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        let result = loader
+            .load("aws", BlockType::Resource, "aws_s3_bucket")
+            .unwrap();
+
+        match result {
+            MappingLookup::Found(mapping) => {
+                assert_eq!(mapping.allow, vec!["s3:CreateBucket"]);
+                assert_eq!(mapping.deny, vec!["s3:GetObject"]);
+                assert!(!mapping.conditional.is_none());
+            }
+            _ => panic!("Expected MappingLookup::Found for .json mapping"),
+        }
+    }
+
+    #[test]
+    fn loader_yaml_takes_priority_over_json() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.json"),
+            r#"{"allow": ["s3:DeleteBucket"]}"#,
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        let result = loader
+            .load("aws", BlockType::Resource, "aws_s3_bucket")
+            .unwrap();
+
+        match result {
+            MappingLookup::Found(mapping) => {
+                assert_eq!(mapping.allow, vec!["s3:CreateBucket"]);
+            }
+            _ => panic!("Expected MappingLookup::Found (yaml should take priority over json)"),
+        }
+    }
+
+    #[test]
+    fn loader_warns_when_yaml_and_json_mapping_exist() {
+        let temp_dir = TempDir::new().unwrap();
+        let resource_dir = temp_dir.path().join("mappings/resource");
+
+        fs::create_dir_all(&resource_dir).unwrap();
+        fs::write(
+            resource_dir.join("aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket",
+        )
+        .unwrap();
+        fs::write(
+            resource_dir.join("aws_s3_bucket.json"),
+            r#"{"allow": ["s3:DeleteBucket"]}"#,
+        )
+        .unwrap();
+
+        let warning = MappingLoader::ambiguity_warning(
+            &resource_dir.join("aws_s3_bucket.yaml"),
+            &resource_dir.join("aws_s3_bucket.json"),
+        )
+        .expect("both files exist, so a warning is expected");
+        assert!(warning.contains("aws_s3_bucket.yaml"));
+        assert!(warning.contains("aws_s3_bucket.json"));
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let result = loader
+            .load("aws", BlockType::Resource, "aws_s3_bucket")
+            .unwrap();
+
+        match result {
+            MappingLookup::Found(mapping) => {
+                assert_eq!(mapping.allow, vec!["s3:CreateBucket"]);
+            }
+            _ => panic!("Expected MappingLookup::Found from the .yaml mapping"),
+        }
+    }
+
+    #[test]
+    fn loader_no_warning_for_single_mapping_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let resource_dir = temp_dir.path().join("mappings/resource");
+
+        fs::create_dir_all(&resource_dir).unwrap();
+        fs::write(
+            resource_dir.join("aws_s3_bucket.json"),
+            r#"{"allow": ["s3:CreateBucket"]}"#,
+        )
+        .unwrap();
+
+        assert!(
+            MappingLoader::ambiguity_warning(
+                &resource_dir.join("aws_s3_bucket.yaml"),
+                &resource_dir.join("aws_s3_bucket.json"),
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn loader_json_takes_priority_over_skip() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/data")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/data/aws_arn.json"),
+            r#"{"allow": ["iam:GetRole"]}"#,
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("mappings/data/aws_arn.skip"), "").unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        let result = loader.load("aws", BlockType::Data, "aws_arn").unwrap();
+
+        assert!(matches!(result, MappingLookup::Found(_)));
+    }
+
+    #[test]
+    fn loader_returns_parse_error_for_invalid_json() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        // Valid YAML flow syntax, but not valid JSON
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.json"),
+            "{allow: [s3:CreateBucket]}",
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        let result = loader.load("aws", BlockType::Resource, "aws_s3_bucket");
+
+        assert!(matches!(result, Err(LoadError::Parse(_, _))));
+    }
+
     #[test]
     fn loader_rejects_path_traversal_in_skip_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -542,4 +1005,135 @@ conditional:
             .unwrap();
         assert!(matches!(result, MappingLookup::NotFound));
     }
+
+    #[test]
+    fn list_types_returns_sorted_unique_mapped_types() {
+        let temp_dir = TempDir::new().unwrap();
+        let resource_dir = temp_dir.path().join("mappings/resource");
+        fs::create_dir_all(&resource_dir).unwrap();
+        fs::write(resource_dir.join("aws_vpc.yaml"), "allow: []").unwrap();
+        fs::write(resource_dir.join("aws_s3_bucket.yaml"), "allow: []").unwrap();
+        fs::write(resource_dir.join("aws_s3_bucket.json"), "{}").unwrap();
+        fs::write(resource_dir.join("aws_arn.skip"), "").unwrap();
+        fs::write(resource_dir.join("README.md"), "").unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        assert_eq!(
+            loader.list_types("aws", BlockType::Resource).unwrap(),
+            vec!["aws_arn", "aws_s3_bucket", "aws_vpc"]
+        );
+        assert!(
+            loader
+                .list_types("aws", BlockType::Data)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn load_all_yields_every_mapping_once_with_its_block_type() {
+        let temp_dir = TempDir::new().unwrap();
+        let mappings_dir = temp_dir.path().join("mappings");
+        for block_type in ["resource", "data", "ephemeral", "action"] {
+            fs::create_dir_all(mappings_dir.join(block_type)).unwrap();
+        }
+        fs::write(
+            mappings_dir.join("resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket",
+        )
+        .unwrap();
+        fs::write(
+            mappings_dir.join("resource/aws_vpc.json"),
+            r#"{"allow": ["ec2:CreateVpc"]}"#,
+        )
+        .unwrap();
+        fs::write(
+            mappings_dir.join("data/aws_ami.yaml"),
+            "allow:\n  - ec2:DescribeImages",
+        )
+        .unwrap();
+        fs::write(mappings_dir.join("data/aws_arn.skip"), "").unwrap();
+        fs::write(
+            mappings_dir.join("ephemeral/aws_secretsmanager_secret_version.yaml"),
+            "allow:\n  - secretsmanager:GetSecretValue",
+        )
+        .unwrap();
+        fs::write(
+            mappings_dir.join("action/aws_lambda_invoke.yaml"),
+            "allow:\n  - lambda:InvokeFunction",
+        )
+        .unwrap();
+        fs::write(mappings_dir.join("resource/README.md"), "").unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        let mappings = loader.load_all().unwrap();
+
+        let entries: Vec<(BlockType, &str, &str)> = mappings
+            .iter()
+            .map(|(block_type, type_name, mapping)| {
+                (*block_type, type_name.as_str(), mapping.allow[0].as_str())
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (BlockType::Resource, "aws_s3_bucket", "s3:CreateBucket"),
+                (BlockType::Resource, "aws_vpc", "ec2:CreateVpc"),
+                (BlockType::Data, "aws_ami", "ec2:DescribeImages"),
+                (
+                    BlockType::Ephemeral,
+                    "aws_secretsmanager_secret_version",
+                    "secretsmanager:GetSecretValue"
+                ),
+                (
+                    BlockType::Action,
+                    "aws_lambda_invoke",
+                    "lambda:InvokeFunction"
+                ),
+            ]
+        );
+
+        // Every lookup went through the cache
+        assert_eq!(loader.loaded_mappings().len(), 5);
+    }
+
+    #[test]
+    fn load_all_without_mappings_directory_is_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        assert!(loader.load_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn load_all_includes_provider_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let mappings_dir = temp_dir.path().join("mappings");
+        fs::create_dir_all(mappings_dir.join("resource")).unwrap();
+        fs::create_dir_all(mappings_dir.join("google/resource")).unwrap();
+        fs::write(
+            mappings_dir.join("resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket",
+        )
+        .unwrap();
+        fs::write(
+            mappings_dir.join("google/resource/google_storage_bucket.yaml"),
+            "allow:\n  - storage.buckets.create",
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        assert_eq!(loader.list_providers().unwrap(), vec!["aws", "google"]);
+        let type_names: Vec<String> = loader
+            .load_all()
+            .unwrap()
+            .into_iter()
+            .map(|(_, type_name, _)| type_name)
+            .collect();
+        assert_eq!(type_names, vec!["aws_s3_bucket", "google_storage_bucket"]);
+        assert!(loader.unused_types().unwrap().is_empty());
+    }
 }
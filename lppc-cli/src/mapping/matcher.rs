@@ -4,24 +4,254 @@
 //! and resolves the required IAM permissions based on the attributes present
 //! in each block.
 
-use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
 use thiserror::Error;
 
+use super::allowlist::wildcard_match;
 use super::loader::{LoadError, MappingLoader, MappingLookup};
-use crate::terraform::{BlockType, TerraformConfig};
+use super::schema::{ActionMapping, ConditionalEvaluation};
+use super::suggest::closest_match;
+use crate::cli::ConflictResolution;
+use crate::diagnostics::Diagnostic;
+use crate::redact::account_id;
+use crate::terraform::{BlockType, TerraformBlock, TerraformConfig};
+
+/// Name of the `--group-by-account` group for groups without a literal account ID.
+pub const UNKNOWN_ACCOUNT_GROUP: &str = "unknown-account";
+
+/// Name of the `--group-by-service` group holding the `NotAction` statements,
+/// which cover every service but the excluded actions.
+pub const NOT_ACTION_GROUP: &str = "not-action";
+
+/// Entry of `GroupPermissions::conditions` for a grant without a condition
+/// (an empty IAM `Condition` object).
+pub const UNCONDITIONAL: &str = "{}";
+
+/// A `NotAction` list of one mapping: the statement allows (or denies) every
+/// action except `actions`.
+///
+/// Each list stays a statement of its own, keyed by its mapping, resources,
+/// and condition. The union of two lists would exclude fewer actions than
+/// either and so grant more than any mapping asked for.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct NotActionStatement {
+    /// Mapping the list comes from (e.g., "resource.aws_s3_bucket"), empty if
+    /// unknown (e.g., read back from a policy)
+    pub mapping: String,
+
+    /// Resource ARNs of the statement, `["*"]` if unscoped
+    pub resources: Vec<String>,
+
+    /// IAM `Condition` object of the statement as JSON text, if any
+    pub condition: Option<String>,
+
+    /// Excluded IAM actions, sorted
+    pub actions: Vec<String>,
+}
+
+impl NotActionStatement {
+    /// Creates a statement excluding the given actions on all resources,
+    /// without a condition.
+    pub fn new(mapping: &str, actions: impl IntoIterator<Item = String>) -> Self {
+        let mut actions: Vec<String> = actions.into_iter().collect();
+        actions.sort();
+        actions.dedup();
+        Self {
+            mapping: mapping.to_string(),
+            resources: vec!["*".to_string()],
+            condition: None,
+            actions,
+        }
+    }
+}
 
 /// Permissions for a single provider group, separating allow and deny.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct GroupPermissions {
     /// IAM actions to allow
     pub allow: HashSet<String>,
 
     /// IAM actions to deny
     pub deny: HashSet<String>,
+
+    /// Allow statements with `NotAction`, one per mapping list
+    pub not_allow: BTreeSet<NotActionStatement>,
+
+    /// Deny statements with `NotAction`, one per mapping list
+    pub not_deny: BTreeSet<NotActionStatement>,
+
+    /// Resource ARNs each allow action is scoped to, from the mappings'
+    /// `resources`. Contains `"*"` if a block grants the action
+    /// without a resolvable ARN; actions without an entry are not scoped.
+    pub resources: BTreeMap<String, BTreeSet<String>>,
+
+    /// IAM `Condition` objects (as JSON text) each allow action is granted
+    /// under, from the mappings' `conditions`. Contains `UNCONDITIONAL` if a
+    /// block grants the action without a condition; actions without an entry
+    /// are not conditional.
+    pub conditions: BTreeMap<String, BTreeSet<String>>,
+
+    /// Addresses of all blocks in the group (e.g., "module.vpc.aws_vpc.main"), sorted
+    pub sources: Vec<String>,
+}
+
+impl GroupPermissions {
+    /// Returns whether the group has no allow, deny, or `NotAction` entries.
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty()
+            && self.deny.is_empty()
+            && self.not_allow.is_empty()
+            && self.not_deny.is_empty()
+    }
+
+    /// Returns the sorted resource ARNs an allow action is scoped to, or
+    /// `["*"]` if it is not scoped.
+    pub fn resources_of(&self, action: &str) -> Vec<String> {
+        match self.resources.get(action) {
+            Some(arns) if !arns.contains("*") => arns.iter().cloned().collect(),
+            _ => vec!["*".to_string()],
+        }
+    }
+
+    /// Returns the sorted IAM `Condition` objects (as JSON text) an allow
+    /// action is granted under, one per alternative grant, or nothing if the
+    /// action is granted unconditionally.
+    pub fn conditions_of(&self, action: &str) -> Vec<String> {
+        match self.conditions.get(action) {
+            Some(conditions) if !conditions.contains(UNCONDITIONAL) => {
+                conditions.iter().cloned().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Adds the resources and conditions of `other`'s allow actions to those
+    /// of this group, e.g. when merging groups.
+    fn merge_scopes(&mut self, other: &GroupPermissions) {
+        for action in &other.allow {
+            self.resources
+                .entry(action.clone())
+                .or_default()
+                .extend(other.resources_of(action));
+
+            let mut conditions = other.conditions_of(action);
+            if conditions.is_empty() {
+                conditions.push(UNCONDITIONAL.to_string());
+            }
+            self.conditions
+                .entry(action.clone())
+                .or_default()
+                .extend(conditions);
+        }
+    }
+
+    /// Returns the actions present in both the allow and the deny set, sorted.
+    pub fn conflicting_actions(&self) -> Vec<String> {
+        let mut conflicts: Vec<String> = self.allow.intersection(&self.deny).cloned().collect();
+        conflicts.sort();
+        conflicts
+    }
+
+    /// Removes conflicting actions from one side according to the given strategy.
+    ///
+    /// With `DenyWins` the conflicting actions are removed from the allow set,
+    /// with `AllowWins` they are removed from the deny set.
+    ///
+    /// # Returns
+    ///
+    /// The sorted list of actions that were removed.
+    pub fn resolve_conflicts(&mut self, strategy: ConflictResolution) -> Vec<String> {
+        let conflicts = self.conflicting_actions();
+        let target = match strategy {
+            ConflictResolution::DenyWins => &mut self.allow,
+            ConflictResolution::AllowWins => &mut self.deny,
+        };
+
+        for action in &conflicts {
+            target.remove(action);
+        }
+
+        conflicts
+    }
+
+    /// Removes allow actions that a wildcard allow action of the same service
+    /// already grants, e.g. `s3:GetObject` next to `s3:Get*`.
+    ///
+    /// Only concrete actions are removed, and only by a wildcard with a
+    /// literal service prefix (so `*` and `*:*` never collapse anything) whose
+    /// resources cover those of the action, and that is either unconditional
+    /// or granted under the same conditions. The deny set is not changed.
+    ///
+    /// # Returns
+    ///
+    /// The removed actions as `"{action} (covered by {wildcard})"`, sorted.
+    pub fn collapse_wildcards(&mut self) -> Vec<String> {
+        let wildcards: Vec<&String> = self
+            .allow
+            .iter()
+            .filter(|action| action.contains(['*', '?']))
+            .collect();
+
+        let mut collapsed: Vec<(String, String)> = Vec::new();
+        for action in self
+            .allow
+            .iter()
+            .filter(|action| !action.contains(['*', '?']))
+        {
+            let Some((service, name)) = action.split_once(':') else {
+                continue;
+            };
+            let action_resources = self.resources_of(action);
+            let action_conditions = self.conditions_of(action);
+
+            let mut covering: Vec<&String> = wildcards
+                .iter()
+                .copied()
+                .filter(|wildcard| {
+                    wildcard.split_once(':').is_some_and(|(prefix, pattern)| {
+                        prefix.eq_ignore_ascii_case(service)
+                            && wildcard_match(&pattern.to_lowercase(), &name.to_lowercase())
+                    })
+                })
+                .filter(|wildcard| {
+                    let resources = self.resources_of(wildcard);
+                    resources == ["*"]
+                        || action_resources
+                            .iter()
+                            .all(|resource| resources.contains(resource))
+                })
+                .filter(|wildcard| {
+                    let conditions = self.conditions_of(wildcard);
+                    conditions.is_empty() || conditions == action_conditions
+                })
+                .collect();
+            covering.sort();
+
+            if let Some(wildcard) = covering.first() {
+                collapsed.push((action.clone(), wildcard.to_string()));
+            }
+        }
+
+        for (action, _) in &collapsed {
+            self.allow.remove(action);
+            self.resources.remove(action);
+            self.conditions.remove(action);
+        }
+
+        let mut removed: Vec<String> = collapsed
+            .into_iter()
+            .map(|(action, wildcard)| format!("{} (covered by {})", action, wildcard))
+            .collect();
+        removed.sort();
+        removed
+    }
 }
 
 /// Result of permission matching for a Terraform configuration.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct PermissionResult {
     /// Map of output name to its resolved permissions.
     /// Key: Output name (e.g., "NetworkDeployer")
@@ -31,6 +261,419 @@ pub struct PermissionResult {
     /// Blocks that had no mapping file available.
     /// These require manual permission review.
     pub missing_mappings: Vec<MissingMapping>,
+
+    /// Sorted names of groups that have blocks but resolved no allow or deny
+    /// actions. They are not part of `groups`.
+    pub empty_groups: Vec<String>,
+}
+
+impl PermissionResult {
+    /// Reports actions that are both allowed and denied within a group.
+    ///
+    /// If a strategy is given, the conflicts are resolved in place; otherwise
+    /// both statements are kept.
+    ///
+    /// # Returns
+    ///
+    /// One diagnostic per group with conflicts: a warning if they were kept
+    /// (the Deny shadows the Allow), informational if they were resolved.
+    pub fn resolve_conflicts(&mut self, strategy: Option<ConflictResolution>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut names: Vec<&String> = self.groups.keys().collect();
+        names.sort();
+        let names: Vec<String> = names.into_iter().cloned().collect();
+
+        for name in names {
+            let Some(permissions) = self.groups.get_mut(&name) else {
+                continue;
+            };
+
+            let conflicts = match strategy {
+                Some(strategy) => permissions.resolve_conflicts(strategy),
+                None => permissions.conflicting_actions(),
+            };
+
+            if conflicts.is_empty() {
+                continue;
+            }
+
+            let resolution = match strategy {
+                Some(ConflictResolution::DenyWins) => "removed from allow",
+                Some(ConflictResolution::AllowWins) => "removed from deny",
+                None => "kept in both statements",
+            };
+
+            let message = format!(
+                "{}: {} action(s) are both allowed and denied ({}): {}",
+                name,
+                conflicts.len(),
+                resolution,
+                conflicts.join(", ")
+            );
+            diagnostics.push(match strategy {
+                Some(_) => Diagnostic::info(message),
+                None => Diagnostic::warn(message),
+            });
+        }
+
+        diagnostics
+    }
+
+    /// Removes allow actions covered by a wildcard allow action of the same
+    /// group (`--collapse-wildcards`); see `GroupPermissions::collapse_wildcards`.
+    ///
+    /// # Returns
+    ///
+    /// The removed actions as `"{group}: {action} (covered by {wildcard})"`, sorted.
+    pub fn collapse_wildcards(&mut self) -> Vec<String> {
+        let mut removed: Vec<String> = self
+            .groups
+            .iter_mut()
+            .flat_map(|(name, permissions)| {
+                permissions
+                    .collapse_wildcards()
+                    .into_iter()
+                    .map(move |action| format!("{}: {}", name, action))
+            })
+            .collect();
+        removed.sort();
+        removed
+    }
+
+    /// Merges groups whose allow, deny, and `NotAction` sets are identical.
+    ///
+    /// The merged group is named after all of its groups, sorted and joined
+    /// with `+` (e.g., "DnsDeployer+NetworkDeployer"), and keeps the sorted
+    /// union of their sources. Groups with a unique permission set are kept
+    /// unchanged.
+    pub fn dedupe_identical_groups(&mut self) {
+        type PermissionKey = (
+            Vec<(String, Vec<String>, Vec<String>)>,
+            Vec<String>,
+            BTreeSet<NotActionStatement>,
+            BTreeSet<NotActionStatement>,
+        );
+        let sorted = |actions: &HashSet<String>| -> Vec<String> {
+            let mut actions: Vec<String> = actions.iter().cloned().collect();
+            actions.sort();
+            actions
+        };
+        let mut by_permissions: BTreeMap<PermissionKey, Vec<String>> = BTreeMap::new();
+
+        for (name, permissions) in &self.groups {
+            // Allow actions scoped to different resources or conditions are
+            // different permissions
+            let mut allow: Vec<(String, Vec<String>, Vec<String>)> = permissions
+                .allow
+                .iter()
+                .map(|action| {
+                    (
+                        action.clone(),
+                        permissions.resources_of(action),
+                        permissions.conditions_of(action),
+                    )
+                })
+                .collect();
+            allow.sort();
+
+            by_permissions
+                .entry((
+                    allow,
+                    sorted(&permissions.deny),
+                    permissions.not_allow.clone(),
+                    permissions.not_deny.clone(),
+                ))
+                .or_default()
+                .push(name.clone());
+        }
+
+        for mut names in by_permissions.into_values() {
+            if names.len() < 2 {
+                continue;
+            }
+            names.sort();
+
+            let mut merged: Option<GroupPermissions> = None;
+            for name in &names {
+                let Some(permissions) = self.groups.remove(name) else {
+                    continue;
+                };
+                match &mut merged {
+                    Some(merged) => merged.sources.extend(permissions.sources),
+                    None => merged = Some(permissions),
+                }
+            }
+
+            let Some(mut merged) = merged else {
+                continue;
+            };
+            merged.sources.sort();
+            merged.sources.dedup();
+
+            let merged_name = names.join("+");
+            log::info!(
+                "{}: merged {} groups with identical permissions",
+                merged_name,
+                names.len()
+            );
+            self.groups.insert(merged_name, merged);
+        }
+    }
+
+    /// Replaces the groups with one group per AWS service prefix.
+    ///
+    /// Each service group (e.g., "s3") holds the union of that service's allow
+    /// and deny actions across all groups, and the sorted union of the sources
+    /// of the groups that contributed to it. Service prefixes are lowercased;
+    /// actions without a prefix (e.g., `*`) end up in "unknown". `NotAction`
+    /// statements span all services and are kept whole in `NOT_ACTION_GROUP`.
+    pub fn regroup_by_service(&mut self) {
+        let mut services: BTreeMap<String, GroupPermissions> = BTreeMap::new();
+
+        for permissions in self.groups.values() {
+            let mut contributed: HashSet<String> = HashSet::new();
+
+            for (actions, is_deny) in [(&permissions.allow, false), (&permissions.deny, true)] {
+                for action in actions {
+                    let service = action
+                        .split_once(':')
+                        .map_or("unknown".to_string(), |(prefix, _)| prefix.to_lowercase());
+                    let group = services.entry(service.clone()).or_default();
+                    if is_deny {
+                        group.deny.insert(action.clone());
+                    } else {
+                        group.allow.insert(action.clone());
+                        group
+                            .resources
+                            .entry(action.clone())
+                            .or_default()
+                            .extend(permissions.resources_of(action));
+                        let mut conditions = permissions.conditions_of(action);
+                        if conditions.is_empty() {
+                            conditions.push(UNCONDITIONAL.to_string());
+                        }
+                        group
+                            .conditions
+                            .entry(action.clone())
+                            .or_default()
+                            .extend(conditions);
+                    }
+                    contributed.insert(service);
+                }
+            }
+
+            if !permissions.not_allow.is_empty() || !permissions.not_deny.is_empty() {
+                let group = services.entry(NOT_ACTION_GROUP.to_string()).or_default();
+                group
+                    .not_allow
+                    .extend(permissions.not_allow.iter().cloned());
+                group.not_deny.extend(permissions.not_deny.iter().cloned());
+                contributed.insert(NOT_ACTION_GROUP.to_string());
+            }
+
+            for service in contributed {
+                if let Some(group) = services.get_mut(&service) {
+                    group.sources.extend(permissions.sources.iter().cloned());
+                }
+            }
+        }
+
+        for group in services.values_mut() {
+            group.sources.sort();
+            group.sources.dedup();
+        }
+
+        log::info!(
+            "Regrouped {} group(s) into {} service group(s)",
+            self.groups.len(),
+            services.len()
+        );
+        self.groups = services.into_iter().collect();
+    }
+
+    /// Replaces the groups with one group per AWS account.
+    ///
+    /// The account of a group is the account ID segment of its role ARN in
+    /// `role_arns` (keyed by group name). Groups without a role ARN, or whose
+    /// account segment is not a literal 12-digit ID (e.g., `${var.account_id}`
+    /// or `*`), end up in `UNKNOWN_ACCOUNT_GROUP`. Each account group holds the
+    /// union of the allow, deny, and sources of its groups, and all of their
+    /// `NotAction` statements.
+    pub fn regroup_by_account(&mut self, role_arns: &HashMap<String, String>) {
+        let mut accounts: BTreeMap<String, GroupPermissions> = BTreeMap::new();
+
+        for (name, permissions) in &self.groups {
+            let account = role_arns
+                .get(name)
+                .and_then(|arn| account_id(arn))
+                .unwrap_or(UNKNOWN_ACCOUNT_GROUP)
+                .to_string();
+            log::debug!("Group {} belongs to account {}", name, account);
+
+            let group = accounts.entry(account).or_default();
+            group.merge_scopes(permissions);
+            group.allow.extend(permissions.allow.iter().cloned());
+            group.deny.extend(permissions.deny.iter().cloned());
+            group
+                .not_allow
+                .extend(permissions.not_allow.iter().cloned());
+            group.not_deny.extend(permissions.not_deny.iter().cloned());
+            group.sources.extend(permissions.sources.iter().cloned());
+        }
+
+        for group in accounts.values_mut() {
+            group.sources.sort();
+            group.sources.dedup();
+        }
+
+        log::info!(
+            "Regrouped {} group(s) into {} account group(s)",
+            self.groups.len(),
+            accounts.len()
+        );
+        self.groups = accounts.into_iter().collect();
+    }
+
+    /// Keeps only the groups whose output name matches `pattern` (`--groups`).
+    ///
+    /// The pattern supports `*` and `?` wildcards and is matched against the
+    /// whole name. Returns the sorted names of the dropped groups.
+    pub fn retain_groups(&mut self, pattern: &str) -> Vec<String> {
+        let mut dropped: Vec<String> = self
+            .groups
+            .keys()
+            .filter(|name| !wildcard_match(pattern, name))
+            .cloned()
+            .collect();
+        dropped.sort();
+
+        for name in &dropped {
+            self.groups.remove(name);
+        }
+        dropped
+    }
+
+    /// Returns a copy of the result with all groups folded into one group.
+    ///
+    /// The group is named `name` and holds the union of the allow, deny, and
+    /// sources of all groups, and all of their `NotAction` statements. Missing
+    /// mappings and empty groups are kept.
+    pub fn combined(&self, name: &str) -> PermissionResult {
+        let mut combined = GroupPermissions::default();
+
+        for permissions in self.groups.values() {
+            combined.merge_scopes(permissions);
+            combined.allow.extend(permissions.allow.iter().cloned());
+            combined.deny.extend(permissions.deny.iter().cloned());
+            combined
+                .not_allow
+                .extend(permissions.not_allow.iter().cloned());
+            combined
+                .not_deny
+                .extend(permissions.not_deny.iter().cloned());
+            combined.sources.extend(permissions.sources.iter().cloned());
+        }
+        combined.sources.sort();
+        combined.sources.dedup();
+
+        log::info!("Combined {} group(s) into {}", self.groups.len(), name);
+        let groups = if self.groups.is_empty() {
+            HashMap::new()
+        } else {
+            HashMap::from([(name.to_string(), combined)])
+        };
+
+        PermissionResult {
+            groups,
+            missing_mappings: self.missing_mappings.clone(),
+            empty_groups: self.empty_groups.clone(),
+        }
+    }
+}
+
+impl PermissionResult {
+    /// Checks that the generated groups are exactly the expected ones.
+    ///
+    /// Fails if a group exists that is not in `expected` (e.g., a new,
+    /// unreviewed deployer role) or an expected group was not generated.
+    pub fn check_expected_groups(&self, expected: &[String]) -> Result<(), MatchError> {
+        let expected: HashSet<&str> = expected.iter().map(String::as_str).collect();
+        let actual: HashSet<&str> = self.groups.keys().map(String::as_str).collect();
+
+        let mut unexpected: Vec<&str> = actual.difference(&expected).copied().collect();
+        unexpected.sort();
+        let mut missing: Vec<&str> = expected.difference(&actual).copied().collect();
+        missing.sort();
+
+        let mut problems = Vec::new();
+        if !unexpected.is_empty() {
+            problems.push(format!("unexpected {}", unexpected.join(", ")));
+        }
+        if !missing.is_empty() {
+            problems.push(format!("missing {}", missing.join(", ")));
+        }
+
+        if problems.is_empty() {
+            return Ok(());
+        }
+
+        Err(MatchError::UnexpectedGroups(problems.join("; ")))
+    }
+
+    /// Fails if any group with blocks resolved no allow and no deny actions.
+    ///
+    /// Such groups are left out of `groups` (and the output), which usually
+    /// means every block type of the group is missing a mapping.
+    pub fn check_empty_groups(&self) -> Result<(), MatchError> {
+        if self.empty_groups.is_empty() {
+            return Ok(());
+        }
+
+        Err(MatchError::EmptyGroups(self.empty_groups.join(", ")))
+    }
+
+    /// Fails if any Terraform type has no mapping (`--fail-on-missing-mappings`).
+    ///
+    /// The types are listed as `block_type.type_name`, sorted.
+    pub fn check_missing_mappings(&self) -> Result<(), MatchError> {
+        if self.missing_mappings.is_empty() {
+            return Ok(());
+        }
+
+        let mut types: Vec<String> = self
+            .missing_mappings
+            .iter()
+            .map(|missing| format!("{}.{}", missing.block_type.as_str(), missing.type_name))
+            .collect();
+        types.sort();
+        Err(MatchError::MissingMappings(types.join(", ")))
+    }
+
+    /// Suggests the closest existing mapping type for every missing mapping.
+    ///
+    /// The mapping files of each provider and block type are listed once and
+    /// compared to the missing type by edit distance (see
+    /// `suggest::closest_match`). Missing mappings without a close enough match
+    /// keep `suggestion: None`.
+    pub fn suggest_missing(&mut self, loader: &MappingLoader) -> Result<(), LoadError> {
+        let mut types_by_block_type: HashMap<(String, BlockType), Vec<String>> = HashMap::new();
+
+        for missing in &mut self.missing_mappings {
+            let provider = MappingLoader::extract_provider(&missing.type_name).unwrap_or("unknown");
+            let known_types =
+                match types_by_block_type.entry((provider.to_string(), missing.block_type)) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(loader.list_types(provider, missing.block_type)?)
+                    }
+                };
+
+            missing.suggestion =
+                closest_match(&missing.type_name, known_types).map(|s| s.to_string());
+        }
+
+        Ok(())
+    }
 }
 
 /// Represents a Terraform block with no corresponding mapping file.
@@ -44,6 +687,83 @@ pub struct MissingMapping {
 
     /// The expected path where the mapping file should be
     pub expected_path: String,
+
+    /// The closest existing mapping type, set by `PermissionResult::suggest_missing`
+    pub suggestion: Option<String>,
+}
+
+/// How the conditional actions of a single block were resolved.
+#[derive(Debug, Clone)]
+pub struct ConditionalExplanation {
+    /// The block address (e.g., "module.vpc.aws_vpc.main")
+    pub address: String,
+
+    /// The block type of the mapping that was evaluated
+    pub block_type: BlockType,
+
+    /// The type name (e.g., "aws_s3_bucket")
+    pub type_name: String,
+
+    /// Whether a mapping file exists for the block's type
+    pub mapping_found: bool,
+
+    /// One entry per conditional key of the mapping, sorted by key
+    pub evaluations: Vec<ConditionalEvaluation>,
+}
+
+impl ConditionalExplanation {
+    /// Renders the explanation as a table for `--explain-conditional`.
+    ///
+    /// ```text
+    /// aws_route53_zone.main (resource aws_route53_zone)
+    /// KEY         PRESENT  ACTIONS
+    /// tags        yes      added: route53:ChangeTagsForResource
+    /// vpc.vpc_id  no       not added: route53:AssociateVPCWithHostedZone
+    /// ```
+    pub fn render(&self) -> String {
+        let mut output = format!(
+            "{} ({} {})\n",
+            self.address,
+            self.block_type.as_str(),
+            self.type_name
+        );
+
+        if !self.mapping_found {
+            output.push_str("No mapping found\n");
+            return output;
+        }
+        if self.evaluations.is_empty() {
+            output.push_str("Mapping has no conditional keys\n");
+            return output;
+        }
+
+        let keys: Vec<String> = self.evaluations.iter().map(|e| e.path.join(".")).collect();
+        let width = keys
+            .iter()
+            .map(String::len)
+            .max()
+            .unwrap_or(0)
+            .max("KEY".len());
+
+        let _ = writeln!(output, "{:<width$}  PRESENT  ACTIONS", "KEY");
+        for (key, evaluation) in keys.iter().zip(&self.evaluations) {
+            let (present, outcome) = if evaluation.present {
+                ("yes", "added")
+            } else {
+                ("no", "not added")
+            };
+            let _ = writeln!(
+                output,
+                "{:<width$}  {:<7}  {}: {}",
+                key,
+                present,
+                outcome,
+                evaluation.actions.join(", ")
+            );
+        }
+
+        output
+    }
 }
 
 /// Errors that can occur during permission matching.
@@ -51,28 +771,57 @@ pub struct MissingMapping {
 pub enum MatchError {
     #[error("Load error: {0}")]
     Load(#[from] LoadError),
+
+    #[error("Groups differ from the expected groups: {0}")]
+    UnexpectedGroups(String),
+
+    #[error("Groups with resources resolved no permissions: {0}")]
+    EmptyGroups(String),
+
+    #[error("Terraform types without a mapping: {0}")]
+    MissingMappings(String),
+
+    #[error("Policies differ from {0} in: {1}")]
+    PolicyChanges(String, String),
 }
 
 /// Matches Terraform blocks to IAM permissions.
 pub struct PermissionMatcher<'a> {
     loader: &'a MappingLoader,
+    block_types: Vec<BlockType>,
 }
 
 impl<'a> PermissionMatcher<'a> {
     /// Creates a new permission matcher with the given loader.
     pub fn new(loader: &'a MappingLoader) -> Self {
-        Self { loader }
+        Self {
+            loader,
+            block_types: BlockType::ALL.to_vec(),
+        }
+    }
+
+    /// Only resolves blocks of the given types (`--block-types`); blocks of
+    /// other types are skipped as if they were not in the configuration.
+    pub fn with_block_types(mut self, block_types: &[BlockType]) -> Self {
+        self.block_types = block_types.to_vec();
+        self
     }
 
     /// Resolves permissions for all blocks in the Terraform configuration.
     ///
     /// This method:
     /// 1. Iterates through all provider groups
-    /// 2. For each block, loads the corresponding mapping file
+    /// 2. For each block, loads the corresponding mapping file (cached by the
+    ///    loader, so repeated types read and parse their file only once)
     /// 3. Adds allow actions to the allow permission set
-    /// 4. Adds deny actions to the deny permission set
+    /// 4. Adds deny actions to the deny permission set, and each `not_allow` /
+    ///    `not_deny` list as a `NotAction` statement of its own
     /// 5. Resolves conditional actions into the allow permission set
-    /// 6. Tracks any blocks without mapping files
+    /// 6. For `import` blocks, adds the target resource mapping's import actions
+    ///    to the allow permission set instead
+    /// 7. Tracks any blocks without mapping files
+    ///
+    /// Blocks whose type is not in `with_block_types` are skipped entirely.
     ///
     /// # Arguments
     ///
@@ -86,13 +835,40 @@ impl<'a> PermissionMatcher<'a> {
         let mut groups: HashMap<String, GroupPermissions> = HashMap::new();
         let mut missing_mappings: Vec<MissingMapping> = Vec::new();
         let mut seen_types: HashSet<(BlockType, String)> = HashSet::new();
+        let mut empty_groups: Vec<String> = Vec::new();
 
         for (output_name, group) in &config.provider_groups {
             let mut group_allow_permissions: HashSet<String> = HashSet::new();
             let mut group_deny_permissions: HashSet<String> = HashSet::new();
-
-            for block in &group.blocks {
-                let type_key = (block.block_type, block.type_name.clone());
+            let mut group_not_allow_statements: BTreeSet<NotActionStatement> = BTreeSet::new();
+            let mut group_not_deny_statements: BTreeSet<NotActionStatement> = BTreeSet::new();
+            let mut group_resources: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+            let mut group_conditions: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+            let mut allow = |action: &String, resources: &[String], condition: &str| {
+                group_allow_permissions.insert(action.clone());
+                group_resources
+                    .entry(action.clone())
+                    .or_default()
+                    .extend(resources.iter().cloned());
+                group_conditions
+                    .entry(action.clone())
+                    .or_default()
+                    .insert(condition.to_string());
+            };
+
+            let blocks: Vec<&TerraformBlock> = group
+                .blocks
+                .iter()
+                .filter(|block| self.block_types.contains(&block.block_type))
+                .collect();
+
+            for block in &blocks {
+                // Import blocks use the `import` section of the target resource's mapping
+                let mapping_block_type = match block.block_type {
+                    BlockType::Import => BlockType::Resource,
+                    other => other,
+                };
+                let type_key = (mapping_block_type, block.type_name.clone());
 
                 // Load mapping (only report missing once per type)
                 let provider =
@@ -100,13 +876,28 @@ impl<'a> PermissionMatcher<'a> {
 
                 match self
                     .loader
-                    .load(provider, block.block_type, &block.type_name)?
+                    .load(provider, mapping_block_type, &block.type_name)?
                 {
+                    MappingLookup::Found(mapping) if block.block_type == BlockType::Import => {
+                        let resources = mapping.resources_of("import", &block.attribute_values);
+                        let condition = Self::condition_of(&mapping, "import");
+                        for action in &mapping.import {
+                            allow(action, &resources, &condition);
+                        }
+
+                        log::debug!(
+                            "Resolved {} import actions for {}",
+                            mapping.import.len(),
+                            block.display_address()
+                        );
+                    }
                     MappingLookup::Found(mapping) => {
-                        // Add allow actions
+                        // Add allow actions, scoped to the block's resources if their ARNs are known
                         let allow_count = mapping.allow.len();
+                        let resources = mapping.resources_of("allow", &block.attribute_values);
+                        let condition = Self::condition_of(&mapping, "allow");
                         for action in &mapping.allow {
-                            group_allow_permissions.insert(action.clone());
+                            allow(action, &resources, &condition);
                         }
 
                         // Add deny actions
@@ -115,12 +906,43 @@ impl<'a> PermissionMatcher<'a> {
                             group_deny_permissions.insert(action.clone());
                         }
 
+                        // NotAction lists invert the statement, so each stays a statement of its own
+                        let mapping_name =
+                            format!("{}.{}", mapping_block_type.as_str(), block.type_name);
+                        for (actions, group, statements) in [
+                            (
+                                &mapping.not_allow,
+                                "not_allow",
+                                &mut group_not_allow_statements,
+                            ),
+                            (
+                                &mapping.not_deny,
+                                "not_deny",
+                                &mut group_not_deny_statements,
+                            ),
+                        ] {
+                            if actions.is_empty() {
+                                continue;
+                            }
+                            statements.insert(NotActionStatement {
+                                resources: mapping.resources_of(group, &block.attribute_values),
+                                condition: mapping
+                                    .conditions
+                                    .get(group)
+                                    .map(|condition| condition.to_string()),
+                                ..NotActionStatement::new(&mapping_name, actions.iter().cloned())
+                            });
+                        }
+
                         // Resolve conditional actions into allow permissions
                         let conditional_actions =
                             mapping.conditional.resolve(&block.present_attributes);
                         let conditional_count = conditional_actions.len();
-                        for action in conditional_actions {
-                            group_allow_permissions.insert(action);
+                        let resources =
+                            mapping.resources_of("conditional", &block.attribute_values);
+                        let condition = Self::condition_of(&mapping, "conditional");
+                        for action in &conditional_actions {
+                            allow(action, &resources, &condition);
                         }
 
                         log::debug!(
@@ -144,50 +966,141 @@ impl<'a> PermissionMatcher<'a> {
                         if !seen_types.contains(&type_key) {
                             seen_types.insert(type_key);
                             missing_mappings.push(MissingMapping {
-                                block_type: block.block_type,
+                                block_type: mapping_block_type,
                                 type_name: block.type_name.clone(),
                                 expected_path: format!(
-                                    "mappings/{}/{}.yaml",
-                                    block.block_type.as_str(),
+                                    "{}/{}.yaml",
+                                    MappingLoader::mapping_dir(
+                                        provider,
+                                        mapping_block_type.as_str()
+                                    ),
                                     block.type_name
                                 ),
+                                suggestion: None,
                             });
                         }
                     }
                 }
             }
 
-            if !group_allow_permissions.is_empty() || !group_deny_permissions.is_empty() {
+            if !group_allow_permissions.is_empty()
+                || !group_deny_permissions.is_empty()
+                || !group_not_allow_statements.is_empty()
+                || !group_not_deny_statements.is_empty()
+            {
+                let mut sources: Vec<String> =
+                    blocks.iter().map(|block| block.display_address()).collect();
+                sources.sort();
+                sources.dedup();
+
                 groups.insert(
                     output_name.clone(),
                     GroupPermissions {
                         allow: group_allow_permissions,
                         deny: group_deny_permissions,
+                        not_allow: group_not_allow_statements,
+                        not_deny: group_not_deny_statements,
+                        resources: group_resources,
+                        conditions: group_conditions,
+                        sources,
                     },
                 );
+            } else if !blocks.is_empty() {
+                log::debug!(
+                    "Group {} has {} block(s) but no resolved actions",
+                    output_name,
+                    blocks.len()
+                );
+                empty_groups.push(output_name.clone());
             }
         }
 
-        // Handle unmapped blocks (no provider found)
+        // Unmapped blocks (no provider found) are reported as diagnostics
         for block in &config.unmapped_blocks {
-            log::warn!(
+            log::debug!(
                 "Block {}.{} could not be mapped to a provider",
                 block.block_type.as_str(),
                 block.type_name
             );
         }
 
+        empty_groups.sort();
+
         Ok(PermissionResult {
             groups,
             missing_mappings,
+            empty_groups,
         })
     }
+
+    /// Returns the IAM `Condition` of a mapping's action group as JSON text,
+    /// or `UNCONDITIONAL` if the group has none.
+    fn condition_of(mapping: &ActionMapping, group: &str) -> String {
+        mapping.conditions.get(group).map_or_else(
+            || UNCONDITIONAL.to_string(),
+            |condition| condition.to_string(),
+        )
+    }
+
+    /// Explains which conditional keys of a block's mapping fired.
+    ///
+    /// Evaluates the block's attributes exactly as `resolve()` does, but also
+    /// reports the conditional keys whose attributes are absent. `import`
+    /// blocks have no conditional actions, so only their mapping is checked.
+    ///
+    /// # Returns
+    ///
+    /// `None` if no block in the configuration has the given address.
+    pub fn explain_conditionals(
+        &self,
+        config: &TerraformConfig,
+        address: &str,
+    ) -> Result<Option<ConditionalExplanation>, MatchError> {
+        let Some(block) = config
+            .provider_groups
+            .values()
+            .flat_map(|group| &group.blocks)
+            .find(|block| block.address == address)
+        else {
+            return Ok(None);
+        };
+
+        let mapping_block_type = match block.block_type {
+            BlockType::Import => BlockType::Resource,
+            other => other,
+        };
+        let provider = MappingLoader::extract_provider(&block.type_name).unwrap_or("unknown");
+
+        let (mapping_found, evaluations) =
+            match self
+                .loader
+                .load(provider, mapping_block_type, &block.type_name)?
+            {
+                MappingLookup::Found(_) if block.block_type == BlockType::Import => {
+                    (true, Vec::new())
+                }
+                MappingLookup::Found(mapping) => (
+                    true,
+                    mapping.conditional.evaluate(&block.present_attributes),
+                ),
+                MappingLookup::Skipped => (true, Vec::new()),
+                MappingLookup::NotFound => (false, Vec::new()),
+            };
+
+        Ok(Some(ConditionalExplanation {
+            address: block.address.clone(),
+            block_type: block.block_type,
+            type_name: block.type_name.clone(),
+            mapping_found,
+            evaluations,
+        }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::terraform::{ProviderGroup, TerraformBlock};
+    use crate::terraform::{Instances, ProviderGroup, TerraformBlock};
     use std::fs;
     use tempfile::TempDir;
 
@@ -201,8 +1114,11 @@ mod tests {
             type_name: type_name.to_string(),
             name: "test".to_string(),
             provider_config_key: "aws".to_string(),
+            attribute_values: HashMap::new(),
             present_attributes,
             address: format!("{}.{}.test", block_type.as_str(), type_name),
+            managed_policy_arns: Vec::new(),
+            instances: Instances::Single,
         }
     }
 
@@ -210,6 +1126,10 @@ mod tests {
         TerraformConfig {
             provider_groups: groups,
             unmapped_blocks: Vec::new(),
+            provider_version_constraints: HashMap::new(),
+            skipped_modules: Vec::new(),
+            unresolved_modules: Vec::new(),
+            module_tree: None,
         }
     }
 
@@ -226,6 +1146,55 @@ mod tests {
         assert!(result.missing_mappings.is_empty());
     }
 
+    #[test]
+    fn resolve_reads_each_mapping_file_once_for_repeated_types() {
+        let temp_dir = TempDir::new().unwrap();
+        let mapping_path = temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml");
+        fs::create_dir_all(mapping_path.parent().unwrap()).unwrap();
+        fs::write(&mapping_path, "allow:\n  - s3:CreateBucket").unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let matcher = PermissionMatcher::new(&loader);
+
+        let mut groups = HashMap::new();
+        for name in ["DataDeployer", "LogsDeployer"] {
+            let blocks = (0..3)
+                .map(|i| TerraformBlock {
+                    name: format!("bucket{}", i),
+                    address: format!("aws_s3_bucket.bucket{}", i),
+                    ..create_test_block(BlockType::Resource, "aws_s3_bucket", HashSet::new())
+                })
+                .collect();
+            groups.insert(
+                name.to_string(),
+                ProviderGroup {
+                    output_name: name.to_string(),
+                    role_arn: None,
+                    has_custom_endpoints: false,
+                    blocks,
+                },
+            );
+        }
+        let config = create_test_config(groups);
+
+        let first = matcher.resolve(&config).unwrap();
+        assert_eq!(loader.loaded_mappings().len(), 1);
+
+        // Served from the in-memory cache: the broken file is never parsed
+        fs::write(&mapping_path, "allow: [").unwrap();
+        let second = matcher.resolve(&config).unwrap();
+
+        for result in [&first, &second] {
+            assert_eq!(result.groups.len(), 2);
+            for permissions in result.groups.values() {
+                assert_eq!(
+                    permissions.allow,
+                    HashSet::from(["s3:CreateBucket".to_string()])
+                );
+            }
+        }
+    }
+
     #[test]
     fn resolve_single_block_with_mapping() {
         let temp_dir = TempDir::new().unwrap();
@@ -248,6 +1217,7 @@ mod tests {
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block],
             },
         );
@@ -264,54 +1234,217 @@ mod tests {
     }
 
     #[test]
-    fn resolve_block_without_mapping() {
+    fn resolve_scopes_allow_actions_to_resource_arn_of_literal_attributes() {
         let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket\nconditional:\n  tags:\n    - s3:PutBucketTagging\n\
+             resource_arn_template: \"arn:aws:s3:::${bucket}\"\n",
+        )
+        .unwrap();
         let loader = MappingLoader::new(temp_dir.path().to_path_buf());
-        let matcher = PermissionMatcher::new(&loader);
 
-        let block = create_test_block(BlockType::Resource, "aws_unknown_resource", HashSet::new());
-        let mut groups = HashMap::new();
-        groups.insert(
-            "TestDeployer".to_string(),
-            ProviderGroup {
-                output_name: "TestDeployer".to_string(),
-                role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
-                blocks: vec![block],
-            },
+        let mut logs = create_test_block(
+            BlockType::Resource,
+            "aws_s3_bucket",
+            HashSet::from([vec!["tags".to_string()]]),
         );
-
-        let config = create_test_config(groups);
-        let result = matcher.resolve(&config).unwrap();
-
-        // No permissions resolved (mapping not found)
-        assert!(result.groups.is_empty());
-        // Missing mapping tracked
-        assert_eq!(result.missing_mappings.len(), 1);
-        assert_eq!(result.missing_mappings[0].type_name, "aws_unknown_resource");
+        logs.attribute_values =
+            HashMap::from([("bucket".to_string(), "my-test-bucket".to_string())]);
+        // No literal bucket name (e.g., interpolated): falls back to "*"
+        let assets = create_test_block(BlockType::Resource, "aws_s3_bucket", HashSet::new());
+        let config = create_test_config(HashMap::from([
+            (
+                "LogsDeployer".to_string(),
+                ProviderGroup {
+                    output_name: "LogsDeployer".to_string(),
+                    role_arn: None,
+                    has_custom_endpoints: false,
+                    blocks: vec![logs.clone()],
+                },
+            ),
+            (
+                "SharedDeployer".to_string(),
+                ProviderGroup {
+                    output_name: "SharedDeployer".to_string(),
+                    role_arn: None,
+                    has_custom_endpoints: false,
+                    blocks: vec![logs, assets],
+                },
+            ),
+        ]));
+
+        let result = PermissionMatcher::new(&loader).resolve(&config).unwrap();
+
+        let logs = &result.groups["LogsDeployer"];
         assert_eq!(
-            result.missing_mappings[0].expected_path,
-            "mappings/resource/aws_unknown_resource.yaml"
+            logs.resources_of("s3:CreateBucket"),
+            vec!["arn:aws:s3:::my-test-bucket"]
+        );
+        assert_eq!(
+            logs.resources_of("s3:PutBucketTagging"),
+            vec!["arn:aws:s3:::my-test-bucket"]
+        );
+        assert_eq!(
+            result.groups["SharedDeployer"].resources_of("s3:CreateBucket"),
+            vec!["*"]
         );
     }
 
     #[test]
-    fn resolve_conditional_actions() {
+    fn resolve_attaches_mapping_conditions_unless_granted_unconditionally() {
         let temp_dir = TempDir::new().unwrap();
-
-        // Create mapping with conditional
         fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
         fs::write(
-            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
-            r#"
-allow:
-  - s3:CreateBucket
-conditional:
-  tags:
-    - s3:PutBucketTagging
-"#,
+            temp_dir.path().join("mappings/resource/aws_instance.yaml"),
+            "allow:\n  - ec2:RunInstances\nconditions:\n  allow:\n    StringEquals:\n      \
+             aws:RequestedRegion: eu-central-1\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir
+                .path()
+                .join("mappings/resource/aws_launch_template.yaml"),
+            "allow:\n  - ec2:RunInstances\n  - ec2:CreateLaunchTemplate\n",
         )
         .unwrap();
-
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        let instance = create_test_block(BlockType::Resource, "aws_instance", HashSet::new());
+        let template =
+            create_test_block(BlockType::Resource, "aws_launch_template", HashSet::new());
+        let config = create_test_config(HashMap::from([
+            (
+                "InstanceDeployer".to_string(),
+                ProviderGroup {
+                    output_name: "InstanceDeployer".to_string(),
+                    role_arn: None,
+                    has_custom_endpoints: false,
+                    blocks: vec![instance.clone()],
+                },
+            ),
+            (
+                "FleetDeployer".to_string(),
+                ProviderGroup {
+                    output_name: "FleetDeployer".to_string(),
+                    role_arn: None,
+                    has_custom_endpoints: false,
+                    blocks: vec![instance, template],
+                },
+            ),
+        ]));
+
+        let result = PermissionMatcher::new(&loader).resolve(&config).unwrap();
+
+        assert_eq!(
+            result.groups["InstanceDeployer"].conditions_of("ec2:RunInstances"),
+            vec![r#"{"StringEquals":{"aws:RequestedRegion":"eu-central-1"}}"#]
+        );
+        let fleet = &result.groups["FleetDeployer"];
+        assert!(fleet.conditions_of("ec2:RunInstances").is_empty());
+        assert!(fleet.conditions_of("ec2:CreateLaunchTemplate").is_empty());
+
+        // Merging keeps the unconditional grant
+        let combined = result.combined("Combined");
+        assert!(
+            combined.groups["Combined"]
+                .conditions_of("ec2:RunInstances")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn resolve_scopes_action_groups_to_their_declared_resources() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_object.yaml"),
+            "allow:\n  - s3:PutObject\nimport:\n  - s3:GetObject\nresources:\n  allow:\n    \
+             - \"arn:aws:s3:::${bucket}/*\"\nresource_arn_template: \"arn:aws:s3:::${bucket}\"\n",
+        )
+        .unwrap();
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        let mut object = create_test_block(BlockType::Resource, "aws_s3_object", HashSet::new());
+        object.attribute_values = HashMap::from([("bucket".to_string(), "logs".to_string())]);
+        let interpolated = create_test_block(BlockType::Resource, "aws_s3_object", HashSet::new());
+        let import = create_test_block(BlockType::Import, "aws_s3_object", HashSet::new());
+        let group = |blocks: Vec<TerraformBlock>| ProviderGroup {
+            output_name: String::new(),
+            role_arn: None,
+            has_custom_endpoints: false,
+            blocks,
+        };
+        let config = create_test_config(HashMap::from([
+            ("LogsDeployer".to_string(), group(vec![object, import])),
+            ("AssetsDeployer".to_string(), group(vec![interpolated])),
+        ]));
+
+        let result = PermissionMatcher::new(&loader).resolve(&config).unwrap();
+
+        let logs = &result.groups["LogsDeployer"];
+        assert_eq!(
+            logs.resources_of("s3:PutObject"),
+            vec!["arn:aws:s3:::logs/*"]
+        );
+        assert_eq!(logs.resources_of("s3:GetObject"), vec!["*"]);
+        assert_eq!(
+            result.groups["AssetsDeployer"].resources_of("s3:PutObject"),
+            vec!["*"]
+        );
+    }
+
+    #[test]
+    fn resolve_block_without_mapping() {
+        let temp_dir = TempDir::new().unwrap();
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let matcher = PermissionMatcher::new(&loader);
+
+        let block = create_test_block(BlockType::Resource, "aws_unknown_resource", HashSet::new());
+        let mut groups = HashMap::new();
+        groups.insert(
+            "TestDeployer".to_string(),
+            ProviderGroup {
+                output_name: "TestDeployer".to_string(),
+                role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
+                blocks: vec![block],
+            },
+        );
+
+        let config = create_test_config(groups);
+        let result = matcher.resolve(&config).unwrap();
+
+        // No permissions resolved (mapping not found)
+        assert!(result.groups.is_empty());
+        // Missing mapping tracked
+        assert_eq!(result.missing_mappings.len(), 1);
+        assert_eq!(result.missing_mappings[0].type_name, "aws_unknown_resource");
+        assert_eq!(
+            result.missing_mappings[0].expected_path,
+            "mappings/resource/aws_unknown_resource.yaml"
+        );
+    }
+
+    #[test]
+    fn resolve_conditional_actions() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Create mapping with conditional
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            r#"
+allow:
+  - s3:CreateBucket
+conditional:
+  tags:
+    - s3:PutBucketTagging
+"#,
+        )
+        .unwrap();
+
         let loader = MappingLoader::new(temp_dir.path().to_path_buf());
         let matcher = PermissionMatcher::new(&loader);
 
@@ -326,6 +1459,7 @@ conditional:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block],
             },
         );
@@ -368,6 +1502,7 @@ conditional:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block],
             },
         );
@@ -387,7 +1522,9 @@ conditional:
         // Create mapping with nested conditional
         fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
         fs::write(
-            temp_dir.path().join("mappings/resource/aws_route53_zone.yaml"),
+            temp_dir
+                .path()
+                .join("mappings/resource/aws_route53_zone.yaml"),
             r#"
 allow:
   - route53:CreateHostedZone
@@ -414,6 +1551,7 @@ conditional:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block],
             },
         );
@@ -423,7 +1561,86 @@ conditional:
 
         let group_perms = result.groups.get("TestDeployer").unwrap();
         assert!(group_perms.allow.contains("route53:CreateHostedZone"));
-        assert!(group_perms.allow.contains("route53:AssociateVPCWithHostedZone"));
+        assert!(
+            group_perms
+                .allow
+                .contains("route53:AssociateVPCWithHostedZone")
+        );
+    }
+
+    #[test]
+    fn resolve_deep_conditional_on_repeated_nested_block() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir
+                .path()
+                .join("mappings/resource/aws_s3_bucket_replication_configuration.yaml"),
+            r#"
+allow:
+  - s3:PutReplicationConfiguration
+conditional:
+  rule:
+    destination:
+      encryption_configuration:
+        replica_kms_key_id:
+          - kms:Encrypt
+      access_control_translation:
+        - s3:ObjectOwnerOverrideToBucketOwner
+"#,
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let matcher = PermissionMatcher::new(&loader);
+
+        // Paths as recorded for two `rule` blocks, only the second of which
+        // sets `destination.encryption_configuration.replica_kms_key_id`
+        let present: HashSet<Vec<String>> = [
+            vec!["rule"],
+            vec!["rule", "id"],
+            vec!["rule", "destination"],
+            vec!["rule", "destination", "bucket"],
+            vec!["rule", "destination", "encryption_configuration"],
+            vec![
+                "rule",
+                "destination",
+                "encryption_configuration",
+                "replica_kms_key_id",
+            ],
+        ]
+        .into_iter()
+        .map(|path| path.into_iter().map(str::to_string).collect())
+        .collect();
+        let block = create_test_block(
+            BlockType::Resource,
+            "aws_s3_bucket_replication_configuration",
+            present,
+        );
+
+        let mut groups = HashMap::new();
+        groups.insert(
+            "TestDeployer".to_string(),
+            ProviderGroup {
+                output_name: "TestDeployer".to_string(),
+                role_arn: None,
+                has_custom_endpoints: false,
+                blocks: vec![block],
+            },
+        );
+
+        let config = create_test_config(groups);
+        let result = matcher.resolve(&config).unwrap();
+
+        let group_perms = result.groups.get("TestDeployer").unwrap();
+        assert!(group_perms.allow.contains("s3:PutReplicationConfiguration"));
+        assert!(group_perms.allow.contains("kms:Encrypt"));
+        assert!(
+            !group_perms
+                .allow
+                .contains("s3:ObjectOwnerOverrideToBucketOwner")
+        );
     }
 
     #[test]
@@ -451,6 +1668,7 @@ conditional:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block1, block2],
             },
         );
@@ -463,6 +1681,55 @@ conditional:
         assert_eq!(group_perms.allow.len(), 2);
     }
 
+    #[test]
+    fn resolve_lists_all_block_addresses_as_sources() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket",
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let matcher = PermissionMatcher::new(&loader);
+
+        let block = |type_name: &str, address: &str| TerraformBlock {
+            address: address.to_string(),
+            ..create_test_block(BlockType::Resource, type_name, HashSet::new())
+        };
+
+        let mut groups = HashMap::new();
+        groups.insert(
+            "StorageDeployer".to_string(),
+            ProviderGroup {
+                output_name: "StorageDeployer".to_string(),
+                role_arn: None,
+                has_custom_endpoints: false,
+                blocks: vec![
+                    block("aws_s3_bucket", "module.logs.aws_s3_bucket.main"),
+                    block("aws_s3_bucket", "aws_s3_bucket.data"),
+                    // Blocks without a mapping are listed as well
+                    block("aws_unknown_type", "aws_unknown_type.x"),
+                ],
+            },
+        );
+
+        let config = create_test_config(groups);
+        let result = matcher.resolve(&config).unwrap();
+
+        let group_perms = result.groups.get("StorageDeployer").unwrap();
+        assert_eq!(
+            group_perms.sources,
+            vec![
+                "aws_s3_bucket.data",
+                "aws_unknown_type.x",
+                "module.logs.aws_s3_bucket.main"
+            ]
+        );
+    }
+
     #[test]
     fn resolve_multiple_groups() {
         let temp_dir = TempDir::new().unwrap();
@@ -475,7 +1742,9 @@ conditional:
         )
         .unwrap();
         fs::write(
-            temp_dir.path().join("mappings/resource/aws_ec2_instance.yaml"),
+            temp_dir
+                .path()
+                .join("mappings/resource/aws_ec2_instance.yaml"),
             "allow:\n  - ec2:RunInstances",
         )
         .unwrap();
@@ -492,6 +1761,7 @@ conditional:
             ProviderGroup {
                 output_name: "StorageDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Storage".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block1],
             },
         );
@@ -500,6 +1770,7 @@ conditional:
             ProviderGroup {
                 output_name: "ComputeDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Compute".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block2],
             },
         );
@@ -525,7 +1796,9 @@ conditional:
         // Create mapping for data source
         fs::create_dir_all(temp_dir.path().join("mappings/data")).unwrap();
         fs::write(
-            temp_dir.path().join("mappings/data/aws_availability_zones.yaml"),
+            temp_dir
+                .path()
+                .join("mappings/data/aws_availability_zones.yaml"),
             "allow:\n  - ec2:DescribeAvailabilityZones",
         )
         .unwrap();
@@ -541,6 +1814,7 @@ conditional:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block],
             },
         );
@@ -552,6 +1826,61 @@ conditional:
         assert!(group_perms.allow.contains("ec2:DescribeAvailabilityZones"));
     }
 
+    #[test]
+    fn resolve_skips_blocks_of_excluded_block_types() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("mappings/data")).unwrap();
+        fs::write(
+            temp_dir
+                .path()
+                .join("mappings/data/aws_availability_zones.yaml"),
+            "allow:\n  - ec2:DescribeAvailabilityZones",
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let matcher = PermissionMatcher::new(&loader).with_block_types(&[BlockType::Data]);
+
+        let mut groups = HashMap::new();
+        groups.insert(
+            "TestDeployer".to_string(),
+            ProviderGroup {
+                output_name: "TestDeployer".to_string(),
+                role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
+                blocks: vec![
+                    create_test_block(BlockType::Data, "aws_availability_zones", HashSet::new()),
+                    create_test_block(BlockType::Resource, "aws_unknown", HashSet::new()),
+                ],
+            },
+        );
+        groups.insert(
+            "ResourcesOnly".to_string(),
+            ProviderGroup {
+                output_name: "ResourcesOnly".to_string(),
+                role_arn: None,
+                has_custom_endpoints: false,
+                blocks: vec![create_test_block(
+                    BlockType::Resource,
+                    "aws_unknown",
+                    HashSet::new(),
+                )],
+            },
+        );
+
+        let result = matcher.resolve(&create_test_config(groups)).unwrap();
+
+        let group_perms = result.groups.get("TestDeployer").unwrap();
+        assert!(group_perms.allow.contains("ec2:DescribeAvailabilityZones"));
+        assert_eq!(
+            group_perms.sources,
+            vec!["data.aws_availability_zones.test".to_string()]
+        );
+        assert!(result.missing_mappings.is_empty());
+        assert!(result.empty_groups.is_empty());
+        assert!(!result.groups.contains_key("ResourcesOnly"));
+    }
+
     #[test]
     fn missing_mapping_tracked_once_per_type() {
         let temp_dir = TempDir::new().unwrap();
@@ -568,6 +1897,7 @@ conditional:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block1, block2],
             },
         );
@@ -595,6 +1925,7 @@ conditional:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block1, block2],
             },
         );
@@ -635,6 +1966,7 @@ deny:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block],
             },
         );
@@ -673,6 +2005,7 @@ deny:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block],
             },
         );
@@ -685,6 +2018,166 @@ deny:
         assert!(group_perms.deny.contains("s3:GetObject"));
     }
 
+    #[test]
+    fn resolve_not_action_mapping_keeps_not_action_sets_apart() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            r#"
+allow:
+  - s3:CreateBucket
+not_allow:
+  - iam:*
+"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_sqs_queue.yaml"),
+            r#"
+not_deny:
+  - sqs:SendMessage
+"#,
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let matcher = PermissionMatcher::new(&loader);
+
+        let group = |name: &str, type_name: &str| {
+            (
+                name.to_string(),
+                ProviderGroup {
+                    output_name: name.to_string(),
+                    role_arn: None,
+                    has_custom_endpoints: false,
+                    blocks: vec![create_test_block(
+                        BlockType::Resource,
+                        type_name,
+                        HashSet::new(),
+                    )],
+                },
+            )
+        };
+        let config = create_test_config(HashMap::from([
+            group("StorageDeployer", "aws_s3_bucket"),
+            group("QueueDeployer", "aws_sqs_queue"),
+        ]));
+        let result = matcher.resolve(&config).unwrap();
+
+        let storage = &result.groups["StorageDeployer"];
+        assert_eq!(
+            storage.allow,
+            HashSet::from(["s3:CreateBucket".to_string()])
+        );
+        assert_eq!(
+            storage.not_allow,
+            BTreeSet::from([NotActionStatement::new(
+                "resource.aws_s3_bucket",
+                ["iam:*".to_string()]
+            )])
+        );
+        assert!(storage.deny.is_empty() && storage.not_deny.is_empty());
+
+        // A group with only a NotAction entry is not empty
+        let queue = &result.groups["QueueDeployer"];
+        assert_eq!(
+            queue.not_deny,
+            BTreeSet::from([NotActionStatement::new(
+                "resource.aws_sqs_queue",
+                ["sqs:SendMessage".to_string()]
+            )])
+        );
+        assert!(result.empty_groups.is_empty());
+    }
+
+    #[test]
+    fn not_action_lists_of_different_resources_stay_separate_statements() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "not_allow:\n  - iam:*\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_sqs_queue.yaml"),
+            "not_allow:\n  - sqs:DeleteQueue\n",
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let mut groups = HashMap::new();
+        groups.insert(
+            "DefaultDeployer".to_string(),
+            ProviderGroup {
+                output_name: "DefaultDeployer".to_string(),
+                role_arn: None,
+                has_custom_endpoints: false,
+                blocks: vec![
+                    create_test_block(BlockType::Resource, "aws_s3_bucket", HashSet::new()),
+                    create_test_block(BlockType::Resource, "aws_sqs_queue", HashSet::new()),
+                ],
+            },
+        );
+        let mut result = PermissionMatcher::new(&loader)
+            .resolve(&create_test_config(groups))
+            .unwrap();
+
+        let expected = BTreeSet::from([
+            NotActionStatement::new("resource.aws_s3_bucket", ["iam:*".to_string()]),
+            NotActionStatement::new("resource.aws_sqs_queue", ["sqs:DeleteQueue".to_string()]),
+        ]);
+        assert_eq!(result.groups["DefaultDeployer"].not_allow, expected);
+
+        // Neither combining nor regrouping merges or splits the lists
+        let combined = result.combined("Deployer");
+        assert_eq!(combined.groups["Deployer"].not_allow, expected);
+
+        result.regroup_by_service();
+        assert_eq!(result.groups.len(), 1);
+        assert_eq!(result.groups[NOT_ACTION_GROUP].not_allow, expected);
+    }
+
+    #[test]
+    fn not_deny_statement_is_not_scoped_to_resource_arn() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket\nnot_deny:\n  - s3:*\n\
+             resource_arn_template: \"arn:aws:s3:::${bucket}\"\n",
+        )
+        .unwrap();
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+
+        let mut logs = create_test_block(BlockType::Resource, "aws_s3_bucket", HashSet::new());
+        logs.attribute_values =
+            HashMap::from([("bucket".to_string(), "my-test-bucket".to_string())]);
+        let config = create_test_config(HashMap::from([(
+            "DefaultDeployer".to_string(),
+            ProviderGroup {
+                output_name: "DefaultDeployer".to_string(),
+                role_arn: None,
+                has_custom_endpoints: false,
+                blocks: vec![logs],
+            },
+        )]));
+
+        let result = PermissionMatcher::new(&loader).resolve(&config).unwrap();
+
+        let permissions = &result.groups["DefaultDeployer"];
+        assert_eq!(
+            permissions.resources_of("s3:CreateBucket"),
+            vec!["arn:aws:s3:::my-test-bucket"]
+        );
+        let not_deny: Vec<&NotActionStatement> = permissions.not_deny.iter().collect();
+        assert_eq!(not_deny.len(), 1);
+        assert_eq!(not_deny[0].resources, vec!["*"]);
+    }
+
     #[test]
     fn resolve_deny_deduplication() {
         let temp_dir = TempDir::new().unwrap();
@@ -712,6 +2205,7 @@ deny:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block1, block2],
             },
         );
@@ -734,7 +2228,9 @@ deny:
         )
         .unwrap();
         fs::write(
-            temp_dir.path().join("mappings/resource/aws_ec2_instance.yaml"),
+            temp_dir
+                .path()
+                .join("mappings/resource/aws_ec2_instance.yaml"),
             "deny:\n  - ec2:TerminateInstances",
         )
         .unwrap();
@@ -751,6 +2247,7 @@ deny:
             ProviderGroup {
                 output_name: "StorageDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Storage".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block1],
             },
         );
@@ -759,6 +2256,7 @@ deny:
             ProviderGroup {
                 output_name: "ComputeDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Compute".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block2],
             },
         );
@@ -805,6 +2303,7 @@ conditional:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block],
             },
         );
@@ -820,6 +2319,623 @@ conditional:
         assert!(group_perms.deny.contains("s3:GetObject"));
     }
 
+    // --- Conflict resolution tests ---
+
+    fn create_conflicting_permissions() -> GroupPermissions {
+        GroupPermissions {
+            allow: HashSet::from(["s3:CreateBucket".to_string(), "s3:DeleteBucket".to_string()]),
+            deny: HashSet::from(["s3:DeleteBucket".to_string(), "s3:GetObject".to_string()]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn conflicting_actions_returns_sorted_intersection() {
+        let permissions = create_conflicting_permissions();
+
+        assert_eq!(permissions.conflicting_actions(), vec!["s3:DeleteBucket"]);
+    }
+
+    #[test]
+    fn resolve_conflicts_deny_wins_removes_from_allow() {
+        let mut permissions = create_conflicting_permissions();
+
+        let removed = permissions.resolve_conflicts(ConflictResolution::DenyWins);
+
+        assert_eq!(removed, vec!["s3:DeleteBucket"]);
+        assert!(!permissions.allow.contains("s3:DeleteBucket"));
+        assert!(permissions.allow.contains("s3:CreateBucket"));
+        assert!(permissions.deny.contains("s3:DeleteBucket"));
+        assert!(permissions.deny.contains("s3:GetObject"));
+    }
+
+    #[test]
+    fn resolve_conflicts_allow_wins_removes_from_deny() {
+        let mut permissions = create_conflicting_permissions();
+
+        let removed = permissions.resolve_conflicts(ConflictResolution::AllowWins);
+
+        assert_eq!(removed, vec!["s3:DeleteBucket"]);
+        assert!(permissions.allow.contains("s3:DeleteBucket"));
+        assert!(permissions.allow.contains("s3:CreateBucket"));
+        assert!(!permissions.deny.contains("s3:DeleteBucket"));
+        assert!(permissions.deny.contains("s3:GetObject"));
+    }
+
+    #[test]
+    fn resolve_conflicts_without_strategy_keeps_both_sets() {
+        let mut result = PermissionResult {
+            groups: HashMap::from([("TestDeployer".to_string(), create_conflicting_permissions())]),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+
+        let diagnostics = result.resolve_conflicts(None);
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::warn(
+                "TestDeployer: 1 action(s) are both allowed and denied (kept in both statements): s3:DeleteBucket"
+            )]
+        );
+
+        let group_perms = result.groups.get("TestDeployer").unwrap();
+        assert!(group_perms.allow.contains("s3:DeleteBucket"));
+        assert!(group_perms.deny.contains("s3:DeleteBucket"));
+    }
+
+    #[test]
+    fn resolve_conflicts_applies_strategy_to_all_groups() {
+        let mut result = PermissionResult {
+            groups: HashMap::from([
+                (
+                    "FirstDeployer".to_string(),
+                    create_conflicting_permissions(),
+                ),
+                (
+                    "SecondDeployer".to_string(),
+                    create_conflicting_permissions(),
+                ),
+            ]),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+
+        let diagnostics = result.resolve_conflicts(Some(ConflictResolution::DenyWins));
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(
+            diagnostics
+                .iter()
+                .all(|diagnostic| diagnostic.severity == crate::diagnostics::Severity::Info)
+        );
+
+        for group_perms in result.groups.values() {
+            assert!(group_perms.conflicting_actions().is_empty());
+            assert!(group_perms.deny.contains("s3:DeleteBucket"));
+        }
+    }
+
+    #[test]
+    fn collapse_wildcards_removes_covered_allow_actions_only() {
+        let mut permissions = GroupPermissions {
+            allow: HashSet::from([
+                "s3:Get*".to_string(),
+                "s3:GetObject".to_string(),
+                "S3:getbucketpolicy".to_string(),
+                "s3:PutObject".to_string(),
+                "ec2:Describe?pc".to_string(),
+                "ec2:DescribeVpc".to_string(),
+                "ec2:DescribeVpcs".to_string(),
+                "sqs:GetQueueUrl".to_string(),
+            ]),
+            deny: HashSet::from(["s3:GetObject".to_string()]),
+            ..Default::default()
+        };
+
+        let removed = permissions.collapse_wildcards();
+
+        assert_eq!(
+            removed,
+            vec![
+                "S3:getbucketpolicy (covered by s3:Get*)",
+                "ec2:DescribeVpc (covered by ec2:Describe?pc)",
+                "s3:GetObject (covered by s3:Get*)",
+            ]
+        );
+        let mut allow: Vec<&String> = permissions.allow.iter().collect();
+        allow.sort();
+        assert_eq!(
+            allow,
+            vec![
+                "ec2:Describe?pc",
+                "ec2:DescribeVpcs",
+                "s3:Get*",
+                "s3:PutObject",
+                "sqs:GetQueueUrl"
+            ]
+        );
+        assert!(permissions.deny.contains("s3:GetObject"));
+    }
+
+    #[test]
+    fn collapse_wildcards_never_crosses_services_or_narrower_resources() {
+        let mut permissions = GroupPermissions {
+            allow: HashSet::from([
+                "*".to_string(),
+                "*:GetObject".to_string(),
+                "s3:Get*".to_string(),
+                "s3:GetObject".to_string(),
+                "s3:GetBucketPolicy".to_string(),
+            ]),
+            resources: BTreeMap::from([
+                (
+                    "s3:Get*".to_string(),
+                    BTreeSet::from(["arn:aws:s3:::logs".to_string()]),
+                ),
+                (
+                    "s3:GetBucketPolicy".to_string(),
+                    BTreeSet::from(["arn:aws:s3:::logs".to_string()]),
+                ),
+            ]),
+            ..Default::default()
+        };
+
+        let removed = permissions.collapse_wildcards();
+
+        assert_eq!(removed, vec!["s3:GetBucketPolicy (covered by s3:Get*)"]);
+        assert!(permissions.allow.contains("s3:GetObject"));
+        assert!(!permissions.resources.contains_key("s3:GetBucketPolicy"));
+    }
+
+    // --- Dedupe tests ---
+
+    fn permissions(allow: &[&str], sources: &[&str]) -> GroupPermissions {
+        GroupPermissions {
+            allow: allow.iter().map(|action| action.to_string()).collect(),
+            deny: HashSet::from(["s3:DeleteBucket".to_string()]),
+            sources: sources.iter().map(|source| source.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dedupe_identical_groups_merges_identical_sets() {
+        let mut result = PermissionResult {
+            groups: HashMap::from([
+                (
+                    "NetworkDeployer".to_string(),
+                    permissions(&["ec2:CreateVpc", "s3:CreateBucket"], &["aws_vpc.main"]),
+                ),
+                (
+                    "DnsDeployer".to_string(),
+                    permissions(&["s3:CreateBucket", "ec2:CreateVpc"], &["aws_vpc.dns"]),
+                ),
+                (
+                    "StorageDeployer".to_string(),
+                    permissions(&["s3:CreateBucket"], &["aws_s3_bucket.main"]),
+                ),
+            ]),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+
+        result.dedupe_identical_groups();
+
+        let mut names: Vec<&String> = result.groups.keys().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["DnsDeployer+NetworkDeployer", "StorageDeployer"]
+        );
+
+        let merged = result.groups.get("DnsDeployer+NetworkDeployer").unwrap();
+        assert_eq!(merged.allow.len(), 2);
+        assert!(merged.deny.contains("s3:DeleteBucket"));
+        assert_eq!(merged.sources, vec!["aws_vpc.dns", "aws_vpc.main"]);
+
+        let storage = result.groups.get("StorageDeployer").unwrap();
+        assert_eq!(storage.sources, vec!["aws_s3_bucket.main"]);
+    }
+
+    #[test]
+    fn dedupe_identical_groups_keeps_groups_differing_in_deny() {
+        let mut other = permissions(&["s3:CreateBucket"], &[]);
+        other.deny.clear();
+        let mut result = PermissionResult {
+            groups: HashMap::from([
+                (
+                    "FirstDeployer".to_string(),
+                    permissions(&["s3:CreateBucket"], &[]),
+                ),
+                ("SecondDeployer".to_string(), other),
+            ]),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+
+        result.dedupe_identical_groups();
+
+        assert_eq!(result.groups.len(), 2);
+        assert!(result.groups.contains_key("FirstDeployer"));
+        assert!(result.groups.contains_key("SecondDeployer"));
+    }
+
+    #[test]
+    fn regroup_by_service_buckets_actions_by_prefix() {
+        let mut result = PermissionResult {
+            groups: HashMap::from([
+                (
+                    "NetworkDeployer".to_string(),
+                    GroupPermissions {
+                        allow: HashSet::from([
+                            "ec2:CreateVpc".to_string(),
+                            "s3:GetObject".to_string(),
+                        ]),
+                        deny: HashSet::from(["ec2:DeleteVpc".to_string()]),
+                        sources: vec!["aws_vpc.main".to_string()],
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "StorageDeployer".to_string(),
+                    permissions(&["s3:CreateBucket"], &["aws_s3_bucket.main"]),
+                ),
+            ]),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+
+        result.regroup_by_service();
+
+        let mut names: Vec<&String> = result.groups.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["ec2", "s3"]);
+
+        let ec2 = &result.groups["ec2"];
+        assert_eq!(ec2.allow, HashSet::from(["ec2:CreateVpc".to_string()]));
+        assert_eq!(ec2.deny, HashSet::from(["ec2:DeleteVpc".to_string()]));
+        assert_eq!(ec2.sources, vec!["aws_vpc.main"]);
+
+        let s3 = &result.groups["s3"];
+        assert_eq!(
+            s3.allow,
+            HashSet::from(["s3:CreateBucket".to_string(), "s3:GetObject".to_string()])
+        );
+        assert_eq!(s3.deny, HashSet::from(["s3:DeleteBucket".to_string()]));
+        assert_eq!(s3.sources, vec!["aws_s3_bucket.main", "aws_vpc.main"]);
+    }
+
+    #[test]
+    fn regroup_by_account_unions_groups_of_the_same_account() {
+        let mut result = PermissionResult {
+            groups: HashMap::from([
+                (
+                    "NetworkDeployer".to_string(),
+                    permissions(&["ec2:CreateVpc"], &["aws_vpc.main"]),
+                ),
+                (
+                    "StorageDeployer".to_string(),
+                    permissions(&["s3:CreateBucket"], &["aws_s3_bucket.main"]),
+                ),
+                (
+                    "DnsDeployer".to_string(),
+                    permissions(&["route53:CreateHostedZone"], &["aws_route53_zone.main"]),
+                ),
+                (
+                    "SharedDeployer".to_string(),
+                    permissions(&["sqs:CreateQueue"], &["aws_sqs_queue.main"]),
+                ),
+                (
+                    "DefaultDeployer".to_string(),
+                    permissions(&["sns:CreateTopic"], &["aws_sns_topic.main"]),
+                ),
+            ]),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+        let role_arns = HashMap::from([
+            (
+                "NetworkDeployer".to_string(),
+                "arn:aws:iam::111111111111:role/Network".to_string(),
+            ),
+            (
+                "StorageDeployer".to_string(),
+                "arn:aws:iam::111111111111:role/Storage".to_string(),
+            ),
+            (
+                "DnsDeployer".to_string(),
+                "arn:aws:iam::222222222222:role/Dns".to_string(),
+            ),
+            (
+                "SharedDeployer".to_string(),
+                "arn:aws:iam::${var.shared_account}:role/Shared".to_string(),
+            ),
+        ]);
+
+        result.regroup_by_account(&role_arns);
+
+        let mut names: Vec<&String> = result.groups.keys().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["111111111111", "222222222222", "unknown-account"]
+        );
+
+        let first = &result.groups["111111111111"];
+        assert_eq!(
+            first.allow,
+            HashSet::from(["ec2:CreateVpc".to_string(), "s3:CreateBucket".to_string()])
+        );
+        assert_eq!(first.deny, HashSet::from(["s3:DeleteBucket".to_string()]));
+        assert_eq!(first.sources, vec!["aws_s3_bucket.main", "aws_vpc.main"]);
+
+        assert_eq!(
+            result.groups["222222222222"].allow,
+            HashSet::from(["route53:CreateHostedZone".to_string()])
+        );
+        assert_eq!(
+            result.groups["unknown-account"].allow,
+            HashSet::from(["sns:CreateTopic".to_string(), "sqs:CreateQueue".to_string()])
+        );
+    }
+
+    #[test]
+    fn combined_unions_all_groups_and_keeps_missing_mappings() {
+        let mut storage = permissions(&["s3:CreateBucket"], &["aws_s3_bucket.main"]);
+        storage.deny.insert("s3:PutBucketAcl".to_string());
+        let result = PermissionResult {
+            groups: HashMap::from([
+                (
+                    "NetworkDeployer".to_string(),
+                    permissions(&["ec2:CreateVpc"], &["aws_vpc.main"]),
+                ),
+                ("StorageDeployer".to_string(), storage),
+            ]),
+            missing_mappings: vec![MissingMapping {
+                block_type: BlockType::Resource,
+                type_name: "aws_foo".to_string(),
+                expected_path: "mappings/resource/aws_foo.yaml".to_string(),
+                suggestion: None,
+            }],
+            empty_groups: vec!["QueueDeployer".to_string()],
+        };
+
+        let combined = result.combined("Combined");
+
+        assert_eq!(combined.groups.len(), 1);
+        let group = &combined.groups["Combined"];
+        assert_eq!(
+            group.allow,
+            HashSet::from(["ec2:CreateVpc".to_string(), "s3:CreateBucket".to_string()])
+        );
+        assert_eq!(
+            group.deny,
+            HashSet::from(["s3:DeleteBucket".to_string(), "s3:PutBucketAcl".to_string()])
+        );
+        assert_eq!(group.sources, vec!["aws_s3_bucket.main", "aws_vpc.main"]);
+        assert_eq!(combined.missing_mappings.len(), 1);
+        assert_eq!(combined.empty_groups, vec!["QueueDeployer"]);
+        assert_eq!(result.groups.len(), 2);
+    }
+
+    #[test]
+    fn combined_without_groups_has_no_groups() {
+        assert!(
+            PermissionResult::default()
+                .combined("Combined")
+                .groups
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn retain_groups_keeps_only_groups_matching_the_pattern() {
+        let mut result = PermissionResult {
+            groups: HashMap::from([
+                (
+                    "NetworkDeployer".to_string(),
+                    permissions(&["ec2:CreateVpc"], &[]),
+                ),
+                (
+                    "StorageDeployer".to_string(),
+                    permissions(&["s3:CreateBucket"], &[]),
+                ),
+                (
+                    "Network".to_string(),
+                    permissions(&["ec2:CreateSubnet"], &[]),
+                ),
+            ]),
+            ..PermissionResult::default()
+        };
+
+        let dropped = result.retain_groups("Network*Deployer");
+
+        assert_eq!(dropped, vec!["Network", "StorageDeployer"]);
+        assert_eq!(
+            result.groups.keys().collect::<Vec<_>>(),
+            vec!["NetworkDeployer"]
+        );
+
+        let dropped = result.retain_groups("Storage*");
+        assert_eq!(dropped, vec!["NetworkDeployer"]);
+        assert!(result.groups.is_empty());
+    }
+
+    #[test]
+    fn regrouping_unions_resources_and_keeps_unscoped_actions_unscoped() {
+        let mut logs = permissions(&["s3:CreateBucket"], &["aws_s3_bucket.logs"]);
+        logs.resources = BTreeMap::from([(
+            "s3:CreateBucket".to_string(),
+            BTreeSet::from(["arn:aws:s3:::logs".to_string()]),
+        )]);
+        let mut assets = permissions(&["s3:CreateBucket"], &["aws_s3_bucket.assets"]);
+        assets.resources = BTreeMap::from([(
+            "s3:CreateBucket".to_string(),
+            BTreeSet::from(["arn:aws:s3:::assets".to_string()]),
+        )]);
+        let unscoped = permissions(&["s3:CreateBucket"], &["aws_s3_bucket.tmp"]);
+        let result = |groups: Vec<(&str, GroupPermissions)>| PermissionResult {
+            groups: groups
+                .into_iter()
+                .map(|(name, permissions)| (name.to_string(), permissions))
+                .collect(),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+
+        let mut scoped = result(vec![
+            ("LogsDeployer", logs.clone()),
+            ("AssetsDeployer", assets),
+        ]);
+        scoped.regroup_by_service();
+        let mut mixed = result(vec![("LogsDeployer", logs), ("TmpDeployer", unscoped)]);
+        mixed.regroup_by_account(&HashMap::new());
+
+        assert_eq!(
+            scoped.groups["s3"].resources_of("s3:CreateBucket"),
+            vec!["arn:aws:s3:::assets", "arn:aws:s3:::logs"]
+        );
+        assert_eq!(
+            mixed.groups[UNKNOWN_ACCOUNT_GROUP].resources_of("s3:CreateBucket"),
+            vec!["*"]
+        );
+    }
+
+    // --- Empty groups tests ---
+
+    #[test]
+    fn resolve_records_group_with_only_missing_mappings_as_empty() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket",
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let matcher = PermissionMatcher::new(&loader);
+
+        let group = |name: &str, type_names: &[&str]| ProviderGroup {
+            output_name: name.to_string(),
+            role_arn: None,
+            has_custom_endpoints: false,
+            blocks: type_names
+                .iter()
+                .map(|type_name| create_test_block(BlockType::Resource, type_name, HashSet::new()))
+                .collect(),
+        };
+        let mut groups = HashMap::new();
+        groups.insert(
+            "StorageDeployer".to_string(),
+            group("StorageDeployer", &["aws_s3_bucket"]),
+        );
+        groups.insert(
+            "NewServiceDeployer".to_string(),
+            group(
+                "NewServiceDeployer",
+                &["aws_new_service", "aws_other_service"],
+            ),
+        );
+
+        let config = create_test_config(groups);
+        let result = matcher.resolve(&config).unwrap();
+
+        assert!(result.groups.contains_key("StorageDeployer"));
+        assert!(!result.groups.contains_key("NewServiceDeployer"));
+        assert_eq!(result.empty_groups, vec!["NewServiceDeployer"]);
+
+        let checked = result.check_empty_groups();
+        assert_eq!(
+            checked.unwrap_err().to_string(),
+            "Groups with resources resolved no permissions: NewServiceDeployer"
+        );
+    }
+
+    #[test]
+    fn check_missing_mappings_lists_sorted_types() {
+        let mut result = PermissionResult::default();
+        assert!(result.check_missing_mappings().is_ok());
+
+        result.missing_mappings = vec![
+            missing(BlockType::Resource, "aws_s3_bucket"),
+            missing(BlockType::Data, "aws_caller_identity"),
+        ];
+        assert_eq!(
+            result.check_missing_mappings().unwrap_err().to_string(),
+            "Terraform types without a mapping: data.aws_caller_identity, resource.aws_s3_bucket"
+        );
+    }
+
+    #[test]
+    fn check_empty_groups_passes_without_empty_groups() {
+        let result = result_with_groups(&["NetworkDeployer"]);
+
+        assert!(result.check_empty_groups().is_ok());
+    }
+
+    // --- Expected groups tests ---
+
+    fn result_with_groups(names: &[&str]) -> PermissionResult {
+        PermissionResult {
+            groups: names
+                .iter()
+                .map(|name| (name.to_string(), create_conflicting_permissions()))
+                .collect(),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        }
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn check_expected_groups_passes_on_exact_match() {
+        let result = result_with_groups(&["DnsDeployer", "NetworkDeployer"]);
+
+        let checked = result.check_expected_groups(&names(&["NetworkDeployer", "DnsDeployer"]));
+
+        assert!(checked.is_ok());
+    }
+
+    #[test]
+    fn check_expected_groups_fails_on_unexpected_group() {
+        let result = result_with_groups(&["DnsDeployer", "NetworkDeployer", "StorageDeployer"]);
+
+        let checked = result.check_expected_groups(&names(&["DnsDeployer", "NetworkDeployer"]));
+
+        let error_message = checked.unwrap_err().to_string();
+        assert_eq!(
+            error_message,
+            "Groups differ from the expected groups: unexpected StorageDeployer"
+        );
+    }
+
+    #[test]
+    fn check_expected_groups_fails_on_missing_group() {
+        let result = result_with_groups(&["NetworkDeployer", "StorageDeployer"]);
+
+        let checked = result.check_expected_groups(&names(&["DnsDeployer", "NetworkDeployer"]));
+
+        let error_message = checked.unwrap_err().to_string();
+        assert_eq!(
+            error_message,
+            "Groups differ from the expected groups: unexpected StorageDeployer; missing DnsDeployer"
+        );
+    }
+
+    #[test]
+    fn check_expected_groups_without_groups_reports_all_missing() {
+        let checked =
+            PermissionResult::default().check_expected_groups(&names(&["NetworkDeployer"]));
+
+        assert!(matches!(checked, Err(MatchError::UnexpectedGroups(_))));
+    }
+
     // --- Skip type tests ---
 
     #[test]
@@ -828,11 +2944,7 @@ conditional:
 
         // Create a .skip file for aws_arn
         fs::create_dir_all(temp_dir.path().join("mappings/data")).unwrap();
-        fs::write(
-            temp_dir.path().join("mappings/data/aws_arn.skip"),
-            "",
-        )
-        .unwrap();
+        fs::write(temp_dir.path().join("mappings/data/aws_arn.skip"), "").unwrap();
 
         let loader = MappingLoader::new(temp_dir.path().to_path_buf());
         let matcher = PermissionMatcher::new(&loader);
@@ -844,6 +2956,7 @@ conditional:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block],
             },
         );
@@ -861,11 +2974,7 @@ conditional:
 
         // Create a .skip file for aws_arn
         fs::create_dir_all(temp_dir.path().join("mappings/data")).unwrap();
-        fs::write(
-            temp_dir.path().join("mappings/data/aws_arn.skip"),
-            "",
-        )
-        .unwrap();
+        fs::write(temp_dir.path().join("mappings/data/aws_arn.skip"), "").unwrap();
 
         let loader = MappingLoader::new(temp_dir.path().to_path_buf());
         let matcher = PermissionMatcher::new(&loader);
@@ -877,6 +2986,7 @@ conditional:
             ProviderGroup {
                 output_name: "TestDeployer".to_string(),
                 role_arn: Some("arn:aws:iam::123456789012:role/Test".to_string()),
+                has_custom_endpoints: false,
                 blocks: vec![block],
             },
         );
@@ -887,4 +2997,169 @@ conditional:
         // No permissions should be added — group should be empty
         assert!(result.groups.is_empty());
     }
+
+    #[test]
+    fn resolve_import_block_uses_import_section_of_resource_mapping() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket\ndeny:\n  - s3:DeleteBucket\nconditional:\n  tags:\n    - s3:PutBucketTagging\nimport:\n  - s3:ListBucket",
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let matcher = PermissionMatcher::new(&loader);
+
+        let mut present = HashSet::new();
+        present.insert(vec!["tags".to_string()]);
+        let block = create_test_block(BlockType::Import, "aws_s3_bucket", present);
+        let mut groups = HashMap::new();
+        groups.insert(
+            "SharedDeployer".to_string(),
+            ProviderGroup {
+                output_name: "SharedDeployer".to_string(),
+                role_arn: Some("arn:aws:iam::210987654321:role/SharedRole".to_string()),
+                has_custom_endpoints: false,
+                blocks: vec![block],
+            },
+        );
+
+        let config = create_test_config(groups);
+        let result = matcher.resolve(&config).unwrap();
+
+        let perms = result.groups.get("SharedDeployer").unwrap();
+        let expected: HashSet<String> = ["s3:ListBucket".to_string()].into_iter().collect();
+        assert_eq!(perms.allow, expected);
+        assert!(perms.deny.is_empty());
+    }
+
+    #[test]
+    fn resolve_import_block_without_resource_mapping_reports_resource_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let matcher = PermissionMatcher::new(&loader);
+
+        let mut groups = HashMap::new();
+        groups.insert(
+            "TestDeployer".to_string(),
+            ProviderGroup {
+                output_name: "TestDeployer".to_string(),
+                role_arn: None,
+                has_custom_endpoints: false,
+                blocks: vec![
+                    create_test_block(BlockType::Resource, "aws_s3_bucket", HashSet::new()),
+                    create_test_block(BlockType::Import, "aws_s3_bucket", HashSet::new()),
+                ],
+            },
+        );
+
+        let config = create_test_config(groups);
+        let result = matcher.resolve(&config).unwrap();
+
+        assert_eq!(result.missing_mappings.len(), 1);
+        assert_eq!(result.missing_mappings[0].block_type, BlockType::Resource);
+        assert_eq!(
+            result.missing_mappings[0].expected_path,
+            "mappings/resource/aws_s3_bucket.yaml"
+        );
+    }
+
+    fn missing(block_type: BlockType, type_name: &str) -> MissingMapping {
+        MissingMapping {
+            block_type,
+            type_name: type_name.to_string(),
+            expected_path: format!("mappings/{}/{}.yaml", block_type.as_str(), type_name),
+            suggestion: None,
+        }
+    }
+
+    #[test]
+    fn suggest_missing_sets_closest_type_per_block_type() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket",
+        )
+        .unwrap();
+        fs::create_dir_all(temp_dir.path().join("mappings/data")).unwrap();
+        fs::write(temp_dir.path().join("mappings/data/aws_vpc.skip"), "").unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let mut result = PermissionResult {
+            groups: HashMap::new(),
+            missing_mappings: vec![
+                missing(BlockType::Resource, "aws_s3_buckets"),
+                missing(BlockType::Resource, "aws_quantum_ledger_table"),
+                missing(BlockType::Data, "aws_vpcs"),
+                missing(BlockType::Ephemeral, "aws_s3_buckets"),
+            ],
+            empty_groups: Vec::new(),
+        };
+
+        result.suggest_missing(&loader).unwrap();
+
+        let suggestions: Vec<Option<&str>> = result
+            .missing_mappings
+            .iter()
+            .map(|m| m.suggestion.as_deref())
+            .collect();
+        assert_eq!(
+            suggestions,
+            vec![Some("aws_s3_bucket"), None, Some("aws_vpc"), None]
+        );
+    }
+
+    #[test]
+    fn explain_conditionals_reports_present_and_absent_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+        fs::write(
+            temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket\nconditional:\n  tags:\n    - s3:PutBucketTagging\n  versioning:\n    - s3:PutBucketVersioning",
+        )
+        .unwrap();
+
+        let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+        let matcher = PermissionMatcher::new(&loader);
+
+        let present = HashSet::from([vec!["tags".to_string()]]);
+        let block = create_test_block(BlockType::Resource, "aws_s3_bucket", present);
+        let config = create_test_config(HashMap::from([(
+            "TestDeployer".to_string(),
+            ProviderGroup {
+                output_name: "TestDeployer".to_string(),
+                role_arn: None,
+                has_custom_endpoints: false,
+                blocks: vec![block],
+            },
+        )]));
+
+        let explanation = matcher
+            .explain_conditionals(&config, "resource.aws_s3_bucket.test")
+            .unwrap()
+            .unwrap();
+
+        assert!(explanation.mapping_found);
+        assert_eq!(explanation.evaluations.len(), 2);
+        assert_eq!(explanation.evaluations[0].path, vec!["tags"]);
+        assert!(explanation.evaluations[0].present);
+        assert_eq!(explanation.evaluations[1].path, vec!["versioning"]);
+        assert!(!explanation.evaluations[1].present);
+        assert_eq!(
+            explanation.render(),
+            "resource.aws_s3_bucket.test (resource aws_s3_bucket)\n\
+             KEY         PRESENT  ACTIONS\n\
+             tags        yes      added: s3:PutBucketTagging\n\
+             versioning  no       not added: s3:PutBucketVersioning\n"
+        );
+
+        assert!(
+            matcher
+                .explain_conditionals(&config, "aws_s3_bucket.unknown")
+                .unwrap()
+                .is_none()
+        );
+    }
 }
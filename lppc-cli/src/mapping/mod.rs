@@ -7,17 +7,36 @@
 //! The module also provides functionality for loading YAML mapping files and resolving
 //! IAM permissions based on Terraform block configurations.
 
+pub mod allowlist;
 pub mod cache;
+pub mod dangerous;
+pub mod delta;
+pub mod lint;
 pub mod loader;
 pub mod matcher;
+pub mod reconcile;
 pub mod repository;
 pub mod schema;
+pub mod service_prefixes;
+pub mod stats;
+pub mod suggest;
+pub mod wildcards;
 pub mod yaml_parser;
 
+pub use allowlist::ActionAllowlist;
+pub use dangerous::DangerousActions;
+pub use delta::{MappingDelta, PolicyDiff};
+pub use lint::MappingLint;
 pub use loader::MappingLoader;
-pub use matcher::{GroupPermissions, MissingMapping, PermissionMatcher, PermissionResult};
+pub use matcher::{
+    ConditionalExplanation, GroupPermissions, MissingMapping, NotActionStatement,
+    PermissionMatcher, PermissionResult, UNCONDITIONAL,
+};
+pub use stats::MappingStats;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tempfile::TempDir;
 use thiserror::Error;
 
 use cache::{CacheError, CacheManager};
@@ -34,6 +53,12 @@ pub enum MappingError {
 
     #[error("Mapping repository not available: {0}")]
     NotAvailable(String),
+
+    #[error("Mapping repository not cached: {0}")]
+    NotCached(String),
+
+    #[error("Invalid local mappings directory: {0}")]
+    InvalidLocalDirectory(String),
 }
 
 /// Represents the local mapping repository state.
@@ -53,25 +78,36 @@ impl MappingRepository {
     ///
     /// 1. If `force_refresh` is true, always update
     /// 2. If not cached, clone the repository
-    /// 3. If cached but older than 24 hours, update
+    /// 3. If cached but at least `cache_ttl` old, update
     /// 4. If network unavailable but cached, use cache with warning
     /// 5. If network unavailable and not cached, return error
     ///
     /// # Arguments
     ///
     /// * `url` - The URL of the mapping repository
+    /// * `reference` - Branch, tag, or commit to check out instead of the
+    ///   default branch; each ref is cached in its own directory
     /// * `force_refresh` - If true, forces an immediate update regardless of cache age
+    /// * `cache_ttl` - Age after which the cache is refreshed (`--cache-ttl`, default 24 hours)
     ///
     /// # Returns
     ///
     /// Returns a `MappingRepository` with the local path to the cached repository,
     /// or an error if the repository is not available and cannot be cloned.
-    pub fn ensure_available(url: &str, force_refresh: bool) -> Result<Self, MappingError> {
-        let cache = CacheManager::new()?;
-        let local_path = cache.get_repo_path(url)?;
-        let is_cached = cache.is_cached(url);
+    pub fn ensure_available(
+        url: &str,
+        reference: Option<&str>,
+        force_refresh: bool,
+        cache_ttl: Duration,
+    ) -> Result<Self, MappingError> {
+        let cache = CacheManager::new()?.with_ttl(cache_ttl);
+        let local_path = cache.get_repo_path(url, reference)?;
+        let is_cached = cache.is_cached(url, reference);
 
         log::debug!("Repository URL: {}", url);
+        if let Some(reference) = reference {
+            log::debug!("Repository ref: {}", reference);
+        }
         log::debug!("Local path: {:?}", local_path);
         log::debug!("Is cached: {}", is_cached);
 
@@ -83,19 +119,19 @@ impl MappingRepository {
             log::debug!("Repository not cached, clone required");
             true
         } else {
-            let needs_it = cache.needs_refresh(url)?;
+            let needs_it = cache.needs_refresh(url, reference)?;
             if needs_it {
-                log::debug!("Cache expired (older than 24 hours), update needed");
+                log::debug!("Cache expired (older than {:?}), update needed", cache_ttl);
             } else {
-                log::debug!("Cache is fresh (updated within 24 hours)");
+                log::debug!("Cache is fresh (updated within {:?})", cache_ttl);
             }
             needs_it
         };
 
         let was_refreshed = if needs_update {
-            match Self::try_update_or_clone(&local_path, url, is_cached) {
+            match Self::try_update_or_clone(&local_path, url, reference, is_cached) {
                 Ok(()) => {
-                    cache.update_timestamp(url)?;
+                    cache.update_timestamp(url, reference)?;
                     true
                 }
                 Err(MappingError::Git(GitError::NetworkUnreachable)) if is_cached => {
@@ -106,6 +142,16 @@ impl MappingRepository {
                     );
                     false
                 }
+                Err(MappingError::Git(GitError::LocalChanges(changes))) => {
+                    // Never discard edits made in the cache - skip the update
+                    log::warn!(
+                        "Mapping repository {} has local changes ({}), skipping update. \
+                        Commit, stash, or discard them to receive updates.",
+                        local_path.display(),
+                        changes
+                    );
+                    false
+                }
                 Err(e) => {
                     if !is_cached {
                         return Err(MappingError::NotAvailable(format!(
@@ -117,7 +163,10 @@ impl MappingRepository {
                 }
             }
         } else {
-            log::debug!("Using cached mapping repository (last updated within 24 hours)");
+            log::debug!(
+                "Using cached mapping repository (last updated within {:?})",
+                cache_ttl
+            );
             false
         };
 
@@ -128,22 +177,115 @@ impl MappingRepository {
         })
     }
 
+    /// Uses the cached mapping repository without any network access
+    /// (`--offline`).
+    ///
+    /// Unlike `ensure_available`, the cache age is not checked and no clone or
+    /// update is attempted. Returns `MappingError::NotCached` if the
+    /// repository (at the given ref) has never been cached.
+    pub fn from_cache(url: &str, reference: Option<&str>) -> Result<Self, MappingError> {
+        Self::from_cache_in(&CacheManager::new()?, url, reference)
+    }
+
+    fn from_cache_in(
+        cache: &CacheManager,
+        url: &str,
+        reference: Option<&str>,
+    ) -> Result<Self, MappingError> {
+        let local_path = cache.get_repo_path(url, reference)?;
+        if !cache.is_cached(url, reference) {
+            return Err(MappingError::NotCached(format!(
+                "--offline was given but no cached version of {} exists at {}. \
+                Run once without --offline to populate the cache.",
+                url,
+                local_path.display()
+            )));
+        }
+
+        log::debug!("Offline: using cached mapping repository {:?}", local_path);
+
+        Ok(Self {
+            local_path,
+            url: url.to_string(),
+            was_refreshed: false,
+        })
+    }
+
+    /// Uses a local directory laid out like the mapping repository
+    /// (`--local-mappings`), without git or the cache.
+    ///
+    /// The directory must contain a `mappings` directory with at least one
+    /// subdirectory (e.g., `mappings/resource` or `mappings/google`). The
+    /// returned repository is never refreshed.
+    pub fn from_local_dir(dir: &Path) -> Result<Self, MappingError> {
+        if !dir.is_dir() {
+            return Err(MappingError::InvalidLocalDirectory(format!(
+                "{} does not exist or is not a directory",
+                dir.display()
+            )));
+        }
+
+        let mappings_dir = dir.join("mappings");
+        let has_subdirectory = std::fs::read_dir(&mappings_dir)
+            .map(|entries| entries.flatten().any(|entry| entry.path().is_dir()))
+            .unwrap_or(false);
+        if !has_subdirectory {
+            return Err(MappingError::InvalidLocalDirectory(format!(
+                "{} contains no mappings/<block_type> or mappings/<provider> directory",
+                dir.display()
+            )));
+        }
+
+        log::debug!("Using local mappings directory {}", dir.display());
+
+        Ok(Self {
+            local_path: dir.to_path_buf(),
+            url: dir.display().to_string(),
+            was_refreshed: false,
+        })
+    }
+
     /// Attempts to update or clone the repository.
     fn try_update_or_clone(
         local_path: &std::path::Path,
         url: &str,
+        reference: Option<&str>,
         is_cached: bool,
     ) -> Result<(), MappingError> {
         if is_cached {
             log::info!("Updating mapping repository...");
-            GitOperations::update(local_path)?;
+            GitOperations::update(local_path, reference)?;
         } else {
             log::info!("Cloning mapping repository...");
-            GitOperations::shallow_clone(url, local_path)?;
+            GitOperations::shallow_clone(url, reference, local_path)?;
         }
         Ok(())
     }
 
+    /// Returns the commit SHA the local repository is checked out at.
+    ///
+    /// Returns `None` (and logs the reason at debug level) if the commit
+    /// cannot be determined, e.g. because git is not installed.
+    pub fn commit_sha(&self) -> Option<String> {
+        match GitOperations::head_commit(&self.local_path) {
+            Ok(sha) => Some(sha),
+            Err(e) => {
+                log::debug!("Cannot determine mapping repository commit: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Checks out `reference` (a branch, tag, or commit) of the repository into
+    /// a temporary directory, e.g. for `--compare-mappings-ref`.
+    ///
+    /// The checkout is removed when the returned `TempDir` is dropped.
+    pub fn checkout_ref(&self, reference: &str) -> Result<TempDir, MappingError> {
+        let temp_dir = TempDir::with_prefix("lppc-mappings-").map_err(GitError::Io)?;
+        GitOperations::checkout_ref(&self.url, reference, temp_dir.path())?;
+        Ok(temp_dir)
+    }
+
     /// Returns the path to the aws mappings directory within the repository.
     ///
     /// This is where the YAML mapping files for AWS resources are located.
@@ -155,20 +297,22 @@ impl MappingRepository {
     ///
     /// # Arguments
     ///
+    /// * `provider` - The provider name (e.g., "aws", "google")
     /// * `block_type` - The block type (e.g., "resource", "data", "ephemeral", "action")
     /// * `resource_type` - The resource type (e.g., "aws_s3_bucket")
     ///
     /// # Returns
     ///
-    /// The full path to the mapping file (e.g., `~/.lppc/user/repo/mappings/resource/aws_s3_bucket.yaml`)
+    /// The full path to the mapping file (e.g., `~/.lppc/user/repo/mappings/resource/aws_s3_bucket.yaml`
+    /// or `~/.lppc/user/repo/mappings/google/resource/google_storage_bucket.yaml`)
     pub fn mapping_file_path(
         &self,
+        provider: &str,
         block_type: &str,
         resource_type: &str,
     ) -> PathBuf {
         self.local_path
-            .join("mappings")
-            .join(block_type)
+            .join(MappingLoader::mapping_dir(provider, block_type))
             .join(format!("{}.yaml", resource_type))
     }
 }
@@ -185,13 +329,21 @@ mod tests {
             was_refreshed: false,
         };
 
-        let path = repo.mapping_file_path("resource", "aws_s3_bucket");
+        let path = repo.mapping_file_path("aws", "resource", "aws_s3_bucket");
         assert_eq!(
             path,
             PathBuf::from(
                 "/home/user/.lppc/bebold-jhr/lppc-aws-test/mappings/resource/aws_s3_bucket.yaml"
             )
         );
+
+        let path = repo.mapping_file_path("google", "resource", "google_storage_bucket");
+        assert_eq!(
+            path,
+            PathBuf::from(
+                "/home/user/.lppc/bebold-jhr/lppc-aws-test/mappings/google/resource/google_storage_bucket.yaml"
+            )
+        );
     }
 
     #[test]
@@ -209,6 +361,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_local_dir_uses_directory_without_refresh() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+
+        let repo = MappingRepository::from_local_dir(temp_dir.path()).unwrap();
+
+        assert_eq!(repo.local_path, temp_dir.path());
+        assert!(!repo.was_refreshed);
+    }
+
+    #[test]
+    fn from_local_dir_rejects_missing_directory_and_layout() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let missing = MappingRepository::from_local_dir(&temp_dir.path().join("missing"));
+        assert!(matches!(
+            missing,
+            Err(MappingError::InvalidLocalDirectory(message)) if message.contains("does not exist")
+        ));
+
+        std::fs::create_dir_all(temp_dir.path().join("mappings")).unwrap();
+        let empty = MappingRepository::from_local_dir(temp_dir.path());
+        assert!(matches!(
+            empty,
+            Err(MappingError::InvalidLocalDirectory(message)) if message.contains("mappings/<block_type>")
+        ));
+    }
+
+    #[test]
+    fn test_from_cache_uses_cached_repository_only() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = CacheManager::with_base_dir(temp_dir.path().join(".lppc")).unwrap();
+        let url = "https://github.com/bebold-jhr/lppc-aws-test";
+
+        let missing = MappingRepository::from_cache_in(&cache, url, None);
+        assert!(matches!(
+            missing,
+            Err(MappingError::NotCached(message)) if message.contains("--offline")
+        ));
+
+        let repo_path = cache.get_repo_path(url, None).unwrap();
+        std::fs::create_dir_all(repo_path.join(".git")).unwrap();
+        let repo = MappingRepository::from_cache_in(&cache, url, None).unwrap();
+        assert_eq!(repo.local_path, repo_path);
+        assert!(!repo.was_refreshed);
+
+        // Each ref is cached separately
+        assert!(MappingRepository::from_cache_in(&cache, url, Some("v2")).is_err());
+    }
+
     // Integration tests for ensure_available would require network access
     // or a mock git server, and are better suited for integration tests
 }
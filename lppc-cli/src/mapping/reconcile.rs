@@ -0,0 +1,302 @@
+//! Reconciliation of allow actions against deny actions.
+//!
+//! AWS evaluates an explicit Deny before any Allow, so an action that is both
+//! allowed and denied is effectively denied. With `--apply-deny` the allow
+//! set of each group is reduced to what is effectively allowed, so reviewers
+//! see the same result AWS would compute. The Deny statement is kept.
+//!
+//! Actions are compared case-insensitively, and deny actions may use the IAM
+//! wildcards `*` and `?`:
+//!
+//! - a concrete allow action is removed if a deny action matches it
+//!   (`s3:GetObject` by `s3:GetObject`, `s3:Get*`, or `*`)
+//! - a wildcard allow action is removed only if a deny action covers all of
+//!   it: the same pattern, or a `prefix*` deny whose prefix it starts with
+//!   (`s3:Get*` by `s3:*`)
+//! - a wildcard allow action that only overlaps denied actions (`s3:Get*`
+//!   with a deny of `s3:GetObject`) is kept, since IAM cannot subtract from a
+//!   wildcard, and reported as partially denied
+
+use crate::diagnostics::Diagnostic;
+
+use super::allowlist::wildcard_match;
+use super::matcher::{GroupPermissions, PermissionResult};
+
+/// Outcome of reconciling one group's allow set against its deny set.
+#[derive(Debug, Clone)]
+pub struct DenyReconciliation {
+    /// The group's permissions with the effectively denied allow actions removed
+    pub permissions: GroupPermissions,
+    /// Removed allow actions as `"{action} (denied by {deny})"`, sorted
+    pub removed: Vec<String>,
+    /// Kept wildcard allow actions that match denied actions, as
+    /// `"{action} (except {deny}, ...)"`, sorted
+    pub partially_denied: Vec<String>,
+}
+
+/// Computes the effective allow set of a group under deny-wins evaluation.
+///
+/// The deny set and the resources and conditions of the kept allow actions
+/// are unchanged.
+pub fn reconcile_deny(permissions: &GroupPermissions) -> DenyReconciliation {
+    let mut denies: Vec<&String> = permissions.deny.iter().collect();
+    denies.sort();
+
+    let mut reconciled = permissions.clone();
+    let mut removed = Vec::new();
+    let mut partially_denied = Vec::new();
+
+    for action in &permissions.allow {
+        if let Some(deny) = denies.iter().find(|deny| covers(deny, action)) {
+            reconciled.allow.remove(action);
+            reconciled.resources.remove(action);
+            reconciled.conditions.remove(action);
+            removed.push(format!("{} (denied by {})", action, deny));
+            continue;
+        }
+
+        if is_wildcard(action) {
+            let overlapping: Vec<&str> = denies
+                .iter()
+                .filter(|deny| !is_wildcard(deny) && matches(action, deny))
+                .map(|deny| deny.as_str())
+                .collect();
+            if !overlapping.is_empty() {
+                partially_denied.push(format!("{} (except {})", action, overlapping.join(", ")));
+            }
+        }
+    }
+
+    removed.sort();
+    partially_denied.sort();
+    DenyReconciliation {
+        permissions: reconciled,
+        removed,
+        partially_denied,
+    }
+}
+
+/// Reconciles every group of a result in place (`--apply-deny`).
+///
+/// Groups whose allow set becomes empty are kept, since their Deny
+/// statement still applies.
+///
+/// # Returns
+///
+/// One informational diagnostic per group with removed actions and one
+/// warning per group with partially denied wildcard actions.
+pub fn apply_deny(result: &mut PermissionResult) -> Vec<Diagnostic> {
+    let mut names: Vec<String> = result.groups.keys().cloned().collect();
+    names.sort();
+
+    let mut diagnostics = Vec::new();
+    for name in names {
+        let Some(permissions) = result.groups.get_mut(&name) else {
+            continue;
+        };
+
+        let reconciliation = reconcile_deny(permissions);
+        *permissions = reconciliation.permissions;
+
+        if !reconciliation.removed.is_empty() {
+            diagnostics.push(Diagnostic::info(format!(
+                "{}: removed {} denied action(s) from allow: {}",
+                name,
+                reconciliation.removed.len(),
+                reconciliation.removed.join(", ")
+            )));
+        }
+        if !reconciliation.partially_denied.is_empty() {
+            diagnostics.push(Diagnostic::warn(format!(
+                "{}: {} wildcard allow action(s) are partially denied and kept: {}",
+                name,
+                reconciliation.partially_denied.len(),
+                reconciliation.partially_denied.join(", ")
+            )));
+        }
+    }
+    diagnostics
+}
+
+fn is_wildcard(action: &str) -> bool {
+    action.contains(['*', '?'])
+}
+
+/// Matches an action against a pattern, case-insensitively.
+fn matches(pattern: &str, action: &str) -> bool {
+    wildcard_match(&pattern.to_lowercase(), &action.to_lowercase())
+}
+
+/// Checks whether every action matched by `allow` is matched by `deny`.
+fn covers(deny: &str, allow: &str) -> bool {
+    if !is_wildcard(allow) {
+        return matches(deny, allow);
+    }
+    if deny.eq_ignore_ascii_case(allow) {
+        return true;
+    }
+
+    // Only a trailing `*` is known to cover any suffix of the allow pattern
+    deny.strip_suffix('*').is_some_and(|prefix| {
+        !is_wildcard(prefix) && allow.to_lowercase().starts_with(&prefix.to_lowercase())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::group;
+    use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+    fn sorted(actions: &HashSet<String>) -> Vec<&str> {
+        let mut sorted: Vec<&str> = actions.iter().map(String::as_str).collect();
+        sorted.sort();
+        sorted
+    }
+
+    #[test]
+    fn concrete_deny_removes_the_same_concrete_allow() {
+        let reconciliation =
+            reconcile_deny(&group(&["s3:GetObject", "s3:PutObject"], &["S3:getobject"]));
+
+        assert_eq!(sorted(&reconciliation.permissions.allow), ["s3:PutObject"]);
+        assert_eq!(
+            reconciliation.removed,
+            ["s3:GetObject (denied by S3:getobject)"]
+        );
+        assert!(reconciliation.permissions.deny.contains("S3:getobject"));
+    }
+
+    #[test]
+    fn wildcard_deny_removes_matching_concrete_allows() {
+        let reconciliation = reconcile_deny(&group(
+            &[
+                "s3:GetObject",
+                "s3:GetBucketPolicy",
+                "s3:PutObject",
+                "ec2:GetConsoleOutput",
+            ],
+            &["s3:Get*", "ec2:Get?onsoleOutput"],
+        ));
+
+        assert_eq!(sorted(&reconciliation.permissions.allow), ["s3:PutObject"]);
+        assert_eq!(
+            reconciliation.removed,
+            [
+                "ec2:GetConsoleOutput (denied by ec2:Get?onsoleOutput)",
+                "s3:GetBucketPolicy (denied by s3:Get*)",
+                "s3:GetObject (denied by s3:Get*)",
+            ]
+        );
+    }
+
+    #[test]
+    fn concrete_deny_keeps_wildcard_allow_as_partially_denied() {
+        let reconciliation = reconcile_deny(&group(
+            &["s3:Get*", "s3:List*"],
+            &["s3:GetObject", "s3:GetBucketPolicy"],
+        ));
+
+        assert_eq!(
+            sorted(&reconciliation.permissions.allow),
+            ["s3:Get*", "s3:List*"]
+        );
+        assert!(reconciliation.removed.is_empty());
+        assert_eq!(
+            reconciliation.partially_denied,
+            ["s3:Get* (except s3:GetBucketPolicy, s3:GetObject)"]
+        );
+    }
+
+    #[test]
+    fn wildcard_allow_is_removed_only_if_the_deny_covers_it() {
+        let reconciliation = reconcile_deny(&group(
+            &["s3:Get*", "s3:List*", "ec2:Describe*", "sqs:Get*"],
+            &["s3:*", "ec2:describe*", "sqs:Get?"],
+        ));
+
+        assert_eq!(sorted(&reconciliation.permissions.allow), ["sqs:Get*"]);
+        assert_eq!(
+            reconciliation.removed,
+            [
+                "ec2:Describe* (denied by ec2:describe*)",
+                "s3:Get* (denied by s3:*)",
+                "s3:List* (denied by s3:*)",
+            ]
+        );
+        // `sqs:Get?` matches only some actions of `sqs:Get*`, and is itself a wildcard
+        assert!(reconciliation.partially_denied.is_empty());
+    }
+
+    #[test]
+    fn full_wildcard_deny_removes_every_allow_and_other_services_are_kept() {
+        let reconciliation = reconcile_deny(&group(&["s3:Get*", "ec2:RunInstances"], &["*"]));
+        assert!(reconciliation.permissions.allow.is_empty());
+
+        let reconciliation =
+            reconcile_deny(&group(&["sqs:GetQueueUrl"], &["s3:Get*", "s3:GetObject"]));
+        assert_eq!(
+            sorted(&reconciliation.permissions.allow),
+            ["sqs:GetQueueUrl"]
+        );
+        assert!(reconciliation.removed.is_empty());
+    }
+
+    #[test]
+    fn resources_of_removed_actions_are_dropped() {
+        let mut group = group(&["s3:GetObject", "s3:PutObject"], &["s3:GetObject"]);
+        let scope = BTreeSet::from(["arn:aws:s3:::logs".to_string()]);
+        group.resources = BTreeMap::from([
+            ("s3:GetObject".to_string(), scope.clone()),
+            ("s3:PutObject".to_string(), scope.clone()),
+        ]);
+
+        let reconciliation = reconcile_deny(&group);
+
+        assert_eq!(
+            reconciliation.permissions.resources,
+            BTreeMap::from([("s3:PutObject".to_string(), scope)])
+        );
+    }
+
+    #[test]
+    fn apply_deny_reports_per_group() {
+        let mut result = PermissionResult {
+            groups: std::collections::HashMap::from([
+                (
+                    "StorageDeployer".to_string(),
+                    group(
+                        &["s3:GetObject", "s3:List*"],
+                        &["s3:GetObject", "s3:ListBucket"],
+                    ),
+                ),
+                (
+                    "NetworkDeployer".to_string(),
+                    group(&["ec2:CreateVpc"], &[]),
+                ),
+            ]),
+            ..Default::default()
+        };
+
+        let diagnostics = apply_deny(&mut result);
+
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "StorageDeployer: removed 1 denied action(s) from allow: s3:GetObject (denied by s3:GetObject)",
+                "StorageDeployer: 1 wildcard allow action(s) are partially denied and kept: s3:List* (except s3:ListBucket)",
+            ]
+        );
+        assert_eq!(
+            sorted(&result.groups["StorageDeployer"].allow),
+            ["s3:List*"]
+        );
+        assert_eq!(
+            sorted(&result.groups["NetworkDeployer"].allow),
+            ["ec2:CreateVpc"]
+        );
+    }
+}
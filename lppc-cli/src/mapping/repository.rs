@@ -22,6 +22,9 @@ pub enum GitError {
     #[error("Network unreachable, using cached version")]
     NetworkUnreachable,
 
+    #[error("Mapping repository cache has local changes: {0}")]
+    LocalChanges(String),
+
     #[error("Git command not found. Please install git.")]
     GitNotInstalled,
 
@@ -67,6 +70,19 @@ impl GitOperations {
             .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '/')
     }
 
+    /// Validates a ref (branch, tag, or commit) for security.
+    ///
+    /// Rejects refs that could be interpreted as command-line arguments or
+    /// contain characters outside of common branch and tag names.
+    fn is_valid_ref(reference: &str) -> bool {
+        if reference.is_empty() || reference.starts_with('-') || reference.contains("..") {
+            return false;
+        }
+        reference
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/' | '.'))
+    }
+
     /// Validates a URL for security.
     ///
     /// Ensures the URL uses a safe protocol and doesn't contain
@@ -99,11 +115,27 @@ impl GitOperations {
         Ok(())
     }
 
+    /// Returns whether a ref looks like an (abbreviated) commit hash.
+    fn is_commit_hash(reference: &str) -> bool {
+        (7..=40).contains(&reference.len()) && reference.chars().all(|c| c.is_ascii_hexdigit())
+    }
+
     /// Clones a repository with shallow clone (depth=1).
     ///
-    /// Creates the parent directories if they don't exist.
-    pub fn shallow_clone(url: &str, target_path: &Path) -> Result<(), GitError> {
+    /// Clones the default branch, or `reference` if given: branches and tags
+    /// via `--branch`, commits by fetching and checking out the commit after
+    /// the clone. Creates the parent directories if they don't exist.
+    pub fn shallow_clone(
+        url: &str,
+        reference: Option<&str>,
+        target_path: &Path,
+    ) -> Result<(), GitError> {
         Self::validate_url(url)?;
+        if let Some(reference) = reference
+            && !Self::is_valid_ref(reference)
+        {
+            return Err(GitError::Git(format!("Invalid ref: {}", reference)));
+        }
         Self::check_git_available()?;
 
         log::info!("Cloning mapping repository from {}...", url);
@@ -135,19 +167,21 @@ impl GitOperations {
 
         // Run git clone with depth=1 for shallow clone
         // Use "--" to separate options from URL argument for security
-        let output = Self::git_command()
-            .args([
-                "clone",
-                "--depth",
-                "1",
-                "--single-branch",
-                "--",
-                url,
-                target_str,
-            ])
-            .output()?;
+        let mut args = vec!["clone", "--depth", "1", "--single-branch"];
+        match reference {
+            Some(commit) if Self::is_commit_hash(commit) => args.push("--no-checkout"),
+            Some(reference) => args.extend(["--branch", reference]),
+            None => {}
+        }
+        args.extend(["--", url, target_str]);
+        let output = Self::git_command().args(&args).output()?;
 
         if output.status.success() {
+            if let Some(commit) = reference
+                && Self::is_commit_hash(commit)
+            {
+                Self::fetch_and_checkout(target_path, commit)?;
+            }
             log::info!("Successfully cloned mapping repository");
             Ok(())
         } else {
@@ -159,8 +193,19 @@ impl GitOperations {
 
     /// Updates an existing repository via fetch + reset.
     ///
-    /// Uses shallow fetch to minimize bandwidth.
-    pub fn update(repo_path: &Path) -> Result<(), GitError> {
+    /// Uses shallow fetch to minimize bandwidth. Refuses to update with
+    /// `GitError::LocalChanges` if the working tree has local modifications
+    /// (e.g., mappings edited in place for testing), since the reset would
+    /// discard them.
+    ///
+    /// With `reference`, the ref is fetched and checked out (detached) instead
+    /// of resetting to the default branch.
+    pub fn update(repo_path: &Path, reference: Option<&str>) -> Result<(), GitError> {
+        if let Some(reference) = reference
+            && !Self::is_valid_ref(reference)
+        {
+            return Err(GitError::Git(format!("Invalid ref: {}", reference)));
+        }
         Self::check_git_available()?;
 
         log::info!("Updating mapping repository...");
@@ -170,6 +215,17 @@ impl GitOperations {
             return Err(GitError::NotFound(repo_path.display().to_string()));
         }
 
+        let changes = Self::local_changes(repo_path)?;
+        if !changes.is_empty() {
+            return Err(GitError::LocalChanges(changes.join(", ")));
+        }
+
+        if let Some(reference) = reference {
+            Self::fetch_and_checkout(repo_path, reference)?;
+            log::info!("Successfully updated mapping repository to {}", reference);
+            return Ok(());
+        }
+
         // Fetch with depth=1
         let fetch_output = Self::git_command()
             .current_dir(repo_path)
@@ -235,6 +291,121 @@ impl GitOperations {
         Ok(())
     }
 
+    /// Fetches `reference` from `origin` (depth=1) and checks it out detached.
+    fn fetch_and_checkout(repo_path: &Path, reference: &str) -> Result<(), GitError> {
+        let fetch_output = Self::git_command()
+            .current_dir(repo_path)
+            .args([
+                "fetch", "--quiet", "--depth", "1", "--", "origin", reference,
+            ])
+            .output()?;
+
+        if !fetch_output.status.success() {
+            let stderr = String::from_utf8_lossy(&fetch_output.stderr);
+            log::error!("Failed to fetch {}: {}", reference, stderr);
+            return Err(Self::classify_error(&stderr));
+        }
+
+        let checkout_output = Self::git_command()
+            .current_dir(repo_path)
+            .args(["checkout", "--quiet", "--detach", "FETCH_HEAD"])
+            .output()?;
+
+        if !checkout_output.status.success() {
+            let stderr = String::from_utf8_lossy(&checkout_output.stderr);
+            log::error!("Failed to check out {}: {}", reference, stderr);
+            return Err(GitError::Git(stderr.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Checks out a branch, tag, or commit of a repository into an empty directory.
+    ///
+    /// Only that revision is fetched (depth=1) into a new repository, so the
+    /// cached clone is left untouched.
+    pub fn checkout_ref(url: &str, reference: &str, target_path: &Path) -> Result<(), GitError> {
+        Self::validate_url(url)?;
+        Self::checkout_ref_from(url, reference, target_path)
+    }
+
+    /// Like [`GitOperations::checkout_ref`], but without validating the source,
+    /// which may be a local repository path.
+    fn checkout_ref_from(
+        source: &str,
+        reference: &str,
+        target_path: &Path,
+    ) -> Result<(), GitError> {
+        if !Self::is_valid_ref(reference) {
+            return Err(GitError::Git(format!("Invalid ref: {}", reference)));
+        }
+        Self::check_git_available()?;
+
+        log::info!("Fetching mapping repository at {}...", reference);
+        log::debug!("Target path: {:?}", target_path);
+
+        let run = |args: &[&str]| -> Result<(), GitError> {
+            let output = Self::git_command()
+                .current_dir(target_path)
+                .args(args)
+                .output()?;
+
+            if output.status.success() {
+                Ok(())
+            } else {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                log::debug!("git {:?} failed: {}", args, stderr);
+                Err(Self::classify_error(&stderr))
+            }
+        };
+
+        // Use "--" to separate options from the source and ref arguments for security
+        run(&["init", "--quiet"])?;
+        run(&["fetch", "--quiet", "--depth", "1", "--", source, reference])?;
+        run(&["checkout", "--quiet", "--detach", "FETCH_HEAD"])?;
+
+        Ok(())
+    }
+
+    /// Lists the paths with local changes in the working tree, including
+    /// untracked files, as reported by `git status --porcelain`.
+    fn local_changes(repo_path: &Path) -> Result<Vec<String>, GitError> {
+        let output = Self::git_command()
+            .current_dir(repo_path)
+            .args(["status", "--porcelain"])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitError::Git(stderr.trim().to_string()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.get(3..))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Returns the full commit SHA of `HEAD` in the given repository.
+    pub fn head_commit(repo_path: &Path) -> Result<String, GitError> {
+        if !repo_path.join(".git").exists() {
+            return Err(GitError::NotFound(repo_path.display().to_string()));
+        }
+
+        let output = Self::git_command()
+            .current_dir(repo_path)
+            .args(["rev-parse", "HEAD"])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitError::Git(stderr.trim().to_string()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
     /// Checks if the remote is reachable by attempting to connect.
     pub fn is_remote_reachable(url: &str) -> bool {
         log::debug!("Checking if remote is reachable: {}", url);
@@ -318,10 +489,167 @@ mod tests {
     fn test_update_nonexistent_repo() {
         let temp_dir =
             std::env::temp_dir().join(format!("lppc_test_git_update_{}", std::process::id()));
-        let result = GitOperations::update(&temp_dir.join("nonexistent"));
+        let result = GitOperations::update(&temp_dir.join("nonexistent"), None);
         assert!(matches!(result, Err(GitError::NotFound(_))));
     }
 
+    #[test]
+    fn test_head_commit_nonexistent_repo() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("lppc_test_git_head_{}", std::process::id()));
+        let result = GitOperations::head_commit(&temp_dir.join("nonexistent"));
+        assert!(matches!(result, Err(GitError::NotFound(_))));
+    }
+
+    /// Creates a git repository with one committed mapping file.
+    fn init_repo_with_mapping(repo_path: &Path) -> std::path::PathBuf {
+        let git = |args: &[&str]| {
+            let output = GitOperations::git_command()
+                .current_dir(repo_path)
+                .args(["-c", "user.name=lppc", "-c", "user.email=lppc@example.com"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+
+        let mapping = repo_path.join("aws_s3_bucket.yaml");
+        std::fs::write(&mapping, "allow:\n  - s3:CreateBucket\n").unwrap();
+        git(&["init", "--quiet"]);
+        git(&["add", "."]);
+        git(&["commit", "--quiet", "-m", "Add mapping"]);
+
+        mapping
+    }
+
+    #[test]
+    fn test_is_valid_ref() {
+        assert!(GitOperations::is_valid_ref("main"));
+        assert!(GitOperations::is_valid_ref("feature/s3-tagging"));
+        assert!(GitOperations::is_valid_ref("v1.2.0"));
+        assert!(GitOperations::is_valid_ref(
+            "3f2c1a9e8b7d6c5f4e3d2c1b0a9f8e7d6c5b4a39"
+        ));
+        assert!(!GitOperations::is_valid_ref(""));
+        assert!(!GitOperations::is_valid_ref("--upload-pack=evil"));
+        assert!(!GitOperations::is_valid_ref("main..feature"));
+        assert!(!GitOperations::is_valid_ref("main;rm"));
+    }
+
+    #[test]
+    fn test_checkout_ref_from_local_repo() {
+        if GitOperations::check_git_available().is_err() {
+            return;
+        }
+        let source = tempfile::TempDir::new().unwrap();
+        let mapping = init_repo_with_mapping(source.path());
+        let output = GitOperations::git_command()
+            .current_dir(source.path())
+            .args(["tag", "v1"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        std::fs::write(&mapping, "allow:\n  - s3:ListBucket\n").unwrap();
+        let target = tempfile::TempDir::new().unwrap();
+
+        GitOperations::checkout_ref_from(source.path().to_str().unwrap(), "v1", target.path())
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(target.path().join("aws_s3_bucket.yaml")).unwrap(),
+            "allow:\n  - s3:CreateBucket\n"
+        );
+    }
+
+    #[test]
+    fn test_update_checks_out_ref() {
+        if GitOperations::check_git_available().is_err() {
+            return;
+        }
+        let source = tempfile::TempDir::new().unwrap();
+        let mapping = init_repo_with_mapping(source.path());
+        let git = |dir: &Path, args: &[&str]| {
+            let output = GitOperations::git_command()
+                .current_dir(dir)
+                .args(["-c", "user.name=lppc", "-c", "user.email=lppc@example.com"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        git(source.path(), &["tag", "v1"]);
+        std::fs::write(&mapping, "allow:\n  - s3:ListBucket\n").unwrap();
+        git(
+            source.path(),
+            &["commit", "--quiet", "-am", "Change mapping"],
+        );
+        let target = tempfile::TempDir::new().unwrap();
+        git(
+            target.path(),
+            &["clone", "--quiet", source.path().to_str().unwrap(), "."],
+        );
+
+        GitOperations::update(target.path(), Some("v1")).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(target.path().join("aws_s3_bucket.yaml")).unwrap(),
+            "allow:\n  - s3:CreateBucket\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid_ref_is_rejected_before_git_runs() {
+        let target = tempfile::TempDir::new().unwrap();
+
+        let clone = GitOperations::shallow_clone(
+            "https://github.com/bebold-jhr/lppc-aws-mappings",
+            Some("--upload-pack=evil"),
+            &target.path().join("repo"),
+        );
+        let update = GitOperations::update(target.path(), Some("main..feature"));
+
+        assert!(matches!(clone, Err(GitError::Git(_))));
+        assert!(matches!(update, Err(GitError::Git(_))));
+        assert!(GitOperations::is_commit_hash("3f2c1a9"));
+        assert!(!GitOperations::is_commit_hash("v1.2.0"));
+    }
+
+    #[test]
+    fn test_local_changes_clean_repo() {
+        if GitOperations::check_git_available().is_err() {
+            return;
+        }
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        init_repo_with_mapping(temp_dir.path());
+
+        let changes = GitOperations::local_changes(temp_dir.path()).unwrap();
+
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_update_with_local_changes_keeps_edits() {
+        if GitOperations::check_git_available().is_err() {
+            return;
+        }
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mapping = init_repo_with_mapping(temp_dir.path());
+        let edited = "allow:\n  - s3:CreateBucket\n  - s3:PutBucketTagging\n";
+        std::fs::write(&mapping, edited).unwrap();
+        std::fs::write(temp_dir.path().join("aws_vpc.yaml"), "allow: []\n").unwrap();
+
+        let result = GitOperations::update(temp_dir.path(), None);
+
+        match result {
+            Err(GitError::LocalChanges(changes)) => {
+                assert_eq!(changes, "aws_s3_bucket.yaml, aws_vpc.yaml");
+            }
+            other => panic!("Expected GitError::LocalChanges, got {:?}", other),
+        }
+        assert_eq!(std::fs::read_to_string(&mapping).unwrap(), edited);
+        assert!(temp_dir.path().join("aws_vpc.yaml").exists());
+    }
+
     #[test]
     fn test_check_git_available() {
         // This test will pass if git is installed on the system
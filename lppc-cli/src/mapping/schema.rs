@@ -5,11 +5,22 @@
 
 use std::collections::{HashMap, HashSet};
 
+/// Action groups of a mapping that produce allow-effect permissions, and so
+/// can carry an IAM `Condition` (`conditions`) and resources (`resources`).
+///
+/// Deny is not supported: a conditional or scoped Deny would deny less than
+/// the mapping author intended.
+pub const ALLOW_GROUPS: [&str; 3] = ["allow", "conditional", "import"];
+
 /// Represents a YAML mapping file for a Terraform block type.
 ///
 /// Each mapping file contains allow actions (always needed), deny actions
-/// (explicitly denied), and conditional actions that depend on the presence
-/// of specific attributes in the Terraform block.
+/// (explicitly denied), optional `NotAction` lists for either effect,
+/// conditional actions that depend on the presence
+/// of specific attributes in the Terraform block, and import actions that are
+/// only needed when the resource is the target of an `import` block. Optional
+/// ARN templates scope the allow-effect groups to the block's resources, and
+/// optional IAM conditions restrict them.
 #[derive(Debug, Clone)]
 pub struct ActionMapping {
     /// Allow actions (always needed for this resource type)
@@ -18,9 +29,109 @@ pub struct ActionMapping {
     /// Deny actions (explicitly denied for this resource type)
     pub deny: Vec<String>,
 
+    /// Actions excluded from an Allow statement using `NotAction`, i.e. the
+    /// statement allows every action except these
+    pub not_allow: Vec<String>,
+
+    /// Actions excluded from a Deny statement using `NotAction`, i.e. the
+    /// statement denies every action except these
+    pub not_deny: Vec<String>,
+
     /// Conditional actions based on attribute presence.
     /// Can be nested to any depth. Always produces allow-effect permissions.
     pub conditional: ConditionalActions,
+
+    /// Actions needed to import an existing resource of this type
+    /// (used for `import` blocks). Always produces allow-effect permissions.
+    pub import: Vec<String>,
+
+    /// IAM `Condition` objects keyed by action group (one of
+    /// `ALLOW_GROUPS`), attached to the statements granting that group's
+    /// actions. Groups without an entry are granted unconditionally.
+    pub conditions: HashMap<String, serde_json::Value>,
+
+    /// Resource ARN templates keyed by action group (one of `ALLOW_GROUPS`),
+    /// with `${attribute}` placeholders for literal attribute values (e.g.,
+    /// `arn:aws:s3:::${bucket}/*`). A `resource_arn_template` in the mapping
+    /// file fills the `allow` and `conditional` entries it does not declare.
+    /// Groups without an entry keep `"*"`.
+    pub resources: HashMap<String, Vec<String>>,
+}
+
+impl ActionMapping {
+    /// Returns every action listed in the mapping, regardless of section.
+    ///
+    /// Covers `allow`, `deny`, all `conditional` leaves, `import`, `not_allow`,
+    /// and `not_deny`, in that order.
+    pub fn all_actions(&self) -> Vec<&str> {
+        let mut actions: Vec<&str> = self
+            .allow
+            .iter()
+            .chain(&self.deny)
+            .map(String::as_str)
+            .collect();
+        self.conditional.collect_actions(&mut actions);
+        actions.extend(
+            self.import
+                .iter()
+                .chain(&self.not_allow)
+                .chain(&self.not_deny)
+                .map(String::as_str),
+        );
+        actions
+    }
+
+    /// Returns the resource ARNs the actions of an action group are scoped to
+    /// for a block with the given literal attribute values.
+    ///
+    /// Fills the group's `resources` templates. Returns `["*"]` if the group
+    /// has none (always for `not_allow` and `not_deny`, whose `NotAction`
+    /// statements an ARN would narrow), or if a placeholder has no literal
+    /// value (e.g., the attribute is missing or interpolated).
+    pub fn resources_of(
+        &self,
+        group: &str,
+        attribute_values: &HashMap<String, String>,
+    ) -> Vec<String> {
+        self.resources
+            .get(group)
+            .and_then(|templates| {
+                templates
+                    .iter()
+                    .map(|template| fill_placeholders(template, attribute_values))
+                    .collect::<Option<Vec<String>>>()
+            })
+            .filter(|resources| !resources.is_empty())
+            .unwrap_or_else(|| vec!["*".to_string()])
+    }
+
+    /// Returns every action the mapping can allow.
+    ///
+    /// Covers `allow`, all `conditional` leaves, and `import`, in that order.
+    pub fn allow_actions(&self) -> Vec<&str> {
+        let mut actions: Vec<&str> = self.allow.iter().map(String::as_str).collect();
+        self.conditional.collect_actions(&mut actions);
+        actions.extend(self.import.iter().map(String::as_str));
+        actions
+    }
+}
+
+/// Replaces every `${attribute}` placeholder of a template with the
+/// attribute's literal value, or returns `None` if a value is missing.
+fn fill_placeholders(template: &str, attribute_values: &HashMap<String, String>) -> Option<String> {
+    let mut rest = template;
+    let mut filled = String::new();
+
+    while let Some(start) = rest.find("${") {
+        let name_start = start + 2;
+        let end = name_start + rest[name_start..].find('}')?;
+        filled.push_str(&rest[..start]);
+        filled.push_str(attribute_values.get(&rest[name_start..end])?);
+        rest = &rest[end + 1..];
+    }
+    filled.push_str(rest);
+
+    Some(filled)
 }
 
 /// Represents conditional actions that depend on attribute presence.
@@ -41,18 +152,44 @@ pub enum ConditionalActions {
     None,
 }
 
+/// Evaluation of one conditional key of a mapping against a block's attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionalEvaluation {
+    /// Attribute path of the conditional key (e.g., `["vpc", "vpc_id"]`)
+    pub path: Vec<String>,
+
+    /// Whether the attribute path (and every path above it) is present
+    pub present: bool,
+
+    /// Actions of the key, added to the allow set only if `present`
+    pub actions: Vec<String>,
+}
+
 impl ConditionalActions {
     /// Checks if this is an empty/none value
     pub fn is_none(&self) -> bool {
         matches!(self, ConditionalActions::None)
     }
 
+    /// Appends the actions of all leaves, independent of attribute presence.
+    fn collect_actions<'a>(&'a self, actions: &mut Vec<&'a str>) {
+        match self {
+            ConditionalActions::None => {}
+            ConditionalActions::Actions(leaf) => actions.extend(leaf.iter().map(String::as_str)),
+            ConditionalActions::Nested(map) => {
+                for value in map.values() {
+                    value.collect_actions(actions);
+                }
+            }
+        }
+    }
+
     /// Resolves actions based on present attribute paths.
     ///
     /// # Arguments
     ///
     /// * `present_paths` - Set of attribute paths present in the terraform block.
-    ///                     Each path is a `Vec<String>` like `["vpc", "vpc_id"]`.
+    ///   Each path is a `Vec<String>` like `["vpc", "vpc_id"]`.
     ///
     /// # Returns
     ///
@@ -79,6 +216,52 @@ impl ConditionalActions {
         self.resolve_recursive(&[], present_paths)
     }
 
+    /// Evaluates every conditional key against present attribute paths.
+    ///
+    /// Unlike `resolve()`, this reports keys whose attributes are absent as
+    /// well, which is what `--explain-conditional` shows to mapping authors.
+    /// A key counts as present exactly when `resolve()` includes its actions.
+    ///
+    /// # Returns
+    ///
+    /// One evaluation per leaf of the conditional tree, sorted by path.
+    pub fn evaluate(&self, present_paths: &HashSet<Vec<String>>) -> Vec<ConditionalEvaluation> {
+        let mut evaluations = Vec::new();
+        self.evaluate_recursive(&[], true, present_paths, &mut evaluations);
+        evaluations.sort_by(|a, b| a.path.cmp(&b.path));
+        evaluations
+    }
+
+    /// `parents_present` tells whether every path above `current_path` is present.
+    fn evaluate_recursive(
+        &self,
+        current_path: &[String],
+        parents_present: bool,
+        present_paths: &HashSet<Vec<String>>,
+        evaluations: &mut Vec<ConditionalEvaluation>,
+    ) {
+        match self {
+            ConditionalActions::None => {}
+
+            ConditionalActions::Actions(actions) => evaluations.push(ConditionalEvaluation {
+                path: current_path.to_vec(),
+                present: parents_present && present_paths.contains(current_path),
+                actions: actions.clone(),
+            }),
+
+            ConditionalActions::Nested(map) => {
+                let present = parents_present
+                    && (current_path.is_empty() || present_paths.contains(current_path));
+
+                for (key, value) in map {
+                    let mut new_path = current_path.to_vec();
+                    new_path.push(key.clone());
+                    value.evaluate_recursive(&new_path, present, present_paths, evaluations);
+                }
+            }
+        }
+    }
+
     fn resolve_recursive(
         &self,
         current_path: &[String],
@@ -147,6 +330,79 @@ mod tests {
         assert!(!nested.is_none());
     }
 
+    #[test]
+    fn all_actions_covers_every_section() {
+        let mapping = ActionMapping {
+            allow: vec!["route53:CreateHostedZone".to_string()],
+            deny: vec!["route53:DeleteHostedZone".to_string()],
+            not_allow: vec!["route53:ListHostedZones".to_string()],
+            not_deny: vec!["route53:GetChange".to_string()],
+            conditional: ConditionalActions::Nested(HashMap::from([(
+                "vpc".to_string(),
+                ConditionalActions::Nested(HashMap::from([(
+                    "vpc_id".to_string(),
+                    ConditionalActions::Actions(vec![
+                        "route53:AssociateVPCWithHostedZone".to_string(),
+                    ]),
+                )])),
+            )])),
+            import: vec!["route53:GetHostedZone".to_string()],
+            conditions: HashMap::new(),
+            resources: HashMap::new(),
+        };
+
+        assert_eq!(
+            mapping.all_actions(),
+            vec![
+                "route53:CreateHostedZone",
+                "route53:DeleteHostedZone",
+                "route53:AssociateVPCWithHostedZone",
+                "route53:GetHostedZone",
+                "route53:ListHostedZones",
+                "route53:GetChange",
+            ]
+        );
+    }
+
+    #[test]
+    fn resources_of_fills_placeholders_of_the_group_templates() {
+        let mapping = ActionMapping {
+            allow: vec!["s3:PutObject".to_string()],
+            deny: Vec::new(),
+            not_allow: Vec::new(),
+            not_deny: Vec::new(),
+            conditional: ConditionalActions::None,
+            import: Vec::new(),
+            conditions: HashMap::new(),
+            resources: HashMap::from([
+                (
+                    "allow".to_string(),
+                    vec![
+                        "arn:aws:s3:::${bucket}".to_string(),
+                        "arn:aws:s3:::${bucket}/*".to_string(),
+                    ],
+                ),
+                (
+                    "conditional".to_string(),
+                    vec!["arn:aws:s3:::${bucket}".to_string()],
+                ),
+            ]),
+        };
+        let values = HashMap::from([("bucket".to_string(), "logs".to_string())]);
+
+        assert_eq!(
+            mapping.resources_of("allow", &values),
+            vec!["arn:aws:s3:::logs", "arn:aws:s3:::logs/*"]
+        );
+        assert_eq!(
+            mapping.resources_of("conditional", &values),
+            vec!["arn:aws:s3:::logs"]
+        );
+        assert_eq!(mapping.resources_of("import", &values), vec!["*"]);
+        assert_eq!(mapping.resources_of("not_deny", &values), vec!["*"]);
+        assert_eq!(mapping.resources_of("allow", &HashMap::new()), vec!["*"]);
+    }
+
     #[test]
     fn resolve_returns_empty_for_none() {
         let conditional = ConditionalActions::None;
@@ -156,8 +412,7 @@ mod tests {
 
     #[test]
     fn resolve_returns_actions_when_path_present() {
-        let conditional =
-            ConditionalActions::Actions(vec!["s3:PutBucketTagging".to_string()]);
+        let conditional = ConditionalActions::Actions(vec!["s3:PutBucketTagging".to_string()]);
 
         let mut present = HashSet::new();
         present.insert(Vec::new()); // Empty path for root-level Actions
@@ -168,8 +423,7 @@ mod tests {
 
     #[test]
     fn resolve_returns_empty_when_path_absent() {
-        let conditional =
-            ConditionalActions::Actions(vec!["s3:PutBucketTagging".to_string()]);
+        let conditional = ConditionalActions::Actions(vec!["s3:PutBucketTagging".to_string()]);
         let present = HashSet::new(); // No paths present
 
         let resolved = conditional.resolve(&present);
@@ -199,16 +453,11 @@ mod tests {
         let mut vpc_id_map = HashMap::new();
         vpc_id_map.insert(
             "vpc_id".to_string(),
-            ConditionalActions::Actions(vec![
-                "route53:AssociateVPCWithHostedZone".to_string(),
-            ]),
+            ConditionalActions::Actions(vec!["route53:AssociateVPCWithHostedZone".to_string()]),
         );
 
         let mut root_map = HashMap::new();
-        root_map.insert(
-            "vpc".to_string(),
-            ConditionalActions::Nested(vpc_id_map),
-        );
+        root_map.insert("vpc".to_string(), ConditionalActions::Nested(vpc_id_map));
 
         let conditional = ConditionalActions::Nested(root_map);
 
@@ -230,16 +479,11 @@ mod tests {
         let mut vpc_id_map = HashMap::new();
         vpc_id_map.insert(
             "vpc_id".to_string(),
-            ConditionalActions::Actions(vec![
-                "route53:AssociateVPCWithHostedZone".to_string(),
-            ]),
+            ConditionalActions::Actions(vec!["route53:AssociateVPCWithHostedZone".to_string()]),
         );
 
         let mut root_map = HashMap::new();
-        root_map.insert(
-            "vpc".to_string(),
-            ConditionalActions::Nested(vpc_id_map),
-        );
+        root_map.insert("vpc".to_string(), ConditionalActions::Nested(vpc_id_map));
 
         let conditional = ConditionalActions::Nested(root_map);
 
@@ -292,16 +536,10 @@ mod tests {
         );
 
         let mut level2_map = HashMap::new();
-        level2_map.insert(
-            "level2".to_string(),
-            ConditionalActions::Nested(level3_map),
-        );
+        level2_map.insert("level2".to_string(), ConditionalActions::Nested(level3_map));
 
         let mut level1_map = HashMap::new();
-        level1_map.insert(
-            "level1".to_string(),
-            ConditionalActions::Nested(level2_map),
-        );
+        level1_map.insert("level1".to_string(), ConditionalActions::Nested(level2_map));
 
         let conditional = ConditionalActions::Nested(level1_map);
 
@@ -323,7 +561,12 @@ mod tests {
         let mapping = ActionMapping {
             allow: vec!["s3:Get*".to_string(), "s3:List*".to_string()],
             deny: vec!["s3:GetObject".to_string()],
+            not_allow: Vec::new(),
+            not_deny: Vec::new(),
             conditional: ConditionalActions::None,
+            import: Vec::new(),
+            conditions: HashMap::new(),
+            resources: HashMap::new(),
         };
 
         assert_eq!(mapping.allow.len(), 2);
@@ -331,4 +574,47 @@ mod tests {
         assert!(mapping.deny.contains(&"s3:GetObject".to_string()));
         assert!(!mapping.allow.contains(&"s3:GetObject".to_string()));
     }
+
+    #[test]
+    fn evaluate_reports_present_and_absent_keys() {
+        let mut vpc_map = HashMap::new();
+        vpc_map.insert(
+            "vpc_id".to_string(),
+            ConditionalActions::Actions(vec!["route53:AssociateVPCWithHostedZone".to_string()]),
+        );
+        let mut root_map = HashMap::new();
+        root_map.insert("vpc".to_string(), ConditionalActions::Nested(vpc_map));
+        root_map.insert(
+            "tags".to_string(),
+            ConditionalActions::Actions(vec!["route53:ChangeTagsForResource".to_string()]),
+        );
+        let conditional = ConditionalActions::Nested(root_map);
+
+        // vpc_id without its parent block is not enough, as in resolve()
+        let mut present = HashSet::new();
+        present.insert(vec!["tags".to_string()]);
+        present.insert(vec!["vpc".to_string(), "vpc_id".to_string()]);
+
+        let evaluations = conditional.evaluate(&present);
+
+        assert_eq!(
+            evaluations,
+            vec![
+                ConditionalEvaluation {
+                    path: vec!["tags".to_string()],
+                    present: true,
+                    actions: vec!["route53:ChangeTagsForResource".to_string()],
+                },
+                ConditionalEvaluation {
+                    path: vec!["vpc".to_string(), "vpc_id".to_string()],
+                    present: false,
+                    actions: vec!["route53:AssociateVPCWithHostedZone".to_string()],
+                },
+            ]
+        );
+        assert_eq!(
+            conditional.resolve(&present),
+            vec!["route53:ChangeTagsForResource"]
+        );
+    }
 }
@@ -0,0 +1,156 @@
+//! Strict validation of action service prefixes.
+//!
+//! This module checks that the service prefix of every action in the loaded
+//! mappings (the part before `:`) is a known AWS service prefix
+//! (`--validate-mappings-strict`), so typos like `s33:GetObject` are caught
+//! before they end up in a policy. The list of known prefixes is bundled in
+//! `aws_service_prefixes.txt`.
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use thiserror::Error;
+
+use super::loader::MappingLoader;
+use super::suggest::closest_match;
+
+/// Bundled list of known AWS service prefixes, one per line.
+const AWS_SERVICE_PREFIXES: &str = include_str!("aws_service_prefixes.txt");
+
+/// Errors reported by the strict mapping validation.
+#[derive(Debug, Error)]
+pub enum ServicePrefixError {
+    #[error("Unknown AWS service prefixes in mappings (likely typos): {0}")]
+    UnknownPrefixes(String),
+}
+
+/// Returns the set of known AWS service prefixes.
+fn known_prefixes() -> &'static HashSet<&'static str> {
+    static PREFIXES: OnceLock<HashSet<&'static str>> = OnceLock::new();
+    PREFIXES.get_or_init(|| {
+        AWS_SERVICE_PREFIXES
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect()
+    })
+}
+
+/// Checks whether the service prefix of an action is a known AWS service prefix.
+///
+/// Prefixes are compared case-insensitively, as IAM does. The bare `*` action
+/// is accepted; any other action without a `:` has no prefix and is rejected.
+pub fn has_known_service_prefix(action: &str) -> bool {
+    if action == "*" {
+        return true;
+    }
+
+    match action.split_once(':') {
+        Some((prefix, _)) => known_prefixes().contains(prefix.to_lowercase().as_str()),
+        None => false,
+    }
+}
+
+/// Describes an action with an unknown service prefix, with a suggestion if
+/// a known prefix is close enough to be the intended one.
+fn describe_unknown(action: &str, candidates: &[String]) -> String {
+    let prefix = action.split_once(':').map_or(action, |(prefix, _)| prefix);
+
+    match closest_match(&prefix.to_lowercase(), candidates) {
+        Some(suggestion) => format!("{} (did you mean {}?)", action, suggestion),
+        None => action.to_string(),
+    }
+}
+
+/// Lists the actions with unknown service prefixes in all loaded AWS mappings.
+///
+/// Mappings of other providers are not checked, since their permissions have
+/// no IAM service prefix. Each entry has the form `"{mapping key}: {action}"`, e.g.
+/// `"aws/resource/aws_s3_bucket: s33:GetObject (did you mean s3?)"`,
+/// sorted by mapping key.
+pub fn unknown_service_prefixes(loader: &MappingLoader) -> Vec<String> {
+    let mut candidates: Vec<String> = known_prefixes().iter().map(|p| p.to_string()).collect();
+    candidates.sort();
+
+    let mut unknown = Vec::new();
+    for (key, mapping) in loader.loaded_mappings() {
+        if !key.starts_with("aws/") {
+            continue;
+        }
+
+        let mut actions = mapping.all_actions();
+        actions.sort();
+        actions.dedup();
+
+        for action in actions {
+            if !has_known_service_prefix(action) {
+                unknown.push(format!(
+                    "{}: {}",
+                    key,
+                    describe_unknown(action, &candidates)
+                ));
+            }
+        }
+    }
+
+    unknown
+}
+
+/// Fails if any loaded mapping contains an action with an unknown service prefix.
+pub fn check_service_prefixes(loader: &MappingLoader) -> Result<(), ServicePrefixError> {
+    let unknown = unknown_service_prefixes(loader);
+
+    if unknown.is_empty() {
+        return Ok(());
+    }
+
+    Err(ServicePrefixError::UnknownPrefixes(unknown.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::loader_with_mapping;
+
+    #[test]
+    fn known_prefixes_are_accepted() {
+        assert!(has_known_service_prefix("s3:GetObject"));
+        assert!(has_known_service_prefix("ec2:CreateVpc"));
+        assert!(has_known_service_prefix(
+            "elasticloadbalancing:CreateLoadBalancer"
+        ));
+        assert!(has_known_service_prefix("S3:GetObject"));
+        assert!(has_known_service_prefix("s3:*"));
+        assert!(has_known_service_prefix("*"));
+    }
+
+    #[test]
+    fn unknown_or_missing_prefixes_are_rejected() {
+        assert!(!has_known_service_prefix("s33:GetObject"));
+        assert!(!has_known_service_prefix("GetObject"));
+        assert!(!has_known_service_prefix(":GetObject"));
+    }
+
+    #[test]
+    fn mapping_with_valid_prefixes_passes() {
+        let (_temp_dir, loader) = loader_with_mapping(
+            "allow:\n  - s3:CreateBucket\ndeny:\n  - s3:DeleteBucket\nconditional:\n  tags:\n    - s3:PutBucketTagging",
+        );
+
+        assert!(check_service_prefixes(&loader).is_ok());
+    }
+
+    #[test]
+    fn mapping_with_invalid_prefix_fails() {
+        let (_temp_dir, loader) = loader_with_mapping(
+            "allow:\n  - s3:CreateBucket\nconditional:\n  policy:\n    - s33:PutBucketPolicy",
+        );
+
+        let error = check_service_prefixes(&loader).unwrap_err();
+
+        let ServicePrefixError::UnknownPrefixes(unknown) = error;
+        assert_eq!(
+            unknown,
+            "aws/resource/aws_s3_bucket: s33:PutBucketPolicy (did you mean s3?)"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! Statistics about the mapping repository.
+//!
+//! The `mapping-stats` subcommand gives mapping maintainers a health overview of the
+//! repository: how many mapping files exist per block type, how many distinct
+//! actions and AWS services they reference, and how many files use
+//! conditionals or denies. Skip files (`.skip`) are not counted.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::terraform::BlockType;
+
+use super::loader::{LoadError, MAPPING_BLOCK_TYPES, MappingLoader};
+
+/// Aggregated statistics over all mappings of a repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappingStats {
+    /// Number of mapping files per block type, in directory order
+    pub files_per_block_type: Vec<(BlockType, usize)>,
+    /// Distinct actions referenced in any section (case-sensitive)
+    pub distinct_actions: usize,
+    /// Lowercased service prefixes of the referenced actions, sorted
+    pub services: BTreeSet<String>,
+    /// Number of files with a non-empty `conditional` section
+    pub files_with_conditionals: usize,
+    /// Number of files with a non-empty `deny` section
+    pub files_with_denies: usize,
+}
+
+impl MappingStats {
+    /// Collects the statistics by loading every mapping of the repository.
+    pub fn collect(loader: &MappingLoader) -> Result<Self, LoadError> {
+        let mappings = loader.load_all()?;
+
+        let files_per_block_type = MAPPING_BLOCK_TYPES
+            .iter()
+            .map(|block_type| {
+                let count = mappings
+                    .iter()
+                    .filter(|(mapping_type, _, _)| mapping_type == block_type)
+                    .count();
+                (*block_type, count)
+            })
+            .collect();
+
+        let mut actions = BTreeSet::new();
+        let mut services = BTreeSet::new();
+        for (_, _, mapping) in &mappings {
+            for action in mapping.all_actions() {
+                if let Some((prefix, _)) = action.split_once(':')
+                    && prefix != "*"
+                {
+                    services.insert(prefix.to_lowercase());
+                }
+                actions.insert(action.to_string());
+            }
+        }
+
+        Ok(Self {
+            files_per_block_type,
+            distinct_actions: actions.len(),
+            services,
+            files_with_conditionals: mappings
+                .iter()
+                .filter(|(_, _, mapping)| !mapping.conditional.is_none())
+                .count(),
+            files_with_denies: mappings
+                .iter()
+                .filter(|(_, _, mapping)| !mapping.deny.is_empty())
+                .count(),
+        })
+    }
+
+    /// Returns the total number of mapping files.
+    pub fn total_files(&self) -> usize {
+        self.files_per_block_type
+            .iter()
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Renders the statistics as an aligned text summary.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        let _ = writeln!(output, "Mapping files:           {}", self.total_files());
+        for (block_type, count) in &self.files_per_block_type {
+            let _ = writeln!(
+                output,
+                "  {:<22} {}",
+                format!("{}:", block_type.as_str()),
+                count
+            );
+        }
+        let _ = writeln!(output, "Distinct actions:        {}", self.distinct_actions);
+        let _ = writeln!(output, "Services covered:        {}", self.services.len());
+        let _ = writeln!(
+            output,
+            "Files with conditionals: {}",
+            self.files_with_conditionals
+        );
+        let _ = writeln!(
+            output,
+            "Files with denies:       {}",
+            self.files_with_denies
+        );
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_mapping(root: &TempDir, path: &str, content: &str) {
+        let path = root.path().join("mappings").join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn fixture() -> TempDir {
+        let root = TempDir::new().unwrap();
+        write_mapping(
+            &root,
+            "resource/aws_s3_bucket.yaml",
+            "allow:\n  - s3:CreateBucket\n  - s3:DeleteBucket\ndeny:\n  - s3:PutBucketAcl\nconditional:\n  tags:\n    - s3:PutBucketTagging",
+        );
+        write_mapping(
+            &root,
+            "resource/aws_vpc.yaml",
+            "allow:\n  - ec2:CreateVpc\n  - ec2:DeleteVpc",
+        );
+        write_mapping(
+            &root,
+            "data/aws_s3_bucket.json",
+            r#"{"allow": ["s3:ListBucket", "S3:CreateBucket"]}"#,
+        );
+        write_mapping(
+            &root,
+            "data/aws_caller_identity.yaml",
+            "allow:\n  - sts:GetCallerIdentity",
+        );
+        write_mapping(&root, "data/aws_partition.skip", "");
+        write_mapping(
+            &root,
+            "ephemeral/aws_secretsmanager_secret_version.yaml",
+            "allow:\n  - secretsmanager:GetSecretValue\nconditional:\n  version_stage:\n    - secretsmanager:DescribeSecret",
+        );
+        root
+    }
+
+    #[test]
+    fn stats_count_files_actions_and_services() {
+        let root = fixture();
+        let loader = MappingLoader::new(root.path().to_path_buf());
+
+        let stats = MappingStats::collect(&loader).unwrap();
+
+        assert_eq!(stats.total_files(), 5);
+        assert_eq!(
+            stats.files_per_block_type,
+            vec![
+                (BlockType::Resource, 2),
+                (BlockType::Data, 2),
+                (BlockType::Ephemeral, 1),
+                (BlockType::Action, 0),
+            ]
+        );
+        // S3:CreateBucket is distinct from s3:CreateBucket, but the same service
+        assert_eq!(stats.distinct_actions, 11);
+        assert_eq!(
+            stats
+                .services
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+            vec!["ec2", "s3", "secretsmanager", "sts"]
+        );
+        assert_eq!(stats.files_with_conditionals, 2);
+        assert_eq!(stats.files_with_denies, 1);
+    }
+
+    #[test]
+    fn render_lists_every_statistic() {
+        let root = fixture();
+        let loader = MappingLoader::new(root.path().to_path_buf());
+
+        let rendered = MappingStats::collect(&loader).unwrap().render();
+
+        assert_eq!(
+            rendered,
+            "Mapping files:           5\n\
+             \x20 resource:              2\n\
+             \x20 data:                  2\n\
+             \x20 ephemeral:             1\n\
+             \x20 action:                0\n\
+             Distinct actions:        11\n\
+             Services covered:        4\n\
+             Files with conditionals: 2\n\
+             Files with denies:       1\n"
+        );
+    }
+
+    #[test]
+    fn empty_repository_has_no_files() {
+        let root = TempDir::new().unwrap();
+        let loader = MappingLoader::new(root.path().to_path_buf());
+
+        let stats = MappingStats::collect(&loader).unwrap();
+
+        assert_eq!(stats.total_files(), 0);
+        assert_eq!(stats.distinct_actions, 0);
+        assert!(stats.services.is_empty());
+    }
+}
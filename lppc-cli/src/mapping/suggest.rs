@@ -0,0 +1,87 @@
+//! "Did you mean" suggestions for missing mappings.
+//!
+//! This module finds the mapping type with the smallest Levenshtein distance
+//! to a type that has no mapping file, so that typos and near-miss names
+//! (e.g. `aws_s3_buckets` instead of `aws_s3_bucket`) can be pointed out.
+
+/// Returns the Levenshtein (edit) distance between two strings.
+///
+/// The distance is the minimum number of single-character insertions,
+/// deletions, or substitutions needed to turn `a` into `b`.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1; b_chars.len() + 1];
+
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution_cost = usize::from(a_char != *b_char);
+            current_row[j + 1] = (previous_row[j] + substitution_cost)
+                .min(previous_row[j + 1] + 1)
+                .min(current_row[j] + 1);
+        }
+
+        previous_row = current_row;
+    }
+
+    previous_row[b_chars.len()]
+}
+
+/// Returns the candidate closest to `name`, if it is close enough to be a likely typo.
+///
+/// A candidate qualifies if its distance is at most a quarter of the length of
+/// `name` (but at least 1). Ties are broken by the order of `candidates`.
+pub fn closest_match<'a>(name: &str, candidates: &'a [String]) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 4).max(1);
+
+    candidates
+        .iter()
+        .map(|candidate| (levenshtein(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn levenshtein_counts_edits() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("aws_vpc", "aws_vpc"), 0);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("abc", ""), 3);
+        assert_eq!(levenshtein("aws_s3_buckets", "aws_s3_bucket"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn closest_match_suggests_near_miss() {
+        let known = candidates(&["aws_s3_bucket", "aws_s3_bucket_policy", "aws_vpc"]);
+
+        assert_eq!(
+            closest_match("aws_s3_buckets", &known),
+            Some("aws_s3_bucket")
+        );
+        assert_eq!(closest_match("aws_vcp", &known), None);
+        assert_eq!(closest_match("aws_vpcs", &known), Some("aws_vpc"));
+    }
+
+    #[test]
+    fn closest_match_returns_none_for_novel_type() {
+        let known = candidates(&["aws_s3_bucket", "aws_s3_bucket_policy", "aws_vpc"]);
+
+        assert_eq!(closest_match("aws_quantum_ledger_table", &known), None);
+    }
+
+    #[test]
+    fn closest_match_returns_none_without_candidates() {
+        assert_eq!(closest_match("aws_s3_buckets", &[]), None);
+    }
+}
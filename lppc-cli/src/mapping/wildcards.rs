@@ -0,0 +1,145 @@
+//! Detection of wildcard actions in mappings.
+//!
+//! A mapping that allows `*` or `*:*` grants every action of every service and
+//! defeats least privilege entirely, so it is reported as an error diagnostic
+//! (and fails the run with `--validate-mappings-strict`). Service wildcards
+//! like `s3:*` are common in mappings and are only noted.
+
+use thiserror::Error;
+
+use crate::diagnostics::Diagnostic;
+
+use super::loader::MappingLoader;
+
+/// Errors reported by the strict mapping validation.
+#[derive(Debug, Error)]
+pub enum WildcardError {
+    #[error("Mappings allow every action, which defeats least privilege: {0}")]
+    FullWildcards(String),
+}
+
+/// Checks whether an action grants every action of every service.
+pub fn is_full_wildcard(action: &str) -> bool {
+    action == "*" || action == "*:*"
+}
+
+/// Checks whether an action grants every action of a single service (e.g. `s3:*`).
+fn is_service_wildcard(action: &str) -> bool {
+    match action.split_once(':') {
+        Some((prefix, name)) => prefix != "*" && name == "*",
+        None => false,
+    }
+}
+
+/// Lists the mappings that allow a full wildcard, as `"{mapping key}: {action}"`,
+/// sorted by mapping key.
+fn full_wildcards(loader: &MappingLoader) -> Vec<String> {
+    let mut found = Vec::new();
+    for (key, mapping) in loader.loaded_mappings() {
+        let mut actions = mapping.allow_actions();
+        actions.sort();
+        actions.dedup();
+
+        for action in actions {
+            if is_full_wildcard(action) {
+                found.push(format!("{}: {}", key, action));
+            }
+        }
+    }
+
+    found
+}
+
+/// Returns diagnostics for the wildcard actions allowed by the loaded mappings.
+///
+/// Full wildcards (`*`, `*:*`) are errors, service wildcards (`s3:*`) are
+/// informational. Mappings without wildcards produce no diagnostics.
+pub fn wildcard_diagnostics(loader: &MappingLoader) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (key, mapping) in loader.loaded_mappings() {
+        let mut actions = mapping.allow_actions();
+        actions.sort();
+        actions.dedup();
+
+        for action in actions {
+            if is_full_wildcard(action) {
+                diagnostics.push(Diagnostic::error(format!(
+                    "Mapping {} allows every action ({}), which defeats least privilege",
+                    key, action
+                )));
+            } else if is_service_wildcard(action) {
+                diagnostics.push(Diagnostic::info(format!(
+                    "Mapping {} allows every action of a service ({})",
+                    key, action
+                )));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Fails if any loaded mapping allows a full wildcard.
+pub fn check_full_wildcards(loader: &MappingLoader) -> Result<(), WildcardError> {
+    let found = full_wildcards(loader);
+
+    if found.is_empty() {
+        return Ok(());
+    }
+
+    Err(WildcardError::FullWildcards(found.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Severity;
+    use crate::test_support::loader_with_mapping;
+
+    #[test]
+    fn full_wildcard_is_flagged_as_error() {
+        let (_temp_dir, loader) = loader_with_mapping(
+            "allow:\n  - s3:CreateBucket\nconditional:\n  policy:\n    - \"*\"",
+        );
+
+        let diagnostics = wildcard_diagnostics(&loader);
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                "Mapping aws/resource/aws_s3_bucket allows every action (*), which defeats least privilege"
+            )]
+        );
+        let WildcardError::FullWildcards(found) = check_full_wildcards(&loader).unwrap_err();
+        assert_eq!(found, "aws/resource/aws_s3_bucket: *");
+    }
+
+    #[test]
+    fn service_wildcard_is_noted_but_passes() {
+        let (_temp_dir, loader) = loader_with_mapping("allow:\n  - s3:*");
+
+        let diagnostics = wildcard_diagnostics(&loader);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Info);
+        assert!(check_full_wildcards(&loader).is_ok());
+    }
+
+    #[test]
+    fn specific_actions_pass_cleanly() {
+        let (_temp_dir, loader) =
+            loader_with_mapping("allow:\n  - s3:CreateBucket\ndeny:\n  - \"*:*\"");
+
+        assert!(wildcard_diagnostics(&loader).is_empty());
+        assert!(check_full_wildcards(&loader).is_ok());
+    }
+
+    #[test]
+    fn full_wildcards_are_recognized() {
+        assert!(is_full_wildcard("*"));
+        assert!(is_full_wildcard("*:*"));
+        assert!(!is_full_wildcard("s3:*"));
+        assert!(!is_service_wildcard("*:*"));
+        assert!(is_service_wildcard("s3:*"));
+    }
+}
@@ -8,7 +8,7 @@ use saphyr::{LoadableYamlNode, Yaml};
 use std::collections::HashMap;
 use thiserror::Error;
 
-use super::schema::{ActionMapping, ConditionalActions};
+use super::schema::{ALLOW_GROUPS, ActionMapping, ConditionalActions};
 
 /// Errors that can occur during YAML parsing.
 #[derive(Debug, Error)]
@@ -16,6 +16,9 @@ pub enum ParseError {
     #[error("YAML parse error: {0}")]
     Yaml(String),
 
+    #[error("JSON parse error: {0}")]
+    Json(String),
+
     #[error("Empty YAML document")]
     Empty,
 
@@ -23,6 +26,25 @@ pub enum ParseError {
     InvalidStructure(String),
 }
 
+/// Parses JSON content into an `ActionMapping`.
+///
+/// JSON mapping files use the same structure as YAML mapping files
+/// (`allow`, `deny`, `not_allow`, `not_deny`, `conditional`, `import`,
+/// `conditions`, `resources`, `resource_arn_template`). The content is validated as strict JSON
+/// first and then parsed with the YAML parser, since every JSON document is
+/// also a valid YAML document.
+///
+/// # Errors
+///
+/// Returns `ParseError::Json` if the content is not valid JSON, or the same
+/// structural errors as [`parse_mapping`].
+pub fn parse_json_mapping(content: &str) -> Result<ActionMapping, ParseError> {
+    serde_json::from_str::<serde_json::Value>(content)
+        .map_err(|e| ParseError::Json(e.to_string()))?;
+
+    parse_mapping(content)
+}
+
 /// Parses YAML content into an `ActionMapping`.
 ///
 /// # Arguments
@@ -72,28 +94,38 @@ pub fn parse_mapping(content: &str) -> Result<ActionMapping, ParseError> {
 
     let allow = parse_string_list_from_mapping(mapping, "allow");
     let deny = parse_string_list_from_mapping(mapping, "deny");
+    let not_allow = parse_string_list_from_mapping(mapping, "not_allow");
+    let not_deny = parse_string_list_from_mapping(mapping, "not_deny");
     let conditional = parse_conditional_from_mapping(mapping)?;
+    let import = parse_string_list_from_mapping(mapping, "import");
+    let conditions = parse_conditions_from_mapping(mapping)?;
+    let resources = parse_resources_from_mapping(mapping)?;
 
     Ok(ActionMapping {
         allow,
         deny,
+        not_allow,
+        not_deny,
         conditional,
+        import,
+        conditions,
+        resources,
     })
 }
 
 /// Parses a string list from a YAML mapping under the given key.
 ///
-/// This shared helper is used for both the `allow` and `deny` keys,
-/// which have identical parsing logic.
+/// This shared helper is used for the `allow`, `deny`, `not_allow`, `not_deny`,
+/// and `import` keys, which have identical parsing logic.
 fn parse_string_list_from_mapping(mapping: &saphyr::Mapping, key: &str) -> Vec<String> {
     for (k, value) in mapping {
-        if k.as_str() == Some(key) {
-            if let Some(arr) = value.as_sequence() {
-                return arr
-                    .iter()
-                    .filter_map(|v: &Yaml| v.as_str().map(|s| s.to_string()))
-                    .collect();
-            }
+        if k.as_str() == Some(key)
+            && let Some(arr) = value.as_sequence()
+        {
+            return arr
+                .iter()
+                .filter_map(|v: &Yaml| v.as_str().map(|s| s.to_string()))
+                .collect();
         }
     }
     Vec::new()
@@ -111,6 +143,174 @@ fn parse_conditional_from_mapping(
     Ok(ConditionalActions::None)
 }
 
+/// Parses the 'conditions' section from a YAML mapping.
+///
+/// Each key must be one of `ALLOW_GROUPS` and each value an IAM
+/// `Condition` object (operator to condition keys), e.g.
+/// `allow: { StringEquals: { "aws:RequestedRegion": "eu-central-1" } }`.
+fn parse_conditions_from_mapping(
+    mapping: &saphyr::Mapping,
+) -> Result<HashMap<String, serde_json::Value>, ParseError> {
+    let Some((_, value)) = mapping
+        .iter()
+        .find(|(key, _)| key.as_str() == Some("conditions"))
+    else {
+        return Ok(HashMap::new());
+    };
+
+    if value.is_null() {
+        return Ok(HashMap::new());
+    }
+    let groups = value.as_mapping().ok_or_else(|| {
+        ParseError::InvalidStructure("Expected mapping in conditions".to_string())
+    })?;
+
+    let mut conditions = HashMap::new();
+    for (key, condition) in groups {
+        let group = allow_group(key, "conditions")?;
+        if !condition.is_mapping() {
+            return Err(ParseError::InvalidStructure(format!(
+                "Expected mapping as condition of '{}'",
+                group
+            )));
+        }
+        conditions.insert(group.to_string(), yaml_to_json(condition));
+    }
+    Ok(conditions)
+}
+
+/// Parses the 'resources' section and the `resource_arn_template` shorthand
+/// from a YAML mapping.
+///
+/// Each key of `resources` must be one of `ALLOW_GROUPS` and each value a
+/// list of resource ARN templates, e.g. `allow: ["arn:aws:s3:::${bucket}/*"]`.
+/// `resource_arn_template` (e.g., `"arn:aws:s3:::${bucket}"`) scopes the
+/// `allow` and `conditional` groups that `resources` leaves out.
+fn parse_resources_from_mapping(
+    mapping: &saphyr::Mapping,
+) -> Result<HashMap<String, Vec<String>>, ParseError> {
+    let mut resources = HashMap::new();
+
+    if let Some(template) = mapping
+        .iter()
+        .find(|(key, _)| key.as_str() == Some("resource_arn_template"))
+        .and_then(|(_, value)| value.as_str())
+    {
+        check_placeholders(template)?;
+        for group in ["allow", "conditional"] {
+            resources.insert(group.to_string(), vec![template.to_string()]);
+        }
+    }
+
+    let Some((_, value)) = mapping
+        .iter()
+        .find(|(key, _)| key.as_str() == Some("resources"))
+    else {
+        return Ok(resources);
+    };
+
+    if value.is_null() {
+        return Ok(resources);
+    }
+    let groups = value
+        .as_mapping()
+        .ok_or_else(|| ParseError::InvalidStructure("Expected mapping in resources".to_string()))?;
+
+    for (key, templates) in groups {
+        let group = allow_group(key, "resources")?;
+        let templates = templates
+            .as_sequence()
+            .and_then(|templates| {
+                templates
+                    .iter()
+                    .map(|template| template.as_str().map(str::to_string))
+                    .collect::<Option<Vec<String>>>()
+            })
+            .ok_or_else(|| {
+                ParseError::InvalidStructure(format!(
+                    "Expected list of strings as resources of '{}'",
+                    group
+                ))
+            })?;
+        for template in &templates {
+            check_placeholders(template)?;
+        }
+        resources.insert(group.to_string(), templates);
+    }
+    Ok(resources)
+}
+
+/// Rejects a resource ARN template with a `{attribute}` placeholder, which
+/// would otherwise end up verbatim in the ARN; placeholders are `${attribute}`.
+fn check_placeholders(template: &str) -> Result<(), ParseError> {
+    let bare = template
+        .match_indices('{')
+        .any(|(index, _)| !template[..index].ends_with('$'));
+    if bare {
+        return Err(ParseError::InvalidStructure(format!(
+            "Resource template '{}' has a '{{attribute}}' placeholder (expected '${{attribute}}')",
+            template
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the action group named by a key of the `section` mapping.
+///
+/// # Errors
+///
+/// Returns `ParseError::InvalidStructure` if the key is not one of
+/// `ALLOW_GROUPS`.
+fn allow_group<'a>(key: &'a Yaml, section: &str) -> Result<&'a str, ParseError> {
+    let group = key.as_str().unwrap_or_default();
+    if !ALLOW_GROUPS.contains(&group) {
+        return Err(ParseError::InvalidStructure(format!(
+            "Unknown action group '{}' in {} (expected one of: {})",
+            group,
+            section,
+            ALLOW_GROUPS.join(", ")
+        )));
+    }
+    Ok(group)
+}
+
+/// Converts a YAML node into the equivalent JSON value.
+///
+/// Mapping keys that are not strings are converted to their JSON text, and
+/// nodes without a JSON equivalent (e.g., aliases) become `null`.
+fn yaml_to_json(yaml: &Yaml) -> serde_json::Value {
+    if let Some(entries) = yaml.as_mapping() {
+        return serde_json::Value::Object(
+            entries
+                .iter()
+                .map(|(key, value)| {
+                    let key = match key.as_str() {
+                        Some(key) => key.to_string(),
+                        None => yaml_to_json(key).to_string(),
+                    };
+                    (key, yaml_to_json(value))
+                })
+                .collect(),
+        );
+    }
+    if let Some(items) = yaml.as_sequence() {
+        return serde_json::Value::Array(items.iter().map(yaml_to_json).collect());
+    }
+    if let Some(text) = yaml.as_str() {
+        return serde_json::Value::String(text.to_string());
+    }
+    if let Some(flag) = yaml.as_bool() {
+        return serde_json::Value::Bool(flag);
+    }
+    if let Some(number) = yaml.as_integer() {
+        return serde_json::Value::from(number);
+    }
+    if let Some(number) = yaml.as_floating_point() {
+        return serde_json::Value::from(number);
+    }
+    serde_json::Value::Null
+}
+
 /// Recursively parses the conditional actions structure.
 ///
 /// The conditional structure can be:
@@ -171,6 +371,170 @@ allow:
         assert!(mapping.allow.contains(&"s3:DeleteBucket".to_string()));
         assert!(mapping.conditional.is_none());
         assert!(mapping.deny.is_empty());
+        assert!(mapping.import.is_empty());
+    }
+
+    #[test]
+    fn parse_mapping_with_import_section() {
+        let yaml = r#"
+allow:
+  - "s3:CreateBucket"
+import:
+  - "s3:GetBucketPolicy"
+  - "s3:ListBucket"
+"#;
+        let mapping = parse_mapping(yaml).unwrap();
+        assert_eq!(mapping.allow, vec!["s3:CreateBucket".to_string()]);
+        assert_eq!(
+            mapping.import,
+            vec![
+                "s3:GetBucketPolicy".to_string(),
+                "s3:ListBucket".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_mapping_with_not_action_sections() {
+        let yaml = r#"
+allow:
+  - "s3:ListAllMyBuckets"
+not_allow:
+  - "iam:*"
+not_deny:
+  - "s3:GetObject"
+"#;
+        let mapping = parse_mapping(yaml).unwrap();
+        assert_eq!(mapping.not_allow, vec!["iam:*".to_string()]);
+        assert_eq!(mapping.not_deny, vec!["s3:GetObject".to_string()]);
+        assert!(mapping.deny.is_empty());
+
+        let mapping = parse_mapping("allow: []\n").unwrap();
+        assert!(mapping.not_allow.is_empty());
+        assert!(mapping.not_deny.is_empty());
+    }
+
+    #[test]
+    fn parse_mapping_with_resource_arn_template() {
+        let yaml = r#"
+allow:
+  - "s3:CreateBucket"
+resource_arn_template: "arn:aws:s3:::${bucket}"
+"#;
+        let mapping = parse_mapping(yaml).unwrap();
+        let template = vec!["arn:aws:s3:::${bucket}".to_string()];
+        assert_eq!(
+            mapping.resources,
+            HashMap::from([
+                ("allow".to_string(), template.clone()),
+                ("conditional".to_string(), template),
+            ])
+        );
+        assert!(parse_mapping("allow: []\n").unwrap().resources.is_empty());
+
+        // Groups declared in `resources` keep their own templates
+        let yaml = "resource_arn_template: \"arn:aws:s3:::${bucket}\"\n\
+                    resources:\n  allow:\n    - \"arn:aws:s3:::${bucket}/*\"\n";
+        let mapping = parse_mapping(yaml).unwrap();
+        assert_eq!(mapping.resources["allow"], vec!["arn:aws:s3:::${bucket}/*"]);
+        assert_eq!(
+            mapping.resources["conditional"],
+            vec!["arn:aws:s3:::${bucket}"]
+        );
+    }
+
+    #[test]
+    fn parse_mapping_with_conditions_per_action_group() {
+        let yaml = r#"
+allow:
+  - "ec2:RunInstances"
+conditions:
+  allow:
+    StringEquals:
+      "aws:RequestedRegion": ["eu-central-1", "eu-west-1"]
+    Bool:
+      "aws:ViaAWSService": false
+"#;
+
+        let mapping = parse_mapping(yaml).unwrap();
+        assert_eq!(mapping.conditions.len(), 1);
+        assert_eq!(
+            mapping.conditions["allow"],
+            serde_json::json!({
+                "StringEquals": { "aws:RequestedRegion": ["eu-central-1", "eu-west-1"] },
+                "Bool": { "aws:ViaAWSService": false }
+            })
+        );
+        assert!(parse_mapping("allow: []\n").unwrap().conditions.is_empty());
+    }
+
+    #[test]
+    fn parse_mapping_with_resources_per_action_group() {
+        let yaml = r#"
+allow:
+  - "s3:PutObject"
+resources:
+  allow:
+    - "arn:aws:s3:::${bucket}/*"
+"#;
+
+        let mapping = parse_mapping(yaml).unwrap();
+        assert_eq!(
+            mapping.resources,
+            HashMap::from([(
+                "allow".to_string(),
+                vec!["arn:aws:s3:::${bucket}/*".to_string()]
+            )])
+        );
+        assert!(matches!(
+            parse_mapping("resources:\n  deny: [\"*\"]\n"),
+            Err(ParseError::InvalidStructure(_))
+        ));
+        assert!(matches!(
+            parse_mapping("resources:\n  allow: \"arn:aws:s3:::${bucket}\"\n"),
+            Err(ParseError::InvalidStructure(_))
+        ));
+    }
+
+    #[test]
+    fn parse_mapping_rejects_placeholders_without_dollar() {
+        for yaml in [
+            "resource_arn_template: \"arn:aws:s3:::{bucket}\"\n",
+            "resources:\n  allow:\n    - \"arn:aws:s3:::${bucket}/{key}\"\n",
+        ] {
+            assert!(matches!(
+                parse_mapping(yaml),
+                Err(ParseError::InvalidStructure(message)) if message.contains("'${attribute}'")
+            ));
+        }
+    }
+
+    #[test]
+    fn parsed_resources_fill_attribute_placeholders() {
+        let yaml =
+            "allow:\n  - s3:CreateBucket\nresources:\n  allow:\n    - \"arn:aws:s3:::${bucket}\"\n";
+        let values = HashMap::from([("bucket".to_string(), "my-bucket".to_string())]);
+
+        let mapping = parse_mapping(yaml).unwrap();
+        assert_eq!(
+            mapping.resources_of("allow", &values),
+            vec!["arn:aws:s3:::my-bucket"]
+        );
+    }
+
+    #[test]
+    fn parse_mapping_rejects_conditions_of_unknown_groups() {
+        let deny = "conditions:\n  deny:\n    Bool:\n      aws:SecureTransport: false\n";
+        assert!(matches!(
+            parse_mapping(deny),
+            Err(ParseError::InvalidStructure(message)) if message.contains("'deny'")
+        ));
+
+        let not_a_mapping = "conditions:\n  allow: [\"aws:RequestedRegion\"]\n";
+        assert!(matches!(
+            parse_mapping(not_a_mapping),
+            Err(ParseError::InvalidStructure(_))
+        ));
     }
 
     #[test]
@@ -429,4 +793,30 @@ allow:
         let mapping = parse_mapping(yaml).unwrap();
         assert!(mapping.deny.is_empty());
     }
+
+    #[test]
+    fn parse_json_mapping_with_all_three_sections() {
+        let json = r#"{
+  "allow": ["s3:CreateBucket"],
+  "deny": ["s3:GetObject"],
+  "conditional": {
+    "tags": ["s3:PutBucketTagging"]
+  }
+}"#;
+        let mapping = parse_json_mapping(json).unwrap();
+
+        assert_eq!(mapping.allow, vec!["s3:CreateBucket"]);
+        assert_eq!(mapping.deny, vec!["s3:GetObject"]);
+
+        let mut present = HashSet::new();
+        present.insert(vec!["tags".to_string()]);
+        let resolved = mapping.conditional.resolve(&present);
+        assert_eq!(resolved, vec!["s3:PutBucketTagging"]);
+    }
+
+    #[test]
+    fn parse_json_mapping_rejects_yaml_syntax() {
+        let result = parse_json_mapping("allow:\n  - s3:CreateBucket");
+        assert!(matches!(result, Err(ParseError::Json(_))));
+    }
 }
@@ -0,0 +1,71 @@
+//! Confirmation before overwriting existing output files.
+//!
+//! When lppc runs interactively (stdin and stderr are terminals) and
+//! `--output-dir` already contains files it is about to write, it lists them
+//! and asks before replacing them. `--force` (or `--yes`) skips the prompt,
+//! and non-interactive runs (CI, pipes) never prompt.
+
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+/// Lists the files that would be overwritten and asks whether to proceed.
+///
+/// Only `y` or `yes` (case-insensitive) confirm; any other answer, including
+/// end of input, declines.
+pub fn confirm_overwrite(
+    files: &[PathBuf],
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<bool> {
+    writeln!(
+        output,
+        "The following files already exist and will be overwritten:"
+    )?;
+    for file in files {
+        writeln!(output, "  - {}", file.display())?;
+    }
+    write!(output, "Proceed? [y/N] ")?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn prompt(answer: &str) -> (bool, String) {
+        let files = vec![PathBuf::from("policies/NetworkDeployer.json")];
+        let mut output = Vec::new();
+
+        let confirmed =
+            confirm_overwrite(&files, &mut Cursor::new(answer.as_bytes()), &mut output).unwrap();
+
+        (confirmed, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn confirm_overwrite_lists_files_and_accepts_yes() {
+        let (confirmed, output) = prompt("yes\n");
+
+        assert!(confirmed);
+        assert_eq!(
+            output,
+            "The following files already exist and will be overwritten:\n\
+             \x20 - policies/NetworkDeployer.json\n\
+             Proceed? [y/N] "
+        );
+        assert!(prompt("Y\n").0);
+    }
+
+    #[test]
+    fn confirm_overwrite_declines_by_default() {
+        assert!(!prompt("\n").0);
+        assert!(!prompt("no\n").0);
+        assert!(!prompt("").0);
+    }
+}
@@ -0,0 +1,294 @@
+//! Debug output formatter.
+//!
+//! This module provides the `debug` output format, a complete and
+//! deterministic dump of the `PermissionResult` for bug reports and
+//! development. Unlike `--verbose` logging it lists every field of the
+//! result, with groups, actions, and addresses sorted:
+//!
+//! ```text
+//! groups (1):
+//!   NetworkDeployer:
+//!     allow (2):
+//!       ec2:CreateVpc
+//!       ec2:DeleteVpc
+//!     deny (1):
+//!       ec2:DeleteVpc
+//!     not_allow (0)
+//!     not_deny (0)
+//!     shadowed (1):
+//!       ec2:DeleteVpc
+//!     resources (0)
+//!     conditions (0)
+//!     sources (1):
+//!       aws_vpc.main
+//! missing_mappings (0)
+//! empty_groups (0)
+//! ```
+//!
+//! `not_allow` and `not_deny` list one `NotAction` statement per line, after
+//! the mapping it comes from. `shadowed` lists the actions that are both
+//! allowed and denied, `resources` the allow actions scoped to resource ARNs
+//! (`resources` of the mappings), and `conditions` the allow actions granted
+//! under a mapping condition.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+
+use crate::mapping::{NotActionStatement, PermissionResult};
+
+use super::formatter::{OutputFormatter, PermissionSets};
+
+/// File name (without extension) of the debug output in `--output-dir`.
+pub const DEBUG_OUTPUT_NAME: &str = "result";
+
+/// Formatter for the permission sets of a single group in the debug format.
+///
+/// The complete result, including missing mappings, is rendered by
+/// `format_debug`, which the writer uses for this format.
+pub struct DebugFormatter;
+
+impl OutputFormatter for DebugFormatter {
+    fn format(&self, permissions: &PermissionSets) -> String {
+        let mut output = String::new();
+        write_list(&mut output, "", "allow", sorted(permissions.allow));
+        write_list(&mut output, "", "deny", sorted(permissions.deny));
+        write_list(
+            &mut output,
+            "",
+            "not_allow",
+            not_actions(permissions.not_allow),
+        );
+        write_list(
+            &mut output,
+            "",
+            "not_deny",
+            not_actions(permissions.not_deny),
+        );
+        if let Some(condition) = permissions.condition {
+            let _ = writeln!(output, "condition: {}", condition);
+        }
+        output
+    }
+
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+}
+
+/// Renders every field of the result in a stable textual form.
+pub fn format_debug(result: &PermissionResult) -> String {
+    let mut output = String::new();
+
+    let mut names: Vec<&String> = result.groups.keys().collect();
+    names.sort();
+    if names.is_empty() {
+        output.push_str("groups (0)\n");
+    } else {
+        let _ = writeln!(output, "groups ({}):", names.len());
+    }
+    for name in names {
+        let permissions = &result.groups[name];
+        let _ = writeln!(output, "  {}:", name);
+        write_list(&mut output, "    ", "allow", sorted(&permissions.allow));
+        write_list(&mut output, "    ", "deny", sorted(&permissions.deny));
+        write_list(
+            &mut output,
+            "    ",
+            "not_allow",
+            not_actions(&permissions.not_allow),
+        );
+        write_list(
+            &mut output,
+            "    ",
+            "not_deny",
+            not_actions(&permissions.not_deny),
+        );
+        write_list(
+            &mut output,
+            "    ",
+            "shadowed",
+            permissions.conflicting_actions(),
+        );
+        let scoped: Vec<String> = sorted(&permissions.allow)
+            .into_iter()
+            .filter_map(|action| {
+                let resources = permissions.resources_of(&action);
+                (resources != ["*"]).then(|| format!("{}: {}", action, resources.join(", ")))
+            })
+            .collect();
+        write_list(&mut output, "    ", "resources", scoped);
+        let conditional: Vec<String> = sorted(&permissions.allow)
+            .into_iter()
+            .filter_map(|action| {
+                let conditions = permissions.conditions_of(&action);
+                (!conditions.is_empty()).then(|| format!("{}: {}", action, conditions.join(" | ")))
+            })
+            .collect();
+        write_list(&mut output, "    ", "conditions", conditional);
+        let mut sources = permissions.sources.clone();
+        sources.sort();
+        write_list(&mut output, "    ", "sources", sources);
+    }
+
+    let missing: Vec<String> = result
+        .missing_mappings
+        .iter()
+        .map(|missing| {
+            format!(
+                "{}.{} (expected: {}, suggestion: {})",
+                missing.block_type.as_str(),
+                missing.type_name,
+                missing.expected_path,
+                missing.suggestion.as_deref().unwrap_or("none")
+            )
+        })
+        .collect();
+    write_list(&mut output, "", "missing_mappings", missing);
+
+    write_list(&mut output, "", "empty_groups", result.empty_groups.clone());
+
+    output
+}
+
+/// Returns the actions of a set in sorted order.
+fn sorted(actions: &HashSet<String>) -> Vec<String> {
+    let mut sorted: Vec<String> = actions.iter().cloned().collect();
+    sorted.sort();
+    sorted
+}
+
+/// Returns one line per `NotAction` statement: its mapping and excluded
+/// actions, followed by its resources and condition if it has any.
+fn not_actions(statements: &BTreeSet<NotActionStatement>) -> Vec<String> {
+    statements
+        .iter()
+        .map(|statement| {
+            let mut line = format!("{}: {}", statement.mapping, statement.actions.join(", "));
+            if statement.resources != ["*"] {
+                let _ = write!(line, " on {}", statement.resources.join(", "));
+            }
+            if let Some(condition) = &statement.condition {
+                let _ = write!(line, " if {}", condition);
+            }
+            line
+        })
+        .collect()
+}
+
+/// Writes `label (count):` followed by one indented line per entry.
+fn write_list(output: &mut String, indent: &str, label: &str, entries: Vec<String>) {
+    if entries.is_empty() {
+        let _ = writeln!(output, "{}{} (0)", indent, label);
+        return;
+    }
+
+    let _ = writeln!(output, "{}{} ({}):", indent, label, entries.len());
+    for entry in entries {
+        let _ = writeln!(output, "{}  {}", indent, entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::{GroupPermissions, MissingMapping};
+    use crate::terraform::BlockType;
+    use crate::test_support::{actions, not_action};
+    use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+    #[test]
+    fn debug_output_lists_sorted_groups_actions_and_missing_mappings() {
+        let result = PermissionResult {
+            groups: HashMap::from([
+                (
+                    "StorageDeployer".to_string(),
+                    GroupPermissions {
+                        allow: actions(&["s3:PutBucketTagging", "s3:CreateBucket"]),
+                        not_allow: BTreeSet::from([not_action(
+                            "resource.aws_s3_bucket",
+                            &["iam:*"],
+                        )]),
+                        resources: BTreeMap::from([(
+                            "s3:CreateBucket".to_string(),
+                            BTreeSet::from(["arn:aws:s3:::main".to_string()]),
+                        )]),
+                        conditions: BTreeMap::from([(
+                            "s3:PutBucketTagging".to_string(),
+                            BTreeSet::from([
+                                r#"{"Bool":{"aws:SecureTransport":"true"}}"#.to_string()
+                            ]),
+                        )]),
+                        sources: vec!["aws_s3_bucket.main".to_string()],
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "NetworkDeployer".to_string(),
+                    GroupPermissions {
+                        allow: actions(&["ec2:DeleteVpc", "ec2:CreateVpc"]),
+                        deny: actions(&["ec2:DeleteVpc"]),
+                        sources: vec![
+                            "module.vpc.aws_vpc.main".to_string(),
+                            "aws_subnet.a".to_string(),
+                        ],
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            missing_mappings: vec![MissingMapping {
+                block_type: BlockType::Resource,
+                type_name: "aws_s3_buckets".to_string(),
+                expected_path: "mappings/resource/aws_s3_buckets.yaml".to_string(),
+                suggestion: Some("aws_s3_bucket".to_string()),
+            }],
+            empty_groups: vec!["QueueDeployer".to_string()],
+        };
+
+        assert_eq!(
+            format_debug(&result),
+            "groups (2):\n\
+             \x20 NetworkDeployer:\n\
+             \x20   allow (2):\n\
+             \x20     ec2:CreateVpc\n\
+             \x20     ec2:DeleteVpc\n\
+             \x20   deny (1):\n\
+             \x20     ec2:DeleteVpc\n\
+             \x20   not_allow (0)\n\
+             \x20   not_deny (0)\n\
+             \x20   shadowed (1):\n\
+             \x20     ec2:DeleteVpc\n\
+             \x20   resources (0)\n\
+             \x20   conditions (0)\n\
+             \x20   sources (2):\n\
+             \x20     aws_subnet.a\n\
+             \x20     module.vpc.aws_vpc.main\n\
+             \x20 StorageDeployer:\n\
+             \x20   allow (2):\n\
+             \x20     s3:CreateBucket\n\
+             \x20     s3:PutBucketTagging\n\
+             \x20   deny (0)\n\
+             \x20   not_allow (1):\n\
+             \x20     resource.aws_s3_bucket: iam:*\n\
+             \x20   not_deny (0)\n\
+             \x20   shadowed (0)\n\
+             \x20   resources (1):\n\
+             \x20     s3:CreateBucket: arn:aws:s3:::main\n\
+             \x20   conditions (1):\n\
+             \x20     s3:PutBucketTagging: {\"Bool\":{\"aws:SecureTransport\":\"true\"}}\n\
+             \x20   sources (1):\n\
+             \x20     aws_s3_bucket.main\n\
+             missing_mappings (1):\n\
+             \x20 resource.aws_s3_buckets (expected: mappings/resource/aws_s3_buckets.yaml, suggestion: aws_s3_bucket)\n\
+             empty_groups (1):\n\
+             \x20 QueueDeployer\n"
+        );
+    }
+
+    #[test]
+    fn debug_output_of_empty_result_lists_every_field() {
+        assert_eq!(
+            format_debug(&PermissionResult::default()),
+            "groups (0)\nmissing_mappings (0)\nempty_groups (0)\n"
+        );
+    }
+}
@@ -4,9 +4,10 @@
 //! and provides a factory function to create the appropriate formatter based on
 //! the output format configuration.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
-use crate::cli::OutputFormat;
+use crate::cli::{OutputFormat, SidStrategy};
+use crate::mapping::{NotActionStatement, UNCONDITIONAL};
 
 /// A pair of allow and deny permission sets passed to formatters.
 ///
@@ -18,6 +19,151 @@ pub struct PermissionSets<'a> {
 
     /// IAM actions to deny
     pub deny: &'a HashSet<String>,
+
+    /// Allow statements with `NotAction` (`GroupPermissions::not_allow`)
+    pub not_allow: &'a BTreeSet<NotActionStatement>,
+
+    /// Deny statements with `NotAction` (`GroupPermissions::not_deny`)
+    pub not_deny: &'a BTreeSet<NotActionStatement>,
+
+    /// Optional IAM `Condition` object attached to Allow statements
+    pub condition: Option<&'a serde_json::Value>,
+
+    /// Resource ARNs the allow actions are scoped to (`GroupPermissions::resources`).
+    /// `None`, or an action without ARNs, means `"*"`.
+    pub resources: Option<&'a BTreeMap<String, BTreeSet<String>>>,
+
+    /// IAM `Condition` objects the allow actions are granted under
+    /// (`GroupPermissions::conditions`). `None`, or an action without
+    /// conditions, means unconditional.
+    pub conditions: Option<&'a BTreeMap<String, BTreeSet<String>>>,
+}
+
+/// Actions sharing one statement: the same resources and condition.
+///
+/// For a `NotAction` statement, `actions` are the excluded actions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopedStatement {
+    /// Resource ARNs of the statement, `["*"]` if unscoped
+    pub resources: Vec<String>,
+
+    /// IAM `Condition` object of the statement, if any
+    pub condition: Option<serde_json::Value>,
+
+    /// IAM actions of the statement, sorted
+    pub actions: Vec<String>,
+}
+
+impl PermissionSets<'_> {
+    /// Partitions allow actions into statements by the resources they are
+    /// scoped to and the conditions they are granted under.
+    ///
+    /// An action granted under alternative conditions appears in one statement
+    /// per condition. The group `condition` is merged into every statement,
+    /// overriding the keys of a mapping condition it shares. Statements are
+    /// sorted by resources, then condition, so the unscoped, unconditional
+    /// actions (`["*"]`) come first.
+    pub fn allow_statements<'b>(
+        &self,
+        actions: impl IntoIterator<Item = &'b String>,
+    ) -> Vec<ScopedStatement> {
+        let mut partitions: BTreeMap<(Vec<String>, Option<String>), Vec<String>> = BTreeMap::new();
+
+        for action in actions {
+            let resources = match self.resources.and_then(|resources| resources.get(action)) {
+                Some(arns) if !arns.is_empty() && !arns.contains("*") => {
+                    arns.iter().cloned().collect()
+                }
+                _ => vec!["*".to_string()],
+            };
+            let conditions: Vec<Option<String>> = match self
+                .conditions
+                .and_then(|conditions| conditions.get(action))
+            {
+                Some(conditions)
+                    if !conditions.is_empty() && !conditions.contains(UNCONDITIONAL) =>
+                {
+                    conditions.iter().cloned().map(Some).collect()
+                }
+                _ => vec![None],
+            };
+
+            for condition in conditions {
+                partitions
+                    .entry((resources.clone(), condition))
+                    .or_default()
+                    .push(action.clone());
+            }
+        }
+
+        partitions
+            .into_iter()
+            .map(|((resources, condition), mut actions)| {
+                actions.sort();
+                ScopedStatement {
+                    resources,
+                    condition: merge_conditions(condition.as_deref(), self.condition),
+                    actions,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the `NotAction` statements of `not_allow` (with `allow`) or
+    /// `not_deny`, in order.
+    ///
+    /// Every statement is kept apart; only statements that differ in nothing
+    /// but their mapping are emitted once. The group `condition` is merged
+    /// into the Allow statements, as for `allow_statements`.
+    pub fn not_action_statements(&self, allow: bool) -> Vec<ScopedStatement> {
+        let (statements, group_condition) = if allow {
+            (self.not_allow, self.condition)
+        } else {
+            (self.not_deny, None)
+        };
+
+        let mut scoped: Vec<ScopedStatement> = Vec::new();
+        for statement in statements {
+            let statement = ScopedStatement {
+                resources: statement.resources.clone(),
+                condition: merge_conditions(statement.condition.as_deref(), group_condition),
+                actions: statement.actions.clone(),
+            };
+            if !scoped.contains(&statement) {
+                scoped.push(statement);
+            }
+        }
+        scoped
+    }
+}
+
+/// Merges the group condition into a mapping condition (JSON text).
+///
+/// Both are IAM `Condition` objects; condition keys are merged per operator,
+/// and the group's value wins for a key present in both.
+fn merge_conditions(
+    mapping: Option<&str>,
+    group: Option<&serde_json::Value>,
+) -> Option<serde_json::Value> {
+    let mut merged = match mapping.and_then(|condition| serde_json::from_str(condition).ok()) {
+        Some(serde_json::Value::Object(operators)) => operators,
+        _ => return group.cloned(),
+    };
+    let Some(serde_json::Value::Object(group)) = group else {
+        return Some(serde_json::Value::Object(merged));
+    };
+
+    for (operator, keys) in group {
+        match (merged.get_mut(operator), keys) {
+            (Some(serde_json::Value::Object(merged_keys)), serde_json::Value::Object(keys)) => {
+                merged_keys.extend(keys.clone());
+            }
+            _ => {
+                merged.insert(operator.clone(), keys.clone());
+            }
+        }
+    }
+    Some(serde_json::Value::Object(merged))
 }
 
 /// Trait for formatting permission sets into output strings.
@@ -33,11 +179,34 @@ pub trait OutputFormatter {
     /// before Allow statements.
     fn format(&self, permissions: &PermissionSets) -> String;
 
+    /// Formats the permission sets of a named provider group.
+    ///
+    /// Formats whose output depends on the group (e.g., resource names derived
+    /// from the group name or role ARN) override this method. The default
+    /// ignores the group and calls `format`.
+    fn format_for_group(
+        &self,
+        _group_name: &str,
+        _role_arn: Option<&str>,
+        permissions: &PermissionSets,
+    ) -> String {
+        self.format(permissions)
+    }
+
     /// Returns the file extension for this format.
     ///
     /// Used when writing output to files to determine the appropriate
     /// file extension.
-    fn extension(&self) -> &'static str;
+    fn extension(&self) -> &str;
+
+    /// Combines the formatted documents of all groups into a single output.
+    ///
+    /// The documents are `(group name, formatted document)` pairs sorted by
+    /// group name. Formats that produce one output per group return `None`
+    /// (the default), which makes the writer emit each document separately.
+    fn combine(&self, _documents: &[(&str, String)]) -> Option<String> {
+        None
+    }
 }
 
 /// Creates the appropriate formatter for the given output format.
@@ -45,18 +214,32 @@ pub trait OutputFormatter {
 /// # Arguments
 ///
 /// * `format` - The output format to create a formatter for
+/// * `sid` - How the JSON formats generate statement Sids (ignored by the others)
 ///
 /// # Returns
 ///
 /// A boxed formatter implementing the `OutputFormatter` trait.
-pub fn create_formatter(format: OutputFormat) -> Box<dyn OutputFormatter> {
+pub fn create_formatter(format: OutputFormat, sid: SidStrategy) -> Box<dyn OutputFormatter> {
+    use super::debug::DebugFormatter;
     use super::hcl::HclFormatter;
+    use super::hcl_locals::HclLocalsFormatter;
     use super::json::JsonFormatter;
+    use super::policy_document::PolicyDocumentFormatter;
+    use super::terraform_role::TerraformRoleFormatter;
+    use super::yaml::YamlFormatter;
 
     match format {
-        OutputFormat::Json => Box::new(JsonFormatter { grouped: false }),
-        OutputFormat::JsonGrouped => Box::new(JsonFormatter { grouped: true }),
+        OutputFormat::Json => Box::new(JsonFormatter {
+            grouped: false,
+            sid,
+        }),
+        OutputFormat::JsonGrouped => Box::new(JsonFormatter { grouped: true, sid }),
         OutputFormat::Hcl => Box::new(HclFormatter { grouped: false }),
         OutputFormat::HclGrouped => Box::new(HclFormatter { grouped: true }),
+        OutputFormat::HclLocals => Box::new(HclLocalsFormatter),
+        OutputFormat::TerraformRole => Box::new(TerraformRoleFormatter),
+        OutputFormat::PolicyDocument => Box::new(PolicyDocumentFormatter),
+        OutputFormat::Yaml => Box::new(YamlFormatter { sid }),
+        OutputFormat::Debug => Box::new(DebugFormatter),
     }
 }
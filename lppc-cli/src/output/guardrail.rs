@@ -0,0 +1,156 @@
+//! Combined deny-only guardrail policy.
+//!
+//! This module unions the deny sets of all groups into a single policy
+//! document without any Allow statement (`--emit-deny-guardrail`). The
+//! document can be attached to many roles, e.g., organization-wide, next to
+//! the per-group policies.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::SidStrategy;
+use crate::mapping::PermissionResult;
+
+use super::OutputError;
+use super::formatter::{OutputFormatter, PermissionSets};
+use super::json::JsonFormatter;
+
+/// Renders the union of all groups' deny actions as a deny-only JSON policy.
+///
+/// Returns `None` if no group denies anything, since a policy without
+/// statements is not valid.
+pub fn format_deny_guardrail(result: &PermissionResult) -> Option<String> {
+    let deny: HashSet<String> = result
+        .groups
+        .values()
+        .flat_map(|group| group.deny.iter().cloned())
+        .collect();
+
+    if deny.is_empty() {
+        return None;
+    }
+
+    let empty = HashSet::new();
+    let no_statements = BTreeSet::new();
+    let formatted = JsonFormatter {
+        grouped: true,
+        sid: SidStrategy::None,
+    }
+    .format(&PermissionSets {
+        allow: &empty,
+        deny: &deny,
+        not_allow: &no_statements,
+        not_deny: &no_statements,
+        condition: None,
+        resources: None,
+        conditions: None,
+    });
+
+    Some(formatted)
+}
+
+/// Writes the deny-only guardrail policy to the given file.
+///
+/// # Returns
+///
+/// The path of the written file, or `None` if there were no deny actions and
+/// nothing was written.
+pub fn write_deny_guardrail(
+    path: &Path,
+    result: &PermissionResult,
+) -> Result<Option<PathBuf>, OutputError> {
+    let Some(policy) = format_deny_guardrail(result) else {
+        log::warn!(
+            "No group has deny actions, deny guardrail {} was not written",
+            path.display()
+        );
+        return Ok(None);
+    };
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, policy)?;
+
+    log::info!("Written: {}", path.display());
+
+    Ok(Some(path.to_path_buf()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::result;
+    use tempfile::TempDir;
+
+    /// Collects `(effect, action)` pairs from all statements of a policy.
+    fn statement_actions(policy: &str) -> Vec<(String, String)> {
+        let parsed: serde_json::Value = serde_json::from_str(policy).unwrap();
+        let mut pairs: Vec<(String, String)> = parsed["Statement"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|statement| {
+                let effect = statement["Effect"].as_str().unwrap().to_string();
+                statement["Action"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(move |action| (effect.clone(), action.as_str().unwrap().to_string()))
+            })
+            .collect();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn guardrail_unions_denies_of_all_groups_without_allow() {
+        let result = result(&[
+            (
+                "NetworkDeployer",
+                &["ec2:CreateVpc"],
+                &["ec2:DeleteVpc", "s3:DeleteBucket"],
+            ),
+            (
+                "StorageDeployer",
+                &["s3:CreateBucket"],
+                &["s3:DeleteBucket"],
+            ),
+            (
+                "DnsDeployer",
+                &["route53:CreateHostedZone"],
+                &["iam:CreateUser"],
+            ),
+        ]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("guardrails/deny.json");
+
+        let written = write_deny_guardrail(&path, &result).unwrap();
+
+        assert_eq!(written, Some(path.clone()));
+        let policy = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            statement_actions(&policy),
+            vec![
+                ("Deny".to_string(), "ec2:DeleteVpc".to_string()),
+                ("Deny".to_string(), "iam:CreateUser".to_string()),
+                ("Deny".to_string(), "s3:DeleteBucket".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn guardrail_is_not_written_without_denies() {
+        let result = result(&[("NetworkDeployer", &["ec2:CreateVpc"], &[])]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("deny.json");
+
+        let written = write_deny_guardrail(&path, &result).unwrap();
+
+        assert_eq!(written, None);
+        assert!(!path.exists());
+    }
+}
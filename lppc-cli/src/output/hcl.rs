@@ -4,7 +4,9 @@
 //! as valid HCL using `jsonencode()` for inline policy documents.
 //! It supports both a flat format (all actions in a single statement)
 //! and a grouped format (one statement per AWS service).
-//! Deny statements appear before Allow statements.
+//! Deny statements appear before Allow statements, and the `NotAction`
+//! statements of an effect (one per `not_allow`/`not_deny` statement, never
+//! split by service) follow its `Action` statements.
 
 use std::collections::{HashMap, HashSet};
 
@@ -40,25 +42,31 @@ impl HclFormatter {
     fn format_single(&self, permissions: &PermissionSets) -> String {
         let mut statement_blocks: Vec<String> = Vec::new();
 
-        if let Some(block) = self.format_statement_block(permissions.deny, "Deny", 4) {
-            statement_blocks.push(block);
-        }
-        if let Some(block) = self.format_statement_block(permissions.allow, "Allow", 4) {
-            statement_blocks.push(block);
-        }
+        statement_blocks.extend(self.format_statement_block(
+            &sorted(permissions.deny),
+            "Deny",
+            "Action",
+            &wildcard(),
+            None,
+            4,
+        ));
+        statement_blocks.extend(self.format_not_action_blocks(permissions, "Deny"));
+        statement_blocks.extend(self.format_statement_block(
+            &sorted(permissions.allow),
+            "Allow",
+            "Action",
+            &wildcard(),
+            permissions.condition,
+            4,
+        ));
+        statement_blocks.extend(self.format_not_action_blocks(permissions, "Allow"));
 
         let statements_content = if statement_blocks.is_empty() {
             "[]".to_string()
         } else if statement_blocks.len() == 1 {
-            format!(
-                "[\n{}\n  ]",
-                statement_blocks[0]
-            )
+            format!("[\n{}\n  ]", statement_blocks[0])
         } else {
-            format!(
-                "[\n{}\n  ]",
-                statement_blocks.join(",\n")
-            )
+            format!("[\n{}\n  ]", statement_blocks.join(",\n"))
         };
 
         format!(
@@ -74,16 +82,19 @@ impl HclFormatter {
     fn format_grouped(&self, permissions: &PermissionSets) -> String {
         let mut all_statements: Vec<String> = Vec::new();
 
-        all_statements.extend(self.create_grouped_statement_blocks(permissions.deny, "Deny"));
-        all_statements.extend(self.create_grouped_statement_blocks(permissions.allow, "Allow"));
+        all_statements.extend(self.create_grouped_statement_blocks(permissions.deny, "Deny", None));
+        all_statements.extend(self.format_not_action_blocks(permissions, "Deny"));
+        all_statements.extend(self.create_grouped_statement_blocks(
+            permissions.allow,
+            "Allow",
+            permissions.condition,
+        ));
+        all_statements.extend(self.format_not_action_blocks(permissions, "Allow"));
 
         let statements_content = if all_statements.is_empty() {
             "[]".to_string()
         } else {
-            format!(
-                "[\n{}\n  ]",
-                all_statements.join(",\n")
-            )
+            format!("[\n{}\n  ]", all_statements.join(",\n"))
         };
 
         format!(
@@ -100,6 +111,7 @@ impl HclFormatter {
         &self,
         permissions: &HashSet<String>,
         effect: &str,
+        condition: Option<&serde_json::Value>,
     ) -> Vec<String> {
         if permissions.is_empty() {
             return Vec::new();
@@ -121,45 +133,89 @@ impl HclFormatter {
                 let mut actions = groups.remove(&service).unwrap_or_default();
                 actions.sort();
                 let actions_hcl = self.format_action_list(&actions);
+                let condition_hcl = Self::format_condition(condition, 4);
 
                 format!(
                     r#"    {{
       Effect   = "{}"
       Action   = {}
-      Resource = "*"
+      Resource = "*"{}
     }}"#,
-                    effect, actions_hcl
+                    effect, actions_hcl, condition_hcl
+                )
+            })
+            .collect()
+    }
+
+    /// Formats one statement block per `not_allow` (for `"Allow"`) or
+    /// `not_deny` (for `"Deny"`) statement.
+    fn format_not_action_blocks(&self, permissions: &PermissionSets, effect: &str) -> Vec<String> {
+        permissions
+            .not_action_statements(effect == "Allow")
+            .iter()
+            .filter_map(|statement| {
+                self.format_statement_block(
+                    &statement.actions,
+                    effect,
+                    "NotAction",
+                    &statement.resources,
+                    statement.condition.as_ref(),
+                    4,
                 )
             })
             .collect()
     }
 
-    /// Formats a single statement block for the given effect. Returns None if empty.
+    /// Formats a single statement block for the given effect, listing the
+    /// sorted actions under `action_key` (`Action` or `NotAction`). Returns
+    /// None if empty.
     fn format_statement_block(
         &self,
-        permissions: &HashSet<String>,
+        actions: &[String],
         effect: &str,
+        action_key: &str,
+        resources: &[String],
+        condition: Option<&serde_json::Value>,
         indent: usize,
     ) -> Option<String> {
-        if permissions.is_empty() {
+        if actions.is_empty() {
             return None;
         }
 
-        let mut sorted: Vec<_> = permissions.iter().collect();
-        sorted.sort();
-
-        let actions_hcl = self.format_action_list(&sorted);
+        let actions_hcl = self.format_action_list(actions);
+        let resources_hcl = self.format_action_list(resources);
+        let condition_hcl = Self::format_condition(condition, indent);
         let indent_str = " ".repeat(indent);
+        // Align the `=` of all attributes, as `terraform fmt` does
+        let width = action_key.len().max("Resource".len());
 
         Some(format!(
             r#"{indent_str}{{
-{indent_str}  Effect   = "{effect}"
-{indent_str}  Action   = {actions_hcl}
-{indent_str}  Resource = "*"
-{indent_str}}}"#
+{indent_str}  {:<width$} = "{effect}"
+{indent_str}  {:<width$} = {actions_hcl}
+{indent_str}  {:<width$} = {resources_hcl}{condition_hcl}
+{indent_str}}}"#,
+            "Effect", action_key, "Resource"
         ))
     }
 
+    /// Formats an optional `Condition` attribute line for a statement block.
+    ///
+    /// The condition is written as compact JSON, which is also a valid HCL
+    /// object expression. Template sequences (`${`, `%{`) are escaped so that
+    /// Terraform does not interpolate them. Returns an empty string if there
+    /// is no condition.
+    fn format_condition(condition: Option<&serde_json::Value>, indent: usize) -> String {
+        let Some(condition) = condition else {
+            return String::new();
+        };
+
+        let json = serde_json::to_string(condition).expect("JSON serialization should not fail");
+        let escaped = json.replace("${", "$${").replace("%{", "%%{");
+
+        format!("\n{}  Condition = {}", " ".repeat(indent), escaped)
+    }
+
     /// Formats a list of actions as HCL.
     ///
     /// For a single action, returns a quoted string.
@@ -181,9 +237,23 @@ impl HclFormatter {
     }
 }
 
+/// Returns the actions of a set, sorted.
+fn sorted(actions: &HashSet<String>) -> Vec<String> {
+    let mut actions: Vec<String> = actions.iter().cloned().collect();
+    actions.sort();
+    actions
+}
+
+/// Returns the resources of an unscoped statement.
+fn wildcard() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::not_action;
+    use std::collections::BTreeSet;
 
     fn test_permissions() -> HashSet<String> {
         let mut perms = HashSet::new();
@@ -205,6 +275,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         assert!(output.starts_with("jsonencode({"));
@@ -218,6 +293,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         assert!(output.contains("Version = \"2012-10-17\""));
@@ -231,6 +311,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         assert!(output.contains("Effect   = \"Allow\""));
@@ -244,6 +329,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         assert!(output.contains("Resource = \"*\""));
@@ -257,6 +347,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         assert!(output.contains("ec2:DescribeInstances"));
@@ -272,6 +367,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let desc_pos = output.find("ec2:DescribeInstances").unwrap();
@@ -290,6 +390,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let effect_count = output.matches("Effect   = \"Allow\"").count();
@@ -304,6 +409,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let ec2_pos = output.find("ec2:").unwrap();
@@ -321,6 +431,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         assert!(output.contains("Action   = \"s3:GetObject\""));
@@ -335,6 +450,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         assert!(output.contains("Statement = []"));
@@ -358,6 +478,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         assert!(output.contains("Effect   = \"Deny\""));
@@ -376,6 +501,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         assert!(output.contains("Effect   = \"Deny\""));
@@ -398,6 +528,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let get_pos = output.find("s3:GetObject").unwrap();
@@ -416,6 +551,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let deny_pos = output.find("Effect   = \"Deny\"").unwrap();
@@ -434,6 +574,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let deny_count = output.matches("Effect   = \"Deny\"").count();
@@ -455,9 +600,85 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         assert!(output.contains("Effect   = \"Allow\""));
         assert!(!output.contains("Effect   = \"Deny\""));
     }
+
+    #[test]
+    fn format_adds_condition_to_allow_statements_only() {
+        let condition = serde_json::json!({
+            "StringEquals": { "aws:RequestedRegion": "eu-central-1" }
+        });
+        let mut deny = HashSet::new();
+        deny.insert("s3:DeleteBucket".to_string());
+
+        for grouped in [false, true] {
+            let formatter = HclFormatter { grouped };
+            let output = formatter.format(&PermissionSets {
+                allow: &test_permissions(),
+                deny: &deny,
+                not_allow: &BTreeSet::new(),
+                not_deny: &BTreeSet::new(),
+                condition: Some(&condition),
+                resources: None,
+                conditions: None,
+            });
+
+            let expected = r#"Condition = {"StringEquals":{"aws:RequestedRegion":"eu-central-1"}}"#;
+            let deny_pos = output.find("Effect   = \"Deny\"").unwrap();
+            let allow_pos = output.find("Effect   = \"Allow\"").unwrap();
+            let condition_pos = output.find(expected).unwrap();
+            assert!(condition_pos > allow_pos && allow_pos > deny_pos);
+            assert!(hcl::from_str::<hcl::Body>(&format!("policy = {}", output)).is_ok());
+        }
+    }
+
+    #[test]
+    fn format_not_action_sets_as_separate_statements_after_their_effect() {
+        let not_allow = BTreeSet::from([not_action(
+            "resource.aws_iam_role",
+            &["iam:*", "organizations:*"],
+        )]);
+        let not_deny = BTreeSet::from([not_action("resource.aws_s3_bucket", &["s3:GetObject"])]);
+
+        for grouped in [false, true] {
+            let formatter = HclFormatter { grouped };
+            let output = formatter.format(&PermissionSets {
+                allow: &test_permissions(),
+                deny: &empty_permissions(),
+                not_allow: &not_allow,
+                not_deny: &not_deny,
+                condition: None,
+                resources: None,
+                conditions: None,
+            });
+
+            let not_deny_pos = output.find("NotAction = \"s3:GetObject\"").unwrap();
+            let allow_pos = output.find("Effect   = \"Allow\"").unwrap();
+            let not_allow_pos = output.find("\"organizations:*\"").unwrap();
+            assert!(not_deny_pos < allow_pos && allow_pos < not_allow_pos);
+            assert_eq!(output.matches("NotAction =").count(), 2);
+            assert!(output.contains("Effect    = \"Deny\""));
+            assert!(hcl::from_str::<hcl::Body>(&format!("policy = {}", output)).is_ok());
+        }
+    }
+
+    #[test]
+    fn format_condition_escapes_template_sequences() {
+        let condition = serde_json::json!({
+            "StringLike": { "aws:PrincipalTag/team": "${aws:username}" }
+        });
+
+        let formatted = HclFormatter::format_condition(Some(&condition), 4);
+
+        assert!(formatted.contains(r#""$${aws:username}""#));
+        assert_eq!(HclFormatter::format_condition(None, 4), "");
+    }
 }
@@ -0,0 +1,172 @@
+//! HCL locals output formatter.
+//!
+//! This module provides the `HclLocalsFormatter` which combines the policies
+//! of all provider groups into a single Terraform file containing a `locals`
+//! map keyed by group name:
+//!
+//! ```hcl
+//! locals {
+//!   policies = {
+//!     NetworkDeployer = jsonencode({
+//!       ...
+//!     })
+//!   }
+//! }
+//! ```
+//!
+//! Each policy document is rendered by the grouped `HclFormatter`, so Deny
+//! handling and statement ordering are identical to the `hcl-grouped` format.
+
+use super::formatter::{OutputFormatter, PermissionSets};
+use super::hcl::HclFormatter;
+
+/// Formatter that emits all groups as a single Terraform `locals` map.
+pub struct HclLocalsFormatter;
+
+impl OutputFormatter for HclLocalsFormatter {
+    fn format(&self, permissions: &PermissionSets) -> String {
+        HclFormatter { grouped: true }.format(permissions)
+    }
+
+    fn extension(&self) -> &'static str {
+        "tf"
+    }
+
+    fn combine(&self, documents: &[(&str, String)]) -> Option<String> {
+        let entries: Vec<String> = documents
+            .iter()
+            .map(|(name, document)| {
+                // Indent continuation lines so the document nests inside the map
+                let indented = document.lines().collect::<Vec<_>>().join("\n    ");
+                format!("    {} = {}", Self::map_key(name), indented)
+            })
+            .collect();
+
+        let policies = if entries.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{\n{}\n  }}", entries.join("\n"))
+        };
+
+        Some(format!("locals {{\n  policies = {}\n}}\n", policies))
+    }
+}
+
+impl HclLocalsFormatter {
+    /// Returns the group name as an HCL map key.
+    ///
+    /// Plain identifiers are used as-is; anything else (e.g., names containing
+    /// hyphens) is quoted with `"` and `\` escaped.
+    fn map_key(name: &str) -> String {
+        let mut chars = name.chars();
+        let is_identifier = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+        if is_identifier {
+            name.to_string()
+        } else {
+            format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeSet, HashSet};
+
+    fn format_group(allow: &[&str], deny: &[&str]) -> String {
+        let allow: HashSet<String> = allow.iter().map(|s| s.to_string()).collect();
+        let deny: HashSet<String> = deny.iter().map(|s| s.to_string()).collect();
+        HclLocalsFormatter.format(&PermissionSets {
+            allow: &allow,
+            deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
+        })
+    }
+
+    #[test]
+    fn combine_contains_all_groups_with_valid_syntax() {
+        let documents = vec![
+            ("ComputeDeployer", format_group(&["ec2:RunInstances"], &[])),
+            (
+                "StorageDeployer",
+                format_group(&["s3:CreateBucket"], &["s3:GetObject"]),
+            ),
+        ];
+
+        let output = HclLocalsFormatter.combine(&documents).unwrap();
+
+        let body: hcl::Body = hcl::from_str(&output).expect("output should be valid HCL");
+        let locals = body.blocks().next().unwrap();
+        assert_eq!(locals.identifier.as_str(), "locals");
+
+        let policies = locals.body().attributes().next().unwrap();
+        assert_eq!(policies.key.as_str(), "policies");
+        let hcl::Expression::Object(map) = &policies.expr else {
+            panic!("policies should be an object");
+        };
+        assert_eq!(map.len(), 2);
+
+        assert!(output.contains("    ComputeDeployer = jsonencode({"));
+        assert!(output.contains("    StorageDeployer = jsonencode({"));
+        assert!(output.contains("s3:GetObject"));
+    }
+
+    #[test]
+    fn combine_quotes_names_with_hyphens() {
+        let documents = vec![("MyRole-Deployer", format_group(&["s3:GetObject"], &[]))];
+
+        let output = HclLocalsFormatter.combine(&documents).unwrap();
+
+        assert!(output.contains("    \"MyRole-Deployer\" = jsonencode({"));
+        assert!(hcl::from_str::<hcl::Body>(&output).is_ok());
+    }
+
+    #[test]
+    fn combine_keeps_deny_before_allow() {
+        let documents = vec![(
+            "TestDeployer",
+            format_group(&["s3:Get*"], &["s3:GetObject"]),
+        )];
+
+        let output = HclLocalsFormatter.combine(&documents).unwrap();
+
+        let deny_pos = output.find("Effect   = \"Deny\"").unwrap();
+        let allow_pos = output.find("Effect   = \"Allow\"").unwrap();
+        assert!(deny_pos < allow_pos);
+    }
+
+    #[test]
+    fn combine_without_groups_produces_empty_map() {
+        let output = HclLocalsFormatter.combine(&[]).unwrap();
+
+        assert!(output.contains("policies = {}"));
+        assert!(hcl::from_str::<hcl::Body>(&output).is_ok());
+    }
+
+    #[test]
+    fn map_key_quotes_only_when_needed() {
+        assert_eq!(
+            HclLocalsFormatter::map_key("NetworkDeployer"),
+            "NetworkDeployer"
+        );
+        assert_eq!(HclLocalsFormatter::map_key("my_deployer"), "my_deployer");
+        assert_eq!(
+            HclLocalsFormatter::map_key("my-deployer"),
+            "\"my-deployer\""
+        );
+        assert_eq!(HclLocalsFormatter::map_key("1Deployer"), "\"1Deployer\"");
+    }
+
+    #[test]
+    fn extension_is_tf() {
+        assert_eq!(HclLocalsFormatter.extension(), "tf");
+    }
+}
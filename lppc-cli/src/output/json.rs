@@ -4,12 +4,28 @@
 //! as valid AWS IAM policy document JSON. It supports both a flat format
 //! (all actions in a single statement) and a grouped format (one statement
 //! per AWS service). Deny statements appear before Allow statements.
+//! Statements get a `Sid` according to the `SidStrategy` (`--sid-strategy`).
+//! Allow actions scoped to resource ARNs (`resources` in the mappings) get their own statements with those ARNs as `Resource`, and
+//! allow actions with a mapping condition (`conditions`) get their own
+//! statements with that `Condition`.
+//! Each `not_allow`/`not_deny` statement becomes a `NotAction` statement of
+//! its own, after the `Action` statement(s) of that effect.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 
 use serde::Serialize;
 
-use super::formatter::{OutputFormatter, PermissionSets};
+use super::formatter::{OutputFormatter, PermissionSets, ScopedStatement};
+use crate::cli::SidStrategy;
+
+/// Maximum size of a customer managed policy, in characters without whitespace.
+pub const MANAGED_POLICY_SIZE_LIMIT: usize = 6144;
+
+/// Returns the size of a policy document as AWS counts it against
+/// `MANAGED_POLICY_SIZE_LIMIT`: whitespace is not counted.
+pub fn policy_size(document: &str) -> usize {
+    document.chars().filter(|c| !c.is_whitespace()).count()
+}
 
 /// AWS IAM policy document structure.
 #[derive(Serialize)]
@@ -21,14 +37,44 @@ struct PolicyDocument {
 }
 
 /// A single statement in an IAM policy.
+///
+/// Exactly one of `action` and `not_action` is set.
 #[derive(Serialize)]
 struct Statement {
+    #[serde(rename = "Sid", skip_serializing_if = "Option::is_none")]
+    sid: Option<String>,
     #[serde(rename = "Effect")]
     effect: &'static str,
-    #[serde(rename = "Action")]
-    action: Vec<String>,
+    #[serde(rename = "Action", skip_serializing_if = "Option::is_none")]
+    action: Option<Vec<String>>,
+    #[serde(rename = "NotAction", skip_serializing_if = "Option::is_none")]
+    not_action: Option<Vec<String>>,
     #[serde(rename = "Resource")]
-    resource: &'static str,
+    resource: Resource,
+    #[serde(rename = "Condition", skip_serializing_if = "Option::is_none")]
+    condition: Option<serde_json::Value>,
+}
+
+/// The `Resource` of a statement: a single ARN (or `*`) or a list of ARNs.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Resource {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Resource {
+    fn new(mut resources: Vec<String>) -> Self {
+        if resources.len() == 1 {
+            Resource::One(resources.remove(0))
+        } else {
+            Resource::Many(resources)
+        }
+    }
+
+    fn is_wildcard(&self) -> bool {
+        matches!(self, Resource::One(resource) if resource == "*")
+    }
 }
 
 /// Formatter that outputs permissions as AWS IAM policy document JSON.
@@ -36,27 +82,24 @@ struct Statement {
 /// When `grouped` is false, up to two statements are generated: one Deny
 /// (if any) and one Allow (if any). When `grouped` is true, permissions
 /// are grouped by service prefix (e.g., "ec2", "s3") with one statement
-/// per service per effect. All Deny statements appear before Allow statements.
+/// per service per effect. Allow actions scoped to resource ARNs are split
+/// into one further statement per set of ARNs, after the unscoped statement.
+/// All Deny statements appear before Allow statements.
 pub struct JsonFormatter {
     /// Whether to group permissions by service prefix.
     pub grouped: bool,
+    /// How the `Sid` of each statement is generated.
+    pub sid: SidStrategy,
 }
 
 impl OutputFormatter for JsonFormatter {
     fn format(&self, permissions: &PermissionSets) -> String {
-        let mut statements = Vec::new();
+        let mut statements = self.create_statements(permissions.deny, "Deny", None);
+        statements.extend(Self::create_not_action_statements(permissions, "Deny"));
+        statements.extend(self.create_statements(permissions.allow, "Allow", Some(permissions)));
+        statements.extend(Self::create_not_action_statements(permissions, "Allow"));
 
-        if self.grouped {
-            statements.extend(self.create_grouped_statements(permissions.deny, "Deny"));
-            statements.extend(self.create_grouped_statements(permissions.allow, "Allow"));
-        } else {
-            if let Some(stmt) = self.create_single_statement(permissions.deny, "Deny") {
-                statements.push(stmt);
-            }
-            if let Some(stmt) = self.create_single_statement(permissions.allow, "Allow") {
-                statements.push(stmt);
-            }
-        }
+        self.assign_sids(&mut statements);
 
         let document = PolicyDocument {
             version: "2012-10-17",
@@ -72,56 +115,150 @@ impl OutputFormatter for JsonFormatter {
 }
 
 impl JsonFormatter {
-    /// Creates a single statement for the given effect. Returns None if permissions is empty.
-    fn create_single_statement(
-        &self,
-        permissions: &HashSet<String>,
-        effect: &'static str,
-    ) -> Option<Statement> {
-        if permissions.is_empty() {
-            return None;
+    /// Sets the `Sid` of each statement according to the strategy.
+    ///
+    /// With `SidStrategy::Service`, the Sid is the effect followed by the
+    /// PascalCase service prefix of the statement's actions in grouped mode
+    /// (e.g., `AllowS3`, `DenyEc2`), and the effect alone otherwise. Both are
+    /// unique within a document, since there is at most one unscoped statement
+    /// per effect (and service); statements scoped to resource ARNs append
+    /// `Scoped` and a counter (e.g., `AllowS3Scoped1`), and further unscoped
+    /// statements (with a mapping condition) append `Conditional` and a
+    /// counter (e.g., `AllowS3Conditional1`). `NotAction` statements
+    /// are not split by service and get the effect followed by `NotAction`
+    /// (e.g., `AllowNotAction`), and further ones a counter from 2 on (e.g.,
+    /// `AllowNotAction2`). All Sids are alphanumeric, as IAM requires.
+    fn assign_sids(&self, statements: &mut [Statement]) {
+        let mut scoped_counts: BTreeMap<String, usize> = BTreeMap::new();
+        let mut conditional_counts: BTreeMap<String, usize> = BTreeMap::new();
+        let mut unscoped: HashSet<String> = HashSet::new();
+        let mut not_action_counts: BTreeMap<String, usize> = BTreeMap::new();
+
+        for (index, statement) in statements.iter_mut().enumerate() {
+            statement.sid = match (self.sid, &statement.action) {
+                (SidStrategy::None, _) => None,
+                (SidStrategy::Sequential, _) => Some(format!("Stmt{}", index + 1)),
+                (SidStrategy::Service, None) => Some(format!("{}NotAction", statement.effect)),
+                (SidStrategy::Service, Some(actions)) if self.grouped => {
+                    let service = actions[0].split(':').next().unwrap_or_default();
+                    Some(format!(
+                        "{}{}",
+                        statement.effect,
+                        Self::pascal_case(service)
+                    ))
+                }
+                (SidStrategy::Service, Some(_)) => Some(statement.effect.to_string()),
+            };
+
+            if let Some(sid) = &mut statement.sid
+                && self.sid == SidStrategy::Service
+            {
+                if statement.action.is_none() {
+                    let count = not_action_counts.entry(sid.clone()).or_default();
+                    *count += 1;
+                    if *count > 1 {
+                        sid.push_str(&count.to_string());
+                    }
+                } else if !statement.resource.is_wildcard() {
+                    let count = scoped_counts.entry(sid.clone()).or_default();
+                    *count += 1;
+                    sid.push_str(&format!("Scoped{}", count));
+                } else if !unscoped.insert(sid.clone()) {
+                    // Further unscoped statements differ by their mapping condition
+                    let count = conditional_counts.entry(sid.clone()).or_default();
+                    *count += 1;
+                    sid.push_str(&format!("Conditional{}", count));
+                }
+            }
         }
+    }
 
-        let mut sorted: Vec<_> = permissions.iter().cloned().collect();
-        sorted.sort();
-
-        Some(Statement {
-            effect,
-            action: sorted,
-            resource: "*",
-        })
+    /// Converts a service prefix to alphanumeric PascalCase, e.g.
+    /// `route53-recovery-control` to `Route53RecoveryControl`.
+    fn pascal_case(service: &str) -> String {
+        service
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                let mut chars = segment.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            })
+            .collect()
     }
 
-    /// Creates multiple statements grouped by service prefix for the given effect.
-    fn create_grouped_statements(
+    /// Creates the statements for the given actions and effect.
+    ///
+    /// In grouped mode the actions are split by service prefix first. With
+    /// `scope`, the actions of each statement are further split by the
+    /// resources they are scoped to and the conditions they are granted
+    /// under; without, every statement uses `"*"` and no condition.
+    fn create_statements(
         &self,
         permissions: &HashSet<String>,
         effect: &'static str,
+        scope: Option<&PermissionSets>,
     ) -> Vec<Statement> {
-        if permissions.is_empty() {
-            return Vec::new();
+        let mut services: BTreeMap<&str, Vec<&String>> = BTreeMap::new();
+        for perm in permissions {
+            let service = if self.grouped {
+                perm.split(':').next().unwrap_or("unknown")
+            } else {
+                ""
+            };
+            services.entry(service).or_default().push(perm);
         }
 
-        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+        let mut statements = Vec::new();
+        for actions in services.into_values() {
+            let partitions = match scope {
+                Some(scope) => scope.allow_statements(actions),
+                None => {
+                    let mut sorted: Vec<String> = actions.into_iter().cloned().collect();
+                    sorted.sort();
+                    vec![ScopedStatement {
+                        resources: vec!["*".to_string()],
+                        condition: None,
+                        actions: sorted,
+                    }]
+                }
+            };
 
-        for perm in permissions {
-            let service = perm.split(':').next().unwrap_or("unknown").to_string();
-            groups.entry(service).or_default().push(perm.clone());
+            for partition in partitions {
+                statements.push(Statement {
+                    sid: None,
+                    effect,
+                    action: Some(partition.actions),
+                    not_action: None,
+                    resource: Resource::new(partition.resources),
+                    condition: partition.condition,
+                });
+            }
         }
+        statements
+    }
 
-        let mut service_names: Vec<_> = groups.keys().cloned().collect();
-        service_names.sort();
-
-        service_names
+    /// Creates one `NotAction` statement per `not_allow` (for `"Allow"`) or
+    /// `not_deny` (for `"Deny"`) statement.
+    ///
+    /// The actions are never split by service: a statement per service would
+    /// grant (or deny) each service's actions through the other statements.
+    fn create_not_action_statements(
+        permissions: &PermissionSets,
+        effect: &'static str,
+    ) -> Vec<Statement> {
+        permissions
+            .not_action_statements(effect == "Allow")
             .into_iter()
-            .map(|service| {
-                let mut actions = groups.remove(&service).unwrap_or_default();
-                actions.sort();
-                Statement {
-                    effect,
-                    action: actions,
-                    resource: "*",
-                }
+            .map(|statement| Statement {
+                sid: None,
+                effect,
+                action: None,
+                not_action: Some(statement.actions),
+                resource: Resource::new(statement.resources),
+                condition: statement.condition,
             })
             .collect()
     }
@@ -130,6 +267,9 @@ impl JsonFormatter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mapping::UNCONDITIONAL;
+    use crate::test_support::not_action;
+    use std::collections::BTreeSet;
 
     fn test_permissions() -> HashSet<String> {
         let mut perms = HashSet::new();
@@ -145,12 +285,20 @@ mod tests {
 
     #[test]
     fn format_produces_valid_json() {
-        let formatter = JsonFormatter { grouped: false };
+        let formatter = JsonFormatter {
+            grouped: false,
+            sid: SidStrategy::None,
+        };
         let allow = test_permissions();
         let deny = empty_permissions();
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value =
@@ -161,12 +309,20 @@ mod tests {
 
     #[test]
     fn format_non_grouped_has_single_statement() {
-        let formatter = JsonFormatter { grouped: false };
+        let formatter = JsonFormatter {
+            grouped: false,
+            sid: SidStrategy::None,
+        };
         let allow = test_permissions();
         let deny = empty_permissions();
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -179,12 +335,20 @@ mod tests {
 
     #[test]
     fn format_non_grouped_actions_sorted() {
-        let formatter = JsonFormatter { grouped: false };
+        let formatter = JsonFormatter {
+            grouped: false,
+            sid: SidStrategy::None,
+        };
         let allow = test_permissions();
         let deny = empty_permissions();
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -197,12 +361,20 @@ mod tests {
 
     #[test]
     fn format_grouped_creates_statement_per_service() {
-        let formatter = JsonFormatter { grouped: true };
+        let formatter = JsonFormatter {
+            grouped: true,
+            sid: SidStrategy::None,
+        };
         let allow = test_permissions();
         let deny = empty_permissions();
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -213,12 +385,20 @@ mod tests {
 
     #[test]
     fn format_grouped_statements_sorted_by_service() {
-        let formatter = JsonFormatter { grouped: true };
+        let formatter = JsonFormatter {
+            grouped: true,
+            sid: SidStrategy::None,
+        };
         let allow = test_permissions();
         let deny = empty_permissions();
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -233,12 +413,20 @@ mod tests {
 
     #[test]
     fn format_grouped_actions_sorted_within_statement() {
-        let formatter = JsonFormatter { grouped: true };
+        let formatter = JsonFormatter {
+            grouped: true,
+            sid: SidStrategy::None,
+        };
         let allow = test_permissions();
         let deny = empty_permissions();
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -251,12 +439,20 @@ mod tests {
 
     #[test]
     fn format_empty_permissions() {
-        let formatter = JsonFormatter { grouped: false };
+        let formatter = JsonFormatter {
+            grouped: false,
+            sid: SidStrategy::None,
+        };
         let allow = empty_permissions();
         let deny = empty_permissions();
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -266,15 +462,26 @@ mod tests {
 
     #[test]
     fn extension_is_json() {
-        let formatter = JsonFormatter { grouped: false };
+        let formatter = JsonFormatter {
+            grouped: false,
+            sid: SidStrategy::None,
+        };
         assert_eq!(formatter.extension(), "json");
     }
 
+    #[test]
+    fn policy_size_ignores_whitespace() {
+        assert_eq!(policy_size("{\n  \"Effect\": \"Allow\"\n}"), 18);
+    }
+
     // --- New deny tests ---
 
     #[test]
     fn format_deny_only() {
-        let formatter = JsonFormatter { grouped: false };
+        let formatter = JsonFormatter {
+            grouped: false,
+            sid: SidStrategy::None,
+        };
         let allow = empty_permissions();
         let mut deny = HashSet::new();
         deny.insert("s3:GetObject".to_string());
@@ -282,6 +489,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -294,7 +506,10 @@ mod tests {
 
     #[test]
     fn format_mixed_allow_and_deny() {
-        let formatter = JsonFormatter { grouped: false };
+        let formatter = JsonFormatter {
+            grouped: false,
+            sid: SidStrategy::None,
+        };
         let mut allow = HashSet::new();
         allow.insert("s3:Get*".to_string());
         allow.insert("s3:List*".to_string());
@@ -304,6 +519,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -317,7 +537,10 @@ mod tests {
 
     #[test]
     fn format_deny_actions_sorted() {
-        let formatter = JsonFormatter { grouped: false };
+        let formatter = JsonFormatter {
+            grouped: false,
+            sid: SidStrategy::None,
+        };
         let allow = empty_permissions();
         let mut deny = HashSet::new();
         deny.insert("s3:PutObject".to_string());
@@ -327,6 +550,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -339,7 +567,10 @@ mod tests {
 
     #[test]
     fn format_grouped_deny_before_allow() {
-        let formatter = JsonFormatter { grouped: true };
+        let formatter = JsonFormatter {
+            grouped: true,
+            sid: SidStrategy::None,
+        };
         let mut allow = HashSet::new();
         allow.insert("ec2:DescribeInstances".to_string());
         let mut deny = HashSet::new();
@@ -348,6 +579,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -360,7 +596,10 @@ mod tests {
 
     #[test]
     fn format_grouped_deny_grouped_by_service() {
-        let formatter = JsonFormatter { grouped: true };
+        let formatter = JsonFormatter {
+            grouped: true,
+            sid: SidStrategy::None,
+        };
         let allow = empty_permissions();
         let mut deny = HashSet::new();
         deny.insert("s3:GetObject".to_string());
@@ -369,6 +608,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -377,14 +621,27 @@ mod tests {
         assert_eq!(statements.len(), 2);
         // Both are Deny, sorted by service
         assert_eq!(statements[0]["Effect"], "Deny");
-        assert!(statements[0]["Action"][0].as_str().unwrap().starts_with("ec2:"));
+        assert!(
+            statements[0]["Action"][0]
+                .as_str()
+                .unwrap()
+                .starts_with("ec2:")
+        );
         assert_eq!(statements[1]["Effect"], "Deny");
-        assert!(statements[1]["Action"][0].as_str().unwrap().starts_with("s3:"));
+        assert!(
+            statements[1]["Action"][0]
+                .as_str()
+                .unwrap()
+                .starts_with("s3:")
+        );
     }
 
     #[test]
     fn format_no_empty_statements() {
-        let formatter = JsonFormatter { grouped: false };
+        let formatter = JsonFormatter {
+            grouped: false,
+            sid: SidStrategy::None,
+        };
         let mut allow = HashSet::new();
         allow.insert("s3:CreateBucket".to_string());
         let deny = empty_permissions();
@@ -392,6 +649,11 @@ mod tests {
         let output = formatter.format(&PermissionSets {
             allow: &allow,
             deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
         });
 
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -401,4 +663,347 @@ mod tests {
         assert_eq!(statements.len(), 1);
         assert_eq!(statements[0]["Effect"], "Allow");
     }
+
+    #[test]
+    fn format_adds_condition_to_allow_statements_only() {
+        let condition = serde_json::json!({
+            "StringEquals": { "aws:RequestedRegion": "eu-central-1" }
+        });
+        let mut deny = HashSet::new();
+        deny.insert("s3:DeleteBucket".to_string());
+
+        for grouped in [false, true] {
+            let formatter = JsonFormatter {
+                grouped,
+                sid: SidStrategy::None,
+            };
+            let output = formatter.format(&PermissionSets {
+                allow: &test_permissions(),
+                deny: &deny,
+                not_allow: &BTreeSet::new(),
+                not_deny: &BTreeSet::new(),
+                condition: Some(&condition),
+                resources: None,
+                conditions: None,
+            });
+
+            let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+            for statement in parsed["Statement"].as_array().unwrap() {
+                if statement["Effect"] == "Allow" {
+                    assert_eq!(statement["Condition"], condition);
+                } else {
+                    assert!(statement.get("Condition").is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn format_without_condition_omits_condition_key() {
+        let formatter = JsonFormatter {
+            grouped: false,
+            sid: SidStrategy::None,
+        };
+
+        let output = formatter.format(&PermissionSets {
+            allow: &test_permissions(),
+            deny: &empty_permissions(),
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
+        });
+
+        assert!(!output.contains("Condition"));
+    }
+
+    fn not_action_statements(grouped: bool, sid: SidStrategy) -> Vec<serde_json::Value> {
+        let not_allow = BTreeSet::from([
+            not_action("resource.aws_iam_role", &["iam:*", "organizations:*"]),
+            not_action("resource.aws_sqs_queue", &["sqs:DeleteQueue"]),
+        ]);
+        let not_deny = BTreeSet::from([not_action("resource.aws_s3_bucket", &["s3:GetObject"])]);
+        let mut deny = HashSet::new();
+        deny.insert("s3:DeleteBucket".to_string());
+
+        let output = JsonFormatter { grouped, sid }.format(&PermissionSets {
+            allow: &test_permissions(),
+            deny: &deny,
+            not_allow: &not_allow,
+            not_deny: &not_deny,
+            condition: None,
+            resources: None,
+            conditions: None,
+        });
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        parsed["Statement"].as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn not_action_statements_stay_apart_after_their_effects_action_statements() {
+        let statements = not_action_statements(true, SidStrategy::None);
+
+        let effects: Vec<(&str, bool)> = statements
+            .iter()
+            .map(|statement| {
+                (
+                    statement["Effect"].as_str().unwrap(),
+                    statement.get("NotAction").is_some(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            effects,
+            vec![
+                ("Deny", false),
+                ("Deny", true),
+                ("Allow", false),
+                ("Allow", false),
+                ("Allow", true),
+                ("Allow", true),
+            ]
+        );
+
+        // Neither merged nor split by service, and never both keys in one statement
+        assert_eq!(
+            statements[4]["NotAction"],
+            serde_json::json!(["iam:*", "organizations:*"])
+        );
+        assert!(statements[4].get("Action").is_none());
+        assert_eq!(statements[4]["Resource"], "*");
+        assert_eq!(
+            statements[5]["NotAction"],
+            serde_json::json!(["sqs:DeleteQueue"])
+        );
+        assert_eq!(
+            statements[1]["NotAction"],
+            serde_json::json!(["s3:GetObject"])
+        );
+    }
+
+    #[test]
+    fn empty_not_action_sets_emit_no_statements() {
+        for statement in scoped_statements(true, SidStrategy::None) {
+            assert!(statement.get("NotAction").is_none());
+        }
+    }
+
+    #[test]
+    fn not_action_statements_get_unique_type_sids() {
+        let sids: Vec<serde_json::Value> = not_action_statements(false, SidStrategy::Service)
+            .iter()
+            .map(|statement| statement["Sid"].clone())
+            .collect();
+
+        assert_eq!(
+            sids,
+            vec![
+                "Deny",
+                "DenyNotAction",
+                "Allow",
+                "AllowNotAction",
+                "AllowNotAction2"
+            ]
+        );
+    }
+
+    fn scoped_statements(grouped: bool, sid: SidStrategy) -> Vec<serde_json::Value> {
+        let mut allow = HashSet::new();
+        allow.insert("s3:CreateBucket".to_string());
+        allow.insert("s3:PutBucketTagging".to_string());
+        allow.insert("ec2:CreateVpc".to_string());
+        let arns = |arns: &[&str]| arns.iter().map(|arn| arn.to_string()).collect();
+        let resources = BTreeMap::from([
+            (
+                "s3:CreateBucket".to_string(),
+                arns(&["arn:aws:s3:::logs", "arn:aws:s3:::assets"]),
+            ),
+            (
+                "s3:PutBucketTagging".to_string(),
+                arns(&["arn:aws:s3:::logs", "*"]),
+            ),
+        ]);
+
+        let output = JsonFormatter { grouped, sid }.format(&PermissionSets {
+            allow: &allow,
+            deny: &empty_permissions(),
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: Some(&resources),
+            conditions: None,
+        });
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        parsed["Statement"].as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn scoped_actions_get_statements_with_their_resource_arns() {
+        let statements = scoped_statements(false, SidStrategy::None);
+
+        assert_eq!(statements.len(), 2);
+        assert_eq!(
+            statements[0]["Action"],
+            serde_json::json!(["ec2:CreateVpc", "s3:PutBucketTagging"])
+        );
+        assert_eq!(statements[0]["Resource"], "*");
+        assert_eq!(
+            statements[1]["Action"],
+            serde_json::json!(["s3:CreateBucket"])
+        );
+        assert_eq!(
+            statements[1]["Resource"],
+            serde_json::json!(["arn:aws:s3:::assets", "arn:aws:s3:::logs"])
+        );
+    }
+
+    #[test]
+    fn scoped_grouped_statements_get_unique_type_sids() {
+        let sids: Vec<serde_json::Value> = scoped_statements(true, SidStrategy::Service)
+            .iter()
+            .map(|statement| statement["Sid"].clone())
+            .collect();
+
+        assert_eq!(sids, vec!["AllowEc2", "AllowS3", "AllowS3Scoped1"]);
+    }
+
+    fn conditional_statements(sid: SidStrategy) -> Vec<serde_json::Value> {
+        let mut allow = HashSet::new();
+        allow.insert("ec2:RunInstances".to_string());
+        allow.insert("ec2:CreateTags".to_string());
+        allow.insert("ec2:DescribeInstances".to_string());
+        let region = r#"{"StringEquals":{"aws:RequestedRegion":"eu-central-1"}}"#;
+        let conditions = BTreeMap::from([
+            (
+                "ec2:RunInstances".to_string(),
+                BTreeSet::from([region.to_string()]),
+            ),
+            (
+                "ec2:CreateTags".to_string(),
+                BTreeSet::from([region.to_string(), UNCONDITIONAL.to_string()]),
+            ),
+        ]);
+        let group_condition = serde_json::json!({
+            "StringEquals": { "aws:PrincipalTag/team": "platform" }
+        });
+
+        let output = JsonFormatter {
+            grouped: false,
+            sid,
+        }
+        .format(&PermissionSets {
+            allow: &allow,
+            deny: &empty_permissions(),
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: Some(&group_condition),
+            resources: None,
+            conditions: Some(&conditions),
+        });
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        parsed["Statement"].as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn conditional_actions_get_statements_with_merged_conditions() {
+        let statements = conditional_statements(SidStrategy::None);
+
+        assert_eq!(statements.len(), 2);
+        assert_eq!(
+            statements[0]["Action"],
+            serde_json::json!(["ec2:CreateTags", "ec2:DescribeInstances"])
+        );
+        assert_eq!(
+            statements[0]["Condition"],
+            serde_json::json!({ "StringEquals": { "aws:PrincipalTag/team": "platform" } })
+        );
+        assert_eq!(
+            statements[1]["Action"],
+            serde_json::json!(["ec2:RunInstances"])
+        );
+        assert_eq!(
+            statements[1]["Condition"],
+            serde_json::json!({
+                "StringEquals": {
+                    "aws:PrincipalTag/team": "platform",
+                    "aws:RequestedRegion": "eu-central-1"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn conditional_statements_get_unique_type_sids() {
+        let sids: Vec<serde_json::Value> = conditional_statements(SidStrategy::Service)
+            .iter()
+            .map(|statement| statement["Sid"].clone())
+            .collect();
+
+        assert_eq!(sids, vec!["Allow", "AllowConditional1"]);
+    }
+
+    fn sids(grouped: bool, sid: SidStrategy) -> Vec<serde_json::Value> {
+        let mut deny = HashSet::new();
+        deny.insert("s3:DeleteBucket".to_string());
+        let output = JsonFormatter { grouped, sid }.format(&PermissionSets {
+            allow: &test_permissions(),
+            deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
+        });
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        parsed["Statement"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|statement| statement["Sid"].clone())
+            .collect()
+    }
+
+    #[test]
+    fn type_sid_strategy_names_statements_after_effect_and_service() {
+        assert_eq!(
+            sids(true, SidStrategy::Service),
+            vec!["DenyS3", "AllowEc2", "AllowS3"]
+        );
+        assert_eq!(sids(false, SidStrategy::Service), vec!["Deny", "Allow"]);
+    }
+
+    #[test]
+    fn sequential_sid_strategy_numbers_statements_uniquely() {
+        assert_eq!(
+            sids(true, SidStrategy::Sequential),
+            vec!["Stmt1", "Stmt2", "Stmt3"]
+        );
+        assert_eq!(sids(false, SidStrategy::Sequential), vec!["Stmt1", "Stmt2"]);
+    }
+
+    #[test]
+    fn none_sid_strategy_omits_sid() {
+        assert!(
+            sids(true, SidStrategy::None)
+                .iter()
+                .all(serde_json::Value::is_null)
+        );
+    }
+
+    #[test]
+    fn type_sids_of_hyphenated_services_are_alphanumeric() {
+        assert_eq!(
+            JsonFormatter::pascal_case("route53-recovery-control"),
+            "Route53RecoveryControl"
+        );
+        assert_eq!(
+            JsonFormatter::pascal_case("elasticloadbalancing"),
+            "Elasticloadbalancing"
+        );
+    }
 }
@@ -0,0 +1,264 @@
+//! Markdown manifest of generated policies.
+//!
+//! This module renders a `MANIFEST.md` that lists every generated group with
+//! its role ARN, the number of allow/deny actions, and the Terraform types it
+//! covers. Groups whose provider overrides service endpoints are marked. It is written next to the policy files for human onboarding.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::mapping::PermissionResult;
+use crate::terraform::{BlockType, TerraformConfig};
+
+use super::OutputError;
+
+/// File name of the manifest inside the output directory.
+pub const MANIFEST_FILE_NAME: &str = "MANIFEST.md";
+
+/// Renders the manifest for all groups in the permission result.
+///
+/// Groups are listed in name order. Role ARNs and Terraform types are taken
+/// from the provider group of the same name in `terraform_config`.
+pub fn format_manifest(result: &PermissionResult, terraform_config: &TerraformConfig) -> String {
+    let mut output_names: Vec<&String> = result.groups.keys().collect();
+    output_names.sort();
+
+    let mut manifest = String::from("# Policy Manifest\n\nGenerated by lppc.\n");
+
+    for name in output_names {
+        let permissions = &result.groups[name];
+        let provider_group = terraform_config.provider_groups.get(name);
+
+        let role_arn = provider_group
+            .and_then(|group| group.role_arn.as_deref())
+            .map(|arn| format!("`{}`", arn))
+            .unwrap_or_else(|| "(none)".to_string());
+
+        let types: BTreeSet<String> = provider_group
+            .map(|group| {
+                group
+                    .blocks
+                    .iter()
+                    .map(|block| match block.block_type {
+                        BlockType::Resource => block.type_name.clone(),
+                        other => format!("{}.{}", other.as_str(), block.type_name),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        manifest.push_str(&format!("\n## {}\n\n", name));
+        manifest.push_str(&format!("- Role ARN: {}\n", role_arn));
+        if provider_group.is_some_and(|group| group.has_custom_endpoints) {
+            manifest.push_str("- Custom endpoints: yes (e.g., LocalStack)\n");
+        }
+        manifest.push_str(&format!("- Allow actions: {}\n", permissions.allow.len()));
+        manifest.push_str(&format!("- Deny actions: {}\n", permissions.deny.len()));
+        manifest.push_str("- Terraform types:\n");
+        for type_name in types {
+            manifest.push_str(&format!("  - `{}`\n", type_name));
+        }
+    }
+
+    manifest
+}
+
+/// Writes the manifest to `MANIFEST.md` in the given directory.
+///
+/// # Returns
+///
+/// The path of the written manifest.
+pub fn write_manifest(
+    dir: &Path,
+    result: &PermissionResult,
+    terraform_config: &TerraformConfig,
+) -> Result<PathBuf, OutputError> {
+    fs::create_dir_all(dir)?;
+
+    let path = dir.join(MANIFEST_FILE_NAME);
+    fs::write(&path, format_manifest(result, terraform_config))?;
+
+    log::info!("Written: {}", path.display());
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::GroupPermissions;
+    use crate::terraform::{Instances, ProviderGroup, TerraformBlock};
+    use crate::test_support::actions;
+    use std::collections::{HashMap, HashSet};
+    use tempfile::TempDir;
+
+    fn block(block_type: BlockType, type_name: &str, name: &str) -> TerraformBlock {
+        TerraformBlock {
+            block_type,
+            type_name: type_name.to_string(),
+            name: name.to_string(),
+            provider_config_key: "aws".to_string(),
+            attribute_values: HashMap::new(),
+            present_attributes: HashSet::new(),
+            address: format!("{}.{}", type_name, name),
+            managed_policy_arns: Vec::new(),
+            instances: Instances::Single,
+        }
+    }
+
+    fn create_test_data() -> (PermissionResult, TerraformConfig) {
+        let mut provider_groups = HashMap::new();
+        provider_groups.insert(
+            "NetworkDeployer".to_string(),
+            ProviderGroup {
+                output_name: "NetworkDeployer".to_string(),
+                role_arn: Some("arn:aws:iam::123456789012:role/NetworkDeployer".to_string()),
+                has_custom_endpoints: false,
+                blocks: vec![
+                    block(BlockType::Resource, "aws_vpc", "main"),
+                    block(BlockType::Resource, "aws_subnet", "a"),
+                    block(BlockType::Resource, "aws_subnet", "b"),
+                    block(BlockType::Data, "aws_availability_zones", "available"),
+                ],
+            },
+        );
+        provider_groups.insert(
+            "DefaultDeployer".to_string(),
+            ProviderGroup {
+                output_name: "DefaultDeployer".to_string(),
+                role_arn: None,
+                has_custom_endpoints: false,
+                blocks: vec![block(BlockType::Resource, "aws_s3_bucket", "main")],
+            },
+        );
+
+        let mut groups = HashMap::new();
+        groups.insert(
+            "NetworkDeployer".to_string(),
+            GroupPermissions {
+                allow: actions(&["ec2:CreateVpc", "ec2:CreateSubnet", "ec2:Describe*"]),
+                deny: actions(&["ec2:DeleteVpc"]),
+                ..Default::default()
+            },
+        );
+        groups.insert(
+            "DefaultDeployer".to_string(),
+            GroupPermissions {
+                allow: actions(&["s3:CreateBucket"]),
+                ..Default::default()
+            },
+        );
+
+        let result = PermissionResult {
+            groups,
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+        let config = TerraformConfig {
+            provider_groups,
+            unmapped_blocks: Vec::new(),
+            provider_version_constraints: HashMap::new(),
+            skipped_modules: Vec::new(),
+            unresolved_modules: Vec::new(),
+            module_tree: None,
+        };
+
+        (result, config)
+    }
+
+    #[test]
+    fn manifest_lists_groups_with_counts_and_role_arns() {
+        let (result, config) = create_test_data();
+
+        let manifest = format_manifest(&result, &config);
+
+        assert!(manifest.contains(
+            "## NetworkDeployer\n\n\
+             - Role ARN: `arn:aws:iam::123456789012:role/NetworkDeployer`\n\
+             - Allow actions: 3\n\
+             - Deny actions: 1\n"
+        ));
+        assert!(manifest.contains(
+            "## DefaultDeployer\n\n\
+             - Role ARN: (none)\n\
+             - Allow actions: 1\n\
+             - Deny actions: 0\n"
+        ));
+    }
+
+    #[test]
+    fn manifest_marks_groups_with_custom_endpoints() {
+        let (result, mut config) = create_test_data();
+        config
+            .provider_groups
+            .get_mut("DefaultDeployer")
+            .unwrap()
+            .has_custom_endpoints = true;
+
+        let manifest = format_manifest(&result, &config);
+
+        assert!(manifest.contains(
+            "## DefaultDeployer\n\n\
+             - Role ARN: (none)\n\
+             - Custom endpoints: yes (e.g., LocalStack)\n\
+             - Allow actions: 1\n"
+        ));
+        assert_eq!(manifest.matches("Custom endpoints").count(), 1);
+    }
+
+    #[test]
+    fn manifest_lists_unique_types_sorted_per_group() {
+        let (result, config) = create_test_data();
+
+        let manifest = format_manifest(&result, &config);
+
+        assert!(manifest.contains(
+            "- Terraform types:\n\
+             \x20 - `aws_subnet`\n\
+             \x20 - `aws_vpc`\n\
+             \x20 - `data.aws_availability_zones`\n"
+        ));
+    }
+
+    #[test]
+    fn manifest_orders_groups_by_name() {
+        let (result, config) = create_test_data();
+
+        let manifest = format_manifest(&result, &config);
+
+        let default_pos = manifest.find("## DefaultDeployer").unwrap();
+        let network_pos = manifest.find("## NetworkDeployer").unwrap();
+        assert!(default_pos < network_pos);
+    }
+
+    #[test]
+    fn write_manifest_creates_file_in_output_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let (result, config) = create_test_data();
+
+        let path = write_manifest(temp_dir.path(), &result, &config).unwrap();
+
+        assert_eq!(path, temp_dir.path().join("MANIFEST.md"));
+        let content = fs::read_to_string(path).unwrap();
+        assert!(content.starts_with("# Policy Manifest"));
+    }
+
+    #[test]
+    fn manifest_role_arns_are_redacted_only_on_request() {
+        let (result, mut config) = create_test_data();
+
+        let manifest = format_manifest(&result, &config);
+        assert!(
+            manifest.contains("- Role ARN: `arn:aws:iam::123456789012:role/NetworkDeployer`\n")
+        );
+
+        config.redact_account_ids();
+
+        let manifest = format_manifest(&result, &config);
+        assert!(
+            manifest.contains("- Role ARN: `arn:aws:iam::XXXXXXXXXXXX:role/NetworkDeployer`\n")
+        );
+        assert!(!manifest.contains("123456789012"));
+    }
+}
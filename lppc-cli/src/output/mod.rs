@@ -3,20 +3,48 @@
 //! This module handles formatting and writing permission results to various
 //! output destinations (stdout or files) in multiple formats (JSON, HCL).
 
+pub mod confirm;
+pub mod debug;
 pub mod formatter;
+pub mod guardrail;
 pub mod hcl;
+pub mod hcl_locals;
 pub mod json;
-
+pub mod manifest;
+pub mod policy_document;
+pub mod provenance;
+pub mod reader;
+pub mod sarif;
+pub mod sources;
+pub mod stubs;
+pub mod template;
+pub mod terraform_role;
+pub mod validate;
+pub mod yaml;
+
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use colored::Colorize;
 use thiserror::Error;
 
-use crate::cli::OutputFormat;
-use crate::mapping::PermissionResult;
-use formatter::{create_formatter, PermissionSets};
+use crate::cli::{OutputFormat, SidStrategy};
+use crate::mapping::{GroupPermissions, PermissionResult};
+use confirm::confirm_overwrite;
+use debug::{DEBUG_OUTPUT_NAME, format_debug};
+use formatter::{PermissionSets, create_formatter};
+pub use guardrail::write_deny_guardrail;
+use json::{MANAGED_POLICY_SIZE_LIMIT, policy_size};
+pub use manifest::write_manifest;
+pub use provenance::{Provenance, write_provenance};
+pub use reader::read_policies;
+pub use sarif::write_sarif;
+pub use sources::write_sources;
+pub use stubs::write_mapping_stubs;
+pub use template::TemplateFormatter;
+pub use validate::run_validation;
 
 /// Errors that can occur during output generation.
 #[derive(Debug, Error)]
@@ -26,6 +54,26 @@ pub enum OutputError {
 
     #[error("Invalid filename: {0}")]
     InvalidFilename(String),
+
+    #[error("--output-dir must be a directory, but {0} is a file")]
+    NotADirectory(PathBuf),
+
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
+
+    #[error("--against must be a directory of policies, but {0} is not a directory")]
+    NotAPolicyDirectory(PathBuf),
+
+    #[error("Invalid policy {0}: {1}")]
+    InvalidPolicy(PathBuf, String),
+
+    #[error("Policy validation failed: {0}")]
+    ValidationFailed(String),
+
+    #[error(
+        "Not overwriting {0} existing file(s) in --output-dir (use --force to skip the prompt)"
+    )]
+    OverwriteDeclined(usize),
 }
 
 /// Sanitizes a filename to prevent path traversal and other security issues.
@@ -67,6 +115,41 @@ fn sanitize_filename(name: &str) -> Option<String> {
     Some(trimmed.to_string())
 }
 
+/// File name (without extension) used by formats that combine all groups into one output.
+const COMBINED_OUTPUT_NAME: &str = "policies";
+
+/// Renders one `Group: N allow, M deny` line per group, sorted by group name.
+///
+/// The `NotAction` statements of a group are counted as `, K not_allow` and
+/// `, L not_deny`, each only if the group has any.
+pub fn format_compact(result: &PermissionResult) -> String {
+    let mut names: Vec<&String> = result.groups.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let permissions = &result.groups[name];
+            let mut line = format!(
+                "{}: {} allow, {} deny",
+                name,
+                permissions.allow.len(),
+                permissions.deny.len()
+            );
+            for (count, label) in [
+                (permissions.not_allow.len(), "not_allow"),
+                (permissions.not_deny.len(), "not_deny"),
+            ] {
+                if count > 0 {
+                    line.push_str(&format!(", {} {}", count, label));
+                }
+            }
+            line.push('\n');
+            line
+        })
+        .collect()
+}
+
 /// Writes permission results to stdout or files.
 ///
 /// The `OutputWriter` handles both stdout output (with headers separating
@@ -75,6 +158,15 @@ pub struct OutputWriter {
     format: OutputFormat,
     output_dir: Option<std::path::PathBuf>,
     no_color: bool,
+    conditions: HashMap<String, serde_json::Value>,
+    role_arns: HashMap<String, String>,
+    filename_prefix: String,
+    filename_suffix: String,
+    confirm_overwrite: bool,
+    compact_stdout: bool,
+    sid_strategy: SidStrategy,
+    template: Option<TemplateFormatter>,
+    split_oversized: bool,
 }
 
 impl OutputWriter {
@@ -94,13 +186,243 @@ impl OutputWriter {
             format,
             output_dir,
             no_color,
+            conditions: HashMap::new(),
+            role_arns: HashMap::new(),
+            filename_prefix: String::new(),
+            filename_suffix: String::new(),
+            confirm_overwrite: false,
+            compact_stdout: false,
+            sid_strategy: SidStrategy::None,
+            template: None,
+            split_oversized: false,
+        }
+    }
+
+    /// Renders every group through a template instead of the output format.
+    pub fn with_template(mut self, template: Option<TemplateFormatter>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Splits JSON policies over the managed policy size limit into
+    /// `<Group>.1.json`, `<Group>.2.json`, ... instead of only warning.
+    ///
+    /// The allow actions are partitioned across the documents; every document
+    /// repeats all deny statements, so each is self-contained.
+    pub fn with_split_oversized(mut self, split: bool) -> Self {
+        self.split_oversized = split;
+        self
+    }
+
+    /// Sets how the JSON formats generate statement Sids.
+    pub fn with_sid_strategy(mut self, sid_strategy: SidStrategy) -> Self {
+        self.sid_strategy = sid_strategy;
+        self
+    }
+
+    /// Prints only the action counts of each group (see [`format_compact`])
+    /// instead of the policy documents when writing to stdout.
+    ///
+    /// Has no effect when writing to a directory.
+    pub fn with_compact_stdout(mut self, compact: bool) -> Self {
+        self.compact_stdout = compact;
+        self
+    }
+
+    /// Asks on stdin before overwriting existing files in the output directory.
+    ///
+    /// Only enable this for interactive runs; the prompt blocks until answered.
+    pub fn with_overwrite_confirmation(mut self, confirm: bool) -> Self {
+        self.confirm_overwrite = confirm;
+        self
+    }
+
+    /// Sets IAM `Condition` objects keyed by group name.
+    ///
+    /// A group's condition is attached to all of its Allow statements.
+    pub fn with_conditions(mut self, conditions: HashMap<String, serde_json::Value>) -> Self {
+        self.conditions = conditions;
+        self
+    }
+
+    /// Sets the role ARNs keyed by group name.
+    ///
+    /// Formats that derive names from the role (e.g., `terraform-role`) use
+    /// them; other formats ignore them.
+    pub fn with_role_arns(mut self, role_arns: HashMap<String, String>) -> Self {
+        self.role_arns = role_arns;
+        self
+    }
+
+    /// Sets a prefix and suffix wrapped around the name of every written file,
+    /// e.g. `iam-policy-` and `-v2` for `iam-policy-NetworkDeployer-v2.json`.
+    pub fn with_filename_affixes(mut self, prefix: &str, suffix: &str) -> Self {
+        self.filename_prefix = prefix.to_string();
+        self.filename_suffix = suffix.to_string();
+        self
+    }
+
+    /// Returns the file name (without extension) for an output name.
+    ///
+    /// The affixed name as a whole passes through `sanitize_filename`, so an
+    /// affix cannot introduce path separators or traversal sequences.
+    fn file_stem(&self, output_name: &str) -> Result<String, OutputError> {
+        let affixed = format!(
+            "{}{}{}",
+            self.filename_prefix, output_name, self.filename_suffix
+        );
+
+        sanitize_filename(&affixed).ok_or_else(|| {
+            OutputError::InvalidFilename(format!(
+                "Output name '{}' contains invalid characters",
+                affixed
+            ))
+        })
+    }
+
+    /// Formats the permissions of one group with its condition and role ARN.
+    fn format_group(
+        &self,
+        formatter: &dyn formatter::OutputFormatter,
+        name: &str,
+        group_perms: &GroupPermissions,
+    ) -> String {
+        self.format_allow_subset(formatter, name, &group_perms.allow, group_perms)
+    }
+
+    /// Formats a group with only the given allow actions and all deny actions.
+    fn format_allow_subset(
+        &self,
+        formatter: &dyn formatter::OutputFormatter,
+        name: &str,
+        allow: &HashSet<String>,
+        group_perms: &GroupPermissions,
+    ) -> String {
+        formatter.format_for_group(
+            name,
+            self.role_arns.get(name).map(String::as_str),
+            &PermissionSets {
+                allow,
+                deny: &group_perms.deny,
+                not_allow: &group_perms.not_allow,
+                not_deny: &group_perms.not_deny,
+                condition: self.conditions.get(name),
+                resources: Some(&group_perms.resources),
+                conditions: Some(&group_perms.conditions),
+            },
+        )
+    }
+
+    /// Formats every group as `(output name, document)` pairs sorted by name.
+    ///
+    /// A JSON policy over `MANAGED_POLICY_SIZE_LIMIT` is reported with a
+    /// warning, or split into `<Group>.1`, `<Group>.2`, ... with
+    /// `with_split_oversized`.
+    fn group_documents(
+        &self,
+        result: &PermissionResult,
+        formatter: &dyn formatter::OutputFormatter,
+    ) -> Vec<(String, String)> {
+        let checks_size = self.template.is_none()
+            && matches!(self.format, OutputFormat::Json | OutputFormat::JsonGrouped);
+
+        let mut output_names: Vec<&String> = result.groups.keys().collect();
+        output_names.sort();
+
+        let mut documents = Vec::new();
+        for name in output_names {
+            let group_perms = &result.groups[name];
+            let formatted = self.format_group(formatter, name, group_perms);
+            let size = policy_size(&formatted);
+
+            if !checks_size || size <= MANAGED_POLICY_SIZE_LIMIT {
+                documents.push((name.clone(), formatted));
+            } else if self.split_oversized {
+                let parts = self.split_group(formatter, name, group_perms);
+                log::warn!(
+                    "Policy of {} has {} characters, more than the {} allowed for a managed policy; split into {} policies",
+                    name,
+                    size,
+                    MANAGED_POLICY_SIZE_LIMIT,
+                    parts.len()
+                );
+                documents.extend(
+                    parts
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, part)| (format!("{}.{}", name, i + 1), part)),
+                );
+            } else {
+                log::warn!(
+                    "Policy of {} has {} characters, more than the {} allowed for a managed policy; AWS will reject it (use --split-oversized to split it)",
+                    name,
+                    size,
+                    MANAGED_POLICY_SIZE_LIMIT
+                );
+                documents.push((name.clone(), formatted));
+            }
         }
+
+        documents
+    }
+
+    /// Partitions the sorted allow actions of a group into documents within
+    /// `MANAGED_POLICY_SIZE_LIMIT`, each with all deny actions.
+    ///
+    /// A document that is over the limit with a single allow action (e.g.
+    /// because of the deny actions) is kept and logged.
+    fn split_group(
+        &self,
+        formatter: &dyn formatter::OutputFormatter,
+        name: &str,
+        group_perms: &GroupPermissions,
+    ) -> Vec<String> {
+        let mut actions: Vec<&String> = group_perms.allow.iter().collect();
+        actions.sort();
+
+        let mut parts = Vec::new();
+        let mut current: HashSet<String> = HashSet::new();
+        let mut current_document = String::new();
+
+        for action in actions {
+            current.insert(action.clone());
+            let document = self.format_allow_subset(formatter, name, &current, group_perms);
+
+            if policy_size(&document) <= MANAGED_POLICY_SIZE_LIMIT || current.len() == 1 {
+                current_document = document;
+                continue;
+            }
+
+            // The action does not fit: close the current part and start a new one
+            current.remove(action);
+            parts.push(std::mem::take(&mut current_document));
+            current = HashSet::from([action.clone()]);
+            current_document = self.format_allow_subset(formatter, name, &current, group_perms);
+        }
+        parts.push(current_document);
+
+        for (i, part) in parts.iter().enumerate() {
+            if policy_size(part) > MANAGED_POLICY_SIZE_LIMIT {
+                log::warn!(
+                    "Policy {}.{} is still over the managed policy limit after splitting",
+                    name,
+                    i + 1
+                );
+            }
+        }
+
+        parts
     }
 
     /// Writes all permission results to output.
     ///
     /// When `output_dir` is set, creates one file per provider group.
     /// Otherwise, writes all groups to stdout with headers.
+    /// Formats that combine all groups (e.g., `hcl-locals`) produce a single
+    /// `policies.<ext>` file or a single stdout document without headers.
+    /// The `debug` format dumps the complete result to `result.txt` or stdout.
+    /// With `with_compact_stdout`, stdout gets only the action counts, whatever
+    /// the format.
     ///
     /// # Arguments
     ///
@@ -108,30 +430,169 @@ impl OutputWriter {
     ///
     /// # Returns
     ///
-    /// `Ok(())` on success, or an `OutputError` if writing fails.
-    pub fn write(&self, result: &PermissionResult) -> Result<(), OutputError> {
-        let formatter = create_formatter(self.format);
+    /// The paths of the written files (empty for stdout output), or an
+    /// `OutputError` if writing fails.
+    pub fn write(&self, result: &PermissionResult) -> Result<Vec<PathBuf>, OutputError> {
+        let formatter: Box<dyn formatter::OutputFormatter> = match &self.template {
+            Some(template) => Box::new(template.clone()),
+            None => create_formatter(self.format, self.sid_strategy),
+        };
+
+        let mut unknown_groups: Vec<&String> = self
+            .conditions
+            .keys()
+            .filter(|name| !result.groups.contains_key(*name))
+            .collect();
+        unknown_groups.sort();
+        for name in unknown_groups {
+            log::warn!("Condition for unknown group '{}' is ignored", name);
+        }
+
+        if self.compact_stdout && self.output_dir.is_none() {
+            write!(io::stdout().lock(), "{}", format_compact(result))?;
+            return Ok(Vec::new());
+        }
+
+        // Formats that produce a single document, as (file name, document)
+        let combined = if self.format == OutputFormat::Debug && self.template.is_none() {
+            Some((DEBUG_OUTPUT_NAME, format_debug(result)))
+        } else if !result.groups.is_empty() {
+            self.combine_groups(result, &*formatter)
+                .map(|combined| (COMBINED_OUTPUT_NAME, combined))
+        } else {
+            None
+        };
+
+        // One document per group otherwise, split if oversized
+        let documents = match &combined {
+            Some(_) => Vec::new(),
+            None => self.group_documents(result, &*formatter),
+        };
+
+        if let Some(dir) = &self.output_dir
+            && self.confirm_overwrite
+        {
+            let names: Vec<&str> = match &combined {
+                Some((name, _)) => vec![name],
+                None => documents.iter().map(|(name, _)| name.as_str()).collect(),
+            };
+            self.confirm_existing_files(dir, &names, formatter.extension())?;
+        }
+
+        if let Some((name, document)) = combined {
+            return match &self.output_dir {
+                Some(dir) => Ok(vec![self.write_combined_to_directory(
+                    dir,
+                    name,
+                    &document,
+                    &*formatter,
+                )?]),
+                None => {
+                    write!(io::stdout().lock(), "{}", document)?;
+                    Ok(Vec::new())
+                }
+            };
+        }
 
         match &self.output_dir {
-            Some(dir) => self.write_to_directory(dir, result, &*formatter),
-            None => self.write_to_stdout(result, &*formatter),
+            Some(dir) => self.write_to_directory(dir, &documents, &*formatter),
+            None => {
+                self.write_to_stdout(&documents)?;
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Prompts before overwriting the existing files among the given outputs.
+    ///
+    /// Returns `OutputError::OverwriteDeclined` unless the user confirms.
+    fn confirm_existing_files(
+        &self,
+        dir: &Path,
+        names: &[&str],
+        extension: &str,
+    ) -> Result<(), OutputError> {
+        let mut existing = Vec::new();
+        for name in names {
+            let path = dir.join(format!("{}.{}", self.file_stem(name)?, extension));
+            if path.exists() {
+                existing.push(path);
+            }
+        }
+        existing.sort();
+
+        if existing.is_empty()
+            || confirm_overwrite(&existing, &mut io::stdin().lock(), &mut io::stderr())?
+        {
+            return Ok(());
         }
+
+        Err(OutputError::OverwriteDeclined(existing.len()))
     }
 
-    /// Writes permission results to stdout with headers.
-    fn write_to_stdout(
+    /// Formats all groups in name order and lets the formatter combine them.
+    ///
+    /// Returns `None` if the formatter writes one output per group.
+    fn combine_groups(
         &self,
         result: &PermissionResult,
         formatter: &dyn formatter::OutputFormatter,
-    ) -> Result<(), OutputError> {
+    ) -> Option<String> {
+        let mut output_names: Vec<&String> = result.groups.keys().collect();
+        output_names.sort();
+
+        let documents: Vec<(&str, String)> = output_names
+            .into_iter()
+            .map(|name| {
+                let formatted = self.format_group(formatter, name, &result.groups[name]);
+                (name.as_str(), formatted)
+            })
+            .collect();
+
+        formatter.combine(&documents)
+    }
+
+    /// Writes a combined document to a single file in the output directory.
+    fn write_combined_to_directory(
+        &self,
+        dir: &Path,
+        name: &str,
+        combined: &str,
+        formatter: &dyn formatter::OutputFormatter,
+    ) -> Result<PathBuf, OutputError> {
+        Self::ensure_directory(dir)?;
+
+        let file_path = dir.join(format!(
+            "{}.{}",
+            self.file_stem(name)?,
+            formatter.extension()
+        ));
+        fs::write(&file_path, combined)?;
+
+        log::info!("Written: {}", file_path.display());
+
+        Ok(file_path)
+    }
+
+    /// Creates the output directory if it doesn't exist.
+    ///
+    /// Returns `OutputError::NotADirectory` if the path exists but is not a
+    /// directory, instead of the less helpful OS error from `create_dir_all`.
+    fn ensure_directory(dir: &Path) -> Result<(), OutputError> {
+        if dir.exists() && !dir.is_dir() {
+            return Err(OutputError::NotADirectory(dir.to_path_buf()));
+        }
+
+        fs::create_dir_all(dir)?;
+        Ok(())
+    }
+
+    /// Writes the documents of all groups to stdout with headers.
+    fn write_to_stdout(&self, documents: &[(String, String)]) -> Result<(), OutputError> {
         let stdout = io::stdout();
         let mut handle = stdout.lock();
 
-        // Sort output names for consistent ordering
-        let mut output_names: Vec<_> = result.groups.keys().collect();
-        output_names.sort();
-
-        for (i, output_name) in output_names.iter().enumerate() {
+        for (i, (output_name, formatted)) in documents.iter().enumerate() {
             if i > 0 {
                 writeln!(handle)?;
             }
@@ -143,35 +604,26 @@ impl OutputWriter {
                 writeln!(handle, "{}", header.cyan().bold())?;
             }
 
-            let group_perms = result.groups.get(*output_name).unwrap();
-            let formatted = formatter.format(&PermissionSets {
-                allow: &group_perms.allow,
-                deny: &group_perms.deny,
-            });
             writeln!(handle, "{}", formatted)?;
         }
 
         Ok(())
     }
 
-    /// Writes permission results to files in a directory.
+    /// Writes the documents of all groups to files in a directory.
     fn write_to_directory(
         &self,
         dir: &Path,
-        result: &PermissionResult,
+        documents: &[(String, String)],
         formatter: &dyn formatter::OutputFormatter,
-    ) -> Result<(), OutputError> {
-        // Create directory if it doesn't exist
-        fs::create_dir_all(dir)?;
+    ) -> Result<Vec<PathBuf>, OutputError> {
+        Self::ensure_directory(dir)?;
 
-        for (output_name, group_perms) in &result.groups {
+        let mut written = Vec::new();
+
+        for (output_name, formatted) in documents {
             // Sanitize the output name to prevent path traversal
-            let safe_name = sanitize_filename(output_name).ok_or_else(|| {
-                OutputError::InvalidFilename(format!(
-                    "Output name '{}' contains invalid characters",
-                    output_name
-                ))
-            })?;
+            let safe_name = self.file_stem(output_name)?;
 
             let filename = format!("{}.{}", safe_name, formatter.extension());
             let file_path = dir.join(&filename);
@@ -190,16 +642,14 @@ impl OutputWriter {
                 )));
             }
 
-            let formatted = formatter.format(&PermissionSets {
-                allow: &group_perms.allow,
-                deny: &group_perms.deny,
-            });
             fs::write(&file_path, formatted)?;
 
             log::info!("Written: {}", file_path.display());
+            written.push(file_path);
         }
 
-        Ok(())
+        written.sort();
+        Ok(written)
     }
 
     /// Writes missing mappings warning to stderr.
@@ -226,12 +676,18 @@ impl OutputWriter {
         }
 
         for missing in &result.missing_mappings {
+            let suggestion = missing
+                .suggestion
+                .as_ref()
+                .map(|s| format!(" - did you mean {}?", s))
+                .unwrap_or_default();
             writeln!(
                 handle,
-                "  - {}.{} (expected: {})",
+                "  - {}.{} (expected: {}){}",
                 missing.block_type.as_str(),
                 missing.type_name,
-                missing.expected_path
+                missing.expected_path,
+                suggestion
             )
             .ok();
         }
@@ -249,7 +705,8 @@ mod tests {
     use super::*;
     use crate::mapping::{GroupPermissions, MissingMapping};
     use crate::terraform::BlockType;
-    use std::collections::{HashMap, HashSet};
+    use crate::test_support::not_action;
+    use std::collections::{BTreeSet, HashMap, HashSet};
     use tempfile::TempDir;
 
     fn create_test_result() -> PermissionResult {
@@ -262,7 +719,7 @@ mod tests {
             "ComputeDeployer".to_string(),
             GroupPermissions {
                 allow: allow1,
-                deny: HashSet::new(),
+                ..Default::default()
             },
         );
 
@@ -273,16 +730,54 @@ mod tests {
             "StorageDeployer".to_string(),
             GroupPermissions {
                 allow: allow2,
-                deny: HashSet::new(),
+                ..Default::default()
             },
         );
 
         PermissionResult {
             groups,
             missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
         }
     }
 
+    #[test]
+    fn compact_output_lists_action_counts_without_policies() {
+        let mut result = create_test_result();
+        result
+            .groups
+            .get_mut("StorageDeployer")
+            .unwrap()
+            .deny
+            .insert("s3:DeleteBucket".to_string());
+
+        let compact = format_compact(&result);
+
+        assert_eq!(
+            compact,
+            "ComputeDeployer: 2 allow, 0 deny\nStorageDeployer: 2 allow, 1 deny\n"
+        );
+        assert!(!compact.contains("Statement"));
+        assert!(!compact.contains('{'));
+    }
+
+    #[test]
+    fn compact_output_counts_not_action_statements() {
+        let mut result = create_test_result();
+        let storage = result.groups.get_mut("StorageDeployer").unwrap();
+        storage.not_allow = BTreeSet::from([
+            not_action("resource.aws_s3_bucket", &["iam:*"]),
+            not_action("resource.aws_sqs_queue", &["sqs:DeleteQueue"]),
+        ]);
+        storage.not_deny = BTreeSet::from([not_action("resource.aws_s3_bucket", &["s3:*"])]);
+
+        assert_eq!(
+            format_compact(&result),
+            "ComputeDeployer: 2 allow, 0 deny\n\
+             StorageDeployer: 2 allow, 0 deny, 2 not_allow, 1 not_deny\n"
+        );
+    }
+
     #[test]
     fn write_to_directory_creates_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -308,6 +803,343 @@ mod tests {
         assert_eq!(parsed["Version"], "2012-10-17");
     }
 
+    #[test]
+    fn write_to_directory_wraps_names_in_affixes() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .with_filename_affixes("iam-policy-", "-v2");
+        let result = create_test_result();
+
+        let written = writer.write(&result).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                temp_dir.path().join("iam-policy-ComputeDeployer-v2.json"),
+                temp_dir.path().join("iam-policy-StorageDeployer-v2.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn write_combined_output_wraps_name_in_affixes() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::HclLocals,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .with_filename_affixes("iam-", "");
+
+        let written = writer.write(&create_test_result()).unwrap();
+
+        assert_eq!(written, vec![temp_dir.path().join("iam-policies.tf")]);
+    }
+
+    #[test]
+    fn write_service_groups_writes_one_file_per_service() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut result = create_test_result();
+        result
+            .groups
+            .get_mut("StorageDeployer")
+            .unwrap()
+            .allow
+            .insert("ec2:DescribeVpcs".to_string());
+        result.regroup_by_service();
+
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        );
+        let written = writer.write(&result).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                temp_dir.path().join("ec2.json"),
+                temp_dir.path().join("s3.json"),
+            ]
+        );
+        let actions = |path: &Path| -> serde_json::Value {
+            let policy: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+            policy["Statement"][0]["Action"].clone()
+        };
+        assert_eq!(
+            actions(&written[0]),
+            serde_json::json!([
+                "ec2:DescribeInstances",
+                "ec2:DescribeVpcs",
+                "ec2:RunInstances"
+            ])
+        );
+        assert_eq!(
+            actions(&written[1]),
+            serde_json::json!(["s3:CreateBucket", "s3:DeleteBucket"])
+        );
+    }
+
+    #[test]
+    fn write_without_confirmation_overwrites_existing_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let existing = temp_dir.path().join("StorageDeployer.json");
+        fs::write(&existing, "stale").unwrap();
+
+        // --force: confirmation disabled, stdin is never read
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .with_overwrite_confirmation(false);
+
+        writer.write(&create_test_result()).unwrap();
+
+        let content = fs::read_to_string(&existing).unwrap();
+        assert!(content.contains("s3:CreateBucket"));
+    }
+
+    #[test]
+    fn write_with_confirmation_and_no_existing_files_does_not_prompt() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Nothing to overwrite, so stdin is never read
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .with_overwrite_confirmation(true);
+
+        let written = writer.write(&create_test_result()).unwrap();
+
+        assert_eq!(written.len(), 2);
+    }
+
+    #[test]
+    fn write_debug_format_writes_single_result_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::Debug,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        );
+
+        let written = writer.write(&create_test_result()).unwrap();
+
+        assert_eq!(written, vec![temp_dir.path().join("result.txt")]);
+        let content = fs::read_to_string(&written[0]).unwrap();
+        assert!(content.starts_with("groups (2):\n"));
+        assert!(content.contains("missing_mappings (0)\n"));
+    }
+
+    #[test]
+    fn write_to_directory_sanitizes_malicious_affixes() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .with_filename_affixes("nested/", "\\x");
+
+        let written = writer.write(&create_test_result()).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                temp_dir.path().join("nested_ComputeDeployer_x.json"),
+                temp_dir.path().join("nested_StorageDeployer_x.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn write_to_directory_rejects_traversal_in_affix() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .with_filename_affixes("../../", "");
+
+        let write_result = writer.write(&create_test_result());
+
+        assert!(matches!(write_result, Err(OutputError::InvalidFilename(_))));
+        let entries: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn write_applies_condition_only_to_named_group() {
+        let temp_dir = TempDir::new().unwrap();
+        let condition = serde_json::json!({
+            "StringEquals": { "aws:RequestedRegion": "eu-central-1" }
+        });
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .with_conditions(HashMap::from([(
+            "StorageDeployer".to_string(),
+            condition.clone(),
+        )]));
+
+        writer.write(&create_test_result()).unwrap();
+
+        let storage: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(temp_dir.path().join("StorageDeployer.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(storage["Statement"][0]["Condition"], condition);
+
+        let compute: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(temp_dir.path().join("ComputeDeployer.json")).unwrap(),
+        )
+        .unwrap();
+        assert!(compute["Statement"][0].get("Condition").is_none());
+    }
+
+    #[test]
+    fn write_to_file_path_as_output_dir_fails_with_descriptive_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("policy.json");
+        fs::write(&file_path, "{}").unwrap();
+
+        for format in [OutputFormat::Json, OutputFormat::HclLocals] {
+            let writer = OutputWriter::new(format, Some(file_path.clone()), true);
+
+            let result = writer.write(&create_test_result());
+
+            let error = result.unwrap_err();
+            assert!(matches!(error, OutputError::NotADirectory(ref path) if *path == file_path));
+            assert!(
+                error
+                    .to_string()
+                    .contains("--output-dir must be a directory")
+            );
+        }
+
+        // The existing file is left untouched
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "{}");
+    }
+
+    #[test]
+    fn write_to_directory_returns_written_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        );
+
+        let written = writer.write(&create_test_result()).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                temp_dir.path().join("ComputeDeployer.json"),
+                temp_dir.path().join("StorageDeployer.json"),
+            ]
+        );
+    }
+
+    fn oversized_result() -> PermissionResult {
+        let allow: HashSet<String> = (0..400)
+            .map(|i| format!("s3:PutBucketConfiguration{:03}", i))
+            .collect();
+        PermissionResult {
+            groups: HashMap::from([(
+                "StorageDeployer".to_string(),
+                GroupPermissions {
+                    allow,
+                    deny: HashSet::from(["s3:DeleteBucket".to_string()]),
+                    ..Default::default()
+                },
+            )]),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn write_oversized_policy_without_split_writes_single_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        );
+
+        let written = writer.write(&oversized_result()).unwrap();
+
+        assert_eq!(written, vec![temp_dir.path().join("StorageDeployer.json")]);
+        let content = fs::read_to_string(&written[0]).unwrap();
+        assert!(policy_size(&content) > MANAGED_POLICY_SIZE_LIMIT);
+    }
+
+    #[test]
+    fn write_split_oversized_partitions_allow_and_repeats_deny() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .with_split_oversized(true);
+        let result = oversized_result();
+
+        let written = writer.write(&result).unwrap();
+
+        assert!(written.len() > 1);
+        assert_eq!(written[0], temp_dir.path().join("StorageDeployer.1.json"));
+        assert_eq!(written[1], temp_dir.path().join("StorageDeployer.2.json"));
+
+        let mut allowed = HashSet::new();
+        for path in &written {
+            let content = fs::read_to_string(path).unwrap();
+            assert!(policy_size(&content) <= MANAGED_POLICY_SIZE_LIMIT);
+
+            let parsed: serde_json::Value = serde_json::from_str(&content).unwrap();
+            let statements = parsed["Statement"].as_array().unwrap();
+            assert_eq!(statements[0]["Effect"], "Deny");
+            assert_eq!(statements[0]["Action"][0], "s3:DeleteBucket");
+            for action in statements[1]["Action"].as_array().unwrap() {
+                assert!(allowed.insert(action.as_str().unwrap().to_string()));
+            }
+        }
+        assert_eq!(allowed, result.groups["StorageDeployer"].allow);
+    }
+
+    #[test]
+    fn write_split_oversized_keeps_small_policies_whole() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::Json,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .with_split_oversized(true);
+
+        let written = writer.write(&create_test_result()).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                temp_dir.path().join("ComputeDeployer.json"),
+                temp_dir.path().join("StorageDeployer.json"),
+            ]
+        );
+    }
+
     #[test]
     fn write_to_directory_creates_dir_if_missing() {
         let temp_dir = TempDir::new().unwrap();
@@ -366,7 +1198,9 @@ mod tests {
                 block_type: BlockType::Resource,
                 type_name: "aws_unknown_resource".to_string(),
                 expected_path: "mappings/resource/aws_unknown_resource.yaml".to_string(),
+                suggestion: None,
             }],
+            empty_groups: Vec::new(),
         };
 
         let writer = OutputWriter::new(OutputFormat::HclGrouped, None, true);
@@ -381,6 +1215,7 @@ mod tests {
         let result = PermissionResult {
             groups: HashMap::new(),
             missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
         };
 
         let writer = OutputWriter::new(OutputFormat::HclGrouped, None, true);
@@ -400,6 +1235,7 @@ mod tests {
         let result = PermissionResult {
             groups: HashMap::new(),
             missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
         };
 
         writer.write(&result).unwrap();
@@ -481,13 +1317,14 @@ mod tests {
             "../../../etc/malicious".to_string(),
             GroupPermissions {
                 allow,
-                deny: HashSet::new(),
+                ..Default::default()
             },
         );
 
         let result = PermissionResult {
             groups,
             missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
         };
 
         let write_result = writer.write(&result);
@@ -499,6 +1336,80 @@ mod tests {
         assert!(!malicious_file.exists());
     }
 
+    #[test]
+    fn write_hcl_locals_creates_single_tf_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::HclLocals,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        );
+        let result = create_test_result();
+
+        writer.write(&result).unwrap();
+
+        let entries: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+
+        let content = fs::read_to_string(temp_dir.path().join("policies.tf")).unwrap();
+        assert!(content.starts_with("locals {"));
+        assert!(content.contains("ComputeDeployer = jsonencode({"));
+        assert!(content.contains("StorageDeployer = jsonencode({"));
+    }
+
+    #[test]
+    fn write_hcl_locals_without_groups_creates_no_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = OutputWriter::new(
+            OutputFormat::HclLocals,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        );
+        let result = PermissionResult {
+            groups: HashMap::new(),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+
+        writer.write(&result).unwrap();
+
+        let entries: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn write_terraform_role_uses_role_arn_per_group() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut role_arns = HashMap::new();
+        role_arns.insert(
+            "ComputeDeployer".to_string(),
+            "arn:aws:iam::123456789012:role/compute-admin".to_string(),
+        );
+        let writer = OutputWriter::new(
+            OutputFormat::TerraformRole,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .with_role_arns(role_arns);
+        let result = create_test_result();
+
+        let written = writer.write(&result).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                temp_dir.path().join("ComputeDeployer.tf"),
+                temp_dir.path().join("StorageDeployer.tf"),
+            ]
+        );
+        let compute = fs::read_to_string(&written[0]).unwrap();
+        assert!(compute.contains("resource \"aws_iam_role\" \"compute_deployer\""));
+        assert!(compute.contains("  name = \"compute-admin\"\n"));
+        let storage = fs::read_to_string(&written[1]).unwrap();
+        assert!(storage.contains("resource \"aws_iam_role_policy\" \"storage_deployer\""));
+        assert!(storage.contains("  name = \"StorageDeployer\"\n"));
+    }
+
     // --- New deny tests ---
 
     #[test]
@@ -517,12 +1428,17 @@ mod tests {
         deny.insert("s3:GetObject".to_string());
         groups.insert(
             "TestDeployer".to_string(),
-            GroupPermissions { allow, deny },
+            GroupPermissions {
+                allow,
+                deny,
+                ..Default::default()
+            },
         );
 
         let result = PermissionResult {
             groups,
             missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
         };
 
         writer.write(&result).unwrap();
@@ -555,14 +1471,15 @@ mod tests {
         groups.insert(
             "TestDeployer".to_string(),
             GroupPermissions {
-                allow: HashSet::new(),
                 deny,
+                ..Default::default()
             },
         );
 
         let result = PermissionResult {
             groups,
             missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
         };
 
         writer.write(&result).unwrap();
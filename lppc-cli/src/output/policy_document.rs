@@ -0,0 +1,317 @@
+//! Terraform policy document output formatter.
+//!
+//! This module provides the `PolicyDocumentFormatter` which emits a native
+//! `aws_iam_policy_document` data source per provider group, so Terraform
+//! validates the statements at plan time:
+//!
+//! ```hcl
+//! data "aws_iam_policy_document" "network_deployer" {
+//!   statement {
+//!     effect    = "Allow"
+//!     resources = ["*"]
+//!     actions = [
+//!       "ec2:CreateVpc",
+//!       "ec2:DeleteVpc"
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! The data source label is the group name as a Terraform identifier, as in
+//! the `terraform-role` format. Deny statements appear before Allow
+//! statements; Allow actions scoped to resource ARNs get their own statements,
+//! and a `--condition` becomes `condition` blocks of the Allow statements.
+//! Each `not_allow`/`not_deny` statement becomes a `not_actions` statement,
+//! after the `actions` statement(s) of that effect, as in the JSON output.
+
+use std::collections::HashSet;
+
+use super::formatter::{OutputFormatter, PermissionSets};
+use super::terraform_role::{TerraformRoleFormatter, escape_hcl_string};
+
+/// Group name used when formatting without group context.
+const DEFAULT_GROUP_NAME: &str = "Deployer";
+
+/// Formatter that emits an `aws_iam_policy_document` data source per group.
+pub struct PolicyDocumentFormatter;
+
+impl OutputFormatter for PolicyDocumentFormatter {
+    fn format(&self, permissions: &PermissionSets) -> String {
+        self.format_for_group(DEFAULT_GROUP_NAME, None, permissions)
+    }
+
+    fn format_for_group(
+        &self,
+        group_name: &str,
+        _role_arn: Option<&str>,
+        permissions: &PermissionSets,
+    ) -> String {
+        let mut statements = Vec::new();
+
+        let wildcard = ["*".to_string()];
+
+        if !permissions.deny.is_empty() {
+            let deny = sorted(permissions.deny);
+            statements.push(format_statement("Deny", &wildcard, "actions", &deny, None));
+        }
+        for statement in permissions.not_action_statements(false) {
+            statements.push(format_statement(
+                "Deny",
+                &statement.resources,
+                "not_actions",
+                &statement.actions,
+                statement.condition.as_ref(),
+            ));
+        }
+        for statement in permissions.allow_statements(permissions.allow) {
+            statements.push(format_statement(
+                "Allow",
+                &statement.resources,
+                "actions",
+                &statement.actions,
+                statement.condition.as_ref(),
+            ));
+        }
+        for statement in permissions.not_action_statements(true) {
+            statements.push(format_statement(
+                "Allow",
+                &statement.resources,
+                "not_actions",
+                &statement.actions,
+                statement.condition.as_ref(),
+            ));
+        }
+
+        format!(
+            "data \"aws_iam_policy_document\" \"{}\" {{\n{}}}\n",
+            TerraformRoleFormatter::resource_identifier(group_name),
+            statements.join("\n")
+        )
+    }
+
+    fn extension(&self) -> &'static str {
+        "tf"
+    }
+}
+
+/// Returns the actions of a set, sorted.
+fn sorted(actions: &HashSet<String>) -> Vec<String> {
+    let mut actions: Vec<String> = actions.iter().cloned().collect();
+    actions.sort();
+    actions
+}
+
+/// Formats a `statement` block with sorted actions under `action_key`
+/// (`actions` or `not_actions`).
+fn format_statement(
+    effect: &str,
+    resources: &[String],
+    action_key: &str,
+    actions: &[String],
+    condition: Option<&serde_json::Value>,
+) -> String {
+    let actions: Vec<String> = actions
+        .iter()
+        .map(|action| format!("      \"{}\"", escape_hcl_string(action)))
+        .collect();
+
+    format!(
+        "  statement {{\n    effect    = \"{}\"\n    resources = {}\n    {} = [\n{}\n    ]\n{}  }}\n",
+        effect,
+        string_list(resources),
+        action_key,
+        actions.join(",\n"),
+        format_conditions(condition)
+    )
+}
+
+/// Formats an IAM `Condition` object as `condition` blocks, one per operator
+/// and key, sorted. Values that are not strings are written as their JSON text.
+fn format_conditions(condition: Option<&serde_json::Value>) -> String {
+    let Some(operators) = condition.and_then(serde_json::Value::as_object) else {
+        return String::new();
+    };
+
+    let mut blocks = Vec::new();
+    for (test, keys) in operators {
+        let Some(keys) = keys.as_object() else {
+            continue;
+        };
+        for (variable, values) in keys {
+            let values: Vec<String> = match values {
+                serde_json::Value::Array(values) => values.iter().map(value_text).collect(),
+                value => vec![value_text(value)],
+            };
+            blocks.push(format!(
+                "\n    condition {{\n      test     = \"{}\"\n      variable = \"{}\"\n      values   = {}\n    }}\n",
+                escape_hcl_string(test),
+                escape_hcl_string(variable),
+                string_list(&values)
+            ));
+        }
+    }
+    blocks.concat()
+}
+
+fn value_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Formats values as a single-line list of HCL strings.
+fn string_list(values: &[String]) -> String {
+    let quoted: Vec<String> = values
+        .iter()
+        .map(|value| format!("\"{}\"", escape_hcl_string(value)))
+        .collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{actions, not_action};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[test]
+    fn policy_document_has_deny_then_allow_statement_with_sorted_actions() {
+        let allow = actions(&["ec2:DeleteVpc", "ec2:CreateVpc"]);
+        let deny = actions(&["ec2:DeleteVpc"]);
+
+        let output = PolicyDocumentFormatter.format_for_group(
+            "NetworkDeployer",
+            None,
+            &PermissionSets {
+                allow: &allow,
+                deny: &deny,
+                not_allow: &BTreeSet::new(),
+                not_deny: &BTreeSet::new(),
+                condition: None,
+                resources: None,
+                conditions: None,
+            },
+        );
+
+        assert_eq!(
+            output,
+            r#"data "aws_iam_policy_document" "network_deployer" {
+  statement {
+    effect    = "Deny"
+    resources = ["*"]
+    actions = [
+      "ec2:DeleteVpc"
+    ]
+  }
+
+  statement {
+    effect    = "Allow"
+    resources = ["*"]
+    actions = [
+      "ec2:CreateVpc",
+      "ec2:DeleteVpc"
+    ]
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn policy_document_writes_scoped_statements_and_conditions() {
+        let allow = actions(&["s3:CreateBucket", "s3:ListAllMyBuckets"]);
+        let deny = HashSet::new();
+        let resources = BTreeMap::from([(
+            "s3:CreateBucket".to_string(),
+            BTreeSet::from(["arn:aws:s3:::logs".to_string()]),
+        )]);
+        let condition = serde_json::json!({"Bool": {"aws:SecureTransport": true}});
+
+        let output = PolicyDocumentFormatter.format_for_group(
+            "My Role.Deployer",
+            None,
+            &PermissionSets {
+                allow: &allow,
+                deny: &deny,
+                not_allow: &BTreeSet::new(),
+                not_deny: &BTreeSet::new(),
+                condition: Some(&condition),
+                resources: Some(&resources),
+                conditions: None,
+            },
+        );
+
+        assert!(output.starts_with("data \"aws_iam_policy_document\" \"my_role_deployer\" {\n"));
+        assert!(output.contains(
+            "    resources = [\"*\"]\n    actions = [\n      \"s3:ListAllMyBuckets\"\n    ]\n"
+        ));
+        assert!(output.contains(
+            "    resources = [\"arn:aws:s3:::logs\"]\n    actions = [\n      \"s3:CreateBucket\"\n    ]\n"
+        ));
+        assert_eq!(
+            output
+                .matches(
+                    "    condition {\n      test     = \"Bool\"\n      variable = \"aws:SecureTransport\"\n      values   = [\"true\"]\n    }\n"
+                )
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn policy_document_writes_not_actions_statements_after_each_effect() {
+        let allow = actions(&["s3:CreateBucket"]);
+        let not_allow = BTreeSet::from([not_action("resource.aws_s3_bucket", &["iam:*"])]);
+        let not_deny = BTreeSet::from([not_action("resource.aws_s3_bucket", &["s3:*"])]);
+
+        let output = PolicyDocumentFormatter.format_for_group(
+            "StorageDeployer",
+            None,
+            &PermissionSets {
+                allow: &allow,
+                deny: &HashSet::new(),
+                not_allow: &not_allow,
+                not_deny: &not_deny,
+                condition: None,
+                resources: None,
+                conditions: None,
+            },
+        );
+
+        assert_eq!(
+            output,
+            r#"data "aws_iam_policy_document" "storage_deployer" {
+  statement {
+    effect    = "Deny"
+    resources = ["*"]
+    not_actions = [
+      "s3:*"
+    ]
+  }
+
+  statement {
+    effect    = "Allow"
+    resources = ["*"]
+    actions = [
+      "s3:CreateBucket"
+    ]
+  }
+
+  statement {
+    effect    = "Allow"
+    resources = ["*"]
+    not_actions = [
+      "iam:*"
+    ]
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn extension_is_tf() {
+        assert_eq!(PolicyDocumentFormatter.extension(), "tf");
+    }
+}
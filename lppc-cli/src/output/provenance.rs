@@ -0,0 +1,186 @@
+//! Provenance metadata for generated policies.
+//!
+//! IAM rejects unknown keys in policy documents, so provenance cannot be
+//! embedded in the policy itself. Instead, this module writes a
+//! `<Group>.provenance.json` sidecar per group (`--emit-provenance`):
+//!
+//! ```json
+//! {
+//!   "group": "NetworkDeployer",
+//!   "lppc_version": "0.1.0",
+//!   "mappings_url": "https://github.com/bebold-jhr/lppc-aws-mappings",
+//!   "mappings_commit": "3f2a9c1...",
+//!   "generated_at": "2026-01-01T12:00:00+00:00"
+//! }
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::mapping::PermissionResult;
+
+use super::{OutputError, sanitize_filename};
+
+/// Suffix of the provenance file written next to each group's policy.
+pub const PROVENANCE_FILE_SUFFIX: &str = ".provenance.json";
+
+/// Where and when the policies of a run were generated.
+#[derive(Debug, Clone)]
+pub struct Provenance {
+    /// Version of lppc that generated the policies
+    pub lppc_version: String,
+
+    /// URL of the mapping repository
+    pub mappings_url: String,
+
+    /// Commit of the mapping repository (None if it could not be determined)
+    pub mappings_commit: Option<String>,
+
+    /// Generation time (RFC 3339)
+    pub generated_at: String,
+}
+
+impl Provenance {
+    /// Creates provenance for the running lppc version at the current time.
+    pub fn new(mappings_url: &str, mappings_commit: Option<String>) -> Self {
+        Self {
+            lppc_version: env!("CARGO_PKG_VERSION").to_string(),
+            mappings_url: mappings_url.to_string(),
+            mappings_commit,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Renders the provenance of one group as pretty-printed JSON.
+///
+/// `mappings_commit` is `null` if the commit could not be determined.
+pub fn format_provenance(group_name: &str, provenance: &Provenance) -> String {
+    let document = serde_json::json!({
+        "group": group_name,
+        "lppc_version": provenance.lppc_version,
+        "mappings_url": provenance.mappings_url,
+        "mappings_commit": provenance.mappings_commit,
+        "generated_at": provenance.generated_at,
+    });
+
+    let mut output =
+        serde_json::to_string_pretty(&document).expect("JSON serialization should not fail");
+    output.push('\n');
+    output
+}
+
+/// Writes `<Group>.provenance.json` for every group into the given directory.
+///
+/// # Returns
+///
+/// The sorted paths of the written files.
+pub fn write_provenance(
+    dir: &Path,
+    result: &PermissionResult,
+    provenance: &Provenance,
+) -> Result<Vec<PathBuf>, OutputError> {
+    fs::create_dir_all(dir)?;
+
+    let mut written = Vec::new();
+
+    for output_name in result.groups.keys() {
+        let safe_name = sanitize_filename(output_name).ok_or_else(|| {
+            OutputError::InvalidFilename(format!(
+                "Output name '{}' contains invalid characters",
+                output_name
+            ))
+        })?;
+
+        let path = dir.join(format!("{}{}", safe_name, PROVENANCE_FILE_SUFFIX));
+        fs::write(&path, format_provenance(output_name, provenance))?;
+
+        log::info!("Written: {}", path.display());
+        written.push(path);
+    }
+
+    written.sort();
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::GroupPermissions;
+    use std::collections::{HashMap, HashSet};
+    use tempfile::TempDir;
+
+    fn provenance(mappings_commit: Option<&str>) -> Provenance {
+        Provenance {
+            lppc_version: "1.2.3".to_string(),
+            mappings_url: "https://github.com/bebold-jhr/lppc-aws-mappings".to_string(),
+            mappings_commit: mappings_commit.map(str::to_string),
+            generated_at: "2026-01-01T12:00:00+00:00".to_string(),
+        }
+    }
+
+    fn result_with_groups(names: &[&str]) -> PermissionResult {
+        PermissionResult {
+            groups: names
+                .iter()
+                .map(|name| {
+                    (
+                        name.to_string(),
+                        GroupPermissions {
+                            allow: HashSet::from(["s3:CreateBucket".to_string()]),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect::<HashMap<_, _>>(),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn write_provenance_writes_file_per_group_with_expected_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = result_with_groups(&["NetworkDeployer", "StorageDeployer"]);
+
+        let written =
+            write_provenance(temp_dir.path(), &result, &provenance(Some("3f2a9c1"))).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                temp_dir.path().join("NetworkDeployer.provenance.json"),
+                temp_dir.path().join("StorageDeployer.provenance.json"),
+            ]
+        );
+
+        let network: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&written[0]).unwrap()).unwrap();
+        assert_eq!(
+            network,
+            serde_json::json!({
+                "group": "NetworkDeployer",
+                "lppc_version": "1.2.3",
+                "mappings_url": "https://github.com/bebold-jhr/lppc-aws-mappings",
+                "mappings_commit": "3f2a9c1",
+                "generated_at": "2026-01-01T12:00:00+00:00",
+            })
+        );
+    }
+
+    #[test]
+    fn format_provenance_without_commit_is_null() {
+        let output = format_provenance("NetworkDeployer", &provenance(None));
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(parsed["mappings_commit"].is_null());
+    }
+
+    #[test]
+    fn provenance_new_uses_crate_version() {
+        let provenance = Provenance::new("https://example.com/mappings", None);
+
+        assert_eq!(provenance.lppc_version, env!("CARGO_PKG_VERSION"));
+        assert!(chrono::DateTime::parse_from_rfc3339(&provenance.generated_at).is_ok());
+    }
+}
@@ -0,0 +1,229 @@
+//! Reading previously written JSON policies back into permissions.
+//!
+//! `lppc diff --against DIR` compares the current result with the policies
+//! an earlier run wrote to DIR with `--output-format json` or `json-grouped`.
+//! Each `<Group>.json` becomes a group; the `Action`s of its `Allow` and
+//! `Deny` statements become the group's allow and deny sets, and each
+//! `NotAction` statement one of its `not_allow` and `not_deny` statements. The parts `<Group>.1.json`,
+//! `<Group>.2.json`, ... of a policy split with `--split-oversized` are merged
+//! into one group. Resources and conditions are not read back.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::OutputError;
+use super::provenance::PROVENANCE_FILE_SUFFIX;
+use super::sources::SOURCES_FILE_SUFFIX;
+use crate::mapping::{GroupPermissions, NotActionStatement, PermissionResult};
+
+/// Reads the JSON policies in `dir` into a result with one group per policy.
+///
+/// Sidecar files (`.sources.json`, `.provenance.json`) and JSON files without
+/// a `Statement` list are skipped.
+///
+/// # Errors
+///
+/// Returns `OutputError::NotAPolicyDirectory` if `dir` is not a directory,
+/// `OutputError::Io` if it cannot be read, or `OutputError::InvalidPolicy` if
+/// a policy is not valid JSON or a statement has an unknown `Effect`.
+pub fn read_policies(dir: &Path) -> Result<PermissionResult, OutputError> {
+    if !dir.is_dir() {
+        return Err(OutputError::NotAPolicyDirectory(dir.to_path_buf()));
+    }
+
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if path.is_file()
+            && file_name.ends_with(".json")
+            && !file_name.ends_with(SOURCES_FILE_SUFFIX)
+            && !file_name.ends_with(PROVENANCE_FILE_SUFFIX)
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut groups: HashMap<String, GroupPermissions> = HashMap::new();
+    for path in paths {
+        let invalid = |message: String| OutputError::InvalidPolicy(path.clone(), message);
+        let content = fs::read_to_string(&path)?;
+        let document: Value = serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+        let Some(statements) = document.get("Statement").and_then(Value::as_array) else {
+            log::debug!("Skipping {}: not a policy document", path.display());
+            continue;
+        };
+
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let permissions = groups.entry(group_name(stem).to_string()).or_default();
+        for statement in statements {
+            let effect = statement.get("Effect").and_then(Value::as_str);
+            let (actions, not_actions) = match effect {
+                Some("Allow") => (&mut permissions.allow, &mut permissions.not_allow),
+                Some("Deny") => (&mut permissions.deny, &mut permissions.not_deny),
+                _ => return Err(invalid(format!("unknown Effect {:?}", effect))),
+            };
+            actions.extend(statement_actions(statement.get("Action")));
+            let excluded = statement_actions(statement.get("NotAction"));
+            if !excluded.is_empty() {
+                not_actions.insert(NotActionStatement::new("", excluded));
+            }
+        }
+    }
+
+    Ok(PermissionResult {
+        groups,
+        missing_mappings: Vec::new(),
+        empty_groups: Vec::new(),
+    })
+}
+
+/// Returns the group of a policy file stem, without the `.N` of a split part.
+fn group_name(stem: &str) -> &str {
+    match stem.rsplit_once('.') {
+        Some((name, part)) if !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => stem,
+    }
+}
+
+/// Returns the actions of an `Action`/`NotAction` value: a string or a list.
+fn statement_actions(value: Option<&Value>) -> HashSet<String> {
+    match value {
+        Some(Value::String(action)) => HashSet::from([action.clone()]),
+        Some(Value::Array(actions)) => actions
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => HashSet::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::OutputFormat;
+    use crate::output::OutputWriter;
+    use crate::test_support::group;
+    use std::collections::BTreeSet;
+    use tempfile::TempDir;
+
+    #[test]
+    fn read_policies_reads_back_written_json_policies() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = PermissionResult {
+            groups: HashMap::from([
+                (
+                    "NetworkDeployer".to_string(),
+                    group(&["ec2:CreateVpc", "ec2:DeleteVpc"], &["iam:CreateUser"]),
+                ),
+                (
+                    "StorageDeployer".to_string(),
+                    group(&["s3:CreateBucket"], &[]),
+                ),
+            ]),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+        OutputWriter::new(
+            OutputFormat::JsonGrouped,
+            Some(temp_dir.path().to_path_buf()),
+            true,
+        )
+        .write(&result)
+        .unwrap();
+        fs::write(
+            temp_dir
+                .path()
+                .join(format!("NetworkDeployer{}", SOURCES_FILE_SUFFIX)),
+            "{\"ec2:CreateVpc\": []}",
+        )
+        .unwrap();
+
+        let read = read_policies(temp_dir.path()).unwrap();
+
+        assert_eq!(read.groups.len(), 2);
+        for (name, permissions) in &result.groups {
+            assert_eq!(read.groups[name].allow, permissions.allow);
+            assert_eq!(read.groups[name].deny, permissions.deny);
+        }
+    }
+
+    #[test]
+    fn read_policies_merges_split_parts_and_accepts_single_actions() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("ComputeDeployer.1.json"),
+            r#"{"Statement": [{"Effect": "Allow", "Action": "ec2:RunInstances", "Resource": "*"}]}"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("ComputeDeployer.2.json"),
+            r#"{"Statement": [
+                {"Effect": "Deny", "Action": ["iam:*"], "Resource": "*"},
+                {"Effect": "Allow", "NotAction": ["iam:*"], "Resource": "*"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let read = read_policies(temp_dir.path()).unwrap();
+
+        assert_eq!(read.groups.len(), 1);
+        let compute = &read.groups["ComputeDeployer"];
+        assert_eq!(
+            compute.allow,
+            HashSet::from(["ec2:RunInstances".to_string()])
+        );
+        assert_eq!(compute.deny, HashSet::from(["iam:*".to_string()]));
+        assert_eq!(
+            compute.not_allow,
+            BTreeSet::from([NotActionStatement::new("", ["iam:*".to_string()])])
+        );
+    }
+
+    #[test]
+    fn read_policies_rejects_invalid_json_and_unknown_effects() {
+        for content in [
+            "{",
+            r#"{"Statement": [{"Effect": "Maybe", "Action": "s3:*"}]}"#,
+        ] {
+            let temp_dir = TempDir::new().unwrap();
+            fs::write(temp_dir.path().join("Deployer.json"), content).unwrap();
+
+            assert!(
+                matches!(
+                    read_policies(temp_dir.path()),
+                    Err(OutputError::InvalidPolicy(..))
+                ),
+                "{} should be rejected",
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn read_policies_requires_a_directory() {
+        let temp_dir = TempDir::new().unwrap();
+
+        assert!(matches!(
+            read_policies(&temp_dir.path().join("missing")),
+            Err(OutputError::NotAPolicyDirectory(_))
+        ));
+    }
+
+    #[test]
+    fn group_name_strips_split_part_numbers_only() {
+        assert_eq!(group_name("Deployer.2"), "Deployer");
+        assert_eq!(group_name("Deployer"), "Deployer");
+        assert_eq!(group_name("my.Deployer"), "my.Deployer");
+    }
+}
@@ -0,0 +1,188 @@
+//! SARIF report of findings for code scanning dashboards.
+//!
+//! This module writes a SARIF 2.1.0 document (`--sarif`) with one result per
+//! finding, so lppc findings can be uploaded to e.g. GitHub code scanning:
+//!
+//! - `missing-mapping`: a block type without a mapping file
+//! - `shadowed-action`: an allowed action that is also denied in the same
+//!   group, so the Allow statement has no effect for it
+//!
+//! Results carry no `locations`, since the source positions of blocks are
+//! not tracked.
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::mapping::PermissionResult;
+
+use super::OutputError;
+
+/// SARIF schema referenced by the written document.
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Rule id of a block type without a mapping file.
+pub const MISSING_MAPPING_RULE: &str = "missing-mapping";
+
+/// Rule id of an allowed action that is also denied in the same group.
+pub const SHADOWED_ACTION_RULE: &str = "shadowed-action";
+
+/// Renders the findings of a result as a pretty-printed SARIF 2.1.0 document.
+///
+/// Missing mappings are listed in the order they were found, followed by the
+/// shadowed actions of each group, sorted by group name and action.
+pub fn format_sarif(result: &PermissionResult) -> String {
+    let mut results: Vec<Value> = result
+        .missing_mappings
+        .iter()
+        .map(|missing| {
+            let mut text = format!(
+                "No mapping for {} {} (expected at {})",
+                missing.block_type.as_str(),
+                missing.type_name,
+                missing.expected_path
+            );
+            if let Some(suggestion) = &missing.suggestion {
+                text.push_str(&format!(" - did you mean {}?", suggestion));
+            }
+            finding(MISSING_MAPPING_RULE, &text)
+        })
+        .collect();
+
+    let mut names: Vec<&String> = result.groups.keys().collect();
+    names.sort();
+    for name in names {
+        for action in result.groups[name].conflicting_actions() {
+            let text = format!(
+                "{}: {} is both allowed and denied, the Allow has no effect",
+                name, action
+            );
+            results.push(finding(SHADOWED_ACTION_RULE, &text));
+        }
+    }
+
+    let document = serde_json::json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "lppc",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": [
+                        rule(MISSING_MAPPING_RULE, "Terraform type without a mapping file"),
+                        rule(SHADOWED_ACTION_RULE, "Allowed action shadowed by a Deny"),
+                    ],
+                },
+            },
+            "results": results,
+        }],
+    });
+
+    let mut output =
+        serde_json::to_string_pretty(&document).expect("JSON serialization should not fail");
+    output.push('\n');
+    output
+}
+
+fn rule(id: &str, description: &str) -> Value {
+    serde_json::json!({
+        "id": id,
+        "shortDescription": { "text": description },
+    })
+}
+
+fn finding(rule_id: &str, text: &str) -> Value {
+    serde_json::json!({
+        "ruleId": rule_id,
+        "level": "warning",
+        "message": { "text": text },
+    })
+}
+
+/// Writes the SARIF report to the given file.
+///
+/// The report is written even without findings, so code scanning can close
+/// previously reported results.
+pub fn write_sarif(path: &Path, result: &PermissionResult) -> Result<(), OutputError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, format_sarif(result))?;
+
+    log::info!("Written: {}", path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::{GroupPermissions, MissingMapping};
+    use crate::terraform::BlockType;
+    use crate::test_support::actions;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn result_with_missing_mapping() -> PermissionResult {
+        PermissionResult {
+            groups: HashMap::from([(
+                "NetworkDeployer".to_string(),
+                GroupPermissions {
+                    allow: actions(&["ec2:CreateVpc", "ec2:DeleteVpc"]),
+                    deny: actions(&["ec2:DeleteVpc"]),
+                    ..Default::default()
+                },
+            )]),
+            missing_mappings: vec![MissingMapping {
+                block_type: BlockType::Resource,
+                type_name: "aws_new_service".to_string(),
+                expected_path: "mappings/resource/aws_new_service.yaml".to_string(),
+                suggestion: None,
+            }],
+            empty_groups: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn sarif_lists_missing_mappings_and_shadowed_actions() {
+        let sarif: Value = serde_json::from_str(&format_sarif(&result_with_missing_mapping()))
+            .expect("SARIF should be valid JSON");
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(sarif["runs"][0]["tool"]["driver"]["name"], "lppc");
+
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], MISSING_MAPPING_RULE);
+        assert_eq!(
+            results[0]["message"]["text"],
+            "No mapping for resource aws_new_service (expected at mappings/resource/aws_new_service.yaml)"
+        );
+        assert_eq!(results[1]["ruleId"], SHADOWED_ACTION_RULE);
+        assert_eq!(
+            results[1]["message"]["text"],
+            "NetworkDeployer: ec2:DeleteVpc is both allowed and denied, the Allow has no effect"
+        );
+    }
+
+    #[test]
+    fn sarif_without_findings_has_empty_results() {
+        let sarif: Value = serde_json::from_str(&format_sarif(&PermissionResult::default()))
+            .expect("SARIF should be valid JSON");
+
+        assert_eq!(sarif["runs"][0]["results"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn write_sarif_creates_parent_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("reports/lppc.sarif");
+
+        write_sarif(&path, &result_with_missing_mapping()).unwrap();
+
+        let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["runs"][0]["results"].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,148 @@
+//! Source address listings for generated policies.
+//!
+//! This module writes a `<Group>.sources.json` file per group listing the
+//! addresses of all Terraform blocks that belong to the group, so every
+//! generated policy can be traced back to the configuration it was derived
+//! from:
+//!
+//! ```json
+//! {
+//!   "group": "NetworkDeployer",
+//!   "sources": [
+//!     "aws_subnet.a",
+//!     "module.vpc.aws_vpc.main"
+//!   ]
+//! }
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::mapping::{GroupPermissions, PermissionResult};
+
+use super::{OutputError, sanitize_filename};
+
+/// Suffix of the sources file written next to each group's policy.
+pub const SOURCES_FILE_SUFFIX: &str = ".sources.json";
+
+/// Renders the sources listing of one group as pretty-printed JSON.
+pub fn format_sources(group_name: &str, permissions: &GroupPermissions) -> String {
+    let document = serde_json::json!({
+        "group": group_name,
+        "sources": permissions.sources,
+    });
+
+    let mut output =
+        serde_json::to_string_pretty(&document).expect("JSON serialization should not fail");
+    output.push('\n');
+    output
+}
+
+/// Writes `<Group>.sources.json` for every group into the given directory.
+///
+/// # Returns
+///
+/// The sorted paths of the written files.
+pub fn write_sources(dir: &Path, result: &PermissionResult) -> Result<Vec<PathBuf>, OutputError> {
+    fs::create_dir_all(dir)?;
+
+    let mut written = Vec::new();
+
+    for (output_name, permissions) in &result.groups {
+        let safe_name = sanitize_filename(output_name).ok_or_else(|| {
+            OutputError::InvalidFilename(format!(
+                "Output name '{}' contains invalid characters",
+                output_name
+            ))
+        })?;
+
+        let path = dir.join(format!("{}{}", safe_name, SOURCES_FILE_SUFFIX));
+        fs::write(&path, format_sources(output_name, permissions))?;
+
+        log::info!("Written: {}", path.display());
+        written.push(path);
+    }
+
+    written.sort();
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+    use tempfile::TempDir;
+
+    fn permissions(sources: &[&str]) -> GroupPermissions {
+        GroupPermissions {
+            allow: HashSet::from(["s3:CreateBucket".to_string()]),
+            sources: sources.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn format_sources_lists_group_and_addresses() {
+        let output = format_sources(
+            "NetworkDeployer",
+            &permissions(&["aws_subnet.a", "module.vpc.aws_vpc.main"]),
+        );
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["group"], "NetworkDeployer");
+        assert_eq!(
+            parsed["sources"],
+            serde_json::json!(["aws_subnet.a", "module.vpc.aws_vpc.main"])
+        );
+    }
+
+    #[test]
+    fn write_sources_writes_file_per_group() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = PermissionResult {
+            groups: HashMap::from([
+                (
+                    "NetworkDeployer".to_string(),
+                    permissions(&["aws_subnet.a", "aws_subnet.b", "aws_vpc.main"]),
+                ),
+                (
+                    "StorageDeployer".to_string(),
+                    permissions(&["aws_s3_bucket.logs"]),
+                ),
+            ]),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+
+        let written = write_sources(temp_dir.path(), &result).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                temp_dir.path().join("NetworkDeployer.sources.json"),
+                temp_dir.path().join("StorageDeployer.sources.json"),
+            ]
+        );
+
+        let network: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&written[0]).unwrap()).unwrap();
+        assert_eq!(
+            network["sources"],
+            serde_json::json!(["aws_subnet.a", "aws_subnet.b", "aws_vpc.main"])
+        );
+    }
+
+    #[test]
+    fn write_sources_rejects_invalid_group_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = PermissionResult {
+            groups: HashMap::from([("..".to_string(), permissions(&["aws_vpc.main"]))]),
+            missing_mappings: Vec::new(),
+            empty_groups: Vec::new(),
+        };
+
+        let written = write_sources(temp_dir.path(), &result);
+
+        assert!(matches!(written, Err(OutputError::InvalidFilename(_))));
+    }
+}
@@ -0,0 +1,159 @@
+//! Stub mapping file generation for missing mappings.
+//!
+//! This module writes a skeleton YAML mapping file for every missing mapping,
+//! laid out exactly like the mapping repository (`mappings/{BLOCK_TYPE}/{TYPE}.yaml`),
+//! so the generated files can be filled in and copied into the repository.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::mapping::MissingMapping;
+
+use super::OutputError;
+
+/// Writes a stub YAML mapping file for each missing mapping below `dir`.
+///
+/// The file path is `dir` joined with the mapping's `expected_path`. Existing
+/// files are left untouched so that work in progress is never overwritten.
+///
+/// # Returns
+///
+/// The paths of the stub files that were created.
+///
+/// # Errors
+///
+/// Returns `OutputError::InvalidFilename` if an expected path would escape
+/// `dir`, or `OutputError::Io` if a directory or file cannot be written.
+pub fn write_mapping_stubs(
+    missing_mappings: &[MissingMapping],
+    dir: &Path,
+) -> Result<Vec<PathBuf>, OutputError> {
+    let mut written = Vec::new();
+
+    for missing in missing_mappings {
+        let relative = Path::new(&missing.expected_path);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(OutputError::InvalidFilename(missing.expected_path.clone()));
+        }
+
+        let path = dir.join(relative);
+        if path.exists() {
+            log::debug!("Stub already exists, skipping: {}", path.display());
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, stub_content(missing))?;
+
+        log::info!("Written stub: {}", path.display());
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Returns the skeleton content of a stub mapping file.
+fn stub_content(missing: &MissingMapping) -> String {
+    format!(
+        "# Auto-generated by lppc --dump-missing for {}.{}\n\
+         # Add the required IAM actions and remove empty sections before committing.\n\
+         ---\n\
+         deny: []\n\
+         allow: []\n",
+        missing.block_type.as_str(),
+        missing.type_name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::yaml_parser::parse_mapping;
+    use crate::terraform::BlockType;
+    use tempfile::TempDir;
+
+    fn missing(block_type: BlockType, type_name: &str) -> MissingMapping {
+        MissingMapping {
+            block_type,
+            type_name: type_name.to_string(),
+            expected_path: format!("mappings/{}/{}.yaml", block_type.as_str(), type_name),
+            suggestion: None,
+        }
+    }
+
+    #[test]
+    fn writes_stubs_at_expected_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing_mappings = vec![
+            missing(BlockType::Resource, "aws_unknown_resource"),
+            missing(BlockType::Data, "aws_unknown_data"),
+        ];
+
+        let written = write_mapping_stubs(&missing_mappings, temp_dir.path()).unwrap();
+
+        assert_eq!(written.len(), 2);
+        let resource_stub = temp_dir
+            .path()
+            .join("mappings/resource/aws_unknown_resource.yaml");
+        let data_stub = temp_dir.path().join("mappings/data/aws_unknown_data.yaml");
+        assert!(resource_stub.exists());
+        assert!(data_stub.exists());
+
+        let content = fs::read_to_string(&resource_stub).unwrap();
+        assert!(content.starts_with("# Auto-generated by lppc --dump-missing"));
+        assert!(content.contains("resource.aws_unknown_resource"));
+        assert!(content.contains("deny: []\n"));
+        assert!(content.contains("allow: []\n"));
+    }
+
+    #[test]
+    fn stub_content_is_a_valid_empty_mapping() {
+        let content = stub_content(&missing(BlockType::Resource, "aws_unknown_resource"));
+
+        let mapping = parse_mapping(&content).unwrap();
+        assert!(mapping.allow.is_empty());
+        assert!(mapping.deny.is_empty());
+    }
+
+    #[test]
+    fn existing_files_are_not_overwritten() {
+        let temp_dir = TempDir::new().unwrap();
+        let stub_dir = temp_dir.path().join("mappings/resource");
+        fs::create_dir_all(&stub_dir).unwrap();
+        fs::write(
+            stub_dir.join("aws_unknown_resource.yaml"),
+            "# work in progress",
+        )
+        .unwrap();
+
+        let written = write_mapping_stubs(
+            &[missing(BlockType::Resource, "aws_unknown_resource")],
+            temp_dir.path(),
+        )
+        .unwrap();
+
+        assert!(written.is_empty());
+        let content = fs::read_to_string(stub_dir.join("aws_unknown_resource.yaml")).unwrap();
+        assert_eq!(content, "# work in progress");
+    }
+
+    #[test]
+    fn rejects_path_traversal_in_expected_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let traversal = MissingMapping {
+            block_type: BlockType::Resource,
+            type_name: "aws_evil".to_string(),
+            expected_path: "../aws_evil.yaml".to_string(),
+            suggestion: None,
+        };
+
+        let result = write_mapping_stubs(&[traversal], temp_dir.path());
+
+        assert!(matches!(result, Err(OutputError::InvalidFilename(_))));
+    }
+}
@@ -0,0 +1,207 @@
+//! Template output formatter.
+//!
+//! This module provides the `TemplateFormatter` behind `--template-file`,
+//! which renders each group's permissions through a user-provided
+//! [minijinja](https://docs.rs/minijinja) template, for output shapes lppc
+//! has no built-in format for (Pulumi, custom DSLs, ...):
+//!
+//! ```jinja
+//! {{ group_name }} ({{ role_arn or "no role" }})
+//! {% for action in allow %}  allow {{ action }}
+//! {% endfor %}
+//! ```
+//!
+//! The template sees `group_name`, `role_arn` (none if unknown), the sorted
+//! `allow` and `deny` lists, and the `not_allow` and `not_deny` lists of
+//! `NotAction` statements, each with its `mapping`, `resources`, `condition`
+//! (none if unconditional), and sorted `actions`.
+
+use std::collections::{BTreeSet, HashSet};
+
+use minijinja::{Environment, context};
+
+use super::OutputError;
+use super::formatter::{OutputFormatter, PermissionSets};
+use crate::mapping::NotActionStatement;
+
+/// Name of the template in the formatter's environment.
+const TEMPLATE_NAME: &str = "template";
+
+/// Group name used when formatting without group context.
+const DEFAULT_GROUP_NAME: &str = "Deployer";
+
+/// Formatter that renders each group through a minijinja template.
+#[derive(Clone)]
+pub struct TemplateFormatter {
+    environment: Environment<'static>,
+    extension: String,
+}
+
+impl TemplateFormatter {
+    /// Compiles a template with the file extension of its output (e.g., `ts`).
+    ///
+    /// The template is rendered once over a sample group, so syntax errors
+    /// and unknown filters are reported before any output is written.
+    pub fn new(source: &str, extension: &str) -> Result<Self, OutputError> {
+        let extension = extension.trim_start_matches('.');
+        if extension.is_empty()
+            || extension.contains("..")
+            || !extension
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+        {
+            return Err(OutputError::InvalidFilename(format!(
+                "Template extension '{}' contains invalid characters",
+                extension
+            )));
+        }
+
+        let mut environment = Environment::new();
+        environment
+            .add_template_owned(TEMPLATE_NAME, source.to_string())
+            .map_err(|e| OutputError::InvalidTemplate(e.to_string()))?;
+
+        let formatter = Self {
+            environment,
+            extension: extension.to_string(),
+        };
+        formatter
+            .render(
+                DEFAULT_GROUP_NAME,
+                None,
+                [&["s3:GetObject"], &[]],
+                [&BTreeSet::new(), &BTreeSet::new()],
+            )
+            .map_err(|e| OutputError::InvalidTemplate(e.to_string()))?;
+
+        Ok(formatter)
+    }
+
+    /// Renders the template with the `allow` and `deny` lists and the
+    /// `not_allow` and `not_deny` statements, in that order.
+    fn render(
+        &self,
+        group_name: &str,
+        role_arn: Option<&str>,
+        [allow, deny]: [&[&str]; 2],
+        [not_allow, not_deny]: [&BTreeSet<NotActionStatement>; 2],
+    ) -> Result<String, minijinja::Error> {
+        self.environment
+            .get_template(TEMPLATE_NAME)?
+            .render(context! {
+                group_name,
+                role_arn,
+                allow,
+                deny,
+                not_allow,
+                not_deny,
+            })
+    }
+}
+
+impl OutputFormatter for TemplateFormatter {
+    fn format(&self, permissions: &PermissionSets) -> String {
+        self.format_for_group(DEFAULT_GROUP_NAME, None, permissions)
+    }
+
+    fn format_for_group(
+        &self,
+        group_name: &str,
+        role_arn: Option<&str>,
+        permissions: &PermissionSets,
+    ) -> String {
+        let allow = sorted(permissions.allow);
+        let deny = sorted(permissions.deny);
+        self.render(
+            group_name,
+            role_arn,
+            [&allow, &deny],
+            [permissions.not_allow, permissions.not_deny],
+        )
+        .unwrap_or_else(|e| {
+            log::error!("Failed to render template for {}: {}", group_name, e);
+            String::new()
+        })
+    }
+
+    fn extension(&self) -> &str {
+        &self.extension
+    }
+}
+
+/// Returns the actions of a set, sorted.
+fn sorted(actions: &HashSet<String>) -> Vec<&str> {
+    let mut actions: Vec<&str> = actions.iter().map(String::as_str).collect();
+    actions.sort();
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{actions, not_action};
+
+    #[test]
+    fn template_renders_group_variables() {
+        let formatter = TemplateFormatter::new(
+            "{{ group_name }} {{ role_arn }}\n\
+             {% for action in allow %}+{{ action }}\n{% endfor %}\
+             {% for action in deny %}-{{ action }}\n{% endfor %}\
+             {% for statement in not_allow %}+not {{ statement.actions | join(\",\") }}\n{% endfor %}\
+             {% for statement in not_deny %}-not {{ statement.actions | join(\",\") }}\n{% endfor %}",
+            "txt",
+        )
+        .unwrap();
+        let allow = actions(&["s3:PutObject", "s3:GetObject"]);
+        let deny = actions(&["s3:DeleteBucket"]);
+        let not_allow = BTreeSet::from([
+            not_action("resource.aws_iam_role", &["iam:*", "sts:*"]),
+            not_action("resource.aws_sqs_queue", &["sqs:*"]),
+        ]);
+        let not_deny = BTreeSet::from([not_action("resource.aws_s3_bucket", &["s3:*"])]);
+
+        let output = formatter.format_for_group(
+            "StorageDeployer",
+            Some("arn:aws:iam::123456789012:role/storage"),
+            &PermissionSets {
+                allow: &allow,
+                deny: &deny,
+                not_allow: &not_allow,
+                not_deny: &not_deny,
+                condition: None,
+                resources: None,
+                conditions: None,
+            },
+        );
+
+        assert_eq!(
+            output,
+            "StorageDeployer arn:aws:iam::123456789012:role/storage\n\
+             +s3:GetObject\n\
+             +s3:PutObject\n\
+             -s3:DeleteBucket\n\
+             +not iam:*,sts:*\n\
+             +not sqs:*\n\
+             -not s3:*\n"
+        );
+    }
+
+    #[test]
+    fn invalid_template_is_rejected() {
+        assert!(matches!(
+            TemplateFormatter::new("{% for action in allow %}", "txt"),
+            Err(OutputError::InvalidTemplate(_))
+        ));
+        assert!(matches!(
+            TemplateFormatter::new("{{ allow | no_such_filter }}", "txt"),
+            Err(OutputError::InvalidTemplate(_))
+        ));
+    }
+
+    #[test]
+    fn extension_is_configurable_and_checked() {
+        assert_eq!(TemplateFormatter::new("", ".ts").unwrap().extension(), "ts");
+        assert!(TemplateFormatter::new("", "../ts").is_err());
+        assert!(TemplateFormatter::new("", "").is_err());
+    }
+}
@@ -0,0 +1,299 @@
+//! Terraform role output formatter.
+//!
+//! This module provides the `TerraformRoleFormatter` which emits a
+//! ready-to-apply Terraform configuration per provider group: an
+//! `aws_iam_role` with a placeholder trust policy and an inline
+//! `aws_iam_role_policy` embedding the generated policy document:
+//!
+//! ```hcl
+//! resource "aws_iam_role" "network_deployer" {
+//!   name = "NetworkDeployer"
+//!   ...
+//! }
+//!
+//! resource "aws_iam_role_policy" "network_deployer" {
+//!   name   = "NetworkDeployer"
+//!   role   = aws_iam_role.network_deployer.id
+//!   policy = jsonencode({
+//!     ...
+//!   })
+//! }
+//! ```
+//!
+//! The policy document is rendered by the grouped `HclFormatter`, so Deny
+//! handling and statement ordering are identical to the `hcl-grouped` format.
+
+use super::formatter::{OutputFormatter, PermissionSets};
+use super::hcl::HclFormatter;
+use crate::redact::account_id;
+
+/// Group name used when formatting without group context.
+const DEFAULT_GROUP_NAME: &str = "Deployer";
+
+/// Principal of the placeholder trust policy when the account is unknown.
+const PLACEHOLDER_PRINCIPAL: &str = "arn:aws:iam::ACCOUNT_ID:root";
+
+/// Formatter that emits an `aws_iam_role` and an inline `aws_iam_role_policy` per group.
+pub struct TerraformRoleFormatter;
+
+impl OutputFormatter for TerraformRoleFormatter {
+    fn format(&self, permissions: &PermissionSets) -> String {
+        self.format_for_group(DEFAULT_GROUP_NAME, None, permissions)
+    }
+
+    fn format_for_group(
+        &self,
+        group_name: &str,
+        role_arn: Option<&str>,
+        permissions: &PermissionSets,
+    ) -> String {
+        let identifier = Self::resource_identifier(group_name);
+        let role_name = escape_hcl_string(&Self::role_name(group_name, role_arn));
+        let principal = role_arn
+            .and_then(account_id)
+            .map(|account| format!("arn:aws:iam::{}:root", account))
+            .unwrap_or_else(|| PLACEHOLDER_PRINCIPAL.to_string());
+
+        // Indent continuation lines so the document nests inside the resource
+        let policy = HclFormatter { grouped: true }
+            .format(permissions)
+            .lines()
+            .collect::<Vec<_>>()
+            .join("\n  ");
+
+        format!(
+            r#"resource "aws_iam_role" "{identifier}" {{
+  name = "{role_name}"
+
+  # Placeholder trust policy: replace the principal with the identities
+  # that are allowed to assume this role.
+  assume_role_policy = jsonencode({{
+    Version = "2012-10-17"
+    Statement = [
+      {{
+        Effect    = "Allow"
+        Action    = "sts:AssumeRole"
+        Principal = {{
+          AWS = "{principal}"
+        }}
+      }}
+    ]
+  }})
+}}
+
+resource "aws_iam_role_policy" "{identifier}" {{
+  name   = "{role_name}"
+  role   = aws_iam_role.{identifier}.id
+  policy = {policy}
+}}
+"#
+        )
+    }
+
+    fn extension(&self) -> &'static str {
+        "tf"
+    }
+}
+
+impl TerraformRoleFormatter {
+    /// Derives the IAM role name from the role ARN, falling back to the group name.
+    ///
+    /// The role name is the last path segment of the ARN's resource part
+    /// (`arn:aws:iam::123456789012:role/path/Name` -> `Name`). ARNs whose name
+    /// contains interpolations or characters IAM does not allow fall back to
+    /// the group name.
+    fn role_name(group_name: &str, role_arn: Option<&str>) -> String {
+        role_arn
+            .and_then(|arn| arn.split_once(":role/"))
+            .and_then(|(_, path)| path.rsplit('/').next())
+            .filter(|name| is_valid_role_name(name))
+            .unwrap_or(group_name)
+            .to_string()
+    }
+
+    /// Converts a group name into a Terraform resource identifier.
+    ///
+    /// CamelCase is converted to snake_case (`NetworkDeployer` ->
+    /// `network_deployer`), characters that are not valid in identifiers are
+    /// replaced with `_`, and a leading digit is prefixed with `_`.
+    pub(super) fn resource_identifier(group_name: &str) -> String {
+        let mut identifier = String::new();
+        let mut previous_lowercase = false;
+
+        for c in group_name.chars() {
+            if c.is_ascii_uppercase() {
+                if previous_lowercase {
+                    identifier.push('_');
+                }
+                identifier.push(c.to_ascii_lowercase());
+                previous_lowercase = false;
+            } else if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                identifier.push(c);
+                previous_lowercase = c.is_ascii_lowercase() || c.is_ascii_digit();
+            } else {
+                identifier.push('_');
+                previous_lowercase = false;
+            }
+        }
+
+        if !identifier.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            identifier.insert(0, '_');
+        }
+
+        identifier
+    }
+}
+
+/// Returns true if `name` is a valid IAM role name (1-64 of `[A-Za-z0-9+=,.@_-]`).
+fn is_valid_role_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+=,.@_-".contains(c))
+}
+
+/// Escapes a value for use inside a quoted HCL string.
+pub(super) fn escape_hcl_string(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace("${", "$${")
+        .replace("%{", "%%{")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::actions;
+    use std::collections::BTreeSet;
+
+    fn is_valid_identifier(identifier: &str) -> bool {
+        let mut chars = identifier.chars();
+        chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    }
+
+    fn format_group(group_name: &str, role_arn: Option<&str>) -> String {
+        let allow = actions(&["s3:CreateBucket", "ec2:CreateVpc"]);
+        let deny = actions(&["s3:DeleteBucket"]);
+        TerraformRoleFormatter.format_for_group(
+            group_name,
+            role_arn,
+            &PermissionSets {
+                allow: &allow,
+                deny: &deny,
+                not_allow: &BTreeSet::new(),
+                not_deny: &BTreeSet::new(),
+                condition: None,
+                resources: None,
+                conditions: None,
+            },
+        )
+    }
+
+    #[test]
+    fn format_emits_role_and_inline_policy_with_valid_identifiers() {
+        let output = format_group(
+            "NetworkDeployer",
+            Some("arn:aws:iam::123456789012:role/NetworkDeployer"),
+        );
+
+        let body: hcl::Body = hcl::from_str(&output).expect("output should be valid HCL");
+        let resources: Vec<(Vec<String>, &hcl::Body)> = body
+            .blocks()
+            .map(|block| {
+                let labels = block
+                    .labels
+                    .iter()
+                    .map(|label| label.as_str().to_string())
+                    .collect();
+                (labels, block.body())
+            })
+            .collect();
+
+        assert_eq!(resources.len(), 2);
+        assert_eq!(resources[0].0, vec!["aws_iam_role", "network_deployer"]);
+        assert_eq!(
+            resources[1].0,
+            vec!["aws_iam_role_policy", "network_deployer"]
+        );
+        for (labels, _) in &resources {
+            assert!(is_valid_identifier(&labels[1]), "invalid: {}", labels[1]);
+        }
+
+        assert!(output.contains("  role   = aws_iam_role.network_deployer.id\n"));
+        assert!(output.contains("  policy = jsonencode({"));
+        assert!(output.contains("\"s3:CreateBucket\""));
+        assert!(output.contains("\"s3:DeleteBucket\""));
+    }
+
+    #[test]
+    fn format_derives_role_name_and_principal_from_role_arn() {
+        let output = format_group(
+            "NetworkDeployer",
+            Some("arn:aws:iam::123456789012:role/deploy/network-admin"),
+        );
+
+        assert!(output.contains("  name = \"network-admin\"\n"));
+        assert!(output.contains("AWS = \"arn:aws:iam::123456789012:root\""));
+    }
+
+    #[test]
+    fn format_without_role_arn_uses_group_name() {
+        let output = format_group("DefaultDeployer", None);
+
+        assert!(output.contains("resource \"aws_iam_role\" \"default_deployer\""));
+        assert!(output.contains("  name = \"DefaultDeployer\"\n"));
+        assert!(output.contains(&format!("AWS = \"{}\"", PLACEHOLDER_PRINCIPAL)));
+    }
+
+    #[test]
+    fn format_with_interpolated_role_arn_falls_back_to_group_name() {
+        let output = format_group(
+            "SharedDeployer",
+            Some("arn:aws:iam::${var.account_id}:role/${var.role_name}"),
+        );
+
+        assert!(output.contains("  name = \"SharedDeployer\"\n"));
+        assert!(output.contains(&format!("AWS = \"{}\"", PLACEHOLDER_PRINCIPAL)));
+        assert!(hcl::from_str::<hcl::Body>(&output).is_ok());
+    }
+
+    #[test]
+    fn resource_identifier_is_valid_for_unusual_group_names() {
+        assert_eq!(
+            TerraformRoleFormatter::resource_identifier("NetworkDeployer"),
+            "network_deployer"
+        );
+        assert_eq!(
+            TerraformRoleFormatter::resource_identifier("dr_regionDeployer"),
+            "dr_region_deployer"
+        );
+        assert_eq!(
+            TerraformRoleFormatter::resource_identifier("1Deployer"),
+            "_1_deployer"
+        );
+        assert_eq!(
+            TerraformRoleFormatter::resource_identifier("My Role.Deployer"),
+            "my_role_deployer"
+        );
+
+        for name in [
+            "NetworkDeployer",
+            "1Deployer",
+            "My Role.Deployer",
+            "ÄDeployer",
+        ] {
+            let identifier = TerraformRoleFormatter::resource_identifier(name);
+            assert!(is_valid_identifier(&identifier), "invalid: {}", identifier);
+        }
+    }
+
+    #[test]
+    fn extension_is_tf() {
+        assert_eq!(TerraformRoleFormatter.extension(), "tf");
+    }
+}
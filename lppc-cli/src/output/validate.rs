@@ -0,0 +1,179 @@
+//! External validation of generated policy files.
+//!
+//! This module runs a user-supplied command (`--validate-cmd`) once per
+//! written policy file, so lppc can be combined with external policy linters
+//! such as `aws accessanalyzer validate-policy`.
+//!
+//! The command template is split on whitespace and executed directly, without
+//! a shell. Every `{}` in an argument is replaced with the file path; if the
+//! template contains no `{}`, the path is appended as the last argument.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::OutputError;
+
+/// Runs the validation command for each file.
+///
+/// All files are validated even if an earlier one fails, so that every
+/// failure is reported in a single run.
+///
+/// # Errors
+///
+/// Returns `OutputError::ValidationFailed` listing every file whose command
+/// could not be started or exited with a non-zero status.
+pub fn run_validation(template: &str, files: &[PathBuf]) -> Result<(), OutputError> {
+    let mut failures = Vec::new();
+
+    for file in files {
+        let args = build_command(template, file);
+        let Some((program, program_args)) = args.split_first() else {
+            return Err(OutputError::ValidationFailed(
+                "--validate-cmd is empty".to_string(),
+            ));
+        };
+
+        log::debug!("Validating {}: {:?}", file.display(), args);
+
+        match Command::new(program).args(program_args).output() {
+            Ok(output) if output.status.success() => {
+                log::info!("Validated: {}", file.display());
+            }
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                log::error!(
+                    "Validation of {} failed ({}):\n{}{}",
+                    file.display(),
+                    output.status,
+                    stdout,
+                    stderr
+                );
+                failures.push(format!("{} ({})", file.display(), output.status));
+            }
+            Err(e) => {
+                failures.push(format!(
+                    "{} (cannot run '{}': {})",
+                    file.display(),
+                    program,
+                    e
+                ));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(OutputError::ValidationFailed(failures.join(", ")))
+    }
+}
+
+/// Builds the argument list for one file from the command template.
+fn build_command(template: &str, file: &Path) -> Vec<String> {
+    let path = file.to_string_lossy();
+    let mut args: Vec<String> = template
+        .split_whitespace()
+        .map(|arg| arg.replace("{}", &path))
+        .collect();
+
+    if !template.contains("{}") && !args.is_empty() {
+        args.push(path.into_owned());
+    }
+
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_policies(dir: &Path, names: &[&str]) -> Vec<PathBuf> {
+        names
+            .iter()
+            .map(|name| {
+                let path = dir.join(name);
+                fs::write(&path, "{}").unwrap();
+                path
+            })
+            .collect()
+    }
+
+    #[test]
+    fn build_command_substitutes_placeholder() {
+        let args = build_command(
+            "aws accessanalyzer validate-policy --policy-document file://{}",
+            Path::new("/out/NetworkDeployer.json"),
+        );
+
+        assert_eq!(
+            args,
+            vec![
+                "aws",
+                "accessanalyzer",
+                "validate-policy",
+                "--policy-document",
+                "file:///out/NetworkDeployer.json"
+            ]
+        );
+    }
+
+    #[test]
+    fn build_command_appends_path_without_placeholder() {
+        let args = build_command("policy-lint --strict", Path::new("/out/a.json"));
+
+        assert_eq!(args, vec!["policy-lint", "--strict", "/out/a.json"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_validation_invokes_command_per_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = write_policies(temp_dir.path(), &["A.json", "B.json"]);
+
+        run_validation("touch {}.validated", &files).unwrap();
+
+        assert!(temp_dir.path().join("A.json.validated").exists());
+        assert!(temp_dir.path().join("B.json.validated").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_validation_reports_non_zero_exit() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = write_policies(temp_dir.path(), &["A.json", "B.json"]);
+
+        let result = run_validation("false {}", &files);
+
+        let Err(OutputError::ValidationFailed(message)) = result else {
+            panic!("expected a validation failure");
+        };
+        assert!(message.contains("A.json"));
+        assert!(message.contains("B.json"));
+    }
+
+    #[test]
+    fn run_validation_reports_missing_command() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = write_policies(temp_dir.path(), &["A.json"]);
+
+        let result = run_validation("lppc-nonexistent-validator {}", &files);
+
+        let Err(OutputError::ValidationFailed(message)) = result else {
+            panic!("expected a validation failure");
+        };
+        assert!(message.contains("cannot run 'lppc-nonexistent-validator'"));
+    }
+
+    #[test]
+    fn run_validation_rejects_empty_template() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = write_policies(temp_dir.path(), &["A.json"]);
+
+        let result = run_validation("   ", &files);
+
+        assert!(matches!(result, Err(OutputError::ValidationFailed(_))));
+    }
+}
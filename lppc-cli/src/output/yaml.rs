@@ -0,0 +1,224 @@
+//! YAML output formatter.
+//!
+//! This module provides the `YamlFormatter` which emits the IAM policy
+//! document of the `json` format as YAML, e.g. for Ansible:
+//!
+//! ```yaml
+//! Version: "2012-10-17"
+//! Statement:
+//!   - Effect: "Allow"
+//!     Action:
+//!       - "ec2:CreateVpc"
+//!     Resource: "*"
+//! ```
+//!
+//! The document is rendered by the `JsonFormatter` and loaded with saphyr
+//! (JSON is a subset of YAML), so statements, Sids, and key order are
+//! identical to the `json` format. All strings are double-quoted, so YAML 1.1
+//! loaders such as PyYAML (Ansible) do not read `2012-10-17` as a date.
+
+use std::fmt::Write;
+
+use saphyr::{LoadableYamlNode, Scalar, Yaml};
+
+use super::formatter::{OutputFormatter, PermissionSets};
+use super::json::JsonFormatter;
+use crate::cli::SidStrategy;
+
+/// Formatter that outputs permissions as an AWS IAM policy document in YAML.
+pub struct YamlFormatter {
+    /// How the `Sid` of each statement is generated.
+    pub sid: SidStrategy,
+}
+
+impl OutputFormatter for YamlFormatter {
+    fn format(&self, permissions: &PermissionSets) -> String {
+        let json = JsonFormatter {
+            grouped: false,
+            sid: self.sid,
+        }
+        .format(permissions);
+
+        let documents = Yaml::load_from_str(&json).expect("JSON output should be valid YAML");
+        let mut output = String::new();
+        write_node(&mut output, &documents[0], 0);
+        output
+    }
+
+    fn extension(&self) -> &'static str {
+        "yaml"
+    }
+}
+
+/// Writes a mapping or sequence in block style, one entry per line.
+fn write_node(output: &mut String, node: &Yaml, indent: usize) {
+    let padding = " ".repeat(indent);
+
+    match node {
+        Yaml::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let _ = write!(output, "{}{}:", padding, key_text(key));
+                write_value(output, value, indent + 2);
+            }
+        }
+        Yaml::Sequence(items) => {
+            for item in items {
+                if is_collection(item) {
+                    // The first line of a nested collection follows the "- "
+                    let mut nested = String::new();
+                    write_node(&mut nested, item, indent + 2);
+                    let _ = write!(output, "{}- {}", padding, &nested[indent + 2..]);
+                } else {
+                    let _ = writeln!(output, "{}- {}", padding, scalar_text(item));
+                }
+            }
+        }
+        _ => {
+            let _ = writeln!(output, "{}{}", padding, scalar_text(node));
+        }
+    }
+}
+
+/// Writes the value of a mapping entry after its key.
+fn write_value(output: &mut String, value: &Yaml, indent: usize) {
+    match value {
+        Yaml::Mapping(mapping) if mapping.is_empty() => output.push_str(" {}\n"),
+        Yaml::Sequence(items) if items.is_empty() => output.push_str(" []\n"),
+        _ if is_collection(value) => {
+            output.push('\n');
+            write_node(output, value, indent);
+        }
+        _ => {
+            let _ = writeln!(output, " {}", scalar_text(value));
+        }
+    }
+}
+
+fn is_collection(node: &Yaml) -> bool {
+    matches!(node, Yaml::Mapping(_) | Yaml::Sequence(_))
+}
+
+/// Returns a mapping key, quoted unless it is a plain identifier.
+fn key_text(key: &Yaml) -> String {
+    match key.as_str() {
+        Some(text)
+            if !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+        {
+            text.to_string()
+        }
+        _ => scalar_text(key),
+    }
+}
+
+/// Returns a scalar, with strings double-quoted (JSON escapes are valid YAML).
+fn scalar_text(node: &Yaml) -> String {
+    match node {
+        Yaml::Value(Scalar::String(text)) => {
+            serde_json::to_string(text).expect("JSON serialization should not fail")
+        }
+        Yaml::Value(Scalar::Integer(value)) => value.to_string(),
+        Yaml::Value(Scalar::FloatingPoint(value)) => value.to_string(),
+        Yaml::Value(Scalar::Boolean(value)) => value.to_string(),
+        _ => "null".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeSet, HashSet};
+
+    fn format(allow: &[&str], deny: &[&str]) -> String {
+        let allow: HashSet<String> = allow.iter().map(|s| s.to_string()).collect();
+        let deny: HashSet<String> = deny.iter().map(|s| s.to_string()).collect();
+
+        YamlFormatter {
+            sid: SidStrategy::None,
+        }
+        .format(&PermissionSets {
+            allow: &allow,
+            deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: None,
+            resources: None,
+            conditions: None,
+        })
+    }
+
+    #[test]
+    fn yaml_output_has_policy_structure_with_deny_first() {
+        assert_eq!(
+            format(&["ec2:DeleteVpc", "ec2:CreateVpc"], &["ec2:DeleteVpc"]),
+            "Version: \"2012-10-17\"\n\
+             Statement:\n\
+             \x20 - Effect: \"Deny\"\n\
+             \x20   Action:\n\
+             \x20     - \"ec2:DeleteVpc\"\n\
+             \x20   Resource: \"*\"\n\
+             \x20 - Effect: \"Allow\"\n\
+             \x20   Action:\n\
+             \x20     - \"ec2:CreateVpc\"\n\
+             \x20     - \"ec2:DeleteVpc\"\n\
+             \x20   Resource: \"*\"\n"
+        );
+    }
+
+    #[test]
+    fn yaml_output_parses_back_to_the_same_actions() {
+        let output = format(&["s3:GetObject", "s3:PutObject"], &[]);
+
+        let documents = Yaml::load_from_str(&output).unwrap();
+        let statement = &documents[0]["Statement"][0];
+        assert_eq!(statement["Effect"].as_str(), Some("Allow"));
+        assert_eq!(statement["Action"][1].as_str(), Some("s3:PutObject"));
+    }
+
+    #[test]
+    fn yaml_output_nests_condition_and_sid() {
+        let allow: HashSet<String> = HashSet::from(["ec2:RunInstances".to_string()]);
+        let deny = HashSet::new();
+        let condition = serde_json::json!({
+            "StringEquals": {"aws:RequestedRegion": ["eu-central-1"]}
+        });
+
+        let output = YamlFormatter {
+            sid: SidStrategy::Sequential,
+        }
+        .format(&PermissionSets {
+            allow: &allow,
+            deny: &deny,
+            not_allow: &BTreeSet::new(),
+            not_deny: &BTreeSet::new(),
+            condition: Some(&condition),
+            resources: None,
+            conditions: None,
+        });
+
+        assert_eq!(
+            output,
+            "Version: \"2012-10-17\"\n\
+             Statement:\n\
+             \x20 - Sid: \"Stmt1\"\n\
+             \x20   Effect: \"Allow\"\n\
+             \x20   Action:\n\
+             \x20     - \"ec2:RunInstances\"\n\
+             \x20   Resource: \"*\"\n\
+             \x20   Condition:\n\
+             \x20     StringEquals:\n\
+             \x20       \"aws:RequestedRegion\":\n\
+             \x20         - \"eu-central-1\"\n"
+        );
+    }
+
+    #[test]
+    fn extension_is_yaml() {
+        assert_eq!(
+            YamlFormatter {
+                sid: SidStrategy::None
+            }
+            .extension(),
+            "yaml"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Account IDs of AWS ARNs, and their redaction for sharing output externally.
+//!
+//! `account_id` extracts the literal 12-digit account ID of an ARN, e.g. to
+//! group roles by account or to build a trust policy principal.
+//!
+//! With `--redact-account-ids`, the 12-digit account ID segment of every ARN
+//! that lppc prints (role ARNs in the manifest and the `terraform-role`
+//! output, log messages) is replaced with `XXXXXXXXXXXX`. Policy documents
+//! contain no ARNs and are therefore unaffected.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Replacement for a redacted account ID.
+pub const REDACTED_ACCOUNT_ID: &str = "XXXXXXXXXXXX";
+
+/// Matches the account ID segment of an ARN (`arn:partition:service:region:account:...`).
+static ARN_ACCOUNT_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(arn:[^:\s]*:[^:\s]*:[^:\s]*:)\d{12}:").expect("valid regex"));
+
+/// Returns the account ID segment of an ARN if it is a literal 12-digit ID.
+///
+/// Interpolations (e.g., `${var.account_id}`), wildcards, and `aws` (AWS
+/// managed policies) are not account IDs.
+pub fn account_id(arn: &str) -> Option<&str> {
+    arn.split(':')
+        .nth(4)
+        .filter(|account| account.len() == 12 && account.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Replaces the account ID of every ARN in the text with `XXXXXXXXXXXX`.
+///
+/// Only literal 12-digit account IDs are redacted; ARNs whose account is an
+/// interpolation (e.g., `${var.account_id}`) or `aws` are left as they are.
+pub fn redact_account_ids(text: &str) -> String {
+    ARN_ACCOUNT_PATTERN
+        .replace_all(text, format!("${{1}}{}:", REDACTED_ACCOUNT_ID))
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_id_accepts_literal_account_ids_only() {
+        assert_eq!(
+            account_id("arn:aws:iam::123456789012:role/NetworkDeployer"),
+            Some("123456789012")
+        );
+        for arn in [
+            "arn:aws:iam::${var.account_id}:role/Deploy",
+            "arn:aws:iam::aws:policy/ReadOnlyAccess",
+            "arn:aws:iam::*:role/Deploy",
+            "arn:aws:iam::12345678901:role/Deploy",
+            "NetworkDeployer",
+        ] {
+            assert_eq!(account_id(arn), None, "{}", arn);
+        }
+    }
+
+    #[test]
+    fn redacts_account_id_of_role_arn() {
+        assert_eq!(
+            redact_account_ids("arn:aws:iam::123456789012:role/NetworkDeployer"),
+            "arn:aws:iam::XXXXXXXXXXXX:role/NetworkDeployer"
+        );
+    }
+
+    #[test]
+    fn redacts_every_arn_in_text() {
+        assert_eq!(
+            redact_account_ids(
+                "role_arn=Some(\"arn:aws-us-gov:iam::123456789012:role/A\"), queue arn:aws:sqs:eu-west-1:210987654321:jobs"
+            ),
+            "role_arn=Some(\"arn:aws-us-gov:iam::XXXXXXXXXXXX:role/A\"), queue arn:aws:sqs:eu-west-1:XXXXXXXXXXXX:jobs"
+        );
+    }
+
+    #[test]
+    fn leaves_non_literal_accounts_unchanged() {
+        for text in [
+            "arn:aws:iam::${var.account_id}:role/Deploy",
+            "arn:aws:iam::aws:policy/ReadOnlyAccess",
+            "arn:aws:iam::*:role/Deploy",
+            "account 123456789012",
+        ] {
+            assert_eq!(redact_account_ids(text), text);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Terraform providers supported by lppc.
+//!
+//! Each entry of `CLOUD_PROVIDERS` maps a resource type prefix (e.g.,
+//! `google_`) to a provider name and tells the HCL parser how to derive the
+//! identity that groups the provider's blocks. Blocks of providers that are
+//! not listed end up in `TerraformConfig::unmapped_blocks`.
+
+/// Where a provider block declares the identity Terraform deploys with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdentitySource {
+    /// `role_arn` of the `assume_role` block, else of the
+    /// `assume_role_with_web_identity` block (AWS)
+    AssumeRole,
+    /// A top-level attribute of the provider block
+    Attribute(&'static str),
+}
+
+/// A supported Terraform provider.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct CloudProvider {
+    /// Provider name as used in `provider` blocks and config keys (e.g., "google")
+    pub name: &'static str,
+
+    /// Prefix of the provider's resource types (e.g., "google_")
+    pub resource_prefix: &'static str,
+
+    /// Prefix of the provider's group names (e.g., "Google" for `GoogleDefaultDeployer`)
+    pub group_prefix: &'static str,
+
+    /// How the identity that groups the provider's blocks is derived
+    pub identity: IdentitySource,
+}
+
+/// The supported providers. AWS keeps unprefixed group names and its
+/// mappings directly under `mappings/`.
+pub const CLOUD_PROVIDERS: &[CloudProvider] = &[
+    CloudProvider {
+        name: "aws",
+        resource_prefix: "aws_",
+        group_prefix: "",
+        identity: IdentitySource::AssumeRole,
+    },
+    CloudProvider {
+        name: "azurerm",
+        resource_prefix: "azurerm_",
+        group_prefix: "Azure",
+        identity: IdentitySource::Attribute("client_id"),
+    },
+    CloudProvider {
+        name: "google",
+        resource_prefix: "google_",
+        group_prefix: "Google",
+        identity: IdentitySource::Attribute("impersonate_service_account"),
+    },
+];
+
+impl CloudProvider {
+    /// Looks up a provider by name (e.g., the label of a `provider` block).
+    pub fn by_name(name: &str) -> Option<&'static CloudProvider> {
+        CLOUD_PROVIDERS
+            .iter()
+            .find(|provider| provider.name == name)
+    }
+
+    /// Looks up the provider of a resource type (e.g., `google_storage_bucket`).
+    pub fn for_type(type_name: &str) -> Option<&'static CloudProvider> {
+        CLOUD_PROVIDERS
+            .iter()
+            .find(|provider| type_name.starts_with(provider.resource_prefix))
+    }
+
+    /// Looks up the provider of a config key (e.g., `google.prod`).
+    pub fn for_config_key(config_key: &str) -> Option<&'static CloudProvider> {
+        config_key.split('.').next().and_then(Self::by_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_types_map_to_their_provider() {
+        assert_eq!(
+            CloudProvider::for_type("aws_s3_bucket").unwrap().name,
+            "aws"
+        );
+        assert_eq!(
+            CloudProvider::for_type("azurerm_resource_group")
+                .unwrap()
+                .name,
+            "azurerm"
+        );
+        assert_eq!(
+            CloudProvider::for_type("google_storage_bucket")
+                .unwrap()
+                .name,
+            "google"
+        );
+        assert!(CloudProvider::for_type("random_id").is_none());
+        assert!(CloudProvider::for_type("googleworkspace_user").is_none());
+    }
+
+    #[test]
+    fn config_keys_map_to_their_provider() {
+        assert_eq!(CloudProvider::for_config_key("aws").unwrap().name, "aws");
+        assert_eq!(
+            CloudProvider::for_config_key("google.prod").unwrap().name,
+            "google"
+        );
+        assert!(CloudProvider::for_config_key("kubernetes").is_none());
+    }
+}
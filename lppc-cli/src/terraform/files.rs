@@ -0,0 +1,65 @@
+//! Terraform and OpenTofu configuration file detection.
+
+use std::path::{Path, PathBuf};
+
+/// Extensions of configuration files that are parsed.
+///
+/// OpenTofu reads `.tofu` files with the same syntax as `.tf` files.
+pub const CONFIG_FILE_EXTENSIONS: &[&str] = &["tf", "tofu"];
+
+/// Returns true if the path has a configuration file extension.
+pub fn is_config_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| CONFIG_FILE_EXTENSIONS.contains(&ext))
+}
+
+/// Removes `.tf` files that are shadowed by a `.tofu` file with the same stem.
+///
+/// OpenTofu ignores `main.tf` if `main.tofu` exists in the same directory,
+/// which lets modules ship OpenTofu-specific variants of individual files.
+pub fn without_shadowed_tf_files(files: Vec<PathBuf>) -> Vec<PathBuf> {
+    let shadowing: Vec<PathBuf> = files
+        .iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "tofu"))
+        .map(|path| path.with_extension("tf"))
+        .collect();
+
+    files
+        .into_iter()
+        .filter(|path| !shadowing.contains(path))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_config_file_accepts_tf_and_tofu() {
+        assert!(is_config_file(Path::new("main.tf")));
+        assert!(is_config_file(Path::new("main.tofu")));
+        assert!(!is_config_file(Path::new("terraform.tfvars")));
+        assert!(!is_config_file(Path::new("main.tf.json")));
+        assert!(!is_config_file(Path::new("tofu")));
+    }
+
+    #[test]
+    fn tofu_file_shadows_tf_file_with_same_stem() {
+        let files = vec![
+            PathBuf::from("/dir/main.tf"),
+            PathBuf::from("/dir/main.tofu"),
+            PathBuf::from("/dir/variables.tf"),
+        ];
+
+        let files = without_shadowed_tf_files(files);
+
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("/dir/main.tofu"),
+                PathBuf::from("/dir/variables.tf")
+            ]
+        );
+    }
+}
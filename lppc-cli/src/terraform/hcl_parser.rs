@@ -6,15 +6,62 @@ use log::{debug, warn};
 use thiserror::Error;
 use walkdir::WalkDir;
 
+use super::cloud::{CLOUD_PROVIDERS, CloudProvider, IdentitySource};
+use super::files::{is_config_file, without_shadowed_tf_files};
 use super::model::{
-    BlockType, ModuleContext, ProviderGroup, ProviderMappings, TerraformBlock, TerraformConfig,
+    BlockType, Instances, ModuleContext, ProviderGroup, ProviderMappings, TerraformBlock,
+    TerraformConfig,
 };
 use super::module_detector::ModulesManifest;
+use super::module_tree::ModuleNode;
 use super::provider::AwsProvider;
+use super::variables::{substitute_variables, tfvars_values, variable_defaults};
+use crate::redact::account_id;
 
 /// Maximum size for .tf files (10 MB) - prevents memory exhaustion from extremely large files.
 const MAX_TF_FILE_SIZE: u64 = 10 * 1024 * 1024;
 
+/// Default maximum number of segments of a recorded attribute path.
+pub const DEFAULT_MAX_ATTRIBUTE_DEPTH: usize = 16;
+
+/// Options that influence how parsed HCL is turned into a `TerraformConfig`.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Treat the account ID segment of role ARNs as a wildcard when grouping
+    /// providers, so `arn:aws:iam::*:role/Deploy` and
+    /// `arn:aws:iam::123456789012:role/Deploy` share one group.
+    pub canonicalize_role_arns: bool,
+
+    /// Skip modules that fail to parse instead of aborting, and record them
+    /// in `TerraformConfig::skipped_modules`. Errors in the root module still abort.
+    pub best_effort: bool,
+
+    /// Name groups whose role ARN contains interpolation after the static
+    /// role name, e.g. `arn:aws:iam::${var.account_id}:role/DeployRole`
+    /// becomes `DeployRoleDeployer` instead of the alias-based name.
+    pub role_name_groups: bool,
+
+    /// Keep only blocks whose provider is configured for this region.
+    /// Blocks of providers without a literal `region` are always kept.
+    pub region: Option<String>,
+
+    /// Maximum number of segments of a recorded attribute path. Attributes
+    /// nested deeper are not recorded, so conditionals keyed on them never fire.
+    pub max_attribute_depth: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            canonicalize_role_arns: false,
+            best_effort: false,
+            role_name_groups: false,
+            region: None,
+            max_attribute_depth: DEFAULT_MAX_ATTRIBUTE_DEPTH,
+        }
+    }
+}
+
 /// Parses HCL files directly without running terraform plan.
 ///
 /// This parser extracts providers, resources, and data sources from .tf files
@@ -29,6 +76,16 @@ impl HclParser {
     /// from module calls are properly resolved so resources in submodules are
     /// assigned to the correct provider group.
     pub fn parse_directory(dir: &Path) -> Result<TerraformConfig, HclParseError> {
+        Self::parse_directory_with_options(dir, &ParseOptions::default())
+    }
+
+    /// Parses all .tf files in a directory using the given options.
+    ///
+    /// See [`HclParser::parse_directory`] for details on module handling.
+    pub fn parse_directory_with_options(
+        dir: &Path,
+        options: &ParseOptions,
+    ) -> Result<TerraformConfig, HclParseError> {
         // Load modules manifest if available (after terraform init)
         let manifest = ModulesManifest::load(dir);
 
@@ -39,8 +96,17 @@ impl HclParser {
 
         // Parse recursively starting from root module
         let root_context = ModuleContext::root();
-        let (all_providers, all_blocks) =
-            Self::extract_blocks_recursive(dir, &root_context, "", manifest.as_ref())?;
+        let mut skipped_modules = Vec::new();
+        let mut module_tree = ModuleNode::default();
+        let (mut all_providers, all_blocks) = Self::extract_blocks_recursive(
+            dir,
+            &root_context,
+            "",
+            manifest.as_ref(),
+            options,
+            &mut skipped_modules,
+            &mut module_tree,
+        )?;
 
         debug!(
             "Parsed {} providers and {} blocks from HCL files",
@@ -48,15 +114,148 @@ impl HclParser {
             all_blocks.len()
         );
 
+        let (all_blocks, unmapped_blocks) = Self::split_unsupported(all_blocks);
+
+        // Settings only the root module determines, from a single pass over its files
+        let root_bodies = Self::parse_root_bodies(dir)?;
+
+        // Substitute known variable values, so e.g. `${var.account_id}` groups by account
+        let variables = Self::variable_values(dir, &root_bodies);
+        for provider in &mut all_providers {
+            if let Some(role_arn) = &mut provider.role_arn {
+                let resolved = substitute_variables(role_arn, &variables);
+                if resolved != *role_arn {
+                    debug!(
+                        "Resolved role_arn of provider {}: {} -> {}",
+                        provider.config_key, role_arn, resolved
+                    );
+                    *role_arn = resolved;
+                }
+            }
+        }
+
         // Group by role_arn and derive names
-        let provider_groups = Self::group_by_role(&all_providers, all_blocks);
+        let provider_groups = Self::group_by_role(&all_providers, all_blocks, options);
+
+        let provider_version_constraints = Self::version_constraints(&root_bodies);
+
+        let unresolved_modules = if manifest.is_none() {
+            Self::unresolved_module_calls(&root_bodies)
+        } else {
+            Vec::new()
+        };
 
         Ok(TerraformConfig {
             provider_groups,
-            unmapped_blocks: Vec::new(), // HCL parsing doesn't produce unmapped blocks
+            unmapped_blocks,
+            provider_version_constraints,
+            skipped_modules,
+            unresolved_modules,
+            module_tree: Some(module_tree),
         })
     }
 
+    /// Parses the root module's .tf files.
+    fn parse_root_bodies(dir: &Path) -> Result<Vec<Body>, HclParseError> {
+        let mut bodies = Vec::new();
+
+        for file_path in Self::collect_tf_files_in_dir(dir)? {
+            // Oversized files were already reported while extracting blocks
+            if std::fs::metadata(&file_path).is_ok_and(|m| m.len() > MAX_TF_FILE_SIZE) {
+                continue;
+            }
+
+            let content = std::fs::read_to_string(&file_path)
+                .map_err(|e| HclParseError::Io(format!("{}: {}", file_path.display(), e)))?;
+
+            let body: Body = hcl::from_str(&content)
+                .map_err(|e| HclParseError::Hcl(format!("{}: {}", file_path.display(), e)))?;
+
+            bodies.push(body);
+        }
+
+        Ok(bodies)
+    }
+
+    /// Collects provider version constraints from the root module's files.
+    ///
+    /// Only the root module is considered, since its `required_providers`
+    /// determine the provider version that is actually installed.
+    fn version_constraints(root_bodies: &[Body]) -> HashMap<String, String> {
+        let constraints: HashMap<String, String> = root_bodies
+            .iter()
+            .flat_map(Self::extract_required_providers)
+            .collect();
+
+        for (provider, constraint) in &constraints {
+            debug!("Provider '{}' version constraint: {}", provider, constraint);
+        }
+
+        constraints
+    }
+
+    /// Collects the values of the root module's input variables: literal
+    /// `variable` defaults, overridden by the automatically loaded `.tfvars`
+    /// files in `dir` (see [`tfvars_values`]).
+    fn variable_values(dir: &Path, root_bodies: &[Body]) -> HashMap<String, String> {
+        let mut values: HashMap<String, String> =
+            root_bodies.iter().flat_map(variable_defaults).collect();
+
+        values.extend(tfvars_values(dir));
+        values
+    }
+
+    /// Extracts provider version constraints from `terraform { required_providers { ... } }`.
+    ///
+    /// Supports both the object syntax and the legacy string syntax:
+    /// ```hcl
+    /// terraform {
+    ///   required_providers {
+    ///     aws    = { source = "hashicorp/aws", version = "~> 5.0" }
+    ///     random = ">= 3.0"
+    ///   }
+    /// }
+    /// ```
+    /// Providers without a version constraint are omitted.
+    pub fn extract_required_providers(body: &Body) -> HashMap<String, String> {
+        let mut constraints = HashMap::new();
+
+        let required_providers = body
+            .blocks()
+            .filter(|b| b.identifier.as_str() == "terraform")
+            .flat_map(|b| b.body().blocks())
+            .filter(|b| b.identifier.as_str() == "required_providers");
+
+        for block in required_providers {
+            for attr in block.body().attributes() {
+                let version = match &attr.expr {
+                    Expression::String(s) => Some(s.clone()),
+                    Expression::Object(obj) => obj.iter().find_map(|(key, value)| {
+                        let is_version = match key {
+                            hcl::expr::ObjectKey::Identifier(ident) => ident.as_str() == "version",
+                            hcl::expr::ObjectKey::Expression(Expression::String(s)) => {
+                                s == "version"
+                            }
+                            _ => false,
+                        };
+
+                        match value {
+                            Expression::String(s) if is_version => Some(s.clone()),
+                            _ => None,
+                        }
+                    }),
+                    _ => None,
+                };
+
+                if let Some(version) = version {
+                    constraints.insert(attr.key.to_string(), version);
+                }
+            }
+        }
+
+        constraints
+    }
+
     /// Logs discovered remote modules for verbose output.
     fn log_discovered_modules(manifest: &ModulesManifest) {
         let remote_modules = manifest.remote_modules();
@@ -72,11 +271,7 @@ impl HclParser {
         );
 
         for module in remote_modules {
-            debug!(
-                "  - {} ({})",
-                module.key,
-                module.source_type.description()
-            );
+            debug!("  - {} ({})", module.key, module.source_type.description());
         }
     }
 
@@ -87,11 +282,20 @@ impl HclParser {
     /// 2. Collects providers, resources, and module calls
     /// 3. For each module call, looks up the module directory in the manifest
     /// 4. Recursively parses each module with its provider context
+    ///
+    /// With `options.best_effort`, a module that fails to parse is skipped
+    /// with a warning and its address is appended to `skipped_modules`.
+    ///
+    /// `node` receives the number of blocks declared in this directory and a
+    /// child node for every module call that was parsed.
     fn extract_blocks_recursive(
         dir: &Path,
         context: &ModuleContext,
         module_key: &str,
         manifest: Option<&ModulesManifest>,
+        options: &ParseOptions,
+        skipped_modules: &mut Vec<String>,
+        node: &mut ModuleNode,
     ) -> Result<(Vec<ParsedProvider>, Vec<TerraformBlock>), HclParseError> {
         let mut all_providers = Vec::new();
         let mut all_blocks = Vec::new();
@@ -102,15 +306,15 @@ impl HclParser {
 
         for file_path in tf_files {
             // Skip oversized files to prevent memory exhaustion
-            if let Ok(metadata) = std::fs::metadata(&file_path) {
-                if metadata.len() > MAX_TF_FILE_SIZE {
-                    warn!(
-                        "Skipping oversized .tf file ({} bytes): {:?}",
-                        metadata.len(),
-                        file_path
-                    );
-                    continue;
-                }
+            if let Ok(metadata) = std::fs::metadata(&file_path)
+                && metadata.len() > MAX_TF_FILE_SIZE
+            {
+                warn!(
+                    "Skipping oversized .tf file ({} bytes): {:?}",
+                    metadata.len(),
+                    file_path
+                );
+                continue;
             }
 
             let content = std::fs::read_to_string(&file_path)
@@ -121,7 +325,7 @@ impl HclParser {
 
             // Extract providers, blocks, and module calls
             let (providers, blocks, calls) =
-                Self::extract_from_body_with_context(&body, context)?;
+                Self::extract_from_body_with_context(&body, context, options)?;
 
             // Only collect providers from root module
             if module_key.is_empty() {
@@ -132,6 +336,8 @@ impl HclParser {
             module_calls.extend(calls);
         }
 
+        node.block_count = all_blocks.len();
+
         // After parsing the directory, recursively parse module directories
         if let Some(manifest) = manifest {
             for call in module_calls {
@@ -142,22 +348,49 @@ impl HclParser {
                     // Verify the module directory exists and is a directory
                     if module_dir.is_dir() {
                         // Check if this is a remote module for enhanced logging
-                        let is_remote = manifest
-                            .find_entry(&child_key)
-                            .map(|e| e.source_type.is_remote())
-                            .unwrap_or(false);
+                        let entry = manifest.find_entry(&child_key);
+                        let is_remote = entry.map(|e| e.source_type.is_remote()).unwrap_or(false);
+
+                        let mut providers: Vec<(String, String)> = call
+                            .provider_mappings
+                            .iter()
+                            .map(|(local, parent)| (local.clone(), parent.clone()))
+                            .collect();
+                        providers.sort();
+                        let mut child_node = ModuleNode {
+                            name: call.name.clone(),
+                            source: entry.map(|e| e.source_type.description()),
+                            providers,
+                            ..ModuleNode::default()
+                        };
 
                         debug!(
                             "Parsing module '{}' at {:?} with context {:?}",
                             child_key, module_dir, child_context.address_prefix
                         );
 
-                        let (_, child_blocks) = Self::extract_blocks_recursive(
+                        let child_result = Self::extract_blocks_recursive(
                             &module_dir,
                             &child_context,
                             &child_key,
                             Some(manifest),
-                        )?;
+                            options,
+                            skipped_modules,
+                            &mut child_node,
+                        );
+
+                        let (_, child_blocks) = match child_result {
+                            Ok(result) => result,
+                            Err(e) if options.best_effort => {
+                                warn!(
+                                    "Skipping module '{}' because it failed to parse: {}",
+                                    child_context.address_prefix, e
+                                );
+                                skipped_modules.push(child_context.address_prefix.clone());
+                                continue;
+                            }
+                            Err(e) => return Err(e),
+                        };
 
                         if is_remote {
                             debug!(
@@ -167,6 +400,7 @@ impl HclParser {
                             );
                         }
 
+                        node.children.push(child_node);
                         all_blocks.extend(child_blocks);
                     } else {
                         warn!(
@@ -186,7 +420,38 @@ impl HclParser {
         Ok((all_providers, all_blocks))
     }
 
-    /// Collects all .tf files directly in a directory (not recursive).
+    /// Returns the sorted names of the root module calls, which cannot be followed
+    /// because no modules manifest exists.
+    ///
+    /// Without `.terraform/modules/modules.json` module directories cannot be located,
+    /// so resources declared inside these modules are not analyzed. This happens when
+    /// `terraform init` did not run or did not write the manifest even though modules
+    /// are declared. The names end up in `TerraformConfig::unresolved_modules`, which
+    /// the run reports as a warning.
+    fn unresolved_module_calls(root_bodies: &[Body]) -> Vec<String> {
+        let mut names: Vec<String> = root_bodies
+            .iter()
+            .flat_map(|body| body.blocks())
+            .filter(|block| block.identifier.as_str() == "module")
+            .filter_map(|block| block.labels.first())
+            .map(|label| label.as_str().to_string())
+            .collect();
+        names.sort();
+
+        if !names.is_empty() {
+            debug!(
+                "No modules manifest (.terraform/modules/modules.json) found for module(s): {}",
+                names.join(", ")
+            );
+        }
+
+        names
+    }
+
+    /// Collects all .tf and .tofu files directly in a directory (not recursive).
+    ///
+    /// Like OpenTofu, a `.tf` file is skipped if a `.tofu` file with the same
+    /// name exists. Files are returned in name order.
     fn collect_tf_files_in_dir(dir: &Path) -> Result<Vec<PathBuf>, HclParseError> {
         let mut files = Vec::new();
 
@@ -198,12 +463,13 @@ impl HclParser {
                 entry.map_err(|e| HclParseError::Io(format!("{}: {}", dir.display(), e)))?;
             let path = entry.path();
 
-            if path.is_file() && path.extension().is_some_and(|ext| ext == "tf") {
+            if path.is_file() && is_config_file(&path) {
                 files.push(path);
             }
         }
 
-        Ok(files)
+        files.sort();
+        Ok(without_shadowed_tf_files(files))
     }
 
     /// Legacy method for parsing without module context.
@@ -238,15 +504,15 @@ impl HclParser {
             }
 
             // Skip oversized files to prevent memory exhaustion
-            if let Ok(metadata) = entry.metadata() {
-                if metadata.len() > MAX_TF_FILE_SIZE {
-                    warn!(
-                        "Skipping oversized .tf file ({} bytes): {:?}",
-                        metadata.len(),
-                        entry.path()
-                    );
-                    continue;
-                }
+            if let Ok(metadata) = entry.metadata()
+                && metadata.len() > MAX_TF_FILE_SIZE
+            {
+                warn!(
+                    "Skipping oversized .tf file ({} bytes): {:?}",
+                    metadata.len(),
+                    entry.path()
+                );
+                continue;
             }
 
             let content = std::fs::read_to_string(entry.path())
@@ -267,22 +533,49 @@ impl HclParser {
             all_blocks.len()
         );
 
+        let (all_blocks, unmapped_blocks) = Self::split_unsupported(all_blocks);
+
         // Group by role_arn and derive names
-        let provider_groups = Self::group_by_role(&all_providers, all_blocks);
+        let provider_groups =
+            Self::group_by_role(&all_providers, all_blocks, &ParseOptions::default());
 
         Ok(TerraformConfig {
             provider_groups,
-            unmapped_blocks: Vec::new(), // HCL parsing doesn't produce unmapped blocks
+            unmapped_blocks,
+            provider_version_constraints: HashMap::new(),
+            skipped_modules: Vec::new(),
+            unresolved_modules: Vec::new(),
+            module_tree: None,
         })
     }
 
+    /// Separates the blocks of unsupported providers (see [`CloudProvider`]),
+    /// which cannot be grouped or mapped, from the others.
+    fn split_unsupported(
+        blocks: Vec<TerraformBlock>,
+    ) -> (Vec<TerraformBlock>, Vec<TerraformBlock>) {
+        let (supported, unsupported): (Vec<_>, Vec<_>) = blocks
+            .into_iter()
+            .partition(|block| CloudProvider::for_type(&block.type_name).is_some());
+
+        for block in &unsupported {
+            debug!(
+                "{} belongs to an unsupported provider",
+                block.display_address()
+            );
+        }
+
+        (supported, unsupported)
+    }
+
     /// Extracts providers and resource/data blocks from an HCL body.
     pub fn extract_from_body(
         body: &Body,
         address_prefix: &str,
     ) -> Result<(Vec<ParsedProvider>, Vec<TerraformBlock>), HclParseError> {
         let context = ModuleContext::root();
-        let (providers, blocks, _) = Self::extract_from_body_with_context(body, &context)?;
+        let (providers, blocks, _) =
+            Self::extract_from_body_with_context(body, &context, &ParseOptions::default())?;
 
         // Apply address prefix if provided (for backwards compatibility)
         let blocks = if address_prefix.is_empty() {
@@ -307,8 +600,8 @@ impl HclParser {
     fn extract_from_body_with_context(
         body: &Body,
         context: &ModuleContext,
-    ) -> Result<(Vec<ParsedProvider>, Vec<TerraformBlock>, Vec<ParsedModuleCall>), HclParseError>
-    {
+        options: &ParseOptions,
+    ) -> Result<ExtractedBody, HclParseError> {
         let mut providers = Vec::new();
         let mut blocks = Vec::new();
         let mut module_calls = Vec::new();
@@ -321,30 +614,47 @@ impl HclParser {
                     }
                 }
                 "resource" => {
-                    if let Some(tf_block) =
-                        Self::parse_resource_block_with_context(block, BlockType::Resource, context)?
-                    {
+                    if let Some(tf_block) = Self::parse_resource_block_with_context(
+                        block,
+                        BlockType::Resource,
+                        context,
+                        options,
+                    )? {
                         blocks.push(tf_block);
                     }
                 }
                 "data" => {
-                    if let Some(tf_block) =
-                        Self::parse_resource_block_with_context(block, BlockType::Data, context)?
-                    {
+                    if let Some(tf_block) = Self::parse_resource_block_with_context(
+                        block,
+                        BlockType::Data,
+                        context,
+                        options,
+                    )? {
                         blocks.push(tf_block);
                     }
                 }
                 "ephemeral" => {
-                    if let Some(tf_block) =
-                        Self::parse_resource_block_with_context(block, BlockType::Ephemeral, context)?
-                    {
+                    if let Some(tf_block) = Self::parse_resource_block_with_context(
+                        block,
+                        BlockType::Ephemeral,
+                        context,
+                        options,
+                    )? {
                         blocks.push(tf_block);
                     }
                 }
                 "action" => {
-                    if let Some(tf_block) =
-                        Self::parse_resource_block_with_context(block, BlockType::Action, context)?
-                    {
+                    if let Some(tf_block) = Self::parse_resource_block_with_context(
+                        block,
+                        BlockType::Action,
+                        context,
+                        options,
+                    )? {
+                        blocks.push(tf_block);
+                    }
+                }
+                "import" => {
+                    if let Some(tf_block) = Self::parse_import_block(block, context) {
                         blocks.push(tf_block);
                     }
                 }
@@ -372,45 +682,185 @@ impl HclParser {
         })
     }
 
-    /// Parses a provider block, extracting alias and role_arn.
+    /// Parses an `import` block into a block of type `BlockType::Import`.
+    ///
+    /// ```hcl
+    /// import {
+    ///   to       = module.storage.aws_s3_bucket.logs
+    ///   id       = "my-log-bucket"
+    ///   provider = aws.shared
+    /// }
+    /// ```
+    ///
+    /// The type and name are taken from the `to` address (after any `module.<name>`
+    /// prefixes). The provider comes from the `provider` attribute and defaults to
+    /// the provider of the type, like for resources. Returns `None` for a `to`
+    /// that is not a resource address.
+    fn parse_import_block(block: &Block, context: &ModuleContext) -> Option<TerraformBlock> {
+        let to = block
+            .body()
+            .attributes()
+            .find(|a| a.key.as_str() == "to")
+            .and_then(|a| Self::expression_to_provider_key(&a.expr))?;
+
+        let segments: Vec<&str> = to.split('.').collect();
+        let mut target = segments.as_slice();
+        while let ["module", _, rest @ ..] = target {
+            target = rest;
+        }
+        let [type_name, name] = target else {
+            return None;
+        };
+
+        let local_provider_key = Self::get_provider_attr(block.body())
+            .unwrap_or_else(|| Self::default_provider_key(type_name).to_string());
+
+        Some(TerraformBlock {
+            block_type: BlockType::Import,
+            type_name: type_name.to_string(),
+            name: name.to_string(),
+            provider_config_key: context.resolve_to_root(&local_provider_key),
+            attribute_values: HashMap::new(),
+            present_attributes: HashSet::new(),
+            address: format!("import.{}", to),
+            managed_policy_arns: Vec::new(),
+            instances: Instances::Single,
+        })
+    }
+
+    /// Returns the provider key a block uses without a `provider` attribute.
+    ///
+    /// This is the name of the type's provider, or the type's first segment
+    /// for unsupported providers (e.g., "random" for `random_id`).
+    fn default_provider_key(type_name: &str) -> &str {
+        match CloudProvider::for_type(type_name) {
+            Some(cloud) => cloud.name,
+            None => type_name.split('_').next().unwrap_or(type_name),
+        }
+    }
+
+    /// Parses a provider block, extracting alias, role_arn, profile, region, and whether
+    /// it overrides service endpoints or sets `default_tags`.
+    ///
+    /// For providers other than AWS, `role_arn` holds the identity named by the
+    /// provider's [`IdentitySource`] (e.g., `impersonate_service_account`).
     fn parse_provider_block(block: &Block) -> Result<Option<ParsedProvider>, HclParseError> {
         // provider "aws" { ... }
         let labels: Vec<&str> = block.labels.iter().map(|l| l.as_str()).collect();
 
-        if labels.first() != Some(&"aws") {
-            return Ok(None); // Only process AWS providers
-        }
+        let Some(cloud) = labels
+            .first()
+            .and_then(|label| CloudProvider::by_name(label))
+        else {
+            return Ok(None); // Only process supported providers
+        };
 
         let alias = Self::get_string_attr(block.body(), "alias");
-        let role_arn = Self::get_assume_role_arn(block.body());
+        let role_arn = match cloud.identity {
+            IdentitySource::AssumeRole => Self::get_assume_role_arn(block.body()),
+            IdentitySource::Attribute(name) => Self::get_expression_as_string(block.body(), name),
+        };
+        let profile = Self::get_string_attr(block.body(), "profile");
+        let region = Self::get_string_attr(block.body(), "region");
+        let has_custom_endpoints = Self::has_endpoints_block(block.body());
+        let has_default_tags = Self::has_default_tags(block.body());
 
         let config_key = match &alias {
-            Some(a) => format!("aws.{}", a),
-            None => "aws".to_string(),
+            Some(a) => format!("{}.{}", cloud.name, a),
+            None => cloud.name.to_string(),
         };
 
         debug!(
-            "Parsed provider: config_key={}, alias={:?}, role_arn={:?}",
-            config_key, alias, role_arn
+            "Parsed provider: config_key={}, alias={:?}, role_arn={:?}, profile={:?}, region={:?}",
+            config_key, alias, role_arn, profile, region
         );
+        if has_custom_endpoints {
+            debug!(
+                "Provider {} overrides service endpoints (e.g., LocalStack)",
+                config_key
+            );
+        }
+        if has_default_tags {
+            debug!(
+                "Provider {} sets default_tags, its resources are tagged",
+                config_key
+            );
+        }
 
         Ok(Some(ParsedProvider {
             config_key,
             alias,
             role_arn,
+            profile,
+            region,
+            has_custom_endpoints,
+            has_default_tags,
         }))
     }
 
-    /// Extracts role_arn from assume_role block.
+    /// Checks whether a provider body contains a non-empty `endpoints` block.
+    fn has_endpoints_block(body: &Body) -> bool {
+        body.blocks().any(|block| {
+            block.identifier.as_str() == "endpoints"
+                && (block.body().attributes().next().is_some()
+                    || block.body().blocks().next().is_some())
+        })
+    }
+
+    /// Checks whether a provider body contains a `default_tags` block with `tags`.
+    fn has_default_tags(body: &Body) -> bool {
+        body.blocks().any(|block| {
+            block.identifier.as_str() == "default_tags"
+                && block
+                    .body()
+                    .attributes()
+                    .any(|attr| attr.key.as_str() == "tags")
+        })
+    }
+
+    /// Extracts role_arn from the assume_role block, or from the
+    /// assume_role_with_web_identity block (OIDC) if there is no assume_role.
     fn get_assume_role_arn(body: &Body) -> Option<String> {
-        for block in body.blocks() {
-            if block.identifier.as_str() == "assume_role" {
-                if let Some(role_arn) = Self::get_expression_as_string(block.body(), "role_arn") {
-                    return Some(role_arn);
+        ["assume_role", "assume_role_with_web_identity"]
+            .iter()
+            .find_map(|identifier| {
+                body.blocks()
+                    .filter(|block| block.identifier.as_str() == *identifier)
+                    .find_map(|block| Self::get_expression_as_string(block.body(), "role_arn"))
+            })
+    }
+
+    /// Extracts the AWS managed policy ARNs attached by a resource body.
+    ///
+    /// Covers `policy_arn` (the `aws_iam_*_policy_attachment` resources) and the
+    /// `managed_policy_arns`/`policy_arns` lists. Only literal strings of the form
+    /// `arn:<partition>:iam::aws:policy/...` are returned; customer managed
+    /// policies and interpolated ARNs are ignored.
+    fn get_managed_policy_arns(body: &Body) -> Vec<String> {
+        let is_managed =
+            |arn: &str| arn.starts_with("arn:") && arn.split(':').nth(4) == Some("aws");
+
+        let mut arns = Vec::new();
+        for attribute in body.attributes() {
+            let values: Vec<&Expression> = match (attribute.key.as_str(), &attribute.expr) {
+                ("policy_arn", expr) => vec![expr],
+                ("managed_policy_arns" | "policy_arns", Expression::Array(items)) => {
+                    items.iter().collect()
+                }
+                _ => continue,
+            };
+
+            for value in values {
+                if let Expression::String(arn) = value
+                    && is_managed(arn)
+                    && arn.contains(":policy/")
+                {
+                    arns.push(arn.clone());
                 }
             }
         }
-        None
+
+        arns
     }
 
     /// Gets a string attribute value, returning None if it contains interpolation.
@@ -443,6 +893,12 @@ impl HclParser {
                 // We serialize them back to their original form
                 format!("{}", t)
             }
+            Expression::Traversal(_) => {
+                // A plain reference like `var.role_arn`, written as its interpolation
+                hcl::format::to_string(expr)
+                    .map(|reference| format!("${{{}}}", reference))
+                    .unwrap_or_else(|_| format!("{:?}", expr))
+            }
             _ => format!("{:?}", expr), // Fallback for complex expressions
         }
     }
@@ -456,6 +912,7 @@ impl HclParser {
         block: &Block,
         block_type: BlockType,
         context: &ModuleContext,
+        options: &ParseOptions,
     ) -> Result<Option<TerraformBlock>, HclParseError> {
         let labels: Vec<&str> = block.labels.iter().map(|l| l.as_str()).collect();
 
@@ -466,20 +923,23 @@ impl HclParser {
         let type_name = labels[0];
         let name = labels[1];
 
-        // Only process AWS resources
-        if !type_name.starts_with("aws_") {
-            return Ok(None);
-        }
-
-        // Get the module-local provider key from explicit `provider` attribute or default to "aws"
-        let local_provider_key =
-            Self::get_provider_attr(block.body()).unwrap_or_else(|| "aws".to_string());
+        // Get the module-local provider key from explicit `provider` attribute or
+        // default to the provider of the type (e.g., "aws" for `aws_s3_bucket`)
+        let local_provider_key = Self::get_provider_attr(block.body())
+            .unwrap_or_else(|| Self::default_provider_key(type_name).to_string());
 
         // Resolve to root provider key using context
         let provider_config_key = context.resolve_to_root(&local_provider_key);
 
         // Collect present attributes
-        let present_attributes = Self::collect_attributes(block.body());
+        let (present_attributes, truncated) =
+            Self::collect_attributes(block.body(), options.max_attribute_depth);
+
+        let managed_policy_arns = if block_type == BlockType::Resource {
+            Self::get_managed_policy_arns(block.body())
+        } else {
+            Vec::new()
+        };
 
         // Build address based on block type
         let type_prefix = match block_type {
@@ -487,6 +947,7 @@ impl HclParser {
             BlockType::Data => format!("data.{}.{}", type_name, name),
             BlockType::Ephemeral => format!("ephemeral.{}.{}", type_name, name),
             BlockType::Action => format!("action.{}.{}", type_name, name),
+            BlockType::Import => format!("import.{}.{}", type_name, name),
         };
 
         // Include module address prefix in the resource address
@@ -496,16 +957,108 @@ impl HclParser {
             format!("{}.{}", context.address_prefix, type_prefix)
         };
 
+        if truncated {
+            debug!(
+                "{} is nested deeper than {} levels; deeper attributes are not recorded \
+                and conditionals keyed on them will not fire",
+                address, options.max_attribute_depth
+            );
+        }
+
         Ok(Some(TerraformBlock {
             block_type,
             type_name: type_name.to_string(),
             name: name.to_string(),
             provider_config_key,
+            attribute_values: Self::get_attribute_values(block.body()),
             present_attributes,
             address,
+            managed_policy_arns,
+            instances: Self::get_instances(block.body()),
         }))
     }
 
+    /// Returns the literal values of the top-level attributes of a block body.
+    ///
+    /// Strings without interpolation, numbers, and bools are recorded as
+    /// strings; other expressions (references, templates, function calls) are
+    /// left out.
+    fn get_attribute_values(body: &Body) -> HashMap<String, String> {
+        body.attributes()
+            .filter_map(|attr| {
+                let value = match &attr.expr {
+                    Expression::String(value) => value.clone(),
+                    Expression::Number(number) => number.to_string(),
+                    Expression::Bool(value) => value.to_string(),
+                    _ => return None,
+                };
+                Some((attr.key.to_string(), value))
+            })
+            .collect()
+    }
+
+    /// Determines the instances declared by the `count` or `for_each`
+    /// meta-argument of a block body.
+    ///
+    /// Only literals are evaluated: a number for `count`, and a list, `toset()`
+    /// of a list, or an object for `for_each`. Anything else (variables,
+    /// locals, function results) yields `Instances::Unknown`.
+    fn get_instances(body: &Body) -> Instances {
+        let meta_argument = |name: &str| {
+            body.attributes()
+                .find(|attr| attr.key.as_str() == name)
+                .map(|attr| &attr.expr)
+        };
+
+        if let Some(count) = meta_argument("count") {
+            return match count {
+                Expression::Number(number) => number
+                    .as_u64()
+                    .map_or(Instances::Unknown, |count| Instances::Count(count as usize)),
+                _ => Instances::Unknown,
+            };
+        }
+
+        match meta_argument("for_each") {
+            Some(for_each) => {
+                Self::literal_for_each_keys(for_each).map_or(Instances::Unknown, Instances::ForEach)
+            }
+            None => Instances::Single,
+        }
+    }
+
+    /// Returns the instance keys of a literal `for_each` expression, sorted
+    /// and deduplicated like a Terraform set.
+    fn literal_for_each_keys(expr: &Expression) -> Option<Vec<String>> {
+        let mut keys: Vec<String> = match expr {
+            Expression::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    Expression::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect::<Option<_>>()?,
+            Expression::Object(object) => object
+                .keys()
+                .map(|key| match key {
+                    hcl::expr::ObjectKey::Identifier(ident) => Some(ident.to_string()),
+                    hcl::expr::ObjectKey::Expression(Expression::String(s)) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect::<Option<_>>()?,
+            Expression::FuncCall(call)
+                if call.name.name.as_str() == "toset" && call.args.len() == 1 =>
+            {
+                return Self::literal_for_each_keys(&call.args[0]);
+            }
+            _ => return None,
+        };
+
+        keys.sort();
+        keys.dedup();
+        Some(keys)
+    }
+
     /// Gets the provider attribute from a block body.
     /// Handles both string literals (e.g., "aws.dns") and references (e.g., aws.dns).
     fn get_provider_attr(body: &Body) -> Option<String> {
@@ -606,28 +1159,39 @@ impl HclParser {
     }
 
     /// Collects attribute names from a block body.
-    fn collect_attributes(body: &Body) -> HashSet<Vec<String>> {
+    ///
+    /// Paths with more than `max_depth` segments are not recorded. The returned
+    /// flag tells whether the body was nested deeper than that.
+    fn collect_attributes(body: &Body, max_depth: usize) -> (HashSet<Vec<String>>, bool) {
         let mut paths = HashSet::new();
-        Self::collect_attrs_recursive(body, &mut Vec::new(), &mut paths);
-        paths
+        let mut truncated = false;
+        Self::collect_attrs_recursive(body, &[], max_depth, &mut paths, &mut truncated);
+        (paths, truncated)
     }
 
     fn collect_attrs_recursive(
         body: &Body,
-        current_path: &mut Vec<String>,
+        current_path: &[String],
+        max_depth: usize,
         paths: &mut HashSet<Vec<String>>,
+        truncated: &mut bool,
     ) {
+        if current_path.len() >= max_depth {
+            *truncated |= body.attributes().next().is_some() || body.blocks().next().is_some();
+            return;
+        }
+
         for attr in body.attributes() {
-            let mut path = current_path.clone();
+            let mut path = current_path.to_vec();
             path.push(attr.key.to_string());
             paths.insert(path);
         }
 
         for block in body.blocks() {
-            let mut path = current_path.clone();
+            let mut path = current_path.to_vec();
             path.push(block.identifier.to_string());
             paths.insert(path.clone());
-            Self::collect_attrs_recursive(block.body(), &mut path, paths);
+            Self::collect_attrs_recursive(block.body(), &path, max_depth, paths, truncated);
         }
     }
 
@@ -635,54 +1199,131 @@ impl HclParser {
     ///
     /// The naming strategy is:
     /// - Group providers by their `role_arn` string **as-is** (exact string match)
+    ///   and their `profile`, so providers using different profiles stay apart
     /// - For each group:
-    ///   - If any provider has no alias (default provider) -> "DefaultDeployer"
+    ///   - If any provider has no alias (default provider) -> "DefaultDeployer",
+    ///     or "{Profile}Deployer" if the group has a profile but no role
     ///   - Otherwise -> "{AlphabeticallyFirstAlias}Deployer"
+    ///
+    /// Providers other than AWS are grouped separately and their group names
+    /// carry the provider's prefix (e.g., "GoogleDefaultDeployer").
+    ///
+    /// With `canonicalize_role_arns`, providers are grouped by their canonical ARN
+    /// (account ID replaced by `*`) and the group keeps the most specific ARN.
+    ///
+    /// With `role_name_groups`, groups whose ARN contains interpolation are named
+    /// after the static role name instead (see [`HclParser::role_name_from_arn`]),
+    /// unless that name is shared by several groups.
     fn group_by_role(
         providers: &[ParsedProvider],
         blocks: Vec<TerraformBlock>,
+        options: &ParseOptions,
     ) -> HashMap<String, ProviderGroup> {
-        // Build role_arn -> providers map
-        let mut role_to_providers: HashMap<Option<String>, Vec<&ParsedProvider>> = HashMap::new();
+        let grouping_key = |provider: &ParsedProvider| -> RoleKey {
+            let role = match &provider.role_arn {
+                Some(arn) if options.canonicalize_role_arns => {
+                    Some(Self::canonicalize_role_arn(arn))
+                }
+                _ => provider.role_arn.clone(),
+            };
+            (
+                Self::cloud_of_key(&provider.config_key),
+                role,
+                provider.profile.clone(),
+            )
+        };
+
+        // Build grouping key -> providers map
+        let mut role_to_providers: HashMap<RoleKey, Vec<&ParsedProvider>> = HashMap::new();
         for provider in providers {
             role_to_providers
-                .entry(provider.role_arn.clone())
+                .entry(grouping_key(provider))
                 .or_default()
                 .push(provider);
         }
 
-        // Build config_key -> role_arn map
-        let mut key_to_role: HashMap<String, Option<String>> = HashMap::new();
+        // Build config_key -> grouping key map
+        let mut key_to_role: HashMap<String, RoleKey> = HashMap::new();
         for provider in providers {
-            key_to_role.insert(provider.config_key.clone(), provider.role_arn.clone());
+            key_to_role.insert(provider.config_key.clone(), grouping_key(provider));
         }
 
-        // Derive output names for each role group
-        let mut role_to_name: HashMap<Option<String>, String> = HashMap::new();
-        for (role_arn, providers) in &role_to_providers {
+        // Derive output names and the ARN reported for each role group
+        let mut role_to_name: HashMap<RoleKey, String> = HashMap::new();
+        let mut role_to_arn: HashMap<RoleKey, Option<String>> = HashMap::new();
+        let mut role_to_endpoints: HashMap<RoleKey, bool> = HashMap::new();
+        for (role_key, providers) in &role_to_providers {
             let name = Self::derive_group_name(providers);
-            role_to_name.insert(role_arn.clone(), name);
+            role_to_name.insert(role_key.clone(), name);
+            role_to_arn.insert(role_key.clone(), Self::most_specific_role_arn(providers));
+            role_to_endpoints.insert(
+                role_key.clone(),
+                providers.iter().any(|p| p.has_custom_endpoints),
+            );
         }
 
+        if options.role_name_groups {
+            Self::apply_role_name_groups(&mut role_to_name);
+        }
+
+        // Build config_key -> region map for --region
+        let key_to_region: HashMap<&str, &str> = providers
+            .iter()
+            .filter_map(|p| Some((p.config_key.as_str(), p.region.as_deref()?)))
+            .collect();
+
+        // Providers with default_tags tag every resource, even without `tags`
+        let keys_with_default_tags: HashSet<&str> = providers
+            .iter()
+            .filter(|p| p.has_default_tags)
+            .map(|p| p.config_key.as_str())
+            .collect();
+
         // Group blocks by their provider's role
         let mut groups: HashMap<String, ProviderGroup> = HashMap::new();
 
-        for block in blocks {
-            let role_arn = key_to_role
+        for mut block in blocks {
+            if let Some(selected) = &options.region
+                && let Some(region) = key_to_region.get(block.provider_config_key.as_str())
+                && region != selected
+            {
+                debug!(
+                    "Skipping {} (provider {} is in region {})",
+                    block.display_address(),
+                    block.provider_config_key,
+                    region
+                );
+                continue;
+            }
+
+            if block.block_type == BlockType::Resource
+                && keys_with_default_tags.contains(block.provider_config_key.as_str())
+            {
+                block.present_attributes.insert(vec!["tags".to_string()]);
+            }
+
+            // Blocks whose provider is not configured use the default group
+            // of their type's provider
+            let cloud = CloudProvider::for_type(&block.type_name).unwrap_or(&CLOUD_PROVIDERS[0]);
+            let role_key = key_to_role
                 .get(&block.provider_config_key)
                 .cloned()
-                .unwrap_or(None);
+                .unwrap_or((cloud, None, None));
 
             let output_name = role_to_name
-                .get(&role_arn)
+                .get(&role_key)
                 .cloned()
-                .unwrap_or_else(|| "DefaultDeployer".to_string());
+                .unwrap_or_else(|| format!("{}DefaultDeployer", cloud.group_prefix));
+
+            let role_arn = role_to_arn.get(&role_key).cloned().unwrap_or(None);
+            let has_custom_endpoints = role_to_endpoints.get(&role_key).copied().unwrap_or(false);
 
             let group = groups
                 .entry(output_name.clone())
                 .or_insert_with(|| ProviderGroup {
                     output_name: output_name.clone(),
-                    role_arn: role_arn.clone(),
+                    role_arn,
+                    has_custom_endpoints,
                     blocks: Vec::new(),
                 });
             group.blocks.push(block);
@@ -701,23 +1342,156 @@ impl HclParser {
 
         // If no providers defined but we have blocks, they go to DefaultDeployer
         if providers.is_empty() && !groups.is_empty() {
-            warn!("No AWS providers defined, using DefaultDeployer for all blocks");
+            warn!("No providers defined, using the default group of each provider for all blocks");
         }
 
         groups
     }
 
+    /// Renames groups with an interpolated role ARN after their static role name.
+    ///
+    /// A role-derived name is only used if no other group would get the same
+    /// name, e.g. the same role name in two interpolated accounts; those groups
+    /// keep their alias-based names.
+    fn apply_role_name_groups(role_to_name: &mut HashMap<RoleKey, String>) {
+        let role_names: HashMap<RoleKey, String> = role_to_name
+            .keys()
+            .filter_map(|role_key| {
+                let arn = role_key.1.as_deref()?;
+                let name = Self::role_name_from_arn(arn)?;
+                Some((role_key.clone(), name))
+            })
+            .collect();
+
+        for (role_key, role_name) in &role_names {
+            let is_unique = role_to_name
+                .iter()
+                .filter(|(other_key, other_name)| {
+                    *other_key != role_key
+                        && role_names.get(*other_key).unwrap_or(*other_name) == role_name
+                })
+                .count()
+                == 0;
+
+            if is_unique {
+                debug!(
+                    "Naming group with role_arn {:?} {} after its role name",
+                    role_key, role_name
+                );
+                role_to_name.insert(role_key.clone(), role_name.clone());
+            } else {
+                warn!(
+                    "Role name {} is shared by several groups, keeping alias-based name for role_arn {:?}",
+                    role_name, role_key
+                );
+            }
+        }
+    }
+
+    /// Derives a group name from the static role name of an interpolated role ARN.
+    ///
+    /// `arn:aws:iam::${var.account_id}:role/path/deploy-role` becomes
+    /// `DeployRoleDeployer`. Returns `None` if the ARN contains no interpolation,
+    /// is not a role ARN, or the role name itself is interpolated.
+    pub fn role_name_from_arn(arn: &str) -> Option<String> {
+        if !arn.contains("${") {
+            return None;
+        }
+
+        let resource = arn.splitn(6, ':').nth(5)?;
+        let role_path = resource.strip_prefix("role/")?;
+        let role_name = role_path.rsplit('/').next()?;
+
+        if role_name.is_empty() || role_name.contains("${") {
+            return None;
+        }
+
+        // Keep only characters that are safe in output filenames
+        let sanitized: String = role_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        let pascal_name = AwsProvider::to_pascal_case(&sanitized);
+        if pascal_name.is_empty() {
+            return None;
+        }
+
+        Some(Self::with_deployer_suffix(&pascal_name))
+    }
+
+    /// Appends "Deployer" unless the name already ends with it (case-insensitive).
+    fn with_deployer_suffix(pascal_name: &str) -> String {
+        if pascal_name.to_lowercase().ends_with("deployer") {
+            let prefix_len = pascal_name.len() - 8;
+            format!("{}Deployer", &pascal_name[..prefix_len])
+        } else {
+            format!("{}Deployer", pascal_name)
+        }
+    }
+
+    /// Replaces the account ID segment of a role ARN with `*`.
+    ///
+    /// `arn:aws:iam::123456789012:role/Deploy` becomes `arn:aws:iam::*:role/Deploy`.
+    /// Strings that are not ARNs are returned unchanged.
+    pub fn canonicalize_role_arn(arn: &str) -> String {
+        let parts: Vec<&str> = arn.splitn(6, ':').collect();
+
+        if parts.len() != 6 || parts[0] != "arn" {
+            return arn.to_string();
+        }
+
+        format!(
+            "{}:{}:{}:{}:*:{}",
+            parts[0], parts[1], parts[2], parts[3], parts[5]
+        )
+    }
+
+    /// Picks the role ARN reported for a group of providers.
+    ///
+    /// Prefers ARNs with a concrete 12-digit account ID over wildcards and
+    /// interpolations; ties are broken alphabetically for deterministic output.
+    fn most_specific_role_arn(providers: &[&ParsedProvider]) -> Option<String> {
+        let mut arns: Vec<&String> = providers
+            .iter()
+            .filter_map(|p| p.role_arn.as_ref())
+            .collect();
+        arns.sort();
+
+        arns.iter()
+            .find(|arn| account_id(arn).is_some())
+            .or_else(|| arns.first())
+            .map(|arn| arn.to_string())
+    }
+
     /// Derives the output name for a group of providers sharing the same role.
     ///
     /// Rules:
-    /// 1. If any provider in the group has no alias (is default) -> "DefaultDeployer"
+    /// 1. If any provider in the group has no alias (is default):
+    ///    - "{Profile}Deployer" if the group has a profile but no role
+    ///    - "DefaultDeployer" otherwise
     /// 2. Otherwise -> "{AlphabeticallyFirstAlias}Deployer" (converted to PascalCase)
     pub fn derive_group_name(providers: &[&ParsedProvider]) -> String {
+        let prefix = providers
+            .first()
+            .map_or("", |p| Self::cloud_of_key(&p.config_key).group_prefix);
         let aliases: Vec<Option<&str>> = providers.iter().map(|p| p.alias.as_deref()).collect();
 
-        // If any provider has no alias (is the default), use "Default"
+        // If any provider has no alias (is the default), use the profile or "Default"
         if aliases.iter().any(|a| a.is_none()) {
-            return "DefaultDeployer".to_string();
+            // Providers of a group share the profile, and the role if it is set
+            return match providers.first() {
+                Some(&ParsedProvider {
+                    role_arn: None,
+                    profile: Some(profile),
+                    ..
+                }) if !profile.is_empty() => Self::with_deployer_suffix(&format!(
+                    "{}{}",
+                    prefix,
+                    AwsProvider::to_pascal_case(profile)
+                )),
+                _ => format!("{}DefaultDeployer", prefix),
+            };
         }
 
         // Otherwise, use alphabetically first alias converted to PascalCase
@@ -725,21 +1499,34 @@ impl HclParser {
         sorted_aliases.sort();
 
         match sorted_aliases.first() {
-            Some(alias) => {
-                let pascal_alias = AwsProvider::to_pascal_case(alias);
-                // Case-insensitive check for "deployer" suffix
-                if pascal_alias.to_lowercase().ends_with("deployer") {
-                    let prefix_len = pascal_alias.len() - 8;
-                    format!("{}Deployer", &pascal_alias[..prefix_len])
-                } else {
-                    format!("{}Deployer", pascal_alias)
-                }
-            }
-            None => "DefaultDeployer".to_string(),
+            Some(alias) => Self::with_deployer_suffix(&format!(
+                "{}{}",
+                prefix,
+                AwsProvider::to_pascal_case(alias)
+            )),
+            None => format!("{}DefaultDeployer", prefix),
         }
     }
+
+    /// Returns the provider of a parsed provider's config key.
+    ///
+    /// Parsed providers always belong to a supported provider; AWS is
+    /// assumed for keys that do not.
+    fn cloud_of_key(config_key: &str) -> &'static CloudProvider {
+        CloudProvider::for_config_key(config_key).unwrap_or(&CLOUD_PROVIDERS[0])
+    }
 }
 
+/// Grouping key of providers: their provider, (canonical) role ARN, and profile.
+type RoleKey = (&'static CloudProvider, Option<String>, Option<String>);
+
+/// Providers, resource/data blocks, and module calls extracted from an HCL body.
+type ExtractedBody = (
+    Vec<ParsedProvider>,
+    Vec<TerraformBlock>,
+    Vec<ParsedModuleCall>,
+);
+
 /// Parsed provider information (intermediate representation).
 #[derive(Debug)]
 pub struct ParsedProvider {
@@ -751,6 +1538,20 @@ pub struct ParsedProvider {
 
     /// Role ARN from assume_role block (may contain interpolation)
     pub role_arn: Option<String>,
+
+    /// Shared credentials profile as a literal string (None if unset or interpolated)
+    pub profile: Option<String>,
+
+    /// Region as a literal string (None if unset or interpolated)
+    pub region: Option<String>,
+
+    /// Whether the provider overrides service endpoints via an `endpoints`
+    /// block (e.g., pointing at LocalStack)
+    pub has_custom_endpoints: bool,
+
+    /// Whether the provider sets `default_tags`, which tags every resource
+    /// it manages
+    pub has_default_tags: bool,
 }
 
 /// Parsed module call information.
@@ -824,27 +1625,319 @@ mod tests {
     }
 
     #[test]
-    fn parse_provider_without_assume_role() {
+    fn parse_provider_with_web_identity_forms_its_own_group() {
         let hcl = r#"
             provider "aws" {
               region = "us-east-1"
             }
+
+            provider "aws" {
+              alias = "ci"
+              assume_role_with_web_identity {
+                role_arn                = "arn:aws:iam::123456789012:role/GitHubActions"
+                web_identity_token_file = "/tmp/web-identity-token"
+              }
+            }
+
+            provider "aws" {
+              alias = "both"
+              assume_role_with_web_identity {
+                role_arn = "arn:aws:iam::123456789012:role/GitHubActions"
+              }
+              assume_role {
+                role_arn = "arn:aws:iam::123456789012:role/Deploy"
+              }
+            }
         "#;
 
         let body: Body = hcl::from_str(hcl).unwrap();
         let (providers, _) = HclParser::extract_from_body(&body, "").unwrap();
 
-        assert_eq!(providers.len(), 1);
-        assert_eq!(providers[0].alias, None);
-        assert_eq!(providers[0].role_arn, None);
-        assert_eq!(providers[0].config_key, "aws");
+        assert_eq!(
+            providers[1].role_arn.as_deref(),
+            Some("arn:aws:iam::123456789012:role/GitHubActions")
+        );
+        // assume_role is preferred over assume_role_with_web_identity
+        assert_eq!(
+            providers[2].role_arn.as_deref(),
+            Some("arn:aws:iam::123456789012:role/Deploy")
+        );
+
+        let blocks = vec![
+            create_block_for_provider("default", "aws"),
+            create_block_for_provider("ci", "aws.ci"),
+        ];
+        let groups = HclParser::group_by_role(&providers, blocks, &ParseOptions::default());
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups["CiDeployer"].blocks[0].name, "ci");
+        assert_eq!(groups["DefaultDeployer"].blocks[0].name, "default");
     }
 
     #[test]
-    fn ignores_non_aws_providers() {
+    fn parse_provider_without_assume_role() {
         let hcl = r#"
-            provider "google" {
-              project = "my-project"
+            provider "aws" {
+              region = "us-east-1"
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (providers, _) = HclParser::extract_from_body(&body, "").unwrap();
+
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].alias, None);
+        assert_eq!(providers[0].role_arn, None);
+        assert_eq!(providers[0].config_key, "aws");
+        assert!(!providers[0].has_custom_endpoints);
+    }
+
+    #[test]
+    fn parse_provider_with_endpoints_block() {
+        let hcl = r#"
+            provider "aws" {
+              alias                       = "local"
+              skip_credentials_validation = true
+
+              endpoints {
+                s3  = "http://localhost:4566"
+                sts = "http://localhost:4566"
+              }
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (providers, _) = HclParser::extract_from_body(&body, "").unwrap();
+
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].config_key, "aws.local");
+        assert!(providers[0].has_custom_endpoints);
+    }
+
+    #[test]
+    fn parse_provider_with_default_tags() {
+        let hcl = r#"
+            provider "aws" {
+              default_tags {
+                tags = {
+                  Team = "platform"
+                }
+              }
+            }
+
+            provider "aws" {
+              alias = "untagged"
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (providers, _) = HclParser::extract_from_body(&body, "").unwrap();
+
+        assert!(providers[0].has_default_tags);
+        assert!(!providers[1].has_default_tags);
+    }
+
+    #[test]
+    fn default_tags_fire_tagging_conditional_of_untagged_resource() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+                provider "aws" {
+                  default_tags {
+                    tags = {
+                      Team = "platform"
+                    }
+                  }
+                }
+
+                provider "aws" {
+                  alias = "untagged"
+                  assume_role {
+                    role_arn = "arn:aws:iam::123456789012:role/Untagged"
+                  }
+                }
+
+                resource "aws_s3_bucket" "tagged_by_default" {}
+
+                data "aws_s3_bucket" "existing" {
+                  bucket = "existing"
+                }
+
+                resource "aws_s3_bucket" "untagged" {
+                  provider = aws.untagged
+                }
+            "#,
+        )
+        .unwrap();
+        let mappings = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(mappings.path().join("mappings/resource")).unwrap();
+        std::fs::write(
+            mappings.path().join("mappings/resource/aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket\nconditional:\n  tags:\n    - s3:PutBucketTagging",
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+        let loader = crate::mapping::MappingLoader::new(mappings.path().to_path_buf());
+        let result = crate::mapping::PermissionMatcher::new(&loader)
+            .resolve(&config)
+            .unwrap();
+
+        assert!(
+            result.groups["DefaultDeployer"]
+                .allow
+                .contains("s3:PutBucketTagging")
+        );
+        assert!(
+            !result.groups["UntaggedDeployer"]
+                .allow
+                .contains("s3:PutBucketTagging")
+        );
+        // Data sources are read, not tagged
+        let data = config.provider_groups["DefaultDeployer"]
+            .blocks
+            .iter()
+            .find(|block| block.block_type == BlockType::Data)
+            .unwrap();
+        assert!(!data.present_attributes.contains(&vec!["tags".to_string()]));
+    }
+
+    #[test]
+    fn parse_provider_with_region() {
+        let hcl = r#"
+            provider "aws" {
+              region = "eu-central-1"
+            }
+
+            provider "aws" {
+              alias  = "dynamic"
+              region = var.region
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (providers, _) = HclParser::extract_from_body(&body, "").unwrap();
+
+        assert_eq!(providers[0].region.as_deref(), Some("eu-central-1"));
+        assert_eq!(providers[1].region, None);
+    }
+
+    #[test]
+    fn group_by_role_keeps_providers_with_different_profiles_apart() {
+        let hcl = r#"
+            provider "aws" {
+              profile = "shared-services"
+            }
+
+            provider "aws" {
+              alias   = "dev"
+              profile = "dev-account"
+            }
+
+            provider "aws" {
+              alias   = "dns"
+              profile = var.profile
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (providers, _) = HclParser::extract_from_body(&body, "").unwrap();
+        assert_eq!(providers[0].profile.as_deref(), Some("shared-services"));
+        assert_eq!(providers[2].profile, None);
+
+        let blocks = vec![
+            create_block_for_provider("shared", "aws"),
+            create_block_for_provider("dev", "aws.dev"),
+            create_block_for_provider("dns", "aws.dns"),
+        ];
+        let groups = HclParser::group_by_role(&providers, blocks, &ParseOptions::default());
+
+        let mut names: Vec<&str> = groups.keys().map(String::as_str).collect();
+        names.sort();
+        // Without an alias and a role, the group is named after the profile
+        assert_eq!(
+            names,
+            vec!["DevDeployer", "DnsDeployer", "SharedServicesDeployer"]
+        );
+        assert_eq!(groups["SharedServicesDeployer"].blocks[0].name, "shared");
+    }
+
+    #[test]
+    fn group_by_role_with_region_keeps_only_selected_region() {
+        let provider =
+            |config_key: &str, alias: Option<&str>, region: Option<&str>| ParsedProvider {
+                config_key: config_key.to_string(),
+                alias: alias.map(str::to_string),
+                role_arn: None,
+                profile: None,
+                region: region.map(str::to_string),
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            };
+        let providers = vec![
+            provider("aws", None, None),
+            provider("aws.frankfurt", Some("frankfurt"), Some("eu-central-1")),
+            provider("aws.virginia", Some("virginia"), Some("us-east-1")),
+        ];
+        let blocks = vec![
+            create_block_for_provider("default", "aws"),
+            create_block_for_provider("frankfurt", "aws.frankfurt"),
+            create_block_for_provider("virginia", "aws.virginia"),
+        ];
+        let options = ParseOptions {
+            region: Some("eu-central-1".to_string()),
+            ..ParseOptions::default()
+        };
+
+        let groups = HclParser::group_by_role(&providers, blocks, &options);
+
+        let mut names: Vec<&str> = groups
+            .values()
+            .flat_map(|group| group.blocks.iter().map(|block| block.name.as_str()))
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["default", "frankfurt"]);
+    }
+
+    #[test]
+    fn group_with_custom_endpoints_provider_is_flagged() {
+        let providers = vec![
+            ParsedProvider {
+                config_key: "aws".to_string(),
+                alias: None,
+                role_arn: None,
+                profile: None,
+                region: None,
+                has_custom_endpoints: true,
+                has_default_tags: false,
+            },
+            ParsedProvider {
+                config_key: "aws.dns".to_string(),
+                alias: Some("dns".to_string()),
+                role_arn: Some("arn:aws:iam::123456789012:role/Dns".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+        ];
+        let blocks = vec![
+            create_block_for_provider("one", "aws"),
+            create_block_for_provider("two", "aws.dns"),
+        ];
+
+        let groups = HclParser::group_by_role(&providers, blocks, &ParseOptions::default());
+
+        assert!(groups["DefaultDeployer"].has_custom_endpoints);
+        assert!(!groups["DnsDeployer"].has_custom_endpoints);
+    }
+
+    #[test]
+    fn ignores_unsupported_providers() {
+        let hcl = r#"
+            provider "kubernetes" {
+              config_path = "~/.kube/config"
             }
         "#;
 
@@ -854,12 +1947,46 @@ mod tests {
         assert!(providers.is_empty());
     }
 
+    #[test]
+    fn parses_identity_of_google_and_azurerm_providers() {
+        let hcl = r#"
+            provider "google" {
+              alias                       = "prod"
+              impersonate_service_account = "deployer@my-project.iam.gserviceaccount.com"
+            }
+
+            provider "azurerm" {
+              client_id = "00000000-0000-0000-0000-000000000000"
+              features {}
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (providers, _) = HclParser::extract_from_body(&body, "").unwrap();
+
+        assert_eq!(providers.len(), 2);
+        assert_eq!(providers[0].config_key, "google.prod");
+        assert_eq!(
+            providers[0].role_arn.as_deref(),
+            Some("deployer@my-project.iam.gserviceaccount.com")
+        );
+        assert_eq!(providers[1].config_key, "azurerm");
+        assert_eq!(
+            providers[1].role_arn.as_deref(),
+            Some("00000000-0000-0000-0000-000000000000")
+        );
+    }
+
     #[test]
     fn derive_name_single_alias() {
-        let providers = vec![ParsedProvider {
+        let providers = [ParsedProvider {
             config_key: "aws.dns".to_string(),
             alias: Some("DnsAccount".to_string()),
             role_arn: Some("arn:aws:iam::123:role/Role".to_string()),
+            profile: None,
+            region: None,
+            has_custom_endpoints: false,
+            has_default_tags: false,
         }];
 
         let refs: Vec<&ParsedProvider> = providers.iter().collect();
@@ -869,16 +1996,24 @@ mod tests {
 
     #[test]
     fn derive_name_with_default_provider() {
-        let providers = vec![
+        let providers = [
             ParsedProvider {
                 config_key: "aws".to_string(),
                 alias: None,
                 role_arn: Some("same_arn".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
             },
             ParsedProvider {
                 config_key: "aws.west".to_string(),
                 alias: Some("west".to_string()),
                 role_arn: Some("same_arn".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
             },
         ];
 
@@ -889,16 +2024,24 @@ mod tests {
 
     #[test]
     fn derive_name_multiple_aliases_alphabetical() {
-        let providers = vec![
+        let providers = [
             ParsedProvider {
                 config_key: "aws.west".to_string(),
                 alias: Some("west".to_string()),
                 role_arn: Some("same_arn".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
             },
             ParsedProvider {
                 config_key: "aws.east".to_string(),
                 alias: Some("east".to_string()),
                 role_arn: Some("same_arn".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
             },
         ];
 
@@ -943,6 +2086,91 @@ mod tests {
                 .contains(&vec!["tags".to_string()])
         );
         assert_eq!(blocks[0].address, "aws_s3_bucket.main");
+        assert_eq!(blocks[0].instances, Instances::Single);
+    }
+
+    #[test]
+    fn parse_resource_block_records_literal_count_and_for_each() {
+        let hcl = r#"
+            resource "aws_s3_bucket" "counted" {
+              count = 3
+            }
+
+            resource "aws_s3_bucket" "set" {
+              for_each = toset(["logs", "assets", "logs"])
+            }
+
+            resource "aws_s3_bucket" "map" {
+              for_each = {
+                primary   = "eu-central-1"
+                "replica" = "eu-west-1"
+              }
+            }
+
+            resource "aws_s3_bucket" "dynamic" {
+              count = var.enabled ? 1 : 0
+            }
+
+            data "aws_s3_bucket" "from_variable" {
+              for_each = var.buckets
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (_, blocks) = HclParser::extract_from_body(&body, "").unwrap();
+
+        let instances: Vec<(&str, &Instances)> = blocks
+            .iter()
+            .map(|b| (b.address.as_str(), &b.instances))
+            .collect();
+        assert_eq!(
+            instances,
+            vec![
+                ("aws_s3_bucket.counted", &Instances::Count(3)),
+                (
+                    "aws_s3_bucket.set",
+                    &Instances::ForEach(vec!["assets".to_string(), "logs".to_string()])
+                ),
+                (
+                    "aws_s3_bucket.map",
+                    &Instances::ForEach(vec!["primary".to_string(), "replica".to_string()])
+                ),
+                ("aws_s3_bucket.dynamic", &Instances::Unknown),
+                ("data.aws_s3_bucket.from_variable", &Instances::Unknown),
+            ]
+        );
+        assert_eq!(blocks[0].display_address(), "aws_s3_bucket.counted[0..3]");
+    }
+
+    #[test]
+    fn parse_records_literal_attribute_values() {
+        let hcl = r#"
+            resource "aws_s3_bucket" "logs" {
+              bucket        = "my-test-bucket"
+              force_destroy = true
+              object_lock   = var.lock
+              acl           = "private-${var.env}"
+            }
+
+            resource "aws_sqs_queue" "jobs" {
+              delay_seconds = 90
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (_, blocks) = HclParser::extract_from_body(&body, "").unwrap();
+
+        assert_eq!(
+            blocks[0].attribute_values,
+            HashMap::from([
+                ("bucket".to_string(), "my-test-bucket".to_string()),
+                ("force_destroy".to_string(), "true".to_string()),
+            ])
+        );
+        assert_eq!(
+            blocks[1].attribute_values,
+            HashMap::from([("delay_seconds".to_string(), "90".to_string())])
+        );
     }
 
     #[test]
@@ -979,96 +2207,285 @@ mod tests {
     }
 
     #[test]
-    fn ignores_non_aws_resources() {
+    fn parse_data_source_with_explicit_provider() {
         let hcl = r#"
-            resource "google_storage_bucket" "main" {
-              name = "my-bucket"
+            data "aws_ami" "x" {
+              provider    = aws.shared
+              most_recent = true
             }
         "#;
 
         let body: Body = hcl::from_str(hcl).unwrap();
         let (_, blocks) = HclParser::extract_from_body(&body, "").unwrap();
 
-        assert!(blocks.is_empty());
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].block_type, BlockType::Data);
+        assert_eq!(blocks[0].provider_config_key, "aws.shared");
     }
 
     #[test]
-    fn groups_providers_by_role_arn_string() {
-        let providers = vec![
-            ParsedProvider {
-                config_key: "aws".to_string(),
-                alias: None,
-                role_arn: Some("arn:aws:iam::${var.account_id}:role/MyRole".to_string()),
-            },
-            ParsedProvider {
-                config_key: "aws.global".to_string(),
-                alias: Some("global".to_string()),
-                role_arn: Some("arn:aws:iam::${var.account_id}:role/MyRole".to_string()),
-            },
-        ];
+    fn data_source_in_module_resolves_provider_to_root() {
+        let hcl = r#"
+            data "aws_ami" "x" {
+              provider = aws.local
+            }
+        "#;
 
-        let blocks = vec![
-            TerraformBlock {
-                block_type: BlockType::Resource,
-                type_name: "aws_s3_bucket".to_string(),
-                name: "one".to_string(),
-                provider_config_key: "aws".to_string(),
-                present_attributes: HashSet::new(),
-                address: "aws_s3_bucket.one".to_string(),
-            },
-            TerraformBlock {
-                block_type: BlockType::Resource,
-                type_name: "aws_s3_bucket".to_string(),
-                name: "two".to_string(),
-                provider_config_key: "aws.global".to_string(),
-                present_attributes: HashSet::new(),
-                address: "aws_s3_bucket.two".to_string(),
-            },
-        ];
+        let mut mappings = ProviderMappings::default();
+        mappings.insert("aws.local".to_string(), "aws.shared".to_string());
+        let context = ModuleContext::root().child("images", &mappings);
 
-        let groups = HclParser::group_by_role(&providers, blocks);
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (_, blocks, _) =
+            HclParser::extract_from_body_with_context(&body, &context, &ParseOptions::default())
+                .unwrap();
 
-        // Both blocks should be in the same group (same role_arn string)
-        assert_eq!(groups.len(), 1);
-        assert!(groups.contains_key("DefaultDeployer"));
-        assert_eq!(groups["DefaultDeployer"].blocks.len(), 2);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].provider_config_key, "aws.shared");
+        assert_eq!(blocks[0].address, "module.images.data.aws_ami.x");
     }
 
     #[test]
-    fn different_role_arns_create_different_groups() {
-        let providers = vec![
-            ParsedProvider {
-                config_key: "aws".to_string(),
-                alias: None,
-                role_arn: Some("arn:aws:iam::123456789012:role/NetworkRole".to_string()),
-            },
-            ParsedProvider {
-                config_key: "aws.dns".to_string(),
-                alias: Some("dns".to_string()),
-                role_arn: Some("arn:aws:iam::987654321012:role/DnsRole".to_string()),
-            },
-        ];
+    fn parse_directory_groups_data_source_by_explicit_provider() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+                provider "aws" {
+                  assume_role {
+                    role_arn = "arn:aws:iam::123456789012:role/NetworkRole"
+                  }
+                }
 
-        let blocks = vec![
-            TerraformBlock {
+                provider "aws" {
+                  alias = "shared"
+                  assume_role {
+                    role_arn = "arn:aws:iam::210987654321:role/SharedRole"
+                  }
+                }
+
+                data "aws_ami" "x" {
+                  provider = aws.shared
+                }
+
+                resource "aws_vpc" "main" {}
+            "#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        let shared = config.provider_groups.get("SharedDeployer").unwrap();
+        assert_eq!(shared.blocks.len(), 1);
+        assert_eq!(shared.blocks[0].block_type, BlockType::Data);
+        assert_eq!(shared.blocks[0].address, "data.aws_ami.x");
+
+        let default = config.provider_groups.get("DefaultDeployer").unwrap();
+        assert_eq!(default.blocks.len(), 1);
+        assert_eq!(default.blocks[0].address, "aws_vpc.main");
+    }
+
+    #[test]
+    fn non_aws_blocks_default_to_the_provider_of_their_type() {
+        let hcl = r#"
+            resource "google_storage_bucket" "main" {
+              name = "my-bucket"
+            }
+
+            resource "random_id" "suffix" {
+              byte_length = 4
+            }
+
+            import {
+              to = google_storage_bucket.main
+              id = "my-bucket"
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (_, blocks) = HclParser::extract_from_body(&body, "").unwrap();
+
+        let keys: Vec<(&str, &str)> = blocks
+            .iter()
+            .map(|b| (b.address.as_str(), b.provider_config_key.as_str()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("google_storage_bucket.main", "google"),
+                ("random_id.suffix", "random"),
+                ("import.google_storage_bucket.main", "google"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_directory_groups_providers_separately_and_leaves_unsupported_blocks_unmapped() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+                provider "aws" {}
+
+                provider "google" {}
+
+                provider "azurerm" {
+                  alias     = "network"
+                  client_id = "00000000-0000-0000-0000-000000000000"
+                  features {}
+                }
+
+                resource "aws_s3_bucket" "main" {}
+
+                resource "google_storage_bucket" "main" {
+                  name     = "my-bucket"
+                  location = "EU"
+                }
+
+                resource "azurerm_virtual_network" "main" {
+                  provider = azurerm.network
+                }
+
+                resource "random_id" "suffix" {
+                  byte_length = 4
+                }
+            "#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        let mut names: Vec<&String> = config.provider_groups.keys().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "AzureNetworkDeployer",
+                "DefaultDeployer",
+                "GoogleDefaultDeployer"
+            ]
+        );
+        assert_eq!(
+            config.provider_groups["GoogleDefaultDeployer"].blocks[0].address,
+            "google_storage_bucket.main"
+        );
+        assert_eq!(
+            config.provider_groups["AzureNetworkDeployer"]
+                .role_arn
+                .as_deref(),
+            Some("00000000-0000-0000-0000-000000000000")
+        );
+        assert_eq!(config.provider_groups["DefaultDeployer"].blocks.len(), 1);
+
+        assert_eq!(config.unmapped_blocks.len(), 1);
+        assert_eq!(config.unmapped_blocks[0].address, "random_id.suffix");
+    }
+
+    #[test]
+    fn groups_providers_by_role_arn_string() {
+        let providers = vec![
+            ParsedProvider {
+                config_key: "aws".to_string(),
+                alias: None,
+                role_arn: Some("arn:aws:iam::${var.account_id}:role/MyRole".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+            ParsedProvider {
+                config_key: "aws.global".to_string(),
+                alias: Some("global".to_string()),
+                role_arn: Some("arn:aws:iam::${var.account_id}:role/MyRole".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+        ];
+
+        let blocks = vec![
+            TerraformBlock {
+                block_type: BlockType::Resource,
+                type_name: "aws_s3_bucket".to_string(),
+                name: "one".to_string(),
+                provider_config_key: "aws".to_string(),
+                attribute_values: HashMap::new(),
+                present_attributes: HashSet::new(),
+                address: "aws_s3_bucket.one".to_string(),
+                managed_policy_arns: Vec::new(),
+                instances: Instances::Single,
+            },
+            TerraformBlock {
+                block_type: BlockType::Resource,
+                type_name: "aws_s3_bucket".to_string(),
+                name: "two".to_string(),
+                provider_config_key: "aws.global".to_string(),
+                attribute_values: HashMap::new(),
+                present_attributes: HashSet::new(),
+                address: "aws_s3_bucket.two".to_string(),
+                managed_policy_arns: Vec::new(),
+                instances: Instances::Single,
+            },
+        ];
+
+        let groups = HclParser::group_by_role(&providers, blocks, &ParseOptions::default());
+
+        // Both blocks should be in the same group (same role_arn string)
+        assert_eq!(groups.len(), 1);
+        assert!(groups.contains_key("DefaultDeployer"));
+        assert_eq!(groups["DefaultDeployer"].blocks.len(), 2);
+    }
+
+    #[test]
+    fn different_role_arns_create_different_groups() {
+        let providers = vec![
+            ParsedProvider {
+                config_key: "aws".to_string(),
+                alias: None,
+                role_arn: Some("arn:aws:iam::123456789012:role/NetworkRole".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+            ParsedProvider {
+                config_key: "aws.dns".to_string(),
+                alias: Some("dns".to_string()),
+                role_arn: Some("arn:aws:iam::987654321012:role/DnsRole".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+        ];
+
+        let blocks = vec![
+            TerraformBlock {
                 block_type: BlockType::Resource,
                 type_name: "aws_vpc".to_string(),
                 name: "main".to_string(),
                 provider_config_key: "aws".to_string(),
+                attribute_values: HashMap::new(),
                 present_attributes: HashSet::new(),
                 address: "aws_vpc.main".to_string(),
+                managed_policy_arns: Vec::new(),
+                instances: Instances::Single,
             },
             TerraformBlock {
                 block_type: BlockType::Resource,
                 type_name: "aws_route53_zone".to_string(),
                 name: "main".to_string(),
                 provider_config_key: "aws.dns".to_string(),
+                attribute_values: HashMap::new(),
                 present_attributes: HashSet::new(),
                 address: "aws_route53_zone.main".to_string(),
+                managed_policy_arns: Vec::new(),
+                instances: Instances::Single,
             },
         ];
 
-        let groups = HclParser::group_by_role(&providers, blocks);
+        let groups = HclParser::group_by_role(&providers, blocks, &ParseOptions::default());
 
         assert_eq!(groups.len(), 2);
         assert!(groups.contains_key("DefaultDeployer"));
@@ -1077,6 +2494,320 @@ mod tests {
         assert_eq!(groups["DnsDeployer"].blocks.len(), 1);
     }
 
+    fn create_block_for_provider(name: &str, provider_config_key: &str) -> TerraformBlock {
+        TerraformBlock {
+            block_type: BlockType::Resource,
+            type_name: "aws_s3_bucket".to_string(),
+            name: name.to_string(),
+            provider_config_key: provider_config_key.to_string(),
+            attribute_values: HashMap::new(),
+            present_attributes: HashSet::new(),
+            address: format!("aws_s3_bucket.{}", name),
+            managed_policy_arns: Vec::new(),
+            instances: Instances::Single,
+        }
+    }
+
+    fn canonicalize() -> ParseOptions {
+        ParseOptions {
+            canonicalize_role_arns: true,
+            ..ParseOptions::default()
+        }
+    }
+
+    fn role_name_groups() -> ParseOptions {
+        ParseOptions {
+            role_name_groups: true,
+            ..ParseOptions::default()
+        }
+    }
+
+    fn create_wildcard_and_concrete_account_providers() -> Vec<ParsedProvider> {
+        vec![
+            ParsedProvider {
+                config_key: "aws.deploy".to_string(),
+                alias: Some("deploy".to_string()),
+                role_arn: Some("arn:aws:iam::*:role/Deploy".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+            ParsedProvider {
+                config_key: "aws.production".to_string(),
+                alias: Some("production".to_string()),
+                role_arn: Some("arn:aws:iam::123456789012:role/Deploy".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn canonicalize_merges_wildcard_and_concrete_account() {
+        let providers = create_wildcard_and_concrete_account_providers();
+        let blocks = vec![
+            create_block_for_provider("one", "aws.deploy"),
+            create_block_for_provider("two", "aws.production"),
+        ];
+
+        let groups = HclParser::group_by_role(&providers, blocks, &canonicalize());
+
+        assert_eq!(groups.len(), 1);
+        let group = &groups["DeployDeployer"];
+        assert_eq!(group.blocks.len(), 2);
+        // The most specific ARN is preserved
+        assert_eq!(
+            group.role_arn.as_deref(),
+            Some("arn:aws:iam::123456789012:role/Deploy")
+        );
+    }
+
+    #[test]
+    fn without_canonicalize_wildcard_and_concrete_account_stay_separate() {
+        let providers = create_wildcard_and_concrete_account_providers();
+        let blocks = vec![
+            create_block_for_provider("one", "aws.deploy"),
+            create_block_for_provider("two", "aws.production"),
+        ];
+
+        let groups = HclParser::group_by_role(&providers, blocks, &ParseOptions::default());
+
+        assert_eq!(groups.len(), 2);
+        assert!(groups.contains_key("DeployDeployer"));
+        assert!(groups.contains_key("ProductionDeployer"));
+    }
+
+    #[test]
+    fn canonicalize_keeps_different_role_names_separate() {
+        let providers = vec![
+            ParsedProvider {
+                config_key: "aws.deploy".to_string(),
+                alias: Some("deploy".to_string()),
+                role_arn: Some("arn:aws:iam::*:role/Deploy".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+            ParsedProvider {
+                config_key: "aws.dns".to_string(),
+                alias: Some("dns".to_string()),
+                role_arn: Some("arn:aws:iam::123456789012:role/Dns".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+        ];
+        let blocks = vec![
+            create_block_for_provider("one", "aws.deploy"),
+            create_block_for_provider("two", "aws.dns"),
+        ];
+
+        let groups = HclParser::group_by_role(&providers, blocks, &canonicalize());
+
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[test]
+    fn canonicalize_role_arn_replaces_account_segment() {
+        assert_eq!(
+            HclParser::canonicalize_role_arn("arn:aws:iam::123456789012:role/Deploy"),
+            "arn:aws:iam::*:role/Deploy"
+        );
+        assert_eq!(
+            HclParser::canonicalize_role_arn("arn:aws:iam::${var.account_id}:role/path/Deploy"),
+            "arn:aws:iam::*:role/path/Deploy"
+        );
+        assert_eq!(
+            HclParser::canonicalize_role_arn("${var.role_arn}"),
+            "${var.role_arn}"
+        );
+    }
+
+    #[test]
+    fn role_name_from_arn_uses_static_role_name_of_interpolated_arn() {
+        assert_eq!(
+            HclParser::role_name_from_arn("arn:aws:iam::${var.account_id}:role/DeployRole"),
+            Some("DeployRoleDeployer".to_string())
+        );
+        assert_eq!(
+            HclParser::role_name_from_arn(
+                "arn:${data.aws_partition.current.partition}:iam::123456789012:role/ci/network-deployer"
+            ),
+            Some("NetworkDeployer".to_string())
+        );
+        assert_eq!(
+            HclParser::role_name_from_arn("arn:aws:iam::${var.account_id}:role/app.deploy"),
+            Some("AppDeployDeployer".to_string())
+        );
+    }
+
+    #[test]
+    fn role_name_from_arn_ignores_static_and_unnamed_arns() {
+        // No interpolation: alias-based naming already identifies the role
+        assert_eq!(
+            HclParser::role_name_from_arn("arn:aws:iam::123456789012:role/DeployRole"),
+            None
+        );
+        // Interpolated role name
+        assert_eq!(
+            HclParser::role_name_from_arn("arn:aws:iam::123456789012:role/${var.role_name}"),
+            None
+        );
+        // Fully interpolated ARN
+        assert_eq!(HclParser::role_name_from_arn("${var.role_arn}"), None);
+        // Not a role ARN
+        assert_eq!(
+            HclParser::role_name_from_arn("arn:aws:iam::${var.account_id}:user/Deploy"),
+            None
+        );
+    }
+
+    #[test]
+    fn role_name_groups_names_interpolated_account_group_after_role() {
+        let providers = vec![ParsedProvider {
+            config_key: "aws".to_string(),
+            alias: None,
+            role_arn: Some("arn:aws:iam::${var.account_id}:role/DeployRole".to_string()),
+            profile: None,
+            region: None,
+            has_custom_endpoints: false,
+            has_default_tags: false,
+        }];
+        let blocks = vec![create_block_for_provider("one", "aws")];
+
+        let groups = HclParser::group_by_role(&providers, blocks, &role_name_groups());
+
+        assert_eq!(groups.len(), 1);
+        let group = &groups["DeployRoleDeployer"];
+        assert_eq!(group.blocks.len(), 1);
+        assert_eq!(
+            group.role_arn.as_deref(),
+            Some("arn:aws:iam::${var.account_id}:role/DeployRole")
+        );
+    }
+
+    #[test]
+    fn without_role_name_groups_interpolated_account_group_uses_alias_name() {
+        let providers = vec![ParsedProvider {
+            config_key: "aws".to_string(),
+            alias: None,
+            role_arn: Some("arn:aws:iam::${var.account_id}:role/DeployRole".to_string()),
+            profile: None,
+            region: None,
+            has_custom_endpoints: false,
+            has_default_tags: false,
+        }];
+        let blocks = vec![create_block_for_provider("one", "aws")];
+
+        let groups = HclParser::group_by_role(&providers, blocks, &ParseOptions::default());
+
+        assert_eq!(groups.len(), 1);
+        assert!(groups.contains_key("DefaultDeployer"));
+    }
+
+    #[test]
+    fn role_name_groups_keeps_alias_names_for_shared_role_name() {
+        let providers = vec![
+            ParsedProvider {
+                config_key: "aws.dev".to_string(),
+                alias: Some("dev".to_string()),
+                role_arn: Some("arn:aws:iam::${var.dev_account}:role/Deploy".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+            ParsedProvider {
+                config_key: "aws.prod".to_string(),
+                alias: Some("prod".to_string()),
+                role_arn: Some("arn:aws:iam::${var.prod_account}:role/Deploy".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+            ParsedProvider {
+                config_key: "aws.dns".to_string(),
+                alias: Some("dns".to_string()),
+                role_arn: Some("arn:aws:iam::${var.dns_account}:role/Route53".to_string()),
+                profile: None,
+                region: None,
+                has_custom_endpoints: false,
+                has_default_tags: false,
+            },
+        ];
+        let blocks = vec![
+            create_block_for_provider("one", "aws.dev"),
+            create_block_for_provider("two", "aws.prod"),
+            create_block_for_provider("three", "aws.dns"),
+        ];
+
+        let groups = HclParser::group_by_role(&providers, blocks, &role_name_groups());
+
+        let mut names: Vec<&String> = groups.keys().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["DevDeployer", "ProdDeployer", "Route53Deployer"]
+        );
+    }
+
+    #[test]
+    fn parse_policy_attachment_extracts_managed_policy_arn() {
+        let hcl = r#"
+            resource "aws_iam_role_policy_attachment" "read_only" {
+              role       = aws_iam_role.deployer.name
+              policy_arn = "arn:aws:iam::aws:policy/AmazonS3ReadOnlyAccess"
+            }
+
+            resource "aws_iam_role_policy_attachment" "custom" {
+              role       = aws_iam_role.deployer.name
+              policy_arn = "arn:aws:iam::123456789012:policy/Custom"
+            }
+
+            resource "aws_iam_role_policy_attachment" "interpolated" {
+              role       = aws_iam_role.deployer.name
+              policy_arn = "arn:${data.aws_partition.current.partition}:iam::aws:policy/ReadOnlyAccess"
+            }
+
+            resource "aws_iam_role" "deployer" {
+              name                = "deployer"
+              assume_role_policy  = "{}"
+              managed_policy_arns = [
+                "arn:aws-us-gov:iam::aws:policy/ReadOnlyAccess",
+                aws_iam_policy.custom.arn,
+              ]
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (_, blocks) = HclParser::extract_from_body(&body, "").unwrap();
+
+        let arns = |name: &str| {
+            blocks
+                .iter()
+                .find(|b| b.name == name)
+                .unwrap()
+                .managed_policy_arns
+                .clone()
+        };
+        assert_eq!(
+            arns("read_only"),
+            vec!["arn:aws:iam::aws:policy/AmazonS3ReadOnlyAccess".to_string()]
+        );
+        assert!(arns("custom").is_empty());
+        assert!(arns("interpolated").is_empty());
+        assert_eq!(
+            arns("deployer"),
+            vec!["arn:aws-us-gov:iam::aws:policy/ReadOnlyAccess".to_string()]
+        );
+    }
+
     #[test]
     fn parse_nested_attributes() {
         let hcl = r#"
@@ -1112,6 +2843,419 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_repeated_nested_blocks_records_paths_without_indices() {
+        let hcl = r#"
+            resource "aws_s3_bucket_replication_configuration" "main" {
+              role   = aws_iam_role.replication.arn
+              bucket = aws_s3_bucket.source.id
+
+              rule {
+                id     = "first"
+                status = "Enabled"
+
+                destination {
+                  bucket = aws_s3_bucket.replica.arn
+                }
+              }
+
+              rule {
+                id     = "second"
+                status = "Enabled"
+
+                destination {
+                  bucket        = aws_s3_bucket.replica.arn
+                  storage_class = "STANDARD_IA"
+
+                  encryption_configuration {
+                    replica_kms_key_id = aws_kms_key.replica.arn
+                  }
+                }
+              }
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (_, blocks) = HclParser::extract_from_body(&body, "").unwrap();
+
+        let path =
+            |segments: &[&str]| -> Vec<String> { segments.iter().map(|s| s.to_string()).collect() };
+        let attributes = &blocks[0].present_attributes;
+
+        // Sub-attributes of any `rule` element are recorded under the same path
+        assert!(attributes.contains(&path(&["rule", "destination", "storage_class"])));
+        assert!(attributes.contains(&path(&[
+            "rule",
+            "destination",
+            "encryption_configuration",
+            "replica_kms_key_id"
+        ])));
+
+        // No path segment carries a list index
+        assert!(
+            attributes
+                .iter()
+                .flatten()
+                .all(|segment| segment.parse::<usize>().is_err())
+        );
+        let rule_paths = attributes
+            .iter()
+            .filter(|p| p.first().map(String::as_str) == Some("rule"))
+            .count();
+        assert_eq!(rule_paths, 8);
+    }
+
+    #[test]
+    fn attribute_collection_stops_at_max_depth() {
+        // 50 levels of nested blocks, with an attribute at the innermost level
+        let nested = format!("{}value = 1{}", "level {\n".repeat(50), "\n}".repeat(50));
+        let hcl = format!("resource \"aws_s3_bucket\" \"main\" {{\n{}\n}}", nested);
+        let body: Body = hcl::from_str(&hcl).unwrap();
+        let options = ParseOptions {
+            max_attribute_depth: 3,
+            ..ParseOptions::default()
+        };
+
+        let (_, blocks, _) =
+            HclParser::extract_from_body_with_context(&body, &ModuleContext::root(), &options)
+                .unwrap();
+
+        let attributes = &blocks[0].present_attributes;
+        assert_eq!(attributes.len(), 3);
+        assert!(attributes.contains(&vec!["level".to_string(); 3]));
+        assert!(attributes.iter().all(|path| path.len() <= 3));
+    }
+
+    #[test]
+    fn attribute_collection_keeps_paths_within_default_depth() {
+        let (paths, truncated) = HclParser::collect_attributes(
+            &hcl::from_str("a {\n b {\n c = 1\n }\n}").unwrap(),
+            DEFAULT_MAX_ATTRIBUTE_DEPTH,
+        );
+
+        assert!(!truncated);
+        assert!(paths.contains(&vec!["a".to_string(), "b".to_string(), "c".to_string()]));
+    }
+
+    #[test]
+    fn extract_required_providers_object_syntax() {
+        let hcl = r#"
+            terraform {
+              required_version = ">= 1.5"
+
+              required_providers {
+                aws = {
+                  source  = "hashicorp/aws"
+                  version = "~> 5.0"
+                }
+              }
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let constraints = HclParser::extract_required_providers(&body);
+
+        assert_eq!(constraints.len(), 1);
+        assert_eq!(constraints.get("aws"), Some(&"~> 5.0".to_string()));
+    }
+
+    #[test]
+    fn extract_required_providers_legacy_string_syntax() {
+        let hcl = r#"
+            terraform {
+              required_providers {
+                aws = ">= 4.0, < 6.0"
+              }
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let constraints = HclParser::extract_required_providers(&body);
+
+        assert_eq!(constraints.get("aws"), Some(&">= 4.0, < 6.0".to_string()));
+    }
+
+    #[test]
+    fn extract_required_providers_skips_provider_without_version() {
+        let hcl = r#"
+            terraform {
+              required_providers {
+                aws = {
+                  source = "hashicorp/aws"
+                }
+              }
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let constraints = HclParser::extract_required_providers(&body);
+
+        assert!(constraints.is_empty());
+    }
+
+    #[test]
+    fn parse_directory_reports_version_constraints() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("versions.tf"),
+            r#"
+                terraform {
+                  required_providers {
+                    aws = {
+                      source  = "hashicorp/aws"
+                      version = "~> 5.0"
+                    }
+                  }
+                }
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"resource "aws_s3_bucket" "main" {}"#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        assert_eq!(
+            config.provider_version_constraints.get("aws"),
+            Some(&"~> 5.0".to_string())
+        );
+    }
+
+    #[test]
+    fn unresolved_module_calls_reported_without_manifest() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+                module "vpc" {
+                  source = "terraform-aws-modules/vpc/aws"
+                }
+
+                module "bucket" {
+                  source = "./modules/bucket"
+                }
+
+                resource "aws_s3_bucket" "main" {}
+            "#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        assert_eq!(config.unresolved_modules, vec!["bucket", "vpc"]);
+    }
+
+    #[test]
+    fn no_unresolved_module_calls_without_modules() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"resource "aws_s3_bucket" "main" {}"#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        assert!(config.unresolved_modules.is_empty());
+    }
+
+    #[test]
+    fn parse_directory_includes_tofu_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"resource "aws_s3_bucket" "main" {}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("network.tofu"),
+            r#"resource "aws_vpc" "main" {}"#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        let group = config.provider_groups.get("DefaultDeployer").unwrap();
+        let mut addresses: Vec<_> = group.blocks.iter().map(|b| b.address.as_str()).collect();
+        addresses.sort();
+        assert_eq!(addresses, vec!["aws_s3_bucket.main", "aws_vpc.main"]);
+    }
+
+    #[test]
+    fn parse_directory_substitutes_variable_values_in_role_arns() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("providers.tf"),
+            r#"
+                provider "aws" {
+                  assume_role {
+                    role_arn = "arn:aws:iam::${var.account_id}:role/Deploy"
+                  }
+                }
+
+                provider "aws" {
+                  alias = "network"
+                  assume_role {
+                    role_arn = var.network_role
+                  }
+                }
+
+                provider "aws" {
+                  alias = "dns"
+                  assume_role {
+                    role_arn = "arn:aws:iam::${var.dns_account_id}:role/Dns"
+                  }
+                }
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("variables.tf"),
+            r#"
+                variable "account_id" {
+                  default = "111111111111"
+                }
+                variable "network_role" {
+                  default = "arn:aws:iam::222222222222:role/Deploy"
+                }
+                variable "dns_account_id" {}
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("terraform.tfvars"),
+            "account_id = \"222222222222\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+                resource "aws_s3_bucket" "main" {}
+
+                resource "aws_vpc" "main" {
+                  provider = aws.network
+                }
+
+                resource "aws_route53_zone" "main" {
+                  provider = aws.dns
+                }
+            "#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        // Both resolve to the same role, so they share one group
+        let mut role_arns: Vec<String> = config.role_arns().into_values().collect();
+        role_arns.sort();
+        assert_eq!(
+            role_arns,
+            vec![
+                "arn:aws:iam::${var.dns_account_id}:role/Dns",
+                "arn:aws:iam::222222222222:role/Deploy",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_directory_groups_by_providers_declared_in_separate_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("providers.tf"),
+            r#"
+                provider "aws" {
+                  assume_role {
+                    role_arn = "arn:aws:iam::123456789012:role/Default"
+                  }
+                }
+
+                provider "aws" {
+                  alias = "network"
+                  assume_role {
+                    role_arn = "arn:aws:iam::123456789012:role/Network"
+                  }
+                }
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+                resource "aws_s3_bucket" "main" {}
+
+                resource "aws_vpc" "main" {
+                  provider = aws.network
+                }
+            "#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        assert_eq!(config.provider_groups.len(), 2);
+        let network = config.provider_groups.get("NetworkDeployer").unwrap();
+        assert_eq!(
+            network.role_arn.as_deref(),
+            Some("arn:aws:iam::123456789012:role/Network")
+        );
+        assert_eq!(network.blocks.len(), 1);
+        assert_eq!(network.blocks[0].address, "aws_vpc.main");
+        let default = config.provider_groups.get("DefaultDeployer").unwrap();
+        assert_eq!(
+            default.role_arn.as_deref(),
+            Some("arn:aws:iam::123456789012:role/Default")
+        );
+        assert_eq!(default.blocks.len(), 1);
+        assert_eq!(default.blocks[0].address, "aws_s3_bucket.main");
+    }
+
+    #[test]
+    fn parse_directory_prefers_tofu_file_over_tf_file_with_same_name() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"resource "aws_s3_bucket" "terraform" {}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tofu"),
+            r#"resource "aws_s3_bucket" "tofu" {}"#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        let group = config.provider_groups.get("DefaultDeployer").unwrap();
+        assert_eq!(group.blocks.len(), 1);
+        assert_eq!(group.blocks[0].address, "aws_s3_bucket.tofu");
+    }
+
+    #[test]
+    fn parse_directory_with_module_call_but_no_manifest_parses_root() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+                module "vpc" {
+                  source = "terraform-aws-modules/vpc/aws"
+                }
+
+                resource "aws_s3_bucket" "main" {}
+            "#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        // Root resources are still analyzed; module resources are not
+        let group = config.provider_groups.get("DefaultDeployer").unwrap();
+        assert_eq!(group.blocks.len(), 1);
+        assert_eq!(group.blocks[0].address, "aws_s3_bucket.main");
+    }
+
     #[test]
     fn parse_simple_provider_mapping() {
         let hcl = r#"
@@ -1189,4 +3333,304 @@ mod tests {
         assert!(mappings.has_mappings());
         assert_eq!(mappings.resolve("aws"), "aws.test");
     }
+
+    #[test]
+    fn parse_provider_mapping_with_only_aliased_key() {
+        let hcl = r#"
+            module "replicated" {
+              source = "./replicated"
+              providers = {
+                aws.replica = aws.dr
+              }
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let module_block = body.blocks().next().unwrap();
+        let mappings = HclParser::parse_module_providers(module_block.body());
+
+        assert_eq!(mappings.resolve("aws.replica"), "aws.dr");
+        assert_eq!(mappings.resolve("aws"), "aws");
+    }
+
+    #[test]
+    fn parse_import_block_with_aliased_provider() {
+        let hcl = r#"
+            import {
+              to       = aws_s3_bucket.x
+              id       = "existing-bucket"
+              provider = aws.shared
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (_, blocks) = HclParser::extract_from_body(&body, "").unwrap();
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].block_type, BlockType::Import);
+        assert_eq!(blocks[0].type_name, "aws_s3_bucket");
+        assert_eq!(blocks[0].name, "x");
+        assert_eq!(blocks[0].provider_config_key, "aws.shared");
+        assert_eq!(blocks[0].address, "import.aws_s3_bucket.x");
+    }
+
+    #[test]
+    fn parse_import_block_into_module_resource() {
+        let hcl = r#"
+            import {
+              to = module.storage.module.logs.aws_s3_bucket.this[0]
+              id = "existing-bucket"
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (_, blocks) = HclParser::extract_from_body(&body, "").unwrap();
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].type_name, "aws_s3_bucket");
+        assert_eq!(blocks[0].name, "this");
+        assert_eq!(blocks[0].provider_config_key, "aws");
+        assert_eq!(
+            blocks[0].address,
+            "import.module.storage.module.logs.aws_s3_bucket.this"
+        );
+    }
+
+    #[test]
+    fn parse_import_block_ignores_invalid_targets() {
+        let hcl = r#"
+            import {
+              to = module.storage
+              id = "existing-bucket"
+            }
+
+            import {
+              id = "missing-to"
+            }
+        "#;
+
+        let body: Body = hcl::from_str(hcl).unwrap();
+        let (_, blocks) = HclParser::extract_from_body(&body, "").unwrap();
+
+        assert!(blocks.is_empty());
+    }
+
+    #[test]
+    fn parse_directory_groups_import_block_by_aliased_provider() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+                provider "aws" {
+                  assume_role {
+                    role_arn = "arn:aws:iam::123456789012:role/NetworkRole"
+                  }
+                }
+
+                provider "aws" {
+                  alias = "shared"
+                  assume_role {
+                    role_arn = "arn:aws:iam::210987654321:role/SharedRole"
+                  }
+                }
+
+                import {
+                  to       = aws_s3_bucket.x
+                  id       = "existing-bucket"
+                  provider = aws.shared
+                }
+
+                resource "aws_s3_bucket" "x" {
+                  provider = aws.shared
+                  bucket   = "existing-bucket"
+                }
+
+                resource "aws_vpc" "main" {}
+            "#,
+        )
+        .unwrap();
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        let shared = config.provider_groups.get("SharedDeployer").unwrap();
+        let mut shared_addresses: Vec<&str> =
+            shared.blocks.iter().map(|b| b.address.as_str()).collect();
+        shared_addresses.sort();
+        assert_eq!(
+            shared_addresses,
+            vec!["aws_s3_bucket.x", "import.aws_s3_bucket.x"]
+        );
+
+        let default = config.provider_groups.get("DefaultDeployer").unwrap();
+        assert_eq!(default.blocks.len(), 1);
+        assert_eq!(default.blocks[0].address, "aws_vpc.main");
+    }
+
+    /// Writes a root module calling a broken and a valid local module, with a modules manifest.
+    fn write_project_with_broken_module(root: &Path) {
+        let write = |relative: &str, content: &str| {
+            let path = root.join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+
+        write(
+            "main.tf",
+            r#"
+                module "broken" {
+                  source = "./modules/broken"
+                }
+
+                module "storage" {
+                  source = "./modules/storage"
+                }
+
+                resource "aws_vpc" "main" {}
+            "#,
+        );
+        write(
+            "modules/broken/main.tf",
+            "resource \"aws_instance\" \"x\" {",
+        );
+        write(
+            "modules/storage/main.tf",
+            r#"resource "aws_s3_bucket" "data" {}"#,
+        );
+        write(
+            ".terraform/modules/modules.json",
+            r#"{"Modules":[
+                {"Key":"","Source":"","Dir":"."},
+                {"Key":"broken","Source":"./modules/broken","Dir":"modules/broken"},
+                {"Key":"storage","Source":"./modules/storage","Dir":"modules/storage"}
+            ]}"#,
+        );
+    }
+
+    #[test]
+    fn parse_directory_builds_module_tree() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let write = |relative: &str, content: &str| {
+            let path = temp_dir.path().join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+
+        write(
+            "main.tf",
+            r#"
+                provider "aws" {
+                  alias = "network"
+                }
+
+                module "network" {
+                  source = "./modules/network"
+                  providers = {
+                    aws = aws.network
+                  }
+                }
+
+                module "storage" {
+                  source = "./modules/storage"
+                }
+
+                resource "aws_iam_role" "deployer" {}
+            "#,
+        );
+        write(
+            "modules/network/main.tf",
+            r#"
+                resource "aws_vpc" "main" {}
+                resource "aws_subnet" "a" {}
+            "#,
+        );
+        write(
+            "modules/storage/main.tf",
+            r#"resource "aws_s3_bucket" "data" {}"#,
+        );
+        write(
+            ".terraform/modules/modules.json",
+            r#"{"Modules":[
+                {"Key":"","Source":"","Dir":"."},
+                {"Key":"network","Source":"./modules/network","Dir":"modules/network"},
+                {"Key":"storage","Source":"./modules/storage","Dir":"modules/storage"}
+            ]}"#,
+        );
+
+        let config = HclParser::parse_directory(temp_dir.path()).unwrap();
+
+        let tree = config
+            .module_tree
+            .expect("HCL parsing should build a module tree");
+        assert_eq!(tree.total_block_count(), 4);
+        assert_eq!(
+            tree.render(),
+            "root (1 block)\n\
+             ├── module.network [local: ./modules/network] providers: aws = aws.network (2 blocks)\n\
+             └── module.storage [local: ./modules/storage] (1 block)\n"
+        );
+    }
+
+    #[test]
+    fn parse_directory_fails_on_broken_module_by_default() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        write_project_with_broken_module(temp_dir.path());
+
+        let result = HclParser::parse_directory(temp_dir.path());
+
+        assert!(matches!(result, Err(HclParseError::Hcl(_))));
+    }
+
+    #[test]
+    fn parse_directory_best_effort_skips_broken_module() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        write_project_with_broken_module(temp_dir.path());
+
+        let options = ParseOptions {
+            best_effort: true,
+            ..ParseOptions::default()
+        };
+        let config = HclParser::parse_directory_with_options(temp_dir.path(), &options).unwrap();
+
+        assert!(config.is_partial());
+        assert_eq!(config.skipped_modules, vec!["module.broken"]);
+
+        let group = config.provider_groups.get("DefaultDeployer").unwrap();
+        let mut addresses: Vec<&str> = group.blocks.iter().map(|b| b.address.as_str()).collect();
+        addresses.sort();
+        assert_eq!(
+            addresses,
+            vec!["aws_vpc.main", "module.storage.aws_s3_bucket.data"]
+        );
+
+        // The valid module still contributes permissions
+        let mappings_dir = tempfile::TempDir::new().unwrap();
+        let resource_dir = mappings_dir.path().join("mappings/resource");
+        std::fs::create_dir_all(&resource_dir).unwrap();
+        std::fs::write(
+            resource_dir.join("aws_s3_bucket.yaml"),
+            "allow:\n  - s3:CreateBucket",
+        )
+        .unwrap();
+
+        let loader = crate::mapping::MappingLoader::new(mappings_dir.path().to_path_buf());
+        let result = crate::mapping::PermissionMatcher::new(&loader)
+            .resolve(&config)
+            .unwrap();
+        let permissions = result.groups.get("DefaultDeployer").unwrap();
+        assert!(permissions.allow.contains("s3:CreateBucket"));
+    }
+
+    #[test]
+    fn parse_directory_best_effort_still_fails_on_broken_root() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("main.tf"), "resource \"aws_vpc\" {").unwrap();
+
+        let options = ParseOptions {
+            best_effort: true,
+            ..ParseOptions::default()
+        };
+        let result = HclParser::parse_directory_with_options(temp_dir.path(), &options);
+
+        assert!(result.is_err());
+    }
 }
@@ -43,10 +43,10 @@ impl ProviderConfig {
             // Find the last assume_role block (for role chaining)
             for item in arr.iter().rev() {
                 if let Some(role_arn_expr) = item.get("role_arn") {
-                    if let Some(constant) = role_arn_expr.get("constant_value") {
-                        if let Some(arn) = constant.as_str() {
-                            return Some(arn.to_string());
-                        }
+                    if let Some(constant) = role_arn_expr.get("constant_value")
+                        && let Some(arn) = constant.as_str()
+                    {
+                        return Some(arn.to_string());
                     }
                     // Also check for references (we can't resolve these, but note them)
                     if role_arn_expr.get("references").is_some() {
@@ -121,13 +121,13 @@ impl ResourceConfig {
     /// This is used for optional permission mapping
     pub fn collect_attribute_paths(&self) -> std::collections::HashSet<Vec<String>> {
         let mut paths = std::collections::HashSet::new();
-        Self::collect_paths_recursive(&self.expressions, &mut Vec::new(), &mut paths);
+        Self::collect_paths_recursive(&self.expressions, &[], &mut paths);
         paths
     }
 
     fn collect_paths_recursive(
         value: &HashMap<String, serde_json::Value>,
-        current_path: &mut Vec<String>,
+        current_path: &[String],
         paths: &mut std::collections::HashSet<Vec<String>>,
     ) {
         for (key, val) in value {
@@ -136,7 +136,7 @@ impl ResourceConfig {
                 continue;
             }
 
-            let mut new_path = current_path.clone();
+            let mut new_path = current_path.to_vec();
             new_path.push(key.clone());
             paths.insert(new_path.clone());
 
@@ -147,7 +147,7 @@ impl ResourceConfig {
                     if let Some(obj) = item.as_object() {
                         let nested: HashMap<String, serde_json::Value> =
                             obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-                        Self::collect_paths_recursive(&nested, &mut new_path, paths);
+                        Self::collect_paths_recursive(&nested, &new_path, paths);
                     }
                 }
             } else if let Some(obj) = val.as_object() {
@@ -157,7 +157,7 @@ impl ResourceConfig {
                         .iter()
                         .map(|(k, v)| (k.clone(), v.clone()))
                         .collect();
-                    Self::collect_paths_recursive(&nested, &mut new_path, paths);
+                    Self::collect_paths_recursive(&nested, &new_path, paths);
                 }
             }
         }
@@ -1,14 +1,20 @@
+mod cloud;
+mod files;
 mod hcl_parser;
 mod json_types;
 mod model;
 mod module_detector;
+mod module_tree;
 mod parser;
 mod plan;
 mod provider;
 mod runner;
+mod variables;
 
-pub use hcl_parser::{HclParseError, HclParser};
-pub use model::{BlockType, ProviderGroup, TerraformBlock, TerraformConfig};
+pub use cloud::{CLOUD_PROVIDERS, CloudProvider, IdentitySource};
+pub use hcl_parser::{DEFAULT_MAX_ATTRIBUTE_DEPTH, HclParseError, HclParser, ParseOptions};
+pub use model::{BlockType, Instances, ProviderGroup, TerraformBlock, TerraformConfig};
+pub use module_tree::ModuleNode;
 pub use parser::{ParseError, TerraformParser};
 pub use plan::PlanExecutor;
 pub use runner::{TerraformError, TerraformRunner};
@@ -1,4 +1,8 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use super::module_tree::ModuleNode;
+use super::runner::TerraformError;
+use crate::redact::redact_account_ids;
 
 /// Represents provider mappings for a module call.
 ///
@@ -104,6 +108,127 @@ pub struct TerraformConfig {
 
     /// Blocks that couldn't be mapped to a provider (for warnings)
     pub unmapped_blocks: Vec<TerraformBlock>,
+
+    /// Provider version constraints from `required_providers` in the root module
+    /// Key: Provider local name (e.g., "aws")
+    /// Value: Version constraint as written (e.g., "~> 5.0")
+    pub provider_version_constraints: HashMap<String, String>,
+
+    /// Addresses of modules that were skipped because they failed to parse
+    /// (only in best-effort mode, e.g., "module.network")
+    pub skipped_modules: Vec<String>,
+
+    /// Names of root module calls that were not followed because no modules
+    /// manifest was found (HCL parsing only, e.g., "vpc")
+    pub unresolved_modules: Vec<String>,
+
+    /// Module hierarchy as parsed (HCL parsing only, `None` otherwise)
+    pub module_tree: Option<ModuleNode>,
+}
+
+impl TerraformConfig {
+    /// Returns true if modules were skipped, so the analysis is incomplete.
+    pub fn is_partial(&self) -> bool {
+        !self.skipped_modules.is_empty()
+    }
+
+    /// Returns the role ARN of every group that has one, keyed by group name.
+    pub fn role_arns(&self) -> HashMap<String, String> {
+        self.provider_groups
+            .iter()
+            .filter_map(|(name, group)| Some((name.clone(), group.role_arn.clone()?)))
+            .collect()
+    }
+
+    /// Returns every attached AWS managed policy ARN with the sorted addresses
+    /// of the blocks attaching it, sorted by ARN.
+    pub fn managed_policy_attachments(&self) -> Vec<(String, Vec<String>)> {
+        let mut attachments: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+
+        let blocks = self
+            .provider_groups
+            .values()
+            .flat_map(|group| &group.blocks)
+            .chain(&self.unmapped_blocks);
+        for block in blocks {
+            for arn in &block.managed_policy_arns {
+                attachments
+                    .entry(arn.as_str())
+                    .or_default()
+                    .insert(block.address.as_str());
+            }
+        }
+
+        attachments
+            .into_iter()
+            .map(|(arn, addresses)| {
+                (
+                    arn.to_string(),
+                    addresses.into_iter().map(str::to_string).collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Redacts the account IDs of all role ARNs and attached policy ARNs
+    /// (`--redact-account-ids`), so nothing printed afterwards reveals them.
+    ///
+    /// Must be called after grouping, which compares the unredacted ARNs.
+    pub fn redact_account_ids(&mut self) {
+        for group in self.provider_groups.values_mut() {
+            if let Some(role_arn) = &group.role_arn {
+                group.role_arn = Some(redact_account_ids(role_arn));
+            }
+        }
+
+        let blocks = self
+            .provider_groups
+            .values_mut()
+            .flat_map(|group| &mut group.blocks)
+            .chain(&mut self.unmapped_blocks);
+        for block in blocks {
+            for arn in &mut block.managed_policy_arns {
+                *arn = redact_account_ids(arn);
+            }
+        }
+    }
+
+    /// Returns the sorted addresses of all resources whose type is in `forbidden_types`.
+    ///
+    /// Both grouped and unmapped blocks are checked. Data sources and other
+    /// block types are ignored, since they do not create the resource.
+    pub fn forbidden_resources(&self, forbidden_types: &[String]) -> Vec<String> {
+        let mut addresses: Vec<String> = self
+            .provider_groups
+            .values()
+            .flat_map(|group| &group.blocks)
+            .chain(&self.unmapped_blocks)
+            .filter(|block| {
+                block.block_type == BlockType::Resource
+                    && forbidden_types.contains(&block.type_name)
+            })
+            .map(TerraformBlock::display_address)
+            .collect();
+
+        addresses.sort();
+        addresses
+    }
+
+    /// Fails if any resource has a type in `forbidden_types` (`--forbidden-types`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `TerraformError::ForbiddenResources` listing the addresses of
+    /// all offending resources.
+    pub fn check_forbidden_types(&self, forbidden_types: &[String]) -> Result<(), TerraformError> {
+        let addresses = self.forbidden_resources(forbidden_types);
+
+        if addresses.is_empty() {
+            Ok(())
+        } else {
+            Err(TerraformError::ForbiddenResources(addresses.join(", ")))
+        }
+    }
 }
 
 /// A group of blocks that share the same assumed role
@@ -115,14 +240,17 @@ pub struct ProviderGroup {
     /// The role ARN (for reference)
     pub role_arn: Option<String>,
 
+    /// Whether any provider of this group overrides service endpoints
+    pub has_custom_endpoints: bool,
+
     /// All terraform blocks using this provider/role
     pub blocks: Vec<TerraformBlock>,
 }
 
-/// Represents a single terraform block (resource, data, ephemeral, or action)
+/// Represents a single terraform block (resource, data, ephemeral, action, or import)
 #[derive(Debug, Clone)]
 pub struct TerraformBlock {
-    /// Block type: "resource", "data", "ephemeral", "action"
+    /// Block type: "resource", "data", "ephemeral", "action", "import"
     pub block_type: BlockType,
 
     /// Type name, e.g., "aws_s3_bucket", "aws_availability_zones"
@@ -134,29 +262,96 @@ pub struct TerraformBlock {
     /// Provider config key (e.g., "aws", "aws.secondary")
     pub provider_config_key: String,
 
+    /// Literal values of the block's top-level attributes (strings, numbers,
+    /// and bools), e.g. `bucket` -> `my-bucket`. Interpolated values are not
+    /// recorded. Used to fill the `resources` templates of mappings.
+    pub attribute_values: HashMap<String, String>,
+
     /// Nested attributes present in this block (for optional permission mapping)
     /// Represented as paths, e.g., [["vpc", "vpc_id"], ["tags"]]
     pub present_attributes: HashSet<Vec<String>>,
 
     /// Full address (e.g., "module.vpc.aws_subnet.main")
     pub address: String,
+
+    /// AWS managed policy ARNs attached by this block as literal strings
+    /// (e.g., "arn:aws:iam::aws:policy/AmazonS3ReadOnlyAccess")
+    pub managed_policy_arns: Vec<String>,
+
+    /// Instances declared by the block's `count` or `for_each` meta-argument
+    pub instances: Instances,
+}
+
+impl TerraformBlock {
+    /// Returns the address with the block's instances, e.g. `aws_s3_bucket.logs[0..3]`.
+    ///
+    /// Use this for addresses shown to the user; `address` stays the plain
+    /// block address used for lookups.
+    pub fn display_address(&self) -> String {
+        format!("{}{}", self.address, self.instances.address_suffix())
+    }
+}
+
+/// Instances of a block with a `count` or `for_each` meta-argument.
+///
+/// Permissions are resolved per type, so this only affects reported addresses.
+/// `count`/`for_each` on module calls are not reflected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Instances {
+    /// Neither `count` nor `for_each`: a single instance without index
+    #[default]
+    Single,
+    /// A literal `count`, e.g. `count = 3`
+    Count(usize),
+    /// The keys of a literal `for_each` set or map, e.g. `toset(["a", "b"])`
+    ForEach(Vec<String>),
+    /// `count` or `for_each` with an expression that is not a literal
+    Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl Instances {
+    /// Returns the suffix appended to the block address: nothing, `[0..3]`,
+    /// `["a", "b"]`, or `[*]` for an unknown number of instances.
+    pub fn address_suffix(&self) -> String {
+        match self {
+            Instances::Single => String::new(),
+            Instances::Count(count) => format!("[0..{}]", count),
+            Instances::ForEach(keys) => {
+                let keys: Vec<String> = keys.iter().map(|key| format!("\"{}\"", key)).collect();
+                format!("[{}]", keys.join(", "))
+            }
+            Instances::Unknown => "[*]".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
 pub enum BlockType {
     Resource,
     Data,
     Ephemeral,
     Action,
+    /// An `import` block; `type_name` and `name` refer to the import target
+    Import,
 }
 
 impl BlockType {
+    /// All block types, the default of `--block-types`.
+    pub const ALL: [BlockType; 5] = [
+        BlockType::Resource,
+        BlockType::Data,
+        BlockType::Ephemeral,
+        BlockType::Action,
+        BlockType::Import,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             BlockType::Resource => "resource",
             BlockType::Data => "data",
             BlockType::Ephemeral => "ephemeral",
             BlockType::Action => "action",
+            BlockType::Import => "import",
         }
     }
 }
@@ -171,6 +366,7 @@ mod tests {
         assert_eq!(BlockType::Data.as_str(), "data");
         assert_eq!(BlockType::Ephemeral.as_str(), "ephemeral");
         assert_eq!(BlockType::Action.as_str(), "action");
+        assert_eq!(BlockType::Import.as_str(), "import");
     }
 
     #[test]
@@ -186,8 +382,11 @@ mod tests {
             type_name: "aws_s3_bucket".to_string(),
             name: "example".to_string(),
             provider_config_key: "aws".to_string(),
+            attribute_values: HashMap::new(),
             present_attributes: HashSet::new(),
             address: "aws_s3_bucket.example".to_string(),
+            managed_policy_arns: Vec::new(),
+            instances: Instances::Single,
         };
 
         let cloned = block.clone();
@@ -284,4 +483,222 @@ mod tests {
         assert_eq!(child.resolve_to_root("aws.secondary"), "aws.eu_west");
         assert_eq!(child.resolve_to_root("aws"), "aws.default_region");
     }
+
+    #[test]
+    fn provider_mappings_resolve_dotted_local_key() {
+        let mut mappings = ProviderMappings::default();
+        mappings.insert("aws.replica".to_string(), "aws.dr".to_string());
+
+        assert_eq!(mappings.resolve("aws.replica"), "aws.dr");
+        // The bare key is not affected by an aliased mapping
+        assert_eq!(mappings.resolve("aws"), "aws");
+    }
+
+    #[test]
+    fn module_context_resolve_to_root_forwarded_alias_chain() {
+        // Root has aws.dr
+        // Level1 maps: aws.replica -> aws.dr
+        // Level2 maps: aws.backup -> aws.replica
+
+        let root = ModuleContext::root();
+
+        let mut level1_mappings = ProviderMappings::default();
+        level1_mappings.insert("aws.replica".to_string(), "aws.dr".to_string());
+        let level1 = root.child("level1", &level1_mappings);
+
+        let mut level2_mappings = ProviderMappings::default();
+        level2_mappings.insert("aws.backup".to_string(), "aws.replica".to_string());
+        let level2 = level1.child("level2", &level2_mappings);
+
+        assert_eq!(level1.resolve_to_root("aws.replica"), "aws.dr");
+        assert_eq!(level2.resolve_to_root("aws.backup"), "aws.dr");
+        assert_eq!(level2.resolve_to_root("aws"), "aws");
+    }
+
+    fn config_with_blocks(blocks: Vec<TerraformBlock>) -> TerraformConfig {
+        TerraformConfig {
+            provider_groups: HashMap::from([(
+                "DefaultDeployer".to_string(),
+                ProviderGroup {
+                    output_name: "DefaultDeployer".to_string(),
+                    role_arn: None,
+                    has_custom_endpoints: false,
+                    blocks,
+                },
+            )]),
+            unmapped_blocks: Vec::new(),
+            provider_version_constraints: HashMap::new(),
+            skipped_modules: Vec::new(),
+            unresolved_modules: Vec::new(),
+            module_tree: None,
+        }
+    }
+
+    fn block(block_type: BlockType, type_name: &str, address: &str) -> TerraformBlock {
+        TerraformBlock {
+            block_type,
+            type_name: type_name.to_string(),
+            name: "main".to_string(),
+            provider_config_key: "aws".to_string(),
+            attribute_values: HashMap::new(),
+            present_attributes: HashSet::new(),
+            address: address.to_string(),
+            managed_policy_arns: Vec::new(),
+            instances: Instances::Single,
+        }
+    }
+
+    #[test]
+    fn display_address_appends_instances() {
+        let with_instances = |instances: Instances| TerraformBlock {
+            instances,
+            ..block(BlockType::Resource, "aws_s3_bucket", "aws_s3_bucket.logs")
+        };
+
+        assert_eq!(
+            with_instances(Instances::Single).display_address(),
+            "aws_s3_bucket.logs"
+        );
+        assert_eq!(
+            with_instances(Instances::Count(3)).display_address(),
+            "aws_s3_bucket.logs[0..3]"
+        );
+        assert_eq!(
+            with_instances(Instances::ForEach(vec!["a".to_string(), "b".to_string()]))
+                .display_address(),
+            "aws_s3_bucket.logs[\"a\", \"b\"]"
+        );
+        assert_eq!(
+            with_instances(Instances::Unknown).display_address(),
+            "aws_s3_bucket.logs[*]"
+        );
+    }
+
+    #[test]
+    fn managed_policy_attachments_groups_addresses_by_arn() {
+        let attachment = |address: &str, arns: &[&str]| TerraformBlock {
+            managed_policy_arns: arns.iter().map(|arn| arn.to_string()).collect(),
+            ..block(
+                BlockType::Resource,
+                "aws_iam_role_policy_attachment",
+                address,
+            )
+        };
+        let config = config_with_blocks(vec![
+            attachment(
+                "aws_iam_role_policy_attachment.read",
+                &["arn:aws:iam::aws:policy/AmazonS3ReadOnlyAccess"],
+            ),
+            attachment(
+                "module.ci.aws_iam_role_policy_attachment.read",
+                &["arn:aws:iam::aws:policy/AmazonS3ReadOnlyAccess"],
+            ),
+            attachment(
+                "aws_iam_role_policy_attachment.ssm",
+                &["arn:aws:iam::aws:policy/AmazonSSMManagedInstanceCore"],
+            ),
+            block(BlockType::Resource, "aws_s3_bucket", "aws_s3_bucket.main"),
+        ]);
+
+        assert_eq!(
+            config.managed_policy_attachments(),
+            vec![
+                (
+                    "arn:aws:iam::aws:policy/AmazonS3ReadOnlyAccess".to_string(),
+                    vec![
+                        "aws_iam_role_policy_attachment.read".to_string(),
+                        "module.ci.aws_iam_role_policy_attachment.read".to_string(),
+                    ]
+                ),
+                (
+                    "arn:aws:iam::aws:policy/AmazonSSMManagedInstanceCore".to_string(),
+                    vec!["aws_iam_role_policy_attachment.ssm".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_forbidden_types_lists_forbidden_resources() {
+        let config = config_with_blocks(vec![
+            block(BlockType::Resource, "aws_s3_bucket", "aws_s3_bucket.main"),
+            block(
+                BlockType::Resource,
+                "aws_iam_user",
+                "module.ci.aws_iam_user.bot",
+            ),
+            block(BlockType::Resource, "aws_iam_user", "aws_iam_user.admin"),
+        ]);
+
+        let result = config.check_forbidden_types(&["aws_iam_user".to_string()]);
+
+        let Err(TerraformError::ForbiddenResources(addresses)) = result else {
+            panic!("expected forbidden resources error");
+        };
+        assert_eq!(addresses, "aws_iam_user.admin, module.ci.aws_iam_user.bot");
+    }
+
+    #[test]
+    fn check_forbidden_types_succeeds_without_forbidden_resources() {
+        let config = config_with_blocks(vec![
+            block(BlockType::Resource, "aws_s3_bucket", "aws_s3_bucket.main"),
+            // Reading an IAM user is not creating one
+            block(
+                BlockType::Data,
+                "aws_iam_user",
+                "data.aws_iam_user.existing",
+            ),
+        ]);
+
+        assert!(
+            config
+                .check_forbidden_types(&["aws_iam_user".to_string()])
+                .is_ok()
+        );
+        assert!(config.check_forbidden_types(&[]).is_ok());
+    }
+
+    #[test]
+    fn forbidden_resources_includes_unmapped_blocks() {
+        let mut config = config_with_blocks(Vec::new());
+        config.unmapped_blocks.push(block(
+            BlockType::Resource,
+            "aws_iam_user",
+            "aws_iam_user.orphan",
+        ));
+
+        assert_eq!(
+            config.forbidden_resources(&["aws_iam_user".to_string()]),
+            vec!["aws_iam_user.orphan"]
+        );
+    }
+
+    #[test]
+    fn redact_account_ids_redacts_role_and_policy_arns() {
+        let mut attachment = block(
+            BlockType::Resource,
+            "aws_iam_role_policy_attachment",
+            "aws_iam_role_policy_attachment.custom",
+        );
+        attachment.managed_policy_arns =
+            vec!["arn:aws:iam::123456789012:policy/DeployBoundary".to_string()];
+        let mut config = config_with_blocks(vec![attachment]);
+        config
+            .provider_groups
+            .get_mut("DefaultDeployer")
+            .unwrap()
+            .role_arn = Some("arn:aws:iam::123456789012:role/Deploy".to_string());
+
+        config.redact_account_ids();
+
+        let group = &config.provider_groups["DefaultDeployer"];
+        assert_eq!(
+            group.role_arn.as_deref(),
+            Some("arn:aws:iam::XXXXXXXXXXXX:role/Deploy")
+        );
+        assert_eq!(
+            group.blocks[0].managed_policy_arns,
+            vec!["arn:aws:iam::XXXXXXXXXXXX:policy/DeployBoundary"]
+        );
+    }
 }
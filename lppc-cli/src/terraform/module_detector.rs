@@ -1,12 +1,15 @@
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 
+use hcl::Expression;
 use log::debug;
 use regex::Regex;
 use serde::Deserialize;
 use walkdir::WalkDir;
 
+use super::files::is_config_file;
 use super::runner::TerraformError;
 
 /// Represents the source type of a terraform module with detailed parsing.
@@ -93,7 +96,11 @@ impl ModuleSourceType {
         // Find subdir delimiter "//" that's not part of a URL scheme.
         let (url, subdir) = Self::split_url_and_subdir(url_part);
 
-        Self::Git { url, ref_spec, subdir }
+        Self::Git {
+            url,
+            ref_spec,
+            subdir,
+        }
     }
 
     /// Splits a URL and subdirectory, handling the "//" delimiter.
@@ -215,9 +222,16 @@ impl ModuleSourceType {
                     .as_ref()
                     .map(|s| format!("//{}", s))
                     .unwrap_or_default();
-                format!("registry: {}{}/{}/{}{}", host, namespace, name, provider, subdir_str)
+                format!(
+                    "registry: {}{}/{}/{}{}",
+                    host, namespace, name, provider, subdir_str
+                )
             }
-            Self::Git { url, ref_spec, subdir } => {
+            Self::Git {
+                url,
+                ref_spec,
+                subdir,
+            } => {
                 let ref_info = ref_spec.as_deref().unwrap_or("default");
                 let subdir_str = subdir
                     .as_ref()
@@ -386,7 +400,6 @@ impl ModulesManifest {
     }
 
     /// Returns all module entries.
-    #[allow(dead_code)]
     pub fn entries(&self) -> &[ModuleEntry] {
         &self.entries
     }
@@ -405,7 +418,10 @@ impl ModulesManifest {
     /// Returns the count of remote modules.
     #[allow(dead_code)]
     pub fn remote_module_count(&self) -> usize {
-        self.entries.iter().filter(|e| e.source_type.is_remote()).count()
+        self.entries
+            .iter()
+            .filter(|e| e.source_type.is_remote())
+            .count()
     }
 
     /// Finds a module entry by its key.
@@ -413,6 +429,27 @@ impl ModulesManifest {
         self.entries.iter().find(|m| m.key == module_key)
     }
 
+    /// Finds a module call the manifest does not list, e.g. one added after
+    /// `terraform init` wrote it.
+    ///
+    /// Checks the module calls declared in `dir`, the directory of the module
+    /// `module_key` ("" for the root module), and those of every called module.
+    ///
+    /// Returns the key of the first unlisted module call, or None if all are listed.
+    pub fn find_missing_module_call(&self, dir: &Path, module_key: &str) -> Option<String> {
+        for name in module_call_names(dir) {
+            let child_key = Self::build_child_key(module_key, &name);
+            let Some(child_dir) = self.find_module_dir(&child_key) else {
+                return Some(child_key);
+            };
+            if let Some(missing) = self.find_missing_module_call(&child_dir, &child_key) {
+                return Some(missing);
+            }
+        }
+
+        None
+    }
+
     /// Builds a module key from a parent key and child module name.
     ///
     /// For example:
@@ -431,7 +468,7 @@ impl ModulesManifest {
 ///
 /// Uses a two-phase approach:
 /// 1. Primary: Parse `.terraform/modules/modules.json` if it exists
-/// 2. Fallback: Parse `.tf` files with regex if modules.json doesn't exist
+/// 2. Fallback: Scan `module` blocks in `.tf` files if modules.json doesn't exist
 pub fn detect_module_sources(working_dir: &Path) -> Result<Vec<ModuleSource>, TerraformError> {
     // Try primary method first
     if let Some(sources) = parse_modules_json(working_dir) {
@@ -442,8 +479,8 @@ pub fn detect_module_sources(working_dir: &Path) -> Result<Vec<ModuleSource>, Te
         return Ok(sources);
     }
 
-    // Fallback to regex parsing
-    debug!("modules.json not found, falling back to regex parsing of .tf files");
+    // Fallback to scanning .tf files
+    debug!("modules.json not found, falling back to scanning .tf files");
     parse_tf_files_for_modules(working_dir)
 }
 
@@ -465,28 +502,30 @@ fn parse_modules_json(working_dir: &Path) -> Option<Vec<ModuleSource>> {
     Some(sources)
 }
 
-/// Maximum size of a .tf file to process with regex (10 MB).
-/// Files larger than this are skipped to prevent ReDoS attacks.
+/// Maximum size of a .tf file to scan for module blocks (10 MB).
+/// Files larger than this are skipped to prevent resource exhaustion.
 const MAX_TF_FILE_SIZE: u64 = 10 * 1024 * 1024;
 
-/// Parses .tf files with regex to extract module sources.
+/// Pattern used when a file cannot be parsed as HCL.
+/// Handles: source = "../../modules/foo"
+///          source = "./local/module"
+/// The (?s) flag makes . match newlines so we can handle multi-line module blocks.
+/// It cannot see past a `}` inside the module body, which is why HCL parsing is preferred.
+static MODULE_SOURCE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)module\s+"[^"]+"\s*\{[^}]*?source\s*=\s*"([^"]+)""#).expect("valid regex")
+});
+
+/// Scans .tf files for `module` blocks to extract module sources.
 ///
 /// This is the fallback method when modules.json doesn't exist (e.g., in CI/CD).
 fn parse_tf_files_for_modules(working_dir: &Path) -> Result<Vec<ModuleSource>, TerraformError> {
     let mut sources = Vec::new();
     let mut seen_sources = HashSet::new();
 
-    // Pattern to match module source declarations
-    // Handles: source = "../../modules/foo"
-    //          source = "./local/module"
-    // The (?s) flag makes . match newlines so we can handle multi-line module blocks
-    let pattern = Regex::new(r#"(?s)module\s+"[^"]+"\s*\{[^}]*?source\s*=\s*"([^"]+)""#)
-        .expect("valid regex");
-
     for entry in WalkDir::new(working_dir)
         .into_iter()
         .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().is_some_and(|ext| ext == "tf"))
+        .filter(|e| is_config_file(e.path()))
     {
         // Skip .terraform directory
         if entry
@@ -497,38 +536,103 @@ fn parse_tf_files_for_modules(working_dir: &Path) -> Result<Vec<ModuleSource>, T
             continue;
         }
 
-        // Check file size to prevent ReDoS on maliciously large files
-        if let Ok(metadata) = entry.metadata() {
-            if metadata.len() > MAX_TF_FILE_SIZE {
-                debug!(
-                    "Skipping oversized .tf file ({} bytes): {:?}",
-                    metadata.len(),
-                    entry.path()
-                );
-                continue;
-            }
+        // Check file size to avoid reading maliciously large files
+        if let Ok(metadata) = entry.metadata()
+            && metadata.len() > MAX_TF_FILE_SIZE
+        {
+            debug!(
+                "Skipping oversized .tf file ({} bytes): {:?}",
+                metadata.len(),
+                entry.path()
+            );
+            continue;
         }
 
         let content = fs::read_to_string(entry.path()).map_err(TerraformError::Io)?;
 
-        for cap in pattern.captures_iter(&content) {
-            if let Some(source_match) = cap.get(1) {
-                let source_str = source_match.as_str();
-                // Deduplicate sources
-                if seen_sources.insert(source_str.to_string()) {
-                    sources.push(ModuleSource::from_source_string(source_str));
-                }
+        let file_sources = match hcl::parse(&content) {
+            Ok(body) => module_sources_from_body(&body),
+            Err(e) => {
+                debug!(
+                    "Cannot parse {:?} as HCL ({}), falling back to regex scan",
+                    entry.path(),
+                    e
+                );
+                module_sources_from_regex(&content)
+            }
+        };
+
+        for source_str in file_sources {
+            // Deduplicate sources
+            if seen_sources.insert(source_str.clone()) {
+                sources.push(ModuleSource::from_source_string(&source_str));
             }
         }
     }
 
-    debug!(
-        "Detected {} module sources from .tf files via regex",
-        sources.len()
-    );
+    debug!("Detected {} module sources from .tf files", sources.len());
     Ok(sources)
 }
 
+/// Returns the literal `source` attribute of each top-level `module` block.
+fn module_sources_from_body(body: &hcl::Body) -> Vec<String> {
+    body.blocks()
+        .filter(|block| block.identifier.as_str() == "module")
+        .filter_map(|block| {
+            block
+                .body()
+                .attributes()
+                .find(|attr| attr.key.as_str() == "source")
+                .and_then(|attr| match &attr.expr {
+                    Expression::String(source) => Some(source.clone()),
+                    _ => None,
+                })
+        })
+        .collect()
+}
+
+/// Returns the names of the `module` blocks in the config files directly in `dir`.
+///
+/// Files that cannot be read or parsed are skipped; parsing reports them later.
+fn module_call_names(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut names = Vec::new();
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if !is_config_file(&path) || fs::metadata(&path).is_ok_and(|m| m.len() > MAX_TF_FILE_SIZE) {
+            continue;
+        }
+
+        let Some(body) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| hcl::parse(&content).ok())
+        else {
+            debug!("Cannot parse {:?} to look for module calls", path);
+            continue;
+        };
+
+        names.extend(
+            body.blocks()
+                .filter(|block| block.identifier.as_str() == "module")
+                .filter_map(|block| block.labels.first())
+                .map(|label| label.as_str().to_string()),
+        );
+    }
+
+    names
+}
+
+/// Returns module sources found by the regex pattern.
+fn module_sources_from_regex(content: &str) -> Vec<String> {
+    MODULE_SOURCE_PATTERN
+        .captures_iter(content)
+        .filter_map(|cap| cap.get(1))
+        .map(|source_match| source_match.as_str().to_string())
+        .collect()
+}
+
 /// Resolves external local modules from the detected sources.
 ///
 /// Returns a list of absolute paths to external modules that need to be copied.
@@ -542,13 +646,14 @@ pub fn resolve_external_modules(
     let working_dir_abs = working_dir.canonicalize().map_err(TerraformError::Io)?;
 
     for source in sources {
-        if source.is_local() && source.is_external_to(&working_dir_abs) {
-            if let Some(resolved_path) = source.resolve_path(&working_dir_abs) {
-                // Deduplicate paths
-                if seen_paths.insert(resolved_path.clone()) {
-                    debug!("Found external module: {:?}", resolved_path);
-                    external_paths.push(resolved_path);
-                }
+        if source.is_local()
+            && source.is_external_to(&working_dir_abs)
+            && let Some(resolved_path) = source.resolve_path(&working_dir_abs)
+        {
+            // Deduplicate paths
+            if seen_paths.insert(resolved_path.clone()) {
+                debug!("Found external module: {:?}", resolved_path);
+                external_paths.push(resolved_path);
             }
         }
     }
@@ -1158,6 +1263,132 @@ mod tests {
         assert_eq!(sources[0].source, "../modules/test");
     }
 
+    #[test]
+    fn detects_module_source_after_nested_braces() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // The regex stops at the first `}` and misses `source` here
+        let tf_content = r#"
+            module "tagged" {
+              tags = {
+                Team = "platform"
+              }
+              source = "./modules/tagged"
+            }
+        "#;
+
+        fs::write(temp_dir.path().join("main.tf"), tf_content).unwrap();
+
+        assert!(module_sources_from_regex(tf_content).is_empty());
+
+        let sources = parse_tf_files_for_modules(temp_dir.path()).unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].source, "./modules/tagged");
+    }
+
+    #[test]
+    fn ignores_source_attributes_outside_module_blocks() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let tf_content = r#"
+            data "archive_file" "lambda" {
+              type   = "zip"
+              source = "./not-a-module"
+            }
+
+            module "real" {
+              source = "./modules/real"
+            }
+        "#;
+
+        fs::write(temp_dir.path().join("main.tf"), tf_content).unwrap();
+
+        let sources = parse_tf_files_for_modules(temp_dir.path()).unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].source, "./modules/real");
+    }
+
+    #[test]
+    fn detects_module_sources_in_tofu_files() {
+        let temp_dir = TempDir::new().unwrap();
+
+        fs::write(
+            temp_dir.path().join("main.tofu"),
+            r#"module "vpc" { source = "./modules/vpc" }"#,
+        )
+        .unwrap();
+
+        let sources = parse_tf_files_for_modules(temp_dir.path()).unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].source, "./modules/vpc");
+    }
+
+    #[test]
+    fn falls_back_to_regex_for_unparseable_files() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Unbalanced braces make this invalid HCL
+        let tf_content = r#"
+            module "broken" {
+              source = "./modules/broken"
+        "#;
+
+        fs::write(temp_dir.path().join("main.tf"), tf_content).unwrap();
+
+        let sources = parse_tf_files_for_modules(temp_dir.path()).unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].source, "./modules/broken");
+    }
+
+    /// Compares the HCL block scan with the regex scan over many generated files.
+    ///
+    /// Run with `cargo test --release -- --ignored bench_module_scan --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_module_scan_many_files() {
+        const FILE_COUNT: usize = 500;
+        const MODULES_PER_FILE: usize = 20;
+
+        let temp_dir = TempDir::new().unwrap();
+        for file_index in 0..FILE_COUNT {
+            let mut content = String::new();
+            for module_index in 0..MODULES_PER_FILE {
+                content.push_str(&format!(
+                    "module \"m_{file_index}_{module_index}\" {{\n  \
+                       source = \"./modules/m_{file_index}_{module_index}\"\n  \
+                       name   = \"value\"\n}}\n\n\
+                     resource \"aws_s3_bucket\" \"b_{file_index}_{module_index}\" {{\n  \
+                       bucket = \"bucket\"\n}}\n\n"
+                ));
+            }
+            fs::write(
+                temp_dir.path().join(format!("file_{file_index}.tf")),
+                content,
+            )
+            .unwrap();
+        }
+
+        let start = std::time::Instant::now();
+        let sources = parse_tf_files_for_modules(temp_dir.path()).unwrap();
+        let hcl_elapsed = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let mut regex_count = 0;
+        for file_index in 0..FILE_COUNT {
+            let content =
+                fs::read_to_string(temp_dir.path().join(format!("file_{file_index}.tf"))).unwrap();
+            regex_count += module_sources_from_regex(&content).len();
+        }
+        let regex_elapsed = start.elapsed();
+
+        println!(
+            "{} files: hcl scan {:?}, regex scan {:?}",
+            FILE_COUNT, hcl_elapsed, regex_elapsed
+        );
+        assert_eq!(sources.len(), FILE_COUNT * MODULES_PER_FILE);
+        assert_eq!(regex_count, FILE_COUNT * MODULES_PER_FILE);
+    }
+
     #[test]
     fn modules_manifest_load_parses_correctly() {
         let temp_dir = TempDir::new().unwrap();
@@ -1216,6 +1447,40 @@ mod tests {
         assert!(manifest.find_module_dir("nonexistent").is_none());
     }
 
+    #[test]
+    fn modules_manifest_finds_module_calls_added_after_init() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".terraform/modules")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("modules/budgets")).unwrap();
+        fs::write(
+            temp_dir.path().join(".terraform/modules/modules.json"),
+            r#"{"Modules":[
+                {"Key":"","Source":"","Dir":"."},
+                {"Key":"budgets","Source":"./modules/budgets","Dir":"modules/budgets"}
+            ]}"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"module "budgets" { source = "./modules/budgets" }"#,
+        )
+        .unwrap();
+
+        let manifest = ModulesManifest::load(temp_dir.path()).unwrap();
+        assert_eq!(manifest.find_missing_module_call(temp_dir.path(), ""), None);
+
+        // A module call in a called module counts as well
+        fs::write(
+            temp_dir.path().join("modules/budgets/main.tf"),
+            r#"module "alerts" { source = "./alerts" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.find_missing_module_call(temp_dir.path(), ""),
+            Some("budgets.alerts".to_string())
+        );
+    }
+
     #[test]
     fn modules_manifest_build_child_key() {
         // From root
@@ -0,0 +1,149 @@
+//! Structural view of the module hierarchy.
+//!
+//! This module provides `ModuleNode`, which the HCL parser fills while it
+//! recursively parses the root module and its module calls. Each node records
+//! the module's source (from `.terraform/modules/modules.json`), the providers
+//! it receives via `providers = { ... }`, and the number of AWS blocks declared
+//! directly in it. `render()` prints the hierarchy as a tree for `--print-tree`.
+
+use std::fmt::Write;
+
+/// A module in the module hierarchy, with its child module calls.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleNode {
+    /// Module call name (empty for the root module)
+    pub name: String,
+
+    /// Human-readable source description (e.g., "local: ./modules/vpc"),
+    /// `None` for the root module or modules missing from the manifest
+    pub source: Option<String>,
+
+    /// Provider mappings passed by the module call as `(local key, parent key)`,
+    /// sorted by local key (e.g., `("aws", "aws.network")`)
+    pub providers: Vec<(String, String)>,
+
+    /// Number of AWS blocks declared directly in this module (excluding children)
+    pub block_count: usize,
+
+    /// Child module calls that were parsed, in declaration order
+    pub children: Vec<ModuleNode>,
+}
+
+impl ModuleNode {
+    /// Returns the number of AWS blocks in this module and all of its children.
+    pub fn total_block_count(&self) -> usize {
+        self.block_count
+            + self
+                .children
+                .iter()
+                .map(ModuleNode::total_block_count)
+                .sum::<usize>()
+    }
+
+    /// Renders the hierarchy as a tree, one module per line.
+    ///
+    /// ```text
+    /// root (1 block)
+    /// ├── module.network [local: ./modules/network] providers: aws = aws.network (2 blocks)
+    /// │   └── module.subnets [local: ./subnets] (4 blocks)
+    /// └── module.dns [registry: terraform-aws-modules/route53/aws] (1 block)
+    /// ```
+    pub fn render(&self) -> String {
+        let mut output = format!("root ({})\n", Self::block_label(self.block_count));
+        self.render_children(&mut output, "");
+        output
+    }
+
+    fn render_children(&self, output: &mut String, prefix: &str) {
+        let count = self.children.len();
+        for (i, child) in self.children.iter().enumerate() {
+            let is_last = i == count - 1;
+            let connector = if is_last { "└── " } else { "├── " };
+
+            let _ = writeln!(output, "{}{}{}", prefix, connector, child.label());
+
+            let child_prefix = if is_last {
+                format!("{}    ", prefix)
+            } else {
+                format!("{}│   ", prefix)
+            };
+            child.render_children(output, &child_prefix);
+        }
+    }
+
+    /// Formats a single line: address, source, provider mappings, and block count.
+    fn label(&self) -> String {
+        let mut label = format!("module.{}", self.name);
+
+        if let Some(source) = &self.source {
+            let _ = write!(label, " [{}]", source);
+        }
+
+        if !self.providers.is_empty() {
+            let mappings: Vec<String> = self
+                .providers
+                .iter()
+                .map(|(local, parent)| format!("{} = {}", local, parent))
+                .collect();
+            let _ = write!(label, " providers: {}", mappings.join(", "));
+        }
+
+        let _ = write!(label, " ({})", Self::block_label(self.block_count));
+        label
+    }
+
+    fn block_label(count: usize) -> String {
+        if count == 1 {
+            "1 block".to_string()
+        } else {
+            format!("{} blocks", count)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, block_count: usize, children: Vec<ModuleNode>) -> ModuleNode {
+        ModuleNode {
+            name: name.to_string(),
+            source: None,
+            providers: Vec::new(),
+            block_count,
+            children,
+        }
+    }
+
+    #[test]
+    fn render_root_without_modules() {
+        assert_eq!(node("", 3, Vec::new()).render(), "root (3 blocks)\n");
+    }
+
+    #[test]
+    fn render_nested_modules_with_connectors() {
+        let mut network = node("network", 2, vec![node("subnets", 4, Vec::new())]);
+        network.source = Some("local: ./modules/network".to_string());
+        network.providers = vec![("aws".to_string(), "aws.network".to_string())];
+        let root = node("", 1, vec![network, node("dns", 1, Vec::new())]);
+
+        assert_eq!(
+            root.render(),
+            "root (1 block)\n\
+             ├── module.network [local: ./modules/network] providers: aws = aws.network (2 blocks)\n\
+             │   └── module.subnets (4 blocks)\n\
+             └── module.dns (1 block)\n"
+        );
+    }
+
+    #[test]
+    fn total_block_count_includes_children() {
+        let root = node(
+            "",
+            1,
+            vec![node("network", 2, vec![node("subnets", 4, Vec::new())])],
+        );
+
+        assert_eq!(root.total_block_count(), 7);
+    }
+}
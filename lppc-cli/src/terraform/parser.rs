@@ -2,7 +2,7 @@ use log::{debug, warn};
 use std::collections::HashMap;
 
 use super::json_types::{Module, TerraformPlan};
-use super::model::{ProviderGroup, TerraformBlock, TerraformConfig};
+use super::model::{Instances, ProviderGroup, TerraformBlock, TerraformConfig};
 use super::provider::{AwsProvider, ProviderRegistry};
 
 /// Parses terraform JSON into our internal model
@@ -30,6 +30,10 @@ impl TerraformParser {
         Ok(TerraformConfig {
             provider_groups,
             unmapped_blocks,
+            provider_version_constraints: HashMap::new(),
+            skipped_modules: Vec::new(),
+            unresolved_modules: Vec::new(),
+            module_tree: None,
         })
     }
 
@@ -95,8 +99,11 @@ impl TerraformParser {
                 type_name: resource.resource_type.clone(),
                 name: resource.name.clone(),
                 provider_config_key: resource.provider_config_key.clone(),
+                attribute_values: HashMap::new(),
                 present_attributes: resource.collect_attribute_paths(),
                 address,
+                managed_policy_arns: Vec::new(),
+                instances: Instances::Single,
             });
         }
 
@@ -151,6 +158,7 @@ impl TerraformParser {
                         ProviderGroup {
                             output_name: name,
                             role_arn: provider.and_then(|p| p.role_arn.clone()),
+                            has_custom_endpoints: false,
                             blocks: Vec::new(),
                         }
                     });
@@ -1,16 +1,18 @@
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use log::debug;
+use log::{debug, info};
 use tempfile::TempDir;
 use walkdir::WalkDir;
 
-use super::hcl_parser::HclParser;
+use super::hcl_parser::{HclParser, ParseOptions};
 use super::model::TerraformConfig;
 use super::module_detector::{
-    detect_module_sources, find_common_ancestor, resolve_external_modules,
+    ModulesManifest, detect_module_sources, find_common_ancestor, resolve_external_modules,
 };
 use super::runner::{TerraformError, TerraformRunner};
+use crate::timings::Timings;
 
 /// Result of executing terraform commands (legacy, for backwards compatibility).
 #[deprecated(
@@ -66,6 +68,60 @@ impl CopyPlan {
 /// - Faster execution
 pub struct PlanExecutor {
     runner: TerraformRunner,
+    parse_options: ParseOptions,
+    keep_sandbox: Option<PathBuf>,
+    no_init: bool,
+}
+
+/// Marker file identifying a kept sandbox, so it can be replaced by the next run.
+const SANDBOX_MARKER: &str = ".lppc-sandbox";
+
+/// Isolated directory the Terraform files are copied to.
+enum Sandbox {
+    /// Random temporary directory, removed when dropped.
+    Temporary(TempDir),
+    /// Fixed directory that is kept for inspection after the run.
+    Kept(PathBuf),
+}
+
+impl Sandbox {
+    /// Creates a temporary sandbox, or prepares the kept sandbox at `keep_at`.
+    ///
+    /// A kept sandbox from a previous run (recognized by its marker file) is
+    /// replaced. Any other non-empty directory is rejected so that user files
+    /// are never deleted.
+    fn create(keep_at: Option<&Path>) -> Result<Self, TerraformError> {
+        let Some(path) = keep_at else {
+            let temp_dir = TempDir::with_prefix("lppc-").map_err(TerraformError::Io)?;
+            return Ok(Self::Temporary(temp_dir));
+        };
+
+        if path.exists() {
+            let is_empty_dir = path.is_dir() && fs::read_dir(path)?.next().is_none();
+
+            if path.join(SANDBOX_MARKER).is_file() {
+                debug!("Removing previous sandbox at {:?}", path);
+                fs::remove_dir_all(path)?;
+            } else if !is_empty_dir {
+                return Err(TerraformError::CopyFailed(format!(
+                    "Sandbox path {} exists and is not an empty directory or a previous lppc sandbox",
+                    path.display()
+                )));
+            }
+        }
+
+        fs::create_dir_all(path)?;
+        fs::write(path.join(SANDBOX_MARKER), "")?;
+
+        Ok(Self::Kept(path.to_path_buf()))
+    }
+
+    fn path(&self) -> &Path {
+        match self {
+            Self::Temporary(temp_dir) => temp_dir.path(),
+            Self::Kept(path) => path,
+        }
+    }
 }
 
 impl PlanExecutor {
@@ -73,9 +129,41 @@ impl PlanExecutor {
     pub fn new() -> Result<Self, TerraformError> {
         Ok(Self {
             runner: TerraformRunner::new()?,
+            parse_options: ParseOptions::default(),
+            keep_sandbox: None,
+            no_init: false,
         })
     }
 
+    /// Sets the options used when parsing the HCL files.
+    pub fn with_parse_options(mut self, parse_options: ParseOptions) -> Self {
+        self.parse_options = parse_options;
+        self
+    }
+
+    /// Sets the maximum duration of `terraform init`; `None` disables the timeout.
+    pub fn with_init_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.runner = self.runner.with_init_timeout(timeout);
+        self
+    }
+
+    /// Keeps the sandbox at the given path after execution instead of deleting it.
+    ///
+    /// The sandbox is kept even if execution fails, so it can be inspected.
+    pub fn with_keep_sandbox(mut self, path: Option<PathBuf>) -> Self {
+        self.keep_sandbox = path;
+        self
+    }
+
+    /// Skips `terraform init` even if the configuration calls modules (`--no-init`).
+    ///
+    /// Without init there is no modules manifest, so the resources inside
+    /// modules are not analyzed (the parser warns about them).
+    pub fn with_no_init(mut self, no_init: bool) -> Self {
+        self.no_init = no_init;
+        self
+    }
+
     /// Executes terraform init and parses HCL files directly.
     ///
     /// All operations are performed in an isolated temporary directory.
@@ -85,11 +173,24 @@ impl PlanExecutor {
     /// 1. Detecting module sources from terraform configuration
     /// 2. Identifying external modules (outside the working directory)
     /// 3. Copying both the working directory and external modules with preserved structure
-    /// 4. Running `terraform init -backend=false` to download registry/git modules
+    /// 4. Running `terraform init -backend=false` to download registry/git modules,
+    ///    unless the configuration calls no modules or `with_no_init` is set. If
+    ///    the working directory was already initialized, its `.terraform/modules`
+    ///    is copied instead
     /// 5. Parsing all HCL files directly (including downloaded modules)
     ///
     /// Returns `None` if no Terraform files are found in the working directory.
     pub fn execute(&self, working_dir: &Path) -> Result<Option<TerraformConfig>, TerraformError> {
+        self.execute_timed(working_dir, &mut Timings::new())
+    }
+
+    /// Like [`PlanExecutor::execute`], but records the duration of each stage
+    /// (`detect modules`, `copy`, `init` if it runs, `parse`) in `timings`.
+    pub fn execute_timed(
+        &self,
+        working_dir: &Path,
+        timings: &mut Timings,
+    ) -> Result<Option<TerraformConfig>, TerraformError> {
         // Check for .tf files first
         if !TerraformRunner::has_terraform_files(working_dir)? {
             debug!("No Terraform files found in working directory");
@@ -97,41 +198,88 @@ impl PlanExecutor {
         }
 
         // Detect module sources from terraform configuration
-        let module_sources = detect_module_sources(working_dir)?;
-        debug!("Detected {} module sources", module_sources.len());
+        let (module_sources, external_modules, initialized_modules) =
+            timings.time("detect modules", || -> Result<_, TerraformError> {
+                let module_sources = detect_module_sources(working_dir)?;
+                debug!("Detected {} module sources", module_sources.len());
+
+                // Resolve external local modules
+                let external_modules = resolve_external_modules(working_dir, &module_sources)?;
+                debug!("Found {} external local modules", external_modules.len());
+
+                Ok((
+                    module_sources,
+                    external_modules,
+                    Self::initialized_modules_dir(working_dir),
+                ))
+            })?;
 
-        // Resolve external local modules
-        let external_modules = resolve_external_modules(working_dir, &module_sources)?;
-        debug!("Found {} external local modules", external_modules.len());
+        // Create isolated sandbox directory
+        let sandbox = Sandbox::create(self.keep_sandbox.as_deref())?;
 
-        // Plan the copy structure
-        let copy_plan = self.plan_copy_structure(working_dir, &external_modules)?;
+        match &sandbox {
+            Sandbox::Temporary(_) => {
+                debug!("Created isolated execution directory: {:?}", sandbox.path())
+            }
+            Sandbox::Kept(path) => info!("Sandbox will be kept at {}", path.display()),
+        }
 
-        // Create isolated temp directory
-        let temp_dir = TempDir::with_prefix("lppc-").map_err(TerraformError::Io)?;
+        let execution_dir = timings.time("copy", || -> Result<_, TerraformError> {
+            // Plan the copy structure
+            let copy_plan = self.plan_copy_structure(working_dir, &external_modules)?;
 
-        debug!(
-            "Created isolated execution directory: {:?}",
-            temp_dir.path()
-        );
+            // Execute the copy plan
+            self.execute_copy_plan(&copy_plan, sandbox.path())?;
 
-        // Execute the copy plan
-        self.execute_copy_plan(&copy_plan, temp_dir.path())?;
+            // Get the execution directory for terraform commands
+            let execution_dir = copy_plan.terraform_execution_dir(sandbox.path());
 
-        // Get the execution directory for terraform commands
-        let execution_dir = copy_plan.terraform_execution_dir(temp_dir.path());
+            // Remove state files from the working directory copy
+            Self::clean_terraform_state(&execution_dir)?;
 
-        // Remove state files from the working directory copy
-        Self::clean_terraform_state(&execution_dir)?;
+            // Reuse the modules of a previous init instead of downloading them again
+            if let Some(modules_dir) = &initialized_modules {
+                Self::copy_terraform_files(
+                    modules_dir,
+                    &execution_dir.join(".terraform").join("modules"),
+                )?;
+            }
 
-        // Log the temp directory structure in verbose mode
-        log_directory_tree(temp_dir.path(), "Prepared temp directory structure");
+            Ok(execution_dir)
+        })?;
 
-        // Run terraform init in the execution directory (downloads modules, no backend)
-        self.runner.init(&execution_dir)?;
+        // Log the temp directory structure in verbose mode
+        log_directory_tree(sandbox.path(), "Prepared temp directory structure");
+
+        // Run terraform init in the execution directory (downloads modules, no backend).
+        // Local modules are located through the manifest init writes as well, so
+        // only a root-module-only configuration can be parsed without it.
+        if module_sources.is_empty() {
+            debug!("No module calls found, skipping terraform init");
+        } else if let Some(modules_dir) = &initialized_modules {
+            info!(
+                "Using the modules of a previous terraform init in {}, skipping terraform init",
+                modules_dir.display()
+            );
+        } else if self.no_init {
+            let remote_count = module_sources
+                .iter()
+                .filter(|source| source.source_type.is_remote())
+                .count();
+            info!(
+                "Skipping terraform init (--no-init): {} module call(s), {} of them remote, are not analyzed",
+                module_sources.len(),
+                remote_count
+            );
+        } else {
+            timings.time("init", || self.runner.init(&execution_dir))?;
+        }
 
         // Parse HCL files directly (no terraform plan!)
-        let config = HclParser::parse_directory(&execution_dir)
+        let config = timings
+            .time("parse", || {
+                HclParser::parse_directory_with_options(&execution_dir, &self.parse_options)
+            })
             .map_err(|e| TerraformError::ParseFailed(e.to_string()))?;
 
         debug!(
@@ -324,6 +472,35 @@ impl PlanExecutor {
         Ok(())
     }
 
+    /// Returns the `.terraform/modules` directory of a previous `terraform init`
+    /// in `working_dir`, if its `modules.json` is valid, every module directory
+    /// it lists exists, and it lists every module call of the configuration.
+    fn initialized_modules_dir(working_dir: &Path) -> Option<PathBuf> {
+        let manifest = ModulesManifest::load(working_dir)?;
+
+        if let Some(entry) = manifest
+            .entries()
+            .iter()
+            .find(|entry| manifest.find_module_dir(&entry.key).is_none())
+        {
+            debug!(
+                "Ignoring stale modules.json: directory of module '{}' not found",
+                entry.key
+            );
+            return None;
+        }
+
+        if let Some(key) = manifest.find_missing_module_call(working_dir, "") {
+            debug!(
+                "Ignoring stale modules.json: module '{}' is not listed",
+                key
+            );
+            return None;
+        }
+
+        Some(working_dir.join(".terraform").join("modules"))
+    }
+
     /// Removes state files from the destination directory.
     ///
     /// This ensures we start with a clean slate for terraform init.
@@ -942,6 +1119,108 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn reuses_modules_of_a_previous_init_without_running_init() {
+        // Skip if terraform is not installed
+        if which::which("terraform").is_err() {
+            eprintln!("Skipping test: terraform not installed");
+            return;
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let vpc_dir = temp_dir.path().join(".terraform/modules/vpc");
+        fs::create_dir_all(&vpc_dir).unwrap();
+        fs::write(
+            temp_dir.path().join(".terraform/modules/modules.json"),
+            r#"{"Modules":[{"Key":"","Source":"","Dir":"."},{"Key":"vpc","Source":"registry.terraform.io/terraform-aws-modules/vpc/aws","Version":"5.0.0","Dir":".terraform/modules/vpc"}]}"#,
+        )
+        .unwrap();
+        fs::write(vpc_dir.join("main.tf"), r#"resource "aws_vpc" "this" {}"#).unwrap();
+        fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+            module "vpc" {
+              source  = "terraform-aws-modules/vpc/aws"
+              version = "5.0.0"
+            }
+            "#,
+        )
+        .unwrap();
+
+        let mut timings = Timings::new();
+        let config = PlanExecutor::new()
+            .unwrap()
+            .execute_timed(temp_dir.path(), &mut timings)
+            .unwrap()
+            .unwrap();
+
+        // The registry module cannot be downloaded here, so init must not run
+        let stages: Vec<&str> = timings.stages().iter().map(|(stage, _)| *stage).collect();
+        assert_eq!(stages, vec!["detect modules", "copy", "parse"]);
+        let addresses: Vec<&str> = config
+            .provider_groups
+            .values()
+            .flat_map(|g| g.blocks.iter().map(|b| b.address.as_str()))
+            .collect();
+        assert_eq!(addresses, vec!["module.vpc.aws_vpc.this"]);
+    }
+
+    #[test]
+    fn stale_modules_json_is_not_reused() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".terraform/modules")).unwrap();
+        fs::write(
+            temp_dir.path().join(".terraform/modules/modules.json"),
+            r#"{"Modules":[{"Key":"vpc","Source":"terraform-aws-modules/vpc/aws","Dir":".terraform/modules/vpc"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(PlanExecutor::initialized_modules_dir(temp_dir.path()), None);
+
+        fs::create_dir(temp_dir.path().join(".terraform/modules/vpc")).unwrap();
+        assert_eq!(
+            PlanExecutor::initialized_modules_dir(temp_dir.path()),
+            Some(temp_dir.path().join(".terraform/modules"))
+        );
+    }
+
+    #[test]
+    fn modules_json_missing_a_module_call_is_not_reused() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".terraform/modules/vpc")).unwrap();
+        fs::write(
+            temp_dir.path().join(".terraform/modules/modules.json"),
+            r#"{"Modules":[{"Key":"vpc","Source":"terraform-aws-modules/vpc/aws","Dir":".terraform/modules/vpc"}]}"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+            module "vpc" {
+              source = "terraform-aws-modules/vpc/aws"
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            PlanExecutor::initialized_modules_dir(temp_dir.path()),
+            Some(temp_dir.path().join(".terraform/modules"))
+        );
+
+        // A module call added after the init needs a new init
+        fs::write(
+            temp_dir.path().join("buckets.tf"),
+            r#"
+            module "buckets" {
+              source = "./modules/buckets"
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(PlanExecutor::initialized_modules_dir(temp_dir.path()), None);
+    }
+
     #[test]
     fn falls_back_to_regex_without_modules_json() {
         // Skip if terraform is not installed
@@ -1094,9 +1373,16 @@ mod tests {
 
         let group = config.provider_groups.get("BillingDeployer").unwrap();
         assert!(
-            group.blocks.iter().any(|b| b.type_name == "aws_budgets_budget"),
+            group
+                .blocks
+                .iter()
+                .any(|b| b.type_name == "aws_budgets_budget"),
             "Expected aws_budgets_budget in BillingDeployer group. Found: {:?}",
-            group.blocks.iter().map(|b| &b.type_name).collect::<Vec<_>>()
+            group
+                .blocks
+                .iter()
+                .map(|b| &b.type_name)
+                .collect::<Vec<_>>()
         );
 
         // Verify the resource address includes module prefix
@@ -1249,10 +1535,11 @@ mod tests {
         );
 
         // Primary bucket should be in us_east group
-        let us_east_group = config
-            .provider_groups
-            .values()
-            .find(|g| g.role_arn.as_ref().is_some_and(|r| r.contains("UsEastRole")));
+        let us_east_group = config.provider_groups.values().find(|g| {
+            g.role_arn
+                .as_ref()
+                .is_some_and(|r| r.contains("UsEastRole"))
+        });
         assert!(us_east_group.is_some(), "Expected a group with UsEastRole");
 
         if let Some(group) = us_east_group {
@@ -1263,10 +1550,11 @@ mod tests {
         }
 
         // Secondary bucket should be in eu_west group
-        let eu_west_group = config
-            .provider_groups
-            .values()
-            .find(|g| g.role_arn.as_ref().is_some_and(|r| r.contains("EuWestRole")));
+        let eu_west_group = config.provider_groups.values().find(|g| {
+            g.role_arn
+                .as_ref()
+                .is_some_and(|r| r.contains("EuWestRole"))
+        });
         assert!(eu_west_group.is_some(), "Expected a group with EuWestRole");
 
         if let Some(group) = eu_west_group {
@@ -1276,4 +1564,273 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn keep_sandbox_persists_after_execute() {
+        // Skip if terraform is not installed
+        if which::which("terraform").is_err() {
+            eprintln!("Skipping test: terraform not installed");
+            return;
+        }
+
+        let working_dir = TempDir::new().unwrap();
+        fs::write(
+            working_dir.path().join("main.tf"),
+            r#"resource "aws_s3_bucket" "main" {}"#,
+        )
+        .unwrap();
+
+        let sandbox_parent = TempDir::new().unwrap();
+        let sandbox = sandbox_parent.path().join("sandbox");
+
+        let executor = PlanExecutor::new()
+            .unwrap()
+            .with_keep_sandbox(Some(sandbox.clone()));
+        let config = executor.execute(working_dir.path()).unwrap();
+
+        assert!(config.is_some());
+        assert!(sandbox.join("main.tf").is_file());
+        assert!(sandbox.join(SANDBOX_MARKER).is_file());
+
+        // A second run replaces the previous sandbox
+        fs::write(sandbox.join("stale.txt"), "").unwrap();
+        executor.execute(working_dir.path()).unwrap();
+
+        assert!(sandbox.join("main.tf").is_file());
+        assert!(!sandbox.join("stale.txt").exists());
+    }
+
+    #[test]
+    fn sandbox_create_rejects_non_empty_foreign_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("important.txt"), "keep me").unwrap();
+
+        let result = Sandbox::create(Some(temp_dir.path()));
+
+        assert!(matches!(result, Err(TerraformError::CopyFailed(_))));
+        assert!(temp_dir.path().join("important.txt").is_file());
+    }
+
+    #[test]
+    fn sandbox_create_uses_empty_existing_directory() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let sandbox = Sandbox::create(Some(temp_dir.path())).unwrap();
+
+        assert_eq!(sandbox.path(), temp_dir.path());
+        assert!(temp_dir.path().join(SANDBOX_MARKER).is_file());
+    }
+
+    #[test]
+    fn temporary_sandbox_is_removed_on_drop() {
+        let sandbox = Sandbox::create(None).unwrap();
+        let path = sandbox.path().to_path_buf();
+        assert!(path.is_dir());
+
+        drop(sandbox);
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn execute_timed_records_each_stage() {
+        // Skip if terraform is not installed
+        if which::which("terraform").is_err() {
+            eprintln!("Skipping test: terraform not installed");
+            return;
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("modules/bucket")).unwrap();
+        fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"module "bucket" { source = "./modules/bucket" }"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("modules/bucket/main.tf"),
+            r#"resource "aws_s3_bucket" "main" {}"#,
+        )
+        .unwrap();
+
+        let mut timings = Timings::new();
+        let config = PlanExecutor::new()
+            .unwrap()
+            .execute_timed(temp_dir.path(), &mut timings)
+            .unwrap();
+
+        assert!(config.is_some());
+        let stages: Vec<&str> = timings.stages().iter().map(|(stage, _)| *stage).collect();
+        assert_eq!(stages, vec!["detect modules", "copy", "init", "parse"]);
+    }
+
+    #[test]
+    fn execute_skips_init_without_module_calls() {
+        // Skip if terraform is not installed
+        if which::which("terraform").is_err() {
+            eprintln!("Skipping test: terraform not installed");
+            return;
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"resource "aws_s3_bucket" "main" {}"#,
+        )
+        .unwrap();
+
+        let mut timings = Timings::new();
+        let config = PlanExecutor::new()
+            .unwrap()
+            .execute_timed(temp_dir.path(), &mut timings)
+            .unwrap()
+            .unwrap();
+
+        let stages: Vec<&str> = timings.stages().iter().map(|(stage, _)| *stage).collect();
+        assert_eq!(stages, vec!["detect modules", "copy", "parse"]);
+        let blocks: usize = config
+            .provider_groups
+            .values()
+            .map(|g| g.blocks.len())
+            .sum();
+        assert_eq!(blocks, 1);
+    }
+
+    #[test]
+    fn no_init_skips_init_and_module_resources() {
+        // Skip if terraform is not installed
+        if which::which("terraform").is_err() {
+            eprintln!("Skipping test: terraform not installed");
+            return;
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("modules/bucket")).unwrap();
+        fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+resource "aws_sqs_queue" "root" {}
+
+module "bucket" { source = "./modules/bucket" }
+"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("modules/bucket/main.tf"),
+            r#"resource "aws_s3_bucket" "main" {}"#,
+        )
+        .unwrap();
+
+        let mut timings = Timings::new();
+        let config = PlanExecutor::new()
+            .unwrap()
+            .with_no_init(true)
+            .execute_timed(temp_dir.path(), &mut timings)
+            .unwrap()
+            .unwrap();
+
+        let stages: Vec<&str> = timings.stages().iter().map(|(stage, _)| *stage).collect();
+        assert_eq!(stages, vec!["detect modules", "copy", "parse"]);
+        let types: Vec<&str> = config
+            .provider_groups
+            .values()
+            .flat_map(|g| g.blocks.iter().map(|b| b.type_name.as_str()))
+            .collect();
+        assert_eq!(types, vec!["aws_sqs_queue"]);
+    }
+
+    #[test]
+    fn forwarded_alias_key_routes_to_parent_alias_group() {
+        // Skip if terraform is not installed
+        if which::which("terraform").is_err() {
+            eprintln!("Skipping test: terraform not installed");
+            return;
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+
+        // Submodule uses the default provider and the forwarded alias key
+        fs::create_dir_all(temp_dir.path().join("modules/replicated")).unwrap();
+        fs::write(
+            temp_dir.path().join("modules/replicated/main.tf"),
+            r#"
+            resource "aws_s3_bucket" "source" {
+              bucket = "source-bucket"
+            }
+
+            resource "aws_s3_bucket" "replica" {
+              provider = aws.replica
+              bucket   = "replica-bucket"
+            }
+            "#,
+        )
+        .unwrap();
+
+        // Root module forwards only the aliased key; `aws` is inherited implicitly
+        fs::write(
+            temp_dir.path().join("main.tf"),
+            r#"
+            terraform { required_version = ">= 1.0" }
+
+            provider "aws" {
+              region = "eu-central-1"
+              assume_role {
+                role_arn = "arn:aws:iam::111111111111:role/PrimaryRole"
+              }
+            }
+
+            provider "aws" {
+              alias  = "dr"
+              region = "eu-west-1"
+              assume_role {
+                role_arn = "arn:aws:iam::222222222222:role/DrRole"
+              }
+            }
+
+            module "replicated" {
+              source = "./modules/replicated"
+              providers = {
+                aws.replica = aws.dr
+              }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let executor = PlanExecutor::new().unwrap();
+        let config = executor.execute(temp_dir.path()).unwrap().unwrap();
+
+        let dr_group = config
+            .provider_groups
+            .values()
+            .find(|g| g.role_arn.as_ref().is_some_and(|r| r.contains("DrRole")))
+            .expect("Expected a group with DrRole");
+        let dr_addresses: Vec<&str> = dr_group.blocks.iter().map(|b| b.address.as_str()).collect();
+        assert_eq!(
+            dr_addresses,
+            vec!["module.replicated.aws_s3_bucket.replica"],
+            "Forwarded aws.replica should resolve to the root aws.dr provider"
+        );
+
+        let primary_group = config
+            .provider_groups
+            .values()
+            .find(|g| {
+                g.role_arn
+                    .as_ref()
+                    .is_some_and(|r| r.contains("PrimaryRole"))
+            })
+            .expect("Expected a group with PrimaryRole");
+        let primary_addresses: Vec<&str> = primary_group
+            .blocks
+            .iter()
+            .map(|b| b.address.as_str())
+            .collect();
+        assert_eq!(
+            primary_addresses,
+            vec!["module.replicated.aws_s3_bucket.source"]
+        );
+
+        assert!(config.unmapped_blocks.is_empty());
+    }
 }
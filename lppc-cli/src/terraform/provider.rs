@@ -72,7 +72,7 @@ impl AwsProvider {
         // If contains separators, split and convert each segment
         if input.contains('_') || input.contains('-') {
             return input
-                .split(|c| c == '_' || c == '-')
+                .split(['_', '-'])
                 .filter(|segment| !segment.is_empty())
                 .map(|segment| {
                     let mut chars = segment.chars();
@@ -196,7 +196,10 @@ mod tests {
 
     #[test]
     fn to_pascal_case_converts_snake_case() {
-        assert_eq!(AwsProvider::to_pascal_case("workload_network_test"), "WorkloadNetworkTest");
+        assert_eq!(
+            AwsProvider::to_pascal_case("workload_network_test"),
+            "WorkloadNetworkTest"
+        );
     }
 
     #[test]
@@ -206,18 +209,30 @@ mod tests {
 
     #[test]
     fn to_pascal_case_converts_screaming_snake_case() {
-        assert_eq!(AwsProvider::to_pascal_case("NETWORK_DEPLOYER"), "NetworkDeployer");
+        assert_eq!(
+            AwsProvider::to_pascal_case("NETWORK_DEPLOYER"),
+            "NetworkDeployer"
+        );
     }
 
     #[test]
     fn to_pascal_case_handles_mixed_separators() {
-        assert_eq!(AwsProvider::to_pascal_case("mixed_case-example"), "MixedCaseExample");
+        assert_eq!(
+            AwsProvider::to_pascal_case("mixed_case-example"),
+            "MixedCaseExample"
+        );
     }
 
     #[test]
     fn to_pascal_case_handles_consecutive_separators() {
-        assert_eq!(AwsProvider::to_pascal_case("double__underscore"), "DoubleUnderscore");
-        assert_eq!(AwsProvider::to_pascal_case("double--hyphen"), "DoubleHyphen");
+        assert_eq!(
+            AwsProvider::to_pascal_case("double__underscore"),
+            "DoubleUnderscore"
+        );
+        assert_eq!(
+            AwsProvider::to_pascal_case("double--hyphen"),
+            "DoubleHyphen"
+        );
     }
 
     #[test]
@@ -230,7 +245,10 @@ mod tests {
     #[test]
     fn to_pascal_case_preserves_existing_pascal_case() {
         // Existing PascalCase (mixed case) is preserved as-is
-        assert_eq!(AwsProvider::to_pascal_case("NetworkDeployer"), "NetworkDeployer");
+        assert_eq!(
+            AwsProvider::to_pascal_case("NetworkDeployer"),
+            "NetworkDeployer"
+        );
         assert_eq!(AwsProvider::to_pascal_case("DnsAccount"), "DnsAccount");
     }
 
@@ -1,15 +1,24 @@
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use log::{debug, info};
 use thiserror::Error;
 use which::which;
 
+use super::files::is_config_file;
+
 /// Executes terraform commands in a working directory.
 pub struct TerraformRunner {
     terraform_path: PathBuf,
+    init_timeout: Option<Duration>,
 }
 
+/// Interval at which a child process with a timeout is polled for completion.
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 impl TerraformRunner {
     /// Creates a new runner, verifying terraform is installed.
     pub fn new() -> Result<Self, TerraformError> {
@@ -17,10 +26,21 @@ impl TerraformRunner {
 
         debug!("Found terraform at: {:?}", terraform_path);
 
-        Ok(Self { terraform_path })
+        Ok(Self {
+            terraform_path,
+            init_timeout: None,
+        })
     }
 
-    /// Checks if the directory contains any Terraform files (.tf extension).
+    /// Sets the maximum duration of `terraform init`.
+    ///
+    /// `None` (the default) waits for terraform init indefinitely.
+    pub fn with_init_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.init_timeout = timeout;
+        self
+    }
+
+    /// Checks if the directory contains any Terraform files (.tf or .tofu extension).
     pub fn has_terraform_files(dir: &Path) -> Result<bool, TerraformError> {
         let entries = std::fs::read_dir(dir)?;
 
@@ -28,12 +48,8 @@ impl TerraformRunner {
             let entry = entry?;
             let path = entry.path();
 
-            if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    if ext == "tf" {
-                        return Ok(true);
-                    }
-                }
+            if path.is_file() && is_config_file(&path) {
+                return Ok(true);
             }
         }
 
@@ -47,13 +63,24 @@ impl TerraformRunner {
     pub fn init(&self, working_dir: &Path) -> Result<(), TerraformError> {
         info!("Running terraform init -backend=false in {:?}", working_dir);
 
-        let output = Command::new(&self.terraform_path)
+        let mut command = Command::new(&self.terraform_path);
+        command
             .args(["init", "-backend=false", "-input=false"])
-            .current_dir(working_dir)
-            .output()
-            .map_err(|e| {
+            .current_dir(working_dir);
+
+        let output = match self.init_timeout {
+            Some(timeout) => run_with_timeout(&mut command, timeout)
+                .map_err(|e| {
+                    TerraformError::CommandFailed(format!(
+                        "Failed to execute terraform init: {}",
+                        e
+                    ))
+                })?
+                .ok_or(TerraformError::Timeout(timeout))?,
+            None => command.output().map_err(|e| {
                 TerraformError::CommandFailed(format!("Failed to execute terraform init: {}", e))
-            })?;
+            })?,
+        };
 
         if output.status.success() {
             debug!("Terraform init successful");
@@ -130,6 +157,59 @@ impl TerraformRunner {
     }
 }
 
+/// Runs a command, killing it if it does not finish within `timeout`.
+///
+/// Stdout and stderr are drained on background threads so that a chatty
+/// child cannot block on a full pipe while it is being polled.
+///
+/// Returns `Ok(None)` if the command was killed because of the timeout.
+fn run_with_timeout(command: &mut Command, timeout: Duration) -> io::Result<Option<Output>> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let stdout_reader = child.stdout.take().map(spawn_reader);
+    let stderr_reader = child.stderr.take().map(spawn_reader);
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(Output {
+                status,
+                stdout: join_reader(stdout_reader),
+                stderr: join_reader(stderr_reader),
+            }));
+        }
+
+        if Instant::now() >= deadline {
+            debug!("Killing process after timeout of {:?}", timeout);
+            child.kill()?;
+            child.wait()?;
+            // The reader threads are not joined: grandchildren (e.g. provider
+            // plugins) may still hold the pipes open.
+            return Ok(None);
+        }
+
+        thread::sleep(TIMEOUT_POLL_INTERVAL);
+    }
+}
+
+fn spawn_reader<R: Read + Send + 'static>(mut pipe: R) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+fn join_reader(reader: Option<thread::JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default()
+}
+
 #[derive(Debug, Error)]
 pub enum TerraformError {
     #[error(
@@ -146,6 +226,9 @@ pub enum TerraformError {
     #[error("Terraform show failed:\n{0}")]
     ShowFailed(String),
 
+    #[error("Terraform init did not finish within {0:?} and was killed")]
+    Timeout(Duration),
+
     #[error("Failed to run terraform command: {0}")]
     CommandFailed(String),
 
@@ -155,6 +238,9 @@ pub enum TerraformError {
     #[error("Failed to parse terraform configuration: {0}")]
     ParseFailed(String),
 
+    #[error("Forbidden resource types found: {0}")]
+    ForbiddenResources(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -200,6 +286,14 @@ mod tests {
         assert!(!result);
     }
 
+    #[test]
+    fn has_terraform_files_with_tofu_file() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("main.tofu"), "").unwrap();
+        let result = TerraformRunner::has_terraform_files(temp_dir.path()).unwrap();
+        assert!(result);
+    }
+
     #[test]
     fn has_terraform_files_ignores_directories() {
         let temp_dir = TempDir::new().unwrap();
@@ -233,6 +327,64 @@ mod tests {
         assert!(message.contains("plan error details"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn run_with_timeout_kills_long_running_process() {
+        let started = Instant::now();
+
+        let output =
+            run_with_timeout(Command::new("sleep").arg("10"), Duration::from_millis(200)).unwrap();
+
+        assert!(output.is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_with_timeout_returns_output_of_fast_process() {
+        let output = run_with_timeout(Command::new("echo").arg("hello"), Duration::from_secs(10))
+            .unwrap()
+            .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+    }
+
+    #[test]
+    fn run_with_timeout_reports_missing_command() {
+        let result = run_with_timeout(
+            &mut Command::new("lppc-nonexistent-command"),
+            Duration::from_secs(1),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn init_succeeds_within_generous_timeout() {
+        // Skip if terraform is not installed
+        if which("terraform").is_err() {
+            eprintln!("Skipping test: terraform not installed");
+            return;
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("main.tf"), "locals {}\n").unwrap();
+
+        let runner = TerraformRunner::new()
+            .unwrap()
+            .with_init_timeout(Some(Duration::from_secs(120)));
+
+        runner.init(temp_dir.path()).unwrap();
+    }
+
+    #[test]
+    fn terraform_error_timeout_message() {
+        let error = TerraformError::Timeout(Duration::from_secs(30));
+        let message = error.to_string();
+        assert!(message.contains("did not finish within 30s"));
+    }
+
     #[test]
     fn terraform_error_show_failed_message() {
         let error = TerraformError::ShowFailed("show error details".to_string());
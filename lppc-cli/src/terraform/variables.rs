@@ -0,0 +1,206 @@
+//! Values of root module input variables.
+//!
+//! Provider `role_arn`s often interpolate input variables, e.g.
+//! `arn:aws:iam::${var.account_id}:role/Deploy`. This module collects the
+//! values Terraform would use without `-var` or `-var-file` arguments, so the
+//! references can be substituted before providers are grouped by role:
+//! `variable` defaults, overridden by `terraform.tfvars`, overridden by
+//! `*.auto.tfvars` files in lexical order (each also as `.json`).
+//!
+//! Only literal strings, numbers, and bools are used. References to other
+//! variables are left as they are.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use hcl::{Body, Expression};
+use log::{debug, warn};
+use regex::{Captures, Regex};
+
+/// Matches a `${var.name}` reference in a template string.
+static VARIABLE_REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\{\s*var\.([A-Za-z_][A-Za-z0-9_-]*)\s*\}").expect("valid regex")
+});
+
+/// Returns the literal `default` of each `variable` block in a body.
+pub fn variable_defaults(body: &Body) -> HashMap<String, String> {
+    body.blocks()
+        .filter(|block| block.identifier.as_str() == "variable")
+        .filter_map(|block| {
+            let name = block.labels.first()?.as_str().to_string();
+            let default = block
+                .body()
+                .attributes()
+                .find(|attr| attr.key.as_str() == "default")?;
+            Some((name, literal_value(&default.expr)?))
+        })
+        .collect()
+}
+
+/// Returns the literal assignments of the variable files Terraform loads
+/// automatically from `dir`, later files overriding earlier ones.
+///
+/// A file that cannot be read or parsed is skipped with a warning.
+pub fn tfvars_values(dir: &Path) -> HashMap<String, String> {
+    let mut auto_files: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".auto.tfvars") || name.ends_with(".auto.tfvars.json"))
+        .collect();
+    auto_files.sort();
+
+    let mut values = HashMap::new();
+    for name in ["terraform.tfvars", "terraform.tfvars.json"]
+        .into_iter()
+        .map(str::to_string)
+        .chain(auto_files)
+    {
+        let path = dir.join(&name);
+        if !path.is_file() {
+            continue;
+        }
+
+        match read_tfvars(&path) {
+            Ok(assignments) => {
+                debug!(
+                    "Loaded {} variable value(s) from {}",
+                    assignments.len(),
+                    name
+                );
+                values.extend(assignments);
+            }
+            Err(e) => warn!("Ignoring variable file {}: {}", path.display(), e),
+        }
+    }
+    values
+}
+
+/// Reads the literal assignments of an HCL or JSON variable file.
+fn read_tfvars(path: &Path) -> Result<HashMap<String, String>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let object: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        return Ok(object
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let value = match value {
+                    serde_json::Value::String(value) => value,
+                    serde_json::Value::Number(number) => number.to_string(),
+                    serde_json::Value::Bool(value) => value.to_string(),
+                    _ => return None,
+                };
+                Some((name, value))
+            })
+            .collect());
+    }
+
+    let body: Body = hcl::from_str(&content).map_err(|e| e.to_string())?;
+    Ok(body
+        .attributes()
+        .filter_map(|attr| Some((attr.key.to_string(), literal_value(&attr.expr)?)))
+        .collect())
+}
+
+/// Returns a literal string, number, or bool as a string.
+fn literal_value(expr: &Expression) -> Option<String> {
+    match expr {
+        Expression::String(value) => Some(value.clone()),
+        Expression::Number(number) => Some(number.to_string()),
+        Expression::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Replaces the `${var.name}` references in `text` that have a value.
+///
+/// References to variables without a value are kept, so the text degrades
+/// to the interpolated form instead of a wrong value.
+pub fn substitute_variables(text: &str, values: &HashMap<String, String>) -> String {
+    VARIABLE_REFERENCE
+        .replace_all(text, |captures: &Captures| {
+            values
+                .get(&captures[1])
+                .cloned()
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn variable_defaults_keep_literal_defaults_only() {
+        let body: Body = hcl::from_str(
+            r#"
+            variable "account_id" {
+              default = "111111111111"
+            }
+            variable "port" {
+              default = 443
+            }
+            variable "role" {
+              default = "${var.prefix}-deploy"
+            }
+            variable "no_default" {}
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            variable_defaults(&body),
+            HashMap::from([
+                ("account_id".to_string(), "111111111111".to_string()),
+                ("port".to_string(), "443".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn tfvars_override_in_terraform_order() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("terraform.tfvars"),
+            "account_id = \"111111111111\"\nenv = \"dev\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("b.auto.tfvars"),
+            "account_id = \"333333333333\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("a.auto.tfvars.json"),
+            r#"{"account_id": "222222222222", "region": "eu-central-1"}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("prod.tfvars"), "env = \"prod\"\n").unwrap();
+        std::fs::write(dir.path().join("broken.auto.tfvars"), "env = {\n").unwrap();
+
+        let values = tfvars_values(dir.path());
+
+        assert_eq!(values["account_id"], "333333333333");
+        assert_eq!(values["env"], "dev");
+        assert_eq!(values["region"], "eu-central-1");
+    }
+
+    #[test]
+    fn substitute_variables_keeps_unknown_references() {
+        let values = HashMap::from([("account_id".to_string(), "123456789012".to_string())]);
+
+        assert_eq!(
+            substitute_variables("arn:aws:iam::${var.account_id}:role/${var.role}", &values),
+            "arn:aws:iam::123456789012:role/${var.role}"
+        );
+        assert_eq!(
+            substitute_variables("${local.role_arn}", &values),
+            "${local.role_arn}"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Fixtures shared by the unit tests.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::mapping::{GroupPermissions, MappingLoader, NotActionStatement, PermissionResult};
+use crate::terraform::BlockType;
+
+/// Returns a set of the given actions.
+pub fn actions(names: &[&str]) -> HashSet<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+/// Returns a list of the given actions, in order.
+pub fn action_list(names: &[&str]) -> Vec<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+/// Returns an unscoped, unconditional `NotAction` statement of a mapping.
+pub fn not_action(mapping: &str, names: &[&str]) -> NotActionStatement {
+    NotActionStatement::new(mapping, names.iter().map(|s| s.to_string()))
+}
+
+/// Returns a group with the given allow and deny actions.
+pub fn group(allow: &[&str], deny: &[&str]) -> GroupPermissions {
+    GroupPermissions {
+        allow: actions(allow),
+        deny: actions(deny),
+        ..Default::default()
+    }
+}
+
+/// Returns a result with one group per `(name, allow, deny)` entry.
+pub fn result(groups: &[(&str, &[&str], &[&str])]) -> PermissionResult {
+    PermissionResult {
+        groups: groups
+            .iter()
+            .map(|(name, allow, deny)| (name.to_string(), group(allow, deny)))
+            .collect::<HashMap<_, _>>(),
+        missing_mappings: Vec::new(),
+        empty_groups: Vec::new(),
+    }
+}
+
+/// Returns a loader whose cache holds `content` as the mapping of
+/// `resource.aws_s3_bucket`, and the temporary directory backing it.
+pub fn loader_with_mapping(content: &str) -> (TempDir, MappingLoader) {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("mappings/resource")).unwrap();
+    fs::write(
+        temp_dir.path().join("mappings/resource/aws_s3_bucket.yaml"),
+        content,
+    )
+    .unwrap();
+
+    let loader = MappingLoader::new(temp_dir.path().to_path_buf());
+    loader
+        .load("aws", BlockType::Resource, "aws_s3_bucket")
+        .unwrap();
+
+    (temp_dir, loader)
+}
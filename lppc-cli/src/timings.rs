@@ -0,0 +1,144 @@
+//! Wall-clock timing of pipeline stages.
+//!
+//! This module provides `Timings`, which records how long each major stage of
+//! a run took (terraform init, copy, parse, resolve, write, ...) so that a
+//! breakdown can be printed with `--timings`.
+
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// Recorded durations of pipeline stages, in the order they ran.
+#[derive(Debug, Default)]
+pub struct Timings {
+    stages: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    /// Creates an empty set of timings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `f`, records its wall-clock duration under `stage`, and returns its result.
+    pub fn time<T>(&mut self, stage: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(stage, start.elapsed());
+        result
+    }
+
+    /// Records a duration for `stage`.
+    ///
+    /// Recording the same stage twice adds the durations together.
+    pub fn record(&mut self, stage: &'static str, duration: Duration) {
+        match self.stages.iter_mut().find(|(label, _)| *label == stage) {
+            Some((_, total)) => *total += duration,
+            None => self.stages.push((stage, duration)),
+        }
+    }
+
+    /// Returns the recorded stages and their durations, in the order they ran.
+    pub fn stages(&self) -> &[(&'static str, Duration)] {
+        &self.stages
+    }
+
+    /// Returns the sum of all recorded durations.
+    pub fn total(&self) -> Duration {
+        self.stages.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    /// Formats the breakdown as one line per stage followed by the total.
+    ///
+    /// ```text
+    /// Timings:
+    ///   init        1.204s
+    ///   parse       0.031s
+    ///   total       1.235s
+    /// ```
+    pub fn report(&self) -> String {
+        let width = self
+            .stages
+            .iter()
+            .map(|(label, _)| label.len())
+            .chain(std::iter::once("total".len()))
+            .max()
+            .unwrap_or_default();
+
+        let mut report = String::from("Timings:\n");
+        for (label, duration) in self
+            .stages
+            .iter()
+            .map(|(label, duration)| (*label, *duration))
+            .chain(std::iter::once(("total", self.total())))
+        {
+            let _ = writeln!(
+                report,
+                "  {:<width$}  {:>8.3}s",
+                label,
+                duration.as_secs_f64()
+            );
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_records_stage_and_returns_result() {
+        let mut timings = Timings::new();
+
+        let value = timings.time("parse", || 42);
+
+        assert_eq!(value, 42);
+        assert_eq!(timings.stages().len(), 1);
+        assert_eq!(timings.stages()[0].0, "parse");
+    }
+
+    #[test]
+    fn time_measures_at_least_the_elapsed_time() {
+        let mut timings = Timings::new();
+
+        timings.time("init", || std::thread::sleep(Duration::from_millis(20)));
+
+        assert!(timings.stages()[0].1 >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn stages_keep_execution_order_and_accumulate_repeats() {
+        let mut timings = Timings::new();
+
+        timings.record("copy", Duration::from_millis(10));
+        timings.record("init", Duration::from_millis(200));
+        timings.record("copy", Duration::from_millis(5));
+
+        assert_eq!(
+            timings.stages(),
+            &[
+                ("copy", Duration::from_millis(15)),
+                ("init", Duration::from_millis(200))
+            ]
+        );
+        assert_eq!(timings.total(), Duration::from_millis(215));
+    }
+
+    #[test]
+    fn report_lists_stages_and_total() {
+        let mut timings = Timings::new();
+        timings.record("init", Duration::from_millis(1204));
+        timings.record("parse", Duration::from_millis(31));
+
+        assert_eq!(
+            timings.report(),
+            "Timings:\n  init      1.204s\n  parse     0.031s\n  total     1.235s\n"
+        );
+    }
+
+    #[test]
+    fn report_without_stages_shows_zero_total() {
+        assert_eq!(Timings::new().report(), "Timings:\n  total     0.000s\n");
+    }
+}
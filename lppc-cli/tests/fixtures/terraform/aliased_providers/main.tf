@@ -0,0 +1,33 @@
+provider "aws" {
+  alias  = "network"
+  region = "eu-central-1"
+
+  assume_role {
+    role_arn = "arn:aws:iam::123456789012:role/NetworkDeployer"
+  }
+}
+
+provider "aws" {
+  alias  = "storage"
+  region = "eu-central-1"
+
+  assume_role {
+    role_arn = "arn:aws:iam::123456789012:role/StorageDeployer"
+  }
+}
+
+resource "aws_vpc" "main" {
+  provider   = aws.network
+  cidr_block = "10.0.0.0/16"
+}
+
+resource "aws_subnet" "private" {
+  provider   = aws.network
+  vpc_id     = aws_vpc.main.id
+  cidr_block = "10.0.1.0/24"
+}
+
+resource "aws_s3_bucket" "logs" {
+  provider = aws.storage
+  bucket   = "logs"
+}
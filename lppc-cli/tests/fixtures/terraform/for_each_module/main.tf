@@ -0,0 +1,19 @@
+provider "aws" {
+  alias  = "queues"
+  region = "eu-central-1"
+
+  assume_role {
+    role_arn = "arn:aws:iam::123456789012:role/QueueDeployer"
+  }
+}
+
+module "queue" {
+  source   = "./modules/queue"
+  for_each = toset(["orders", "invoices"])
+
+  providers = {
+    aws = aws.queues
+  }
+
+  name = each.key
+}
@@ -0,0 +1,16 @@
+variable "name" {
+  type = string
+}
+
+resource "aws_sqs_queue" "dead_letter" {
+  name = "${var.name}-dlq"
+}
+
+resource "aws_sqs_queue" "this" {
+  name = var.name
+
+  redrive_policy = jsonencode({
+    deadLetterTargetArn = aws_sqs_queue.dead_letter.arn
+    maxReceiveCount     = 5
+  })
+}
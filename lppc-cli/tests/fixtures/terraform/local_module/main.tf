@@ -0,0 +1,9 @@
+provider "aws" {
+  region = "eu-central-1"
+}
+
+module "network" {
+  source = "./modules/network"
+
+  cidr_block = "10.0.0.0/16"
+}
@@ -0,0 +1,16 @@
+variable "cidr_block" {
+  type = string
+}
+
+resource "aws_vpc" "this" {
+  cidr_block = var.cidr_block
+
+  tags = {
+    Name = "main"
+  }
+}
+
+resource "aws_subnet" "this" {
+  vpc_id     = aws_vpc.this.id
+  cidr_block = cidrsubnet(var.cidr_block, 8, 1)
+}
@@ -0,0 +1,13 @@
+provider "aws" {
+  region = "eu-central-1"
+}
+
+data "aws_caller_identity" "current" {}
+
+resource "aws_s3_bucket" "artifacts" {
+  bucket = "artifacts-${data.aws_caller_identity.current.account_id}"
+
+  tags = {
+    Team = "platform"
+  }
+}
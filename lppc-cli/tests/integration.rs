@@ -1,3 +1,4 @@
 mod integration {
     mod cli_tests;
+    mod pipeline_tests;
 }
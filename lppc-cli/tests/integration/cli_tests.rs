@@ -74,7 +74,8 @@ fn test_help_shows_all_options() {
         .stdout(predicate::str::contains("--output-dir"))
         .stdout(predicate::str::contains("--output-format"))
         .stdout(predicate::str::contains("--mappings-url"))
-        .stdout(predicate::str::contains("--refresh-mappings"));
+        .stdout(predicate::str::contains("--refresh-mappings"))
+        .stdout(predicate::str::contains("--resolve-conflicts"));
 }
 
 #[test]
@@ -155,6 +156,26 @@ fn test_output_format_invalid_value_is_rejected() {
         .stderr(predicate::str::contains("invalid value"));
 }
 
+#[test]
+fn test_emit_manifest_requires_output_dir() {
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args(["--emit-manifest", "--mappings-url", TEST_REPO_URL])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--output-dir"));
+}
+
+#[test]
+fn test_validate_cmd_requires_output_dir() {
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args(["--validate-cmd", "true {}", "--mappings-url", TEST_REPO_URL])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--output-dir"));
+}
+
 #[test]
 fn test_output_format_json() {
     Command::cargo_bin("lppc")
@@ -354,6 +375,18 @@ fn test_nonexistent_repo_fails() {
         .failure();
 }
 
+#[test]
+fn test_local_mappings_without_mappings_directory_fails() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args(["--local-mappings", temp_dir.path().to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid local mappings directory"));
+}
+
 // ============================================================================
 // Milestone 3: Working directory and Terraform execution tests
 // ============================================================================
@@ -500,3 +533,413 @@ fn test_relative_working_dir_resolves_correctly() {
         .assert()
         .success();
 }
+
+#[test]
+fn test_local_mappings_work_without_network() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--working-dir",
+            &format!("{}/terraform/single_provider", fixtures),
+            "--local-mappings",
+            &format!("{}/mappings", fixtures),
+            "--output-format",
+            "json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("s3:CreateBucket"));
+}
+
+#[test]
+fn test_template_file_renders_each_group() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let template = temp_dir.path().join("policy.j2");
+    std::fs::write(
+        &template,
+        "{% for action in allow %}allow {{ action }}\n{% endfor %}",
+    )
+    .unwrap();
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--working-dir",
+            &format!("{}/terraform/single_provider", fixtures),
+            "--local-mappings",
+            &format!("{}/mappings", fixtures),
+            "--template-file",
+            template.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("allow s3:CreateBucket"));
+}
+
+#[test]
+fn test_group_by_account_with_redacted_account_ids() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    // Grouping uses the real account ID even though printed ARNs are redacted
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--working-dir",
+            &format!("{}/terraform/aliased_providers", fixtures),
+            "--local-mappings",
+            &format!("{}/mappings", fixtures),
+            "--output-format",
+            "json",
+            "--group-by-account",
+            "--redact-account-ids",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("123456789012"))
+        .stdout(predicate::str::contains("unknown-account").not())
+        .stdout(predicate::str::contains("ec2:CreateVpc"))
+        .stdout(predicate::str::contains("s3:CreateBucket"));
+}
+
+// ============================================================================
+// Exit code tests (don't require network)
+// ============================================================================
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn test_exit_code_generic_failure() {
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--working-dir",
+            &format!("{}/terraform/single_provider", FIXTURES),
+            "--local-mappings",
+            &format!("{}/mappings", FIXTURES),
+            "--forbidden-types",
+            "aws_s3_bucket",
+        ])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Forbidden resource types found"));
+}
+
+#[test]
+fn test_exit_code_terraform_not_found() {
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .env("PATH", "/nonexistent")
+        .args([
+            "--working-dir",
+            &format!("{}/terraform/single_provider", FIXTURES),
+            "--local-mappings",
+            &format!("{}/mappings", FIXTURES),
+        ])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Terraform is not installed"));
+}
+
+#[test]
+fn test_exit_code_mapping_repository_unreachable() {
+    let home = tempfile::TempDir::new().expect("Failed to create temp dir");
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .env("HOME", home.path())
+        .args([
+            "--working-dir",
+            &format!("{}/terraform/single_provider", FIXTURES),
+            "--mappings-url",
+            "https://lppc.invalid/bebold-jhr/lppc-aws-mappings",
+        ])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("Mapping repository not available"));
+}
+
+#[test]
+fn test_offline_without_cache_fails_without_network() {
+    let home = tempfile::TempDir::new().expect("Failed to create temp dir");
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .env("HOME", home.path())
+        .args([
+            "--working-dir",
+            &format!("{}/terraform/single_provider", FIXTURES),
+            "--mappings-url",
+            "https://lppc.invalid/bebold-jhr/lppc-aws-mappings",
+            "--offline",
+        ])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("no cached version"));
+}
+
+#[test]
+fn test_exit_code_empty_group() {
+    let working_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        working_dir.path().join("main.tf"),
+        "resource \"aws_iam_role\" \"deployer\" {\n  name = \"deployer\"\n}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--working-dir",
+            working_dir.path().to_str().unwrap(),
+            "--local-mappings",
+            &format!("{}/mappings", FIXTURES),
+            "--fail-on-empty-group",
+        ])
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains("resolved no permissions"));
+}
+
+#[test]
+fn test_exit_code_missing_mappings() {
+    let working_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        working_dir.path().join("main.tf"),
+        "resource \"aws_s3_bucket\" \"logs\" {\n  bucket = \"logs\"\n}\n\n\
+         resource \"aws_iam_role\" \"deployer\" {\n  name = \"deployer\"\n}\n",
+    )
+    .unwrap();
+
+    // The output is still written before the run fails
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--working-dir",
+            working_dir.path().to_str().unwrap(),
+            "--local-mappings",
+            &format!("{}/mappings", FIXTURES),
+            "--output-format",
+            "json",
+            "--fail-on-missing-mappings",
+        ])
+        .assert()
+        .code(7)
+        .stdout(predicate::str::contains("s3:CreateBucket"))
+        .stderr(predicate::str::contains("aws_iam_role"));
+}
+
+#[test]
+fn test_diagnostics_reported_when_missing_mappings_fail_the_run() {
+    let working_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        working_dir.path().join("main.tf"),
+        "resource \"aws_iam_role\" \"deployer\" {\n  name = \"deployer\"\n}\n\n\
+         module \"network\" {\n  source = \"./modules/network\"\n}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--working-dir",
+            working_dir.path().to_str().unwrap(),
+            "--local-mappings",
+            &format!("{}/mappings", FIXTURES),
+            "--no-init",
+            "--fail-on-missing-mappings",
+        ])
+        .assert()
+        .code(7)
+        .stderr(predicate::str::contains(
+            "1 module(s) were not analyzed because no modules manifest was found",
+        ));
+}
+
+#[test]
+fn test_exit_code_invalid_mapping_file() {
+    let mappings = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let resource_dir = mappings.path().join("mappings/resource");
+    std::fs::create_dir_all(&resource_dir).unwrap();
+    std::fs::write(
+        resource_dir.join("aws_s3_bucket.yaml"),
+        "allow: [s3:CreateBucket\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--working-dir",
+            &format!("{}/terraform/single_provider", FIXTURES),
+            "--local-mappings",
+            mappings.path().to_str().unwrap(),
+        ])
+        .assert()
+        .code(6)
+        .stderr(predicate::str::contains("Parse error"));
+}
+
+#[test]
+fn test_validate_mappings_reports_invalid_files() {
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "validate-mappings",
+            "--local-mappings",
+            &format!("{}/mappings", FIXTURES),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0 invalid"));
+
+    let mappings = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let resource_dir = mappings.path().join("mappings/resource");
+    std::fs::create_dir_all(&resource_dir).unwrap();
+    std::fs::write(
+        resource_dir.join("aws_s3_bucket.yaml"),
+        "alow:\n  - s3:CreateBucket\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "validate-mappings",
+            "--local-mappings",
+            mappings.path().to_str().unwrap(),
+        ])
+        .assert()
+        .code(6)
+        .stdout(predicate::str::contains(
+            "invalid  mappings/resource/aws_s3_bucket.yaml",
+        ))
+        .stdout(predicate::str::contains("did you mean 'allow'?"));
+}
+
+#[test]
+fn test_compare_mappings_ref_conflicts_with_local_mappings() {
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--local-mappings",
+            &format!("{}/mappings", FIXTURES),
+            "--compare-mappings-ref",
+            "main",
+        ])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_mapping_stats_subcommand_prints_statistics() {
+    for command in ["mapping-stats", "stats"] {
+        Command::cargo_bin("lppc")
+            .unwrap()
+            .args([
+                command,
+                "--local-mappings",
+                &format!("{}/mappings", FIXTURES),
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Mapping files:"))
+            .stdout(predicate::str::contains("Services covered:"));
+    }
+
+    // The former flag no longer exists
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .arg("--mapping-stats")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_unresolved_modules_reported_without_init() {
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--working-dir",
+            &format!("{}/terraform/local_module", FIXTURES),
+            "--local-mappings",
+            &format!("{}/mappings", FIXTURES),
+            "--no-init",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "1 module(s) were not analyzed because no modules manifest was found",
+        ))
+        .stderr(predicate::str::contains("network"));
+}
+
+#[test]
+fn test_provider_version_constraints_reported() {
+    let working_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        working_dir.path().join("main.tf"),
+        r#"
+terraform {
+  required_providers {
+    aws = {
+      source  = "hashicorp/aws"
+      version = "~> 5.0"
+    }
+    random = {
+      source  = "hashicorp/random"
+      version = ">= 3.0"
+    }
+  }
+}
+
+resource "aws_s3_bucket" "main" {}
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--working-dir",
+            working_dir.path().to_str().unwrap(),
+            "--local-mappings",
+            &format!("{}/mappings", FIXTURES),
+            "--output-format",
+            "json",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Provider version constraints: aws ~> 5.0, random >= 3.0",
+        ));
+}
+
+#[test]
+fn test_dedupe_identical_groups_conflicts_with_condition_and_manifest() {
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--dedupe-identical-groups",
+            "--condition",
+            r#"NetworkDeployer:{"Bool":{"aws:SecureTransport":"true"}}"#,
+        ])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    Command::cargo_bin("lppc")
+        .unwrap()
+        .args([
+            "--dedupe-identical-groups",
+            "--emit-manifest",
+            "--output-dir",
+            "out",
+        ])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
@@ -0,0 +1,156 @@
+//! End-to-end tests of the analysis pipeline.
+//!
+//! Each test copies a Terraform fixture from `tests/fixtures/terraform` through
+//! `PlanExecutor` (sandbox, `terraform init`, `HclParser`) and resolves it with
+//! `MappingLoader` and `PermissionMatcher` against the sample mapping repository
+//! in `tests/fixtures/mappings`, so no network access to the mapping repository
+//! is needed. `terraform init` still installs the AWS provider.
+//!
+//! The tests are skipped if terraform is not installed.
+
+use std::path::PathBuf;
+
+use lppc::mapping::{MappingLoader, PermissionMatcher, PermissionResult};
+use lppc::terraform::{PlanExecutor, TerraformError};
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Runs the pipeline on a Terraform fixture, or returns `None` if terraform
+/// is not installed.
+fn analyze(fixture: &str) -> Option<PermissionResult> {
+    let executor = match PlanExecutor::new() {
+        Ok(executor) => executor,
+        Err(TerraformError::NotFound) => {
+            eprintln!(
+                "Skipping pipeline test for {}: terraform not found",
+                fixture
+            );
+            return None;
+        }
+        Err(e) => panic!("Cannot create executor: {}", e),
+    };
+
+    let working_dir = fixtures_dir().join("terraform").join(fixture);
+    let config = executor
+        .execute(&working_dir)
+        .unwrap_or_else(|e| panic!("Failed to analyze {}: {}", fixture, e))
+        .expect("fixture contains Terraform files");
+
+    let loader = MappingLoader::new(fixtures_dir().join("mappings"));
+    let result = PermissionMatcher::new(&loader).resolve(&config).unwrap();
+
+    Some(result)
+}
+
+/// Returns the group names of a result, sorted.
+fn group_names(result: &PermissionResult) -> Vec<&str> {
+    let mut names: Vec<&str> = result.groups.keys().map(String::as_str).collect();
+    names.sort();
+    names
+}
+
+/// Returns the allowed actions of a group, sorted.
+fn allowed_actions(result: &PermissionResult, group: &str) -> Vec<String> {
+    let mut actions: Vec<String> = result.groups[group].allow.iter().cloned().collect();
+    actions.sort();
+    actions
+}
+
+#[test]
+fn test_pipeline_single_provider() {
+    let Some(result) = analyze("single_provider") else {
+        return;
+    };
+
+    assert_eq!(group_names(&result), vec!["DefaultDeployer"]);
+    assert_eq!(
+        allowed_actions(&result, "DefaultDeployer"),
+        vec![
+            "s3:CreateBucket",
+            "s3:DeleteBucket",
+            "s3:ListBucket",
+            "s3:PutBucketTagging",
+            "sts:GetCallerIdentity",
+        ]
+    );
+    assert!(result.missing_mappings.is_empty());
+}
+
+#[test]
+fn test_pipeline_aliased_providers() {
+    let Some(result) = analyze("aliased_providers") else {
+        return;
+    };
+
+    assert_eq!(
+        group_names(&result),
+        vec!["NetworkDeployer", "StorageDeployer"]
+    );
+    assert_eq!(
+        allowed_actions(&result, "NetworkDeployer"),
+        vec![
+            "ec2:CreateSubnet",
+            "ec2:CreateVpc",
+            "ec2:DeleteSubnet",
+            "ec2:DeleteVpc",
+            "ec2:DescribeSubnets",
+            "ec2:DescribeVpcs",
+        ]
+    );
+    // The bucket has no tags, so the conditional tagging action is not added
+    assert_eq!(
+        allowed_actions(&result, "StorageDeployer"),
+        vec!["s3:CreateBucket", "s3:DeleteBucket", "s3:ListBucket"]
+    );
+}
+
+#[test]
+fn test_pipeline_local_module() {
+    let Some(result) = analyze("local_module") else {
+        return;
+    };
+
+    assert_eq!(group_names(&result), vec!["DefaultDeployer"]);
+    assert_eq!(
+        allowed_actions(&result, "DefaultDeployer"),
+        vec![
+            "ec2:CreateSubnet",
+            "ec2:CreateTags",
+            "ec2:CreateVpc",
+            "ec2:DeleteSubnet",
+            "ec2:DeleteVpc",
+            "ec2:DescribeSubnets",
+            "ec2:DescribeVpcs",
+        ]
+    );
+
+    let mut sources = result.groups["DefaultDeployer"].sources.clone();
+    sources.sort();
+    assert_eq!(
+        sources,
+        vec![
+            "module.network.aws_subnet.this",
+            "module.network.aws_vpc.this"
+        ]
+    );
+}
+
+#[test]
+fn test_pipeline_for_each_module_with_passed_provider() {
+    let Some(result) = analyze("for_each_module") else {
+        return;
+    };
+
+    assert_eq!(group_names(&result), vec!["QueuesDeployer"]);
+    assert_eq!(
+        allowed_actions(&result, "QueuesDeployer"),
+        vec![
+            "sqs:CreateQueue",
+            "sqs:DeleteQueue",
+            "sqs:GetQueueAttributes",
+            "sqs:SetQueueAttributes",
+        ]
+    );
+}
@@ -1,7 +1,7 @@
 use anyhow::{bail, Context, Result};
 use log::debug;
 use serde::Deserialize;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
@@ -67,6 +67,32 @@ pub struct ComputedActions {
     pub deny: Vec<String>,
 }
 
+/// Maps service reference names to the IAM action prefix used in generated actions.
+///
+/// By default the service name from the reference file is used as the prefix.
+/// An override replaces it for services whose canonical action prefix differs
+/// from the `service` field (e.g. a sub-prefix or different casing).
+#[derive(Debug, Clone, Default)]
+pub struct PrefixNormalization {
+    overrides: HashMap<String, String>,
+}
+
+impl PrefixNormalization {
+    pub fn new(overrides: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            overrides: overrides.into_iter().collect(),
+        }
+    }
+
+    /// Returns the action prefix for `service`.
+    pub fn action_prefix<'a>(&'a self, service: &'a str) -> &'a str {
+        self.overrides
+            .get(service)
+            .map(String::as_str)
+            .unwrap_or(service)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ServiceActions {
     #[serde(rename = "Name")]
@@ -116,12 +142,15 @@ pub fn compute_selected_actions(
     all_actions: &[Action],
     allow_indices: &HashSet<usize>,
     deny_indices: &HashSet<usize>,
+    normalization: &PrefixNormalization,
 ) -> ComputedActions {
     debug_assert!(
         allow_indices.is_disjoint(deny_indices),
         "allow_indices and deny_indices must be disjoint"
     );
 
+    let service_prefix = normalization.action_prefix(service_prefix);
+
     let list_actions: Vec<usize> = all_actions
         .iter()
         .enumerate()
@@ -358,7 +387,13 @@ mod tests {
 
         let allow: HashSet<usize> = [0, 1].into_iter().collect();
         let deny: HashSet<usize> = HashSet::new();
-        let result = compute_selected_actions("ec2", &actions, &allow, &deny);
+        let result = compute_selected_actions(
+            "ec2",
+            &actions,
+            &allow,
+            &deny,
+            &PrefixNormalization::default(),
+        );
 
         assert!(result.allow.contains(&"ec2:List*".to_string()));
         assert!(!result.allow.iter().any(|s| s.contains("ListSubnets")));
@@ -375,7 +410,13 @@ mod tests {
 
         let allow: HashSet<usize> = [0].into_iter().collect();
         let deny: HashSet<usize> = HashSet::new();
-        let result = compute_selected_actions("ec2", &actions, &allow, &deny);
+        let result = compute_selected_actions(
+            "ec2",
+            &actions,
+            &allow,
+            &deny,
+            &PrefixNormalization::default(),
+        );
 
         assert!(!result.allow.contains(&"ec2:List*".to_string()));
         assert!(result.allow.contains(&"ec2:ListSubnets".to_string()));
@@ -393,7 +434,13 @@ mod tests {
 
         let allow: HashSet<usize> = [0, 1, 2, 3, 4].into_iter().collect();
         let deny: HashSet<usize> = HashSet::new();
-        let result = compute_selected_actions("ec2", &actions, &allow, &deny);
+        let result = compute_selected_actions(
+            "ec2",
+            &actions,
+            &allow,
+            &deny,
+            &PrefixNormalization::default(),
+        );
 
         assert!(result.allow.contains(&"ec2:List*".to_string()));
         assert!(result.allow.contains(&"ec2:Describe*".to_string()));
@@ -410,7 +457,13 @@ mod tests {
 
         let allow: HashSet<usize> = [0, 2].into_iter().collect();
         let deny: HashSet<usize> = HashSet::new();
-        let result = compute_selected_actions("ec2", &actions, &allow, &deny);
+        let result = compute_selected_actions(
+            "ec2",
+            &actions,
+            &allow,
+            &deny,
+            &PrefixNormalization::default(),
+        );
 
         assert!(result.allow.contains(&"ec2:CreateSubnet".to_string()));
         assert!(result.allow.contains(&"ec2:CreateTags".to_string()));
@@ -426,7 +479,13 @@ mod tests {
 
         let allow: HashSet<usize> = HashSet::new();
         let deny: HashSet<usize> = HashSet::new();
-        let result = compute_selected_actions("ec2", &actions, &allow, &deny);
+        let result = compute_selected_actions(
+            "ec2",
+            &actions,
+            &allow,
+            &deny,
+            &PrefixNormalization::default(),
+        );
 
         assert!(result.allow.is_empty());
         assert!(result.deny.is_empty());
@@ -442,7 +501,13 @@ mod tests {
 
         let allow: HashSet<usize> = HashSet::new();
         let deny: HashSet<usize> = [0, 1].into_iter().collect();
-        let result = compute_selected_actions("ec2", &actions, &allow, &deny);
+        let result = compute_selected_actions(
+            "ec2",
+            &actions,
+            &allow,
+            &deny,
+            &PrefixNormalization::default(),
+        );
 
         assert!(result.allow.is_empty());
         assert_eq!(result.deny.len(), 2);
@@ -450,6 +515,36 @@ mod tests {
         assert!(result.deny.contains(&"ec2:DeleteSubnet".to_string()));
     }
 
+    #[test]
+    fn compute_selected_actions_uses_overridden_prefix() {
+        let actions = vec![
+            create_test_action("ListClusters", false),
+            create_test_action("ListTasks", false),
+            create_test_action("CreateCluster", false),
+            create_test_action("DeleteCluster", false),
+        ];
+        let normalization =
+            PrefixNormalization::new([("wrongprefix".to_string(), "ecs".to_string())]);
+
+        let allow: HashSet<usize> = [0, 1, 2].into_iter().collect();
+        let deny: HashSet<usize> = [3].into_iter().collect();
+        let result =
+            compute_selected_actions("wrongprefix", &actions, &allow, &deny, &normalization);
+
+        assert_eq!(result.allow, vec!["ecs:CreateCluster", "ecs:List*"]);
+        assert_eq!(result.deny, vec!["ecs:DeleteCluster"]);
+    }
+
+    #[test]
+    fn prefix_normalization_defaults_to_service_name() {
+        let normalization =
+            PrefixNormalization::new([("wrongprefix".to_string(), "ecs".to_string())]);
+
+        assert_eq!(normalization.action_prefix("wrongprefix"), "ecs");
+        assert_eq!(normalization.action_prefix("ec2"), "ec2");
+        assert_eq!(PrefixNormalization::default().action_prefix("ec2"), "ec2");
+    }
+
     #[test]
     fn wildcard_in_allow_when_some_denied_but_none_deselected() {
         let actions = vec![
@@ -461,7 +556,13 @@ mod tests {
         // ListSubnets in allow, ListVpcs in deny -> none deselected -> wildcard in allow
         let allow: HashSet<usize> = [0].into_iter().collect();
         let deny: HashSet<usize> = [1].into_iter().collect();
-        let result = compute_selected_actions("ec2", &actions, &allow, &deny);
+        let result = compute_selected_actions(
+            "ec2",
+            &actions,
+            &allow,
+            &deny,
+            &PrefixNormalization::default(),
+        );
 
         assert!(result.allow.contains(&"ec2:List*".to_string()));
         assert!(!result.allow.iter().any(|s| s.contains("ListSubnets")));
@@ -479,7 +580,13 @@ mod tests {
         // ListSubnets in allow, ListVpcs in deny, ListRouteTables deselected -> no wildcard
         let allow: HashSet<usize> = [0].into_iter().collect();
         let deny: HashSet<usize> = [1].into_iter().collect();
-        let result = compute_selected_actions("ec2", &actions, &allow, &deny);
+        let result = compute_selected_actions(
+            "ec2",
+            &actions,
+            &allow,
+            &deny,
+            &PrefixNormalization::default(),
+        );
 
         assert!(!result.allow.contains(&"ec2:List*".to_string()));
         assert!(result.allow.contains(&"ec2:ListSubnets".to_string()));
@@ -496,7 +603,13 @@ mod tests {
         // All List actions in deny
         let allow: HashSet<usize> = HashSet::new();
         let deny: HashSet<usize> = [0, 1].into_iter().collect();
-        let result = compute_selected_actions("ec2", &actions, &allow, &deny);
+        let result = compute_selected_actions(
+            "ec2",
+            &actions,
+            &allow,
+            &deny,
+            &PrefixNormalization::default(),
+        );
 
         assert!(!result.deny.contains(&"ec2:List*".to_string()));
         assert!(result.deny.contains(&"ec2:ListSubnets".to_string()));
@@ -519,7 +632,13 @@ mod tests {
         // GetSubnetCidr only one -> no Get wildcard (needs len > 1)
         let allow: HashSet<usize> = [0, 2, 4, 5].into_iter().collect();
         let deny: HashSet<usize> = [1].into_iter().collect();
-        let result = compute_selected_actions("ec2", &actions, &allow, &deny);
+        let result = compute_selected_actions(
+            "ec2",
+            &actions,
+            &allow,
+            &deny,
+            &PrefixNormalization::default(),
+        );
 
         assert!(result.allow.contains(&"ec2:List*".to_string()));
         assert!(!result.allow.contains(&"ec2:Describe*".to_string()));
@@ -18,4 +18,55 @@ pub struct Args {
     /// Enable verbose logging output
     #[arg(long)]
     pub verbose: bool,
+
+    /// Use PREFIX instead of the service name in generated actions (repeatable)
+    #[arg(
+        long = "prefix-override",
+        value_name = "SERVICE=PREFIX",
+        value_parser = parse_prefix_override
+    )]
+    pub prefix_overrides: Vec<(String, String)>,
+}
+
+/// Parses a `SERVICE=PREFIX` pair for `--prefix-override`.
+fn parse_prefix_override(value: &str) -> Result<(String, String), String> {
+    let (service, prefix) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected SERVICE=PREFIX, got '{}'", value))?;
+
+    let is_valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    if !is_valid(service) || !is_valid(prefix) {
+        return Err(format!(
+            "SERVICE and PREFIX must be non-empty and contain only letters, digits, '-' or '_', got '{}'",
+            value
+        ));
+    }
+
+    Ok((service.to_string(), prefix.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_prefix_override_splits_service_and_prefix() {
+        assert_eq!(
+            parse_prefix_override("wrongprefix=ecs"),
+            Ok(("wrongprefix".to_string(), "ecs".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_prefix_override_rejects_invalid_values() {
+        assert!(parse_prefix_override("ecs").is_err());
+        assert!(parse_prefix_override("=ecs").is_err());
+        assert!(parse_prefix_override("ecs=").is_err());
+        assert!(parse_prefix_override("ecs=ecs:Create").is_err());
+    }
 }
@@ -36,6 +36,14 @@ pub fn generate_files(config: &GeneratorConfig) -> Result<GeneratedFiles> {
         );
     }
 
+    let overlapping = find_overlapping_actions(&config.allow_actions, &config.deny_actions);
+    if !overlapping.is_empty() {
+        bail!(
+            "Allow wildcards overlap with deny actions: {}. Reconsider the selection so that no denied action is covered by an allowed wildcard.",
+            overlapping.join(", ")
+        );
+    }
+
     let mapping_path = generate_mapping_file(config)?;
     let test_files = generate_integration_tests(config)?;
 
@@ -46,6 +54,32 @@ pub fn generate_files(config: &GeneratorConfig) -> Result<GeneratedFiles> {
     })
 }
 
+/// Returns each overlap between an allow wildcard and a deny action as
+/// `"{wildcard} covers {action}"`, in the order of the deny list.
+///
+/// IAM action names are matched case-insensitively.
+fn find_overlapping_actions(allow_actions: &[String], deny_actions: &[String]) -> Vec<String> {
+    let wildcard_prefixes: Vec<(&String, String)> = allow_actions
+        .iter()
+        .filter_map(|allow| {
+            allow
+                .strip_suffix('*')
+                .map(|prefix| (allow, prefix.to_ascii_lowercase()))
+        })
+        .collect();
+
+    deny_actions
+        .iter()
+        .flat_map(|deny| {
+            let deny_lower = deny.to_ascii_lowercase();
+            wildcard_prefixes
+                .iter()
+                .filter(move |(_, prefix)| deny_lower.starts_with(prefix.as_str()))
+                .map(move |(wildcard, _)| format!("{} covers {}", wildcard, deny))
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct GeneratedFiles {
     pub mapping_file: String,
@@ -655,6 +689,46 @@ mod tests {
         assert!(err_msg.contains("Invalid terraform type"));
     }
 
+    #[test]
+    fn generate_files_rejects_allow_wildcard_overlapping_deny() {
+        let temp_dir = setup_test_dir();
+
+        let config = GeneratorConfig {
+            working_dir: temp_dir.path(),
+            block_type: BlockType::Resource,
+            terraform_type: "aws_subnet",
+            service_reference_url: "https://example.com/ec2.json",
+            allow_actions: vec!["ec2:CreateSubnet".to_string(), "ec2:Describe*".to_string()],
+            deny_actions: vec!["ec2:DescribeSubnets".to_string()],
+            provider_versions: &test_provider_versions(),
+        };
+
+        let result = generate_files(&config);
+        assert!(result.is_err());
+        let err_msg = result.unwrap_err().to_string();
+        assert!(err_msg.contains("ec2:Describe* covers ec2:DescribeSubnets"));
+
+        let mapping_file = temp_dir.path().join("mappings/resource/aws_subnet.yaml");
+        let test_dir = temp_dir.path().join("integration-tests/resource/aws_subnet");
+        assert!(!mapping_file.exists());
+        assert!(!test_dir.exists());
+    }
+
+    #[test]
+    fn find_overlapping_actions_reports_only_covered_deny_actions() {
+        let allow = vec!["ec2:List*".to_string(), "ec2:CreateSubnet".to_string()];
+        let deny = vec![
+            "ec2:DeleteSubnet".to_string(),
+            "ec2:listSubnets".to_string(),
+        ];
+
+        let overlapping = find_overlapping_actions(&allow, &deny);
+        assert_eq!(overlapping, vec!["ec2:List* covers ec2:listSubnets"]);
+
+        let overlapping = find_overlapping_actions(&["ec2:Get*".to_string()], &deny);
+        assert!(overlapping.is_empty());
+    }
+
     #[test]
     fn generate_files_fails_if_test_directory_exists() {
         let temp_dir = setup_test_dir();
@@ -13,7 +13,9 @@ use log::{debug, info};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use action::{compute_selected_actions, get_preselected_indices, load_service_actions};
+use action::{
+    compute_selected_actions, get_preselected_indices, load_service_actions, PrefixNormalization,
+};
 use block_type::BlockType;
 use cli::Args;
 use generator::{generate_files, print_success_message, GeneratorConfig};
@@ -105,9 +107,12 @@ fn run() -> Result<()> {
         preselected_action_indices.len()
     );
 
+    let normalization = PrefixNormalization::new(args.prefix_overrides);
+
     let selected = select_actions(
         &service_actions.actions,
         &selected_service.service,
+        &normalization,
         &preselected_action_indices,
     )?;
     info!(
@@ -121,6 +126,7 @@ fn run() -> Result<()> {
         &service_actions.actions,
         &selected.allow_indices,
         &selected.deny_indices,
+        &normalization,
     );
 
     debug!("Allow actions: {:?}", computed.allow);
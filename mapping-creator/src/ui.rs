@@ -15,7 +15,9 @@ use ratatui::{
 use std::collections::HashSet;
 use std::io::{stdout, Stdout};
 
-use crate::action::{compute_selected_actions, Action, ComputedActions, SelectedActions};
+use crate::action::{
+    compute_selected_actions, Action, ComputedActions, PrefixNormalization, SelectedActions,
+};
 use crate::block_type::BlockType;
 use crate::service::ServiceReference;
 
@@ -369,6 +371,7 @@ const ACTION_WARNING: &str =
 struct ActionSelector<'a> {
     actions: &'a [Action],
     service_prefix: &'a str,
+    normalization: PrefixNormalization,
     allow_indices: HashSet<usize>,
     deny_indices: HashSet<usize>,
     filtered_indices: Vec<usize>,
@@ -387,6 +390,7 @@ impl<'a> ActionSelector<'a> {
         Self {
             actions,
             service_prefix,
+            normalization: PrefixNormalization::default(),
             allow_indices,
             deny_indices,
             filtered_indices,
@@ -479,6 +483,11 @@ impl<'a> ActionSelector<'a> {
         }
     }
 
+    fn with_normalization(mut self, normalization: PrefixNormalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
     fn can_confirm(&self) -> bool {
         !self.allow_indices.is_empty() || !self.deny_indices.is_empty()
     }
@@ -489,6 +498,7 @@ impl<'a> ActionSelector<'a> {
             self.actions,
             &self.allow_indices,
             &self.deny_indices,
+            &self.normalization,
         )
     }
 }
@@ -636,11 +646,13 @@ fn render_action_controls(frame: &mut Frame, area: Rect, can_confirm: bool) {
 pub fn select_actions(
     actions: &[Action],
     service_prefix: &str,
+    normalization: &PrefixNormalization,
     preselected_indices: &[usize],
 ) -> Result<SelectedActions> {
     let _guard = TerminalGuard::new()?;
     let mut terminal = create_terminal()?;
-    let mut selector = ActionSelector::new(actions, service_prefix, preselected_indices);
+    let mut selector = ActionSelector::new(actions, service_prefix, preselected_indices)
+        .with_normalization(normalization.clone());
 
     loop {
         terminal
@@ -657,13 +669,11 @@ pub fn select_actions(
                 KeyCode::Down => selector.move_down(),
                 KeyCode::Char(' ') => selector.cycle_current(),
                 KeyCode::Tab => selector.toggle_all(),
-                KeyCode::Enter => {
-                    if selector.can_confirm() {
-                        return Ok(SelectedActions {
-                            allow_indices: selector.allow_indices,
-                            deny_indices: selector.deny_indices,
-                        });
-                    }
+                KeyCode::Enter if selector.can_confirm() => {
+                    return Ok(SelectedActions {
+                        allow_indices: selector.allow_indices,
+                        deny_indices: selector.deny_indices,
+                    });
                 }
                 KeyCode::Backspace => selector.remove_char(),
                 KeyCode::Esc => {